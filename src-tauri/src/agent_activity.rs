@@ -0,0 +1,366 @@
+/// Per-pane tree of Claude Task subagents and compactions, built from hook events.
+/// Subagents link to the Task that spawned them through `parent_id`. Hooks only
+/// carry `session_id` and the Task `tool_use_id`, so a Task is nested when it
+/// is started from a session other than the pane's main one.
+use serde_json::Value;
+
+use crate::types::{CompactionRecord, PaneAgentActivity, SubagentNode, SubagentStatus};
+
+const SUBAGENT_TOOL_NAME: &str = "Task";
+
+fn str_field<'a>(value: &'a Value, key: &str) -> Option<&'a str> {
+    value.get(key).and_then(|v| v.as_str())
+}
+
+fn finish_subagent(node: &mut SubagentNode, status: SubagentStatus, now: &str) {
+    node.status = status;
+    node.finished_at = Some(now.to_string());
+}
+
+/// Mark every still-running subagent as interrupted. Returns true if any changed.
+fn interrupt_running(activity: &mut PaneAgentActivity, now: &str) -> bool {
+    let mut changed = false;
+    for node in &mut activity.subagents {
+        if node.status == SubagentStatus::Running {
+            finish_subagent(node, SubagentStatus::Interrupted, now);
+            changed = true;
+        }
+    }
+    changed
+}
+
+/// The running subagent whose own hooks report `session_id`. The first time a
+/// foreign session shows up it is bound to the oldest running subagent that
+/// has no session yet.
+fn subagent_for_session<'a>(
+    activity: &'a mut PaneAgentActivity,
+    session_id: Option<&str>,
+) -> Option<&'a mut SubagentNode> {
+    let session_id = session_id.filter(|id| Some(*id) != activity.session_id.as_deref())?;
+    let running = |n: &&mut SubagentNode| n.status == SubagentStatus::Running;
+    if activity
+        .subagents
+        .iter()
+        .any(|n| n.session_id.as_deref() == Some(session_id))
+    {
+        return activity
+            .subagents
+            .iter_mut()
+            .filter(running)
+            .find(|n| n.session_id.as_deref() == Some(session_id));
+    }
+    let node = activity
+        .subagents
+        .iter_mut()
+        .filter(running)
+        .find(|n| n.session_id.is_none())?;
+    node.session_id = Some(session_id.to_string());
+    Some(node)
+}
+
+/// Update a pane's activity from a Claude hook payload.
+/// Returns true if the activity changed and should be re-broadcast.
+pub fn apply_claude_hook(activity: &mut PaneAgentActivity, hook: &Value, now: &str) -> bool {
+    let event_name = str_field(hook, "hook_event_name").unwrap_or_default();
+    let session_id = str_field(hook, "session_id");
+    let tool_name = str_field(hook, "tool_name");
+
+    match event_name {
+        "SessionStart" => {
+            // Resuming after a compaction keeps the same session; anything else
+            // with a new session ID starts a fresh tree.
+            if session_id.is_some() && session_id != activity.session_id.as_deref() {
+                let pane_id = std::mem::take(&mut activity.pane_id);
+                *activity = PaneAgentActivity {
+                    pane_id,
+                    session_id: session_id.map(str::to_string),
+                    ..Default::default()
+                };
+                return true;
+            }
+            if activity.ended_at.is_some() {
+                activity.ended_at = None;
+                activity.end_reason = None;
+                return true;
+            }
+            false
+        }
+        "PreToolUse" if tool_name == Some(SUBAGENT_TOOL_NAME) => {
+            let input = hook.get("tool_input").cloned().unwrap_or(Value::Null);
+            let id = str_field(hook, "tool_use_id")
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("task-{}", activity.subagents.len() + 1));
+            if session_id.is_some() && activity.session_id.is_none() {
+                activity.session_id = session_id.map(str::to_string);
+            }
+            let parent_id = subagent_for_session(activity, session_id).map(|n| n.id.clone());
+            activity.subagents.push(SubagentNode {
+                id,
+                parent_id,
+                description: str_field(&input, "description").map(str::to_string),
+                subagent_type: str_field(&input, "subagent_type").map(str::to_string),
+                status: SubagentStatus::Running,
+                started_at: now.to_string(),
+                finished_at: None,
+                session_id: None,
+            });
+            true
+        }
+        "PostToolUse" if tool_name == Some(SUBAGENT_TOOL_NAME) => {
+            let tool_use_id = str_field(hook, "tool_use_id");
+            let node = match tool_use_id {
+                Some(id) => activity.subagents.iter_mut().find(|n| n.id == id),
+                None => activity
+                    .subagents
+                    .iter_mut()
+                    .find(|n| n.status == SubagentStatus::Running),
+            };
+            match node {
+                Some(node) if node.status != SubagentStatus::Completed => {
+                    finish_subagent(node, SubagentStatus::Completed, now);
+                    true
+                }
+                _ => false,
+            }
+        }
+        "SubagentStop" => {
+            // SubagentStop does not name the Task that finished. Prefer the
+            // subagent bound to the reporting session, otherwise assume the
+            // oldest running one. PostToolUse(Task) still names its Task exactly.
+            let bound = subagent_for_session(activity, session_id).map(|n| n.id.clone());
+            let node = activity.subagents.iter_mut().find(|n| match &bound {
+                Some(id) => n.id == *id,
+                None => n.status == SubagentStatus::Running,
+            });
+            match node {
+                Some(node) => {
+                    finish_subagent(node, SubagentStatus::Completed, now);
+                    true
+                }
+                None => false,
+            }
+        }
+        "PreCompact" => {
+            activity.compactions.push(CompactionRecord {
+                timestamp: now.to_string(),
+                trigger: str_field(hook, "trigger").map(str::to_string),
+                custom_instructions: str_field(hook, "custom_instructions")
+                    .filter(|s| !s.is_empty())
+                    .map(str::to_string),
+            });
+            true
+        }
+        "Stop" => interrupt_running(activity, now),
+        "SessionEnd" => {
+            interrupt_running(activity, now);
+            activity.ended_at = Some(now.to_string());
+            activity.end_reason = str_field(hook, "reason").map(str::to_string);
+            true
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn activity() -> PaneAgentActivity {
+        PaneAgentActivity {
+            pane_id: "p1".into(),
+            session_id: Some("s1".into()),
+            ..Default::default()
+        }
+    }
+
+    fn task_start(id: &str, description: &str) -> Value {
+        json!({
+            "session_id": "s1",
+            "hook_event_name": "PreToolUse",
+            "tool_name": "Task",
+            "tool_use_id": id,
+            "tool_input": { "description": description, "subagent_type": "general-purpose", "prompt": "..." }
+        })
+    }
+
+    fn task_end(id: &str) -> Value {
+        json!({
+            "session_id": "s1",
+            "hook_event_name": "PostToolUse",
+            "tool_name": "Task",
+            "tool_use_id": id
+        })
+    }
+
+    #[test]
+    fn task_pre_and_post_tool_use_track_subagent_lifecycle() {
+        let mut a = activity();
+        assert!(apply_claude_hook(
+            &mut a,
+            &task_start("toolu_1", "Find callers"),
+            "t1"
+        ));
+        assert_eq!(a.subagents.len(), 1);
+        assert_eq!(a.subagents[0].status, SubagentStatus::Running);
+        assert_eq!(a.subagents[0].description.as_deref(), Some("Find callers"));
+        assert_eq!(
+            a.subagents[0].subagent_type.as_deref(),
+            Some("general-purpose")
+        );
+
+        assert!(apply_claude_hook(&mut a, &task_end("toolu_1"), "t2"));
+        assert_eq!(a.subagents[0].status, SubagentStatus::Completed);
+        assert_eq!(a.subagents[0].finished_at.as_deref(), Some("t2"));
+    }
+
+    #[test]
+    fn parallel_subagents_complete_by_tool_use_id() {
+        let mut a = activity();
+        apply_claude_hook(&mut a, &task_start("toolu_1", "A"), "t1");
+        apply_claude_hook(&mut a, &task_start("toolu_2", "B"), "t1");
+        apply_claude_hook(&mut a, &task_end("toolu_2"), "t2");
+
+        assert_eq!(a.subagents[0].status, SubagentStatus::Running);
+        assert_eq!(a.subagents[1].status, SubagentStatus::Completed);
+    }
+
+    #[test]
+    fn task_from_a_subagent_session_links_to_that_subagent() {
+        let mut a = activity();
+        apply_claude_hook(&mut a, &task_start("toolu_1", "Outer"), "t1");
+        apply_claude_hook(&mut a, &task_start("toolu_2", "Sibling"), "t1");
+        let mut nested = task_start("toolu_3", "Inner");
+        nested["session_id"] = json!("s1-agent");
+        apply_claude_hook(&mut a, &nested, "t2");
+        // The same subagent session keeps its binding.
+        let mut nested_again = task_start("toolu_4", "Inner 2");
+        nested_again["session_id"] = json!("s1-agent");
+        apply_claude_hook(&mut a, &nested_again, "t3");
+
+        assert_eq!(a.subagents[0].parent_id, None);
+        assert_eq!(a.subagents[1].parent_id, None);
+        assert_eq!(a.subagents[2].parent_id.as_deref(), Some("toolu_1"));
+        assert_eq!(a.subagents[3].parent_id.as_deref(), Some("toolu_1"));
+    }
+
+    #[test]
+    fn subagent_stop_completes_oldest_running_subagent() {
+        let stop = json!({ "session_id": "s1", "hook_event_name": "SubagentStop" });
+
+        let mut a = activity();
+        apply_claude_hook(&mut a, &task_start("toolu_1", "A"), "t1");
+        apply_claude_hook(&mut a, &task_start("toolu_2", "B"), "t1");
+        assert!(apply_claude_hook(&mut a, &stop, "t2"));
+        assert_eq!(a.subagents[0].status, SubagentStatus::Completed);
+        assert_eq!(a.subagents[1].status, SubagentStatus::Running);
+
+        assert!(apply_claude_hook(&mut a, &stop, "t3"));
+        assert_eq!(a.subagents[1].status, SubagentStatus::Completed);
+        assert!(!apply_claude_hook(&mut a, &stop, "t4"));
+
+        // A later PostToolUse for an already-completed subagent is a no-op.
+        assert!(!apply_claude_hook(&mut a, &task_end("toolu_2"), "t5"));
+        assert_eq!(a.subagents[1].finished_at.as_deref(), Some("t3"));
+    }
+
+    #[test]
+    fn subagent_stop_from_a_bound_session_completes_that_subagent() {
+        let mut a = activity();
+        apply_claude_hook(&mut a, &task_start("toolu_1", "A"), "t1");
+        apply_claude_hook(&mut a, &task_start("toolu_2", "B"), "t1");
+        for (id, session) in [("toolu_3", "s1-agent-a"), ("toolu_4", "s1-agent-b")] {
+            let mut nested = task_start(id, "Inner");
+            nested["session_id"] = json!(session);
+            apply_claude_hook(&mut a, &nested, "t2");
+            apply_claude_hook(&mut a, &task_end(id), "t3");
+        }
+        assert_eq!(a.subagents[3].parent_id.as_deref(), Some("toolu_2"));
+
+        let stop = json!({ "session_id": "s1-agent-b", "hook_event_name": "SubagentStop" });
+        assert!(apply_claude_hook(&mut a, &stop, "t4"));
+        assert_eq!(a.subagents[0].status, SubagentStatus::Running);
+        assert_eq!(a.subagents[1].status, SubagentStatus::Completed);
+    }
+
+    #[test]
+    fn stop_interrupts_running_subagents() {
+        let mut a = activity();
+        apply_claude_hook(&mut a, &task_start("toolu_1", "A"), "t1");
+        let stop = json!({ "session_id": "s1", "hook_event_name": "Stop" });
+        assert!(apply_claude_hook(&mut a, &stop, "t2"));
+        assert_eq!(a.subagents[0].status, SubagentStatus::Interrupted);
+        assert!(!apply_claude_hook(&mut a, &stop, "t3"));
+    }
+
+    #[test]
+    fn pre_compact_records_compaction() {
+        let mut a = activity();
+        let hook = json!({
+            "session_id": "s1",
+            "hook_event_name": "PreCompact",
+            "trigger": "manual",
+            "custom_instructions": "keep the test plan"
+        });
+        assert!(apply_claude_hook(&mut a, &hook, "t1"));
+        assert_eq!(a.compactions.len(), 1);
+        assert_eq!(a.compactions[0].trigger.as_deref(), Some("manual"));
+        assert_eq!(
+            a.compactions[0].custom_instructions.as_deref(),
+            Some("keep the test plan")
+        );
+
+        let auto = json!({ "hook_event_name": "PreCompact", "trigger": "auto", "custom_instructions": "" });
+        apply_claude_hook(&mut a, &auto, "t2");
+        assert_eq!(a.compactions[1].custom_instructions, None);
+    }
+
+    #[test]
+    fn session_start_with_new_session_resets_tree() {
+        let mut a = activity();
+        apply_claude_hook(&mut a, &task_start("toolu_1", "A"), "t1");
+        apply_claude_hook(
+            &mut a,
+            &json!({ "hook_event_name": "PreCompact", "trigger": "auto" }),
+            "t2",
+        );
+
+        // Compaction resumes the same session and keeps history.
+        let resume =
+            json!({ "session_id": "s1", "hook_event_name": "SessionStart", "source": "compact" });
+        assert!(!apply_claude_hook(&mut a, &resume, "t3"));
+        assert_eq!(a.subagents.len(), 1);
+
+        let fresh =
+            json!({ "session_id": "s2", "hook_event_name": "SessionStart", "source": "clear" });
+        assert!(apply_claude_hook(&mut a, &fresh, "t4"));
+        assert_eq!(a.pane_id, "p1");
+        assert_eq!(a.session_id.as_deref(), Some("s2"));
+        assert!(a.subagents.is_empty());
+        assert!(a.compactions.is_empty());
+    }
+
+    #[test]
+    fn session_end_records_reason_and_interrupts() {
+        let mut a = activity();
+        apply_claude_hook(&mut a, &task_start("toolu_1", "A"), "t1");
+        let end = json!({ "session_id": "s1", "hook_event_name": "SessionEnd", "reason": "prompt_input_exit" });
+        assert!(apply_claude_hook(&mut a, &end, "t2"));
+        assert_eq!(a.ended_at.as_deref(), Some("t2"));
+        assert_eq!(a.end_reason.as_deref(), Some("prompt_input_exit"));
+        assert_eq!(a.subagents[0].status, SubagentStatus::Interrupted);
+    }
+
+    #[test]
+    fn unrelated_events_are_ignored() {
+        let mut a = activity();
+        let bash =
+            json!({ "hook_event_name": "PostToolUse", "tool_name": "Bash", "tool_input": {} });
+        assert!(!apply_claude_hook(&mut a, &bash, "t1"));
+        assert!(!apply_claude_hook(
+            &mut a,
+            &json!({ "hook_event_name": "Notification" }),
+            "t1"
+        ));
+    }
+}
@@ -0,0 +1,535 @@
+/// Claude subagents (`.claude/agents/*.md`) and custom slash commands
+/// (`.claude/commands/**/*.md`) at user (`~/.claude`) and project scope.
+/// A project definition overrides a user definition with the same name.
+/// Commands are named by their file stem; a subdirectory only namespaces
+/// them in Claude's listing (`/test (project:frontend)`).
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::frontmatter::{self, Frontmatter, FrontmatterValue};
+use crate::paths;
+use crate::permission_rules;
+use crate::types::{ClaudeDefinition, ClaudeDefinitionInfo, ClaudeDefinitionKind, SkillScope};
+
+/// Aliases Claude accepts for `model`, besides full model IDs.
+const MODEL_ALIASES: &[&str] = &["inherit", "sonnet", "opus", "haiku", "opusplan"];
+
+impl ClaudeDefinitionKind {
+    fn dir_name(self) -> &'static str {
+        match self {
+            ClaudeDefinitionKind::Agent => "agents",
+            ClaudeDefinitionKind::Command => "commands",
+        }
+    }
+
+    /// Subagents list tools under `tools`, commands under `allowed-tools`.
+    fn tools_key(self) -> &'static str {
+        match self {
+            ClaudeDefinitionKind::Agent => "tools",
+            ClaudeDefinitionKind::Command => "allowed-tools",
+        }
+    }
+}
+
+pub(crate) fn definitions_dir(
+    kind: ClaudeDefinitionKind,
+    scope: SkillScope,
+    project_path: Option<&str>,
+) -> Result<PathBuf> {
+    let base = match scope {
+        SkillScope::User => paths::claude_user_dir(),
+        SkillScope::Project => {
+            let project = project_path.context("Project definitions need a project path")?;
+            Path::new(project).join(".claude")
+        }
+    };
+    Ok(base.join(kind.dir_name()))
+}
+
+/// `file_name` is the path below the definitions directory without `.md`;
+/// commands may use subdirectories (`frontend/test`).
+fn definition_path(dir: &Path, kind: ClaudeDefinitionKind, file_name: &str) -> Result<PathBuf> {
+    let components: Vec<&str> = file_name.split('/').collect();
+    let valid = components.iter().all(|c| {
+        !c.is_empty()
+            && *c != "."
+            && *c != ".."
+            && c.chars()
+                .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | '.'))
+    });
+    if !valid || (kind == ClaudeDefinitionKind::Agent && components.len() > 1) {
+        bail!("Invalid name \"{file_name}\"");
+    }
+    Ok(dir.join(format!("{file_name}.md")))
+}
+
+/// Split a command's file name into its directory and its name.
+fn split_file_name(file_name: &str) -> (Option<String>, String) {
+    match file_name.rsplit_once('/') {
+        Some((namespace, name)) => (Some(namespace.to_string()), name.to_string()),
+        None => (None, file_name.to_string()),
+    }
+}
+
+/// Where a definition is stored, below the definitions directory.
+fn definition_file_name(definition: &ClaudeDefinition) -> String {
+    let name = definition.name.trim();
+    match definition
+        .namespace
+        .as_deref()
+        .map(|n| n.trim().trim_matches('/'))
+        .filter(|n| !n.is_empty())
+    {
+        Some(namespace) => format!("{namespace}/{name}"),
+        None => name.to_string(),
+    }
+}
+
+pub fn validate_definition(
+    kind: ClaudeDefinitionKind,
+    definition: &ClaudeDefinition,
+) -> Vec<String> {
+    let mut issues = Vec::new();
+    let name = definition.name.trim();
+    if name.is_empty() {
+        issues.push("Name is required".to_string());
+    } else if kind == ClaudeDefinitionKind::Agent
+        && !name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+    {
+        issues.push(
+            "Subagent names may only contain lowercase letters, digits and hyphens".to_string(),
+        );
+    } else if name.contains('/') {
+        issues.push("Names cannot contain \"/\"; put commands in a directory instead".to_string());
+    }
+    if kind == ClaudeDefinitionKind::Agent && definition.namespace.is_some() {
+        issues.push("Subagents cannot be placed in a directory".to_string());
+    }
+    let has_description = definition
+        .description
+        .as_deref()
+        .is_some_and(|d| !d.trim().is_empty());
+    if kind == ClaudeDefinitionKind::Agent && !has_description {
+        issues
+            .push("Description is required; Claude uses it to decide when to delegate".to_string());
+    }
+    if let Some(model) = definition.model.as_deref().map(str::trim) {
+        if !model.is_empty() && !MODEL_ALIASES.contains(&model) && !model.starts_with("claude-") {
+            issues.push(format!(
+                "Unknown model \"{model}\"; use {} or a full model ID",
+                MODEL_ALIASES.join(", ")
+            ));
+        }
+    }
+    for tool in &definition.tools {
+        if let Err(e) = permission_rules::parse_rule(tool) {
+            issues.push(format!("Tool \"{tool}\": {e}"));
+        }
+    }
+    issues
+}
+
+fn definition_from(
+    kind: ClaudeDefinitionKind,
+    file_name: &str,
+    frontmatter: &Frontmatter,
+    body: String,
+) -> ClaudeDefinition {
+    let get = |key: &str| frontmatter.get_str(key).map(str::to_string);
+    let (namespace, name) = match kind {
+        // A subagent is identified by its frontmatter name, a command by its
+        // file stem.
+        ClaudeDefinitionKind::Agent => (None, get("name").unwrap_or_else(|| file_name.to_string())),
+        ClaudeDefinitionKind::Command => split_file_name(file_name),
+    };
+    ClaudeDefinition {
+        name,
+        namespace,
+        description: get("description"),
+        model: get("model"),
+        tools: frontmatter.get_list(kind.tools_key()),
+        argument_hint: argument_hint(frontmatter),
+        body,
+    }
+}
+
+/// `argument-hint: [file]` is written unquoted and so parses as a list.
+fn argument_hint(frontmatter: &Frontmatter) -> Option<String> {
+    match frontmatter.get("argument-hint")? {
+        FrontmatterValue::Scalar(hint) => Some(hint.clone()),
+        FrontmatterValue::List(items) => Some(format!("[{}]", items.join(", "))),
+    }
+}
+
+fn collect_files(dir: &Path, prefix: &str, recursive: bool, out: &mut Vec<(String, PathBuf)>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Some(file_name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        if path.is_dir() {
+            if recursive {
+                collect_files(&path, &format!("{prefix}{file_name}/"), true, out);
+            }
+        } else if let Some(stem) = file_name.strip_suffix(".md") {
+            out.push((format!("{prefix}{stem}"), path));
+        }
+    }
+}
+
+fn list_in(dir: &Path, kind: ClaudeDefinitionKind, scope: SkillScope) -> Vec<ClaudeDefinitionInfo> {
+    let mut files = Vec::new();
+    collect_files(dir, "", kind == ClaudeDefinitionKind::Command, &mut files);
+    let mut infos: Vec<ClaudeDefinitionInfo> = files
+        .into_iter()
+        .map(|(file_name, path)| {
+            let parsed = fs::read_to_string(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|content| frontmatter::parse_document(&content));
+            let (definition, issues) = match parsed {
+                Ok((frontmatter, body)) => {
+                    let definition = definition_from(kind, &file_name, &frontmatter, body);
+                    let issues = validate_definition(kind, &definition);
+                    (definition, issues)
+                }
+                Err(e) => (
+                    definition_from(kind, &file_name, &Frontmatter::default(), String::new()),
+                    vec![format!("{e:#}")],
+                ),
+            };
+            ClaudeDefinitionInfo {
+                kind,
+                scope,
+                name: definition.name,
+                namespace: definition.namespace,
+                file_name,
+                description: definition.description,
+                model: definition.model,
+                tools: definition.tools,
+                path: path.to_string_lossy().to_string(),
+                issues,
+                shadowed_by: None,
+                overrides: None,
+            }
+        })
+        .collect();
+    infos.sort_by(|a, b| a.name.cmp(&b.name));
+    infos
+}
+
+/// Mark user definitions hidden by a project definition of the same name.
+fn mark_shadowing(user: &mut [ClaudeDefinitionInfo], project: &mut [ClaudeDefinitionInfo]) {
+    for project_def in project.iter_mut() {
+        if let Some(user_def) = user.iter_mut().find(|u| u.name == project_def.name) {
+            user_def.shadowed_by = Some(project_def.path.clone());
+            project_def.overrides = Some(user_def.path.clone());
+        }
+    }
+}
+
+/// User definitions, then project definitions when a project is given.
+pub fn list_definitions(
+    kind: ClaudeDefinitionKind,
+    project_path: Option<&str>,
+) -> Result<Vec<ClaudeDefinitionInfo>> {
+    let mut user = list_in(
+        &definitions_dir(kind, SkillScope::User, None)?,
+        kind,
+        SkillScope::User,
+    );
+    let mut project = match project_path {
+        Some(_) => list_in(
+            &definitions_dir(kind, SkillScope::Project, project_path)?,
+            kind,
+            SkillScope::Project,
+        ),
+        None => Vec::new(),
+    };
+    mark_shadowing(&mut user, &mut project);
+    user.extend(project);
+    Ok(user)
+}
+
+pub fn get_definition(
+    kind: ClaudeDefinitionKind,
+    scope: SkillScope,
+    project_path: Option<&str>,
+    file_name: &str,
+) -> Result<ClaudeDefinition> {
+    let path = definition_path(
+        &definitions_dir(kind, scope, project_path)?,
+        kind,
+        file_name,
+    )?;
+    let content =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let (frontmatter, body) = frontmatter::parse_document(&content)?;
+    Ok(definition_from(kind, file_name, &frontmatter, body))
+}
+
+fn write_definition(
+    dir: &Path,
+    kind: ClaudeDefinitionKind,
+    original: Option<&str>,
+    definition: &ClaudeDefinition,
+) -> Result<()> {
+    let issues = validate_definition(kind, definition);
+    if !issues.is_empty() {
+        bail!("{}", issues.join("; "));
+    }
+    let name = definition.name.trim();
+    let file_name = definition_file_name(definition);
+    let path = definition_path(dir, kind, &file_name)?;
+    let original_path = original
+        .map(|original| definition_path(dir, kind, original))
+        .transpose()?;
+    if original_path.as_ref() != Some(&path) && path.exists() {
+        bail!("\"{file_name}\" already exists");
+    }
+
+    // Keep any frontmatter keys Workbench does not manage.
+    let source = original_path.as_deref().unwrap_or(&path);
+    let mut frontmatter = if source.is_file() {
+        frontmatter::parse_document(&fs::read_to_string(source)?)?.0
+    } else {
+        Frontmatter::default()
+    };
+    if kind == ClaudeDefinitionKind::Agent {
+        frontmatter.set_str("name", name);
+    }
+    frontmatter.set_optional("description", definition.description.as_deref());
+    frontmatter.set_list(kind.tools_key(), &definition.tools);
+    frontmatter.set_optional("model", definition.model.as_deref());
+    if kind == ClaudeDefinitionKind::Command {
+        let hint = definition.argument_hint.as_deref().map(str::trim);
+        if argument_hint(&frontmatter).as_deref() != hint.filter(|h| !h.is_empty()) {
+            frontmatter.set_optional("argument-hint", hint);
+        }
+    }
+    let content = if frontmatter.render().is_empty() {
+        definition.body.clone()
+    } else {
+        frontmatter::render_document(&frontmatter, &definition.body)
+    };
+    paths::atomic_write(&path, &content)?;
+
+    if let Some(original_path) = original_path.filter(|p| *p != path) {
+        fs::remove_file(&original_path)
+            .with_context(|| format!("Failed to remove {}", original_path.display()))?;
+    }
+    Ok(())
+}
+
+/// Create a definition (`original` = None) or rewrite one, renaming its
+/// file when the name changed.
+pub fn save_definition(
+    kind: ClaudeDefinitionKind,
+    scope: SkillScope,
+    project_path: Option<&str>,
+    original: Option<&str>,
+    definition: &ClaudeDefinition,
+) -> Result<()> {
+    write_definition(
+        &definitions_dir(kind, scope, project_path)?,
+        kind,
+        original,
+        definition,
+    )
+}
+
+/// Returns false if the definition did not exist.
+pub fn delete_definition(
+    kind: ClaudeDefinitionKind,
+    scope: SkillScope,
+    project_path: Option<&str>,
+    file_name: &str,
+) -> Result<bool> {
+    let path = definition_path(
+        &definitions_dir(kind, scope, project_path)?,
+        kind,
+        file_name,
+    )?;
+    if !path.is_file() {
+        return Ok(false);
+    }
+    fs::remove_file(&path)?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn agent(name: &str) -> ClaudeDefinition {
+        ClaudeDefinition {
+            name: name.to_string(),
+            namespace: None,
+            description: Some("Reviews diffs. Use after code changes.".to_string()),
+            model: Some("sonnet".to_string()),
+            tools: vec!["Read".to_string(), "Grep".to_string()],
+            argument_hint: None,
+            body: "You are a code reviewer.\n".to_string(),
+        }
+    }
+
+    #[test]
+    fn validates_definitions() {
+        assert!(validate_definition(ClaudeDefinitionKind::Agent, &agent("reviewer")).is_empty());
+
+        let mut bad = agent("Code Reviewer");
+        bad.description = None;
+        bad.model = Some("gpt-4".to_string());
+        let issues = validate_definition(ClaudeDefinitionKind::Agent, &bad);
+        assert_eq!(issues.len(), 3, "{issues:?}");
+
+        // Commands need no description and allow mixed-case names.
+        let command = ClaudeDefinition {
+            name: "Deploy".to_string(),
+            ..Default::default()
+        };
+        assert!(validate_definition(ClaudeDefinitionKind::Command, &command).is_empty());
+    }
+
+    #[test]
+    fn writes_agents_and_renames_them() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let kind = ClaudeDefinitionKind::Agent;
+
+        write_definition(dir, kind, None, &agent("reviewer")).unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.join("reviewer.md")).unwrap(),
+            "---\nname: reviewer\ndescription: Reviews diffs. Use after code changes.\ntools: Read, Grep\nmodel: sonnet\n---\nYou are a code reviewer.\n"
+        );
+        assert!(write_definition(dir, kind, None, &agent("reviewer")).is_err());
+
+        let mut renamed = agent("strict-reviewer");
+        renamed.model = None;
+        write_definition(dir, kind, Some("reviewer"), &renamed).unwrap();
+        assert!(!dir.join("reviewer.md").exists());
+        let listed = list_in(dir, kind, SkillScope::User);
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].name, "strict-reviewer");
+        assert_eq!(listed[0].model, None);
+        assert_eq!(listed[0].tools, ["Read", "Grep"]);
+
+        assert!(write_definition(dir, kind, None, &agent("../escape")).is_err());
+    }
+
+    #[test]
+    fn commands_use_allowed_tools_and_nested_names() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let kind = ClaudeDefinitionKind::Command;
+        fs::create_dir_all(dir.join("frontend")).unwrap();
+        fs::write(
+            dir.join("frontend/test.md"),
+            "---\nallowed-tools: Bash(npm test:*)\nargument-hint: [file]\n---\nRun tests for $ARGUMENTS\n",
+        )
+        .unwrap();
+        fs::write(dir.join("plain.md"), "Explain this code\n").unwrap();
+
+        let listed = list_in(dir, kind, SkillScope::Project);
+        let names: Vec<&str> = listed.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, ["plain", "test"]);
+        assert_eq!(listed[1].namespace.as_deref(), Some("frontend"));
+        assert_eq!(listed[1].file_name, "frontend/test");
+        assert_eq!(listed[1].tools, ["Bash(npm test:*)"]);
+
+        // Untouched fields keep their original formatting.
+        let test = get_definition_in(dir, kind, "frontend/test");
+        assert_eq!(test.argument_hint.as_deref(), Some("[file]"));
+        assert_eq!(
+            (test.name.as_str(), test.namespace.as_deref()),
+            ("test", Some("frontend"))
+        );
+        write_definition(dir, kind, Some("frontend/test"), &test).unwrap();
+        assert!(fs::read_to_string(dir.join("frontend/test.md"))
+            .unwrap()
+            .contains("argument-hint: [file]\n"));
+
+        let mut plain = get_definition_in(dir, kind, "plain");
+        assert_eq!(plain.body, "Explain this code\n");
+        plain.description = Some("Explain code".to_string());
+        write_definition(dir, kind, Some("plain"), &plain).unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.join("plain.md")).unwrap(),
+            "---\ndescription: Explain code\n---\nExplain this code\n"
+        );
+
+        // Without frontmatter fields the file stays plain Markdown.
+        let bare = ClaudeDefinition {
+            name: "bare".to_string(),
+            body: "Do it\n".to_string(),
+            ..Default::default()
+        };
+        write_definition(dir, kind, None, &bare).unwrap();
+        assert_eq!(fs::read_to_string(dir.join("bare.md")).unwrap(), "Do it\n");
+
+        // New commands go into their directory; the name itself has no slash.
+        let nested = ClaudeDefinition {
+            namespace: Some("git".to_string()),
+            name: "commit".to_string(),
+            ..bare.clone()
+        };
+        write_definition(dir, kind, None, &nested).unwrap();
+        assert!(dir.join("git/commit.md").is_file());
+        let slashed = ClaudeDefinition {
+            name: "git/push".to_string(),
+            ..bare
+        };
+        assert!(write_definition(dir, kind, None, &slashed).is_err());
+    }
+
+    fn get_definition_in(dir: &Path, kind: ClaudeDefinitionKind, name: &str) -> ClaudeDefinition {
+        let content = fs::read_to_string(definition_path(dir, kind, name).unwrap()).unwrap();
+        let (frontmatter, body) = frontmatter::parse_document(&content).unwrap();
+        definition_from(kind, name, &frontmatter, body)
+    }
+
+    #[test]
+    fn project_definitions_shadow_user_ones() {
+        let tmp = tempfile::tempdir().unwrap();
+        let user_dir = tmp.path().join("user");
+        let project_dir = tmp.path().join("project");
+        for dir in [&user_dir, &project_dir] {
+            fs::create_dir_all(dir).unwrap();
+            fs::write(dir.join("review.md"), "Review\n").unwrap();
+        }
+        fs::write(user_dir.join("deploy.md"), "Deploy\n").unwrap();
+        // Claude invokes both as /lint, whatever directory they are in.
+        fs::create_dir_all(project_dir.join("ci")).unwrap();
+        fs::write(user_dir.join("lint.md"), "Lint\n").unwrap();
+        fs::write(project_dir.join("ci/lint.md"), "Lint in CI\n").unwrap();
+
+        let kind = ClaudeDefinitionKind::Command;
+        let mut user = list_in(&user_dir, kind, SkillScope::User);
+        let mut project = list_in(&project_dir, kind, SkillScope::Project);
+        mark_shadowing(&mut user, &mut project);
+
+        let review = user.iter().find(|d| d.name == "review").unwrap();
+        let project_review = project.iter().find(|d| d.name == "review").unwrap();
+        assert_eq!(
+            review.shadowed_by.as_deref(),
+            Some(project_review.path.as_str())
+        );
+        assert_eq!(
+            project_review.overrides.as_deref(),
+            Some(review.path.as_str())
+        );
+        let lint = user.iter().find(|d| d.name == "lint").unwrap();
+        assert!(lint.shadowed_by.as_deref().unwrap().ends_with("ci/lint.md"));
+        assert!(user
+            .iter()
+            .find(|d| d.name == "deploy")
+            .unwrap()
+            .shadowed_by
+            .is_none());
+    }
+}
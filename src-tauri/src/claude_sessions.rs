@@ -1,9 +1,9 @@
 /// Claude CLI session discovery — reads ~/.claude/projects/<encoded-path>/*.jsonl
-use anyhow::Result;
 use std::fs;
 use std::io::BufRead;
 
-use crate::paths;
+use crate::claude_transcript;
+use crate::session_tail;
 use crate::session_utils;
 use crate::types::DiscoveredClaudeSession;
 
@@ -61,55 +61,25 @@ pub(crate) fn parse_session_jsonl(
         label = session_utils::fallback_label(&session_id);
     }
 
+    let tail = session_tail::read_session_tail(path, claude_transcript::parse_transcript_entry);
     Some(DiscoveredClaudeSession {
         session_id,
         label,
         timestamp,
-        last_message_role: None,
+        last_message_role: tail.last_message_role,
+        last_assistant_snippet: tail.last_assistant_snippet,
+        awaiting_input: tail.awaiting_input,
+        pending_tool_since: tail.pending_tool_since,
+        worktree_path: None,
+        branch: None,
     })
 }
 
-/// Discover Claude CLI sessions by reading ~/.claude/projects/<encoded-path>/*.jsonl
-pub fn discover_claude_sessions(project_path: &str) -> Result<Vec<DiscoveredClaudeSession>> {
-    let encoded = paths::encode_project_path(project_path);
-    let sessions_dir = paths::claude_user_dir().join("projects").join(&encoded);
-
-    if !sessions_dir.is_dir() {
-        return Ok(Vec::new());
-    }
-
-    let mut sessions = Vec::new();
-
-    for entry in fs::read_dir(&sessions_dir)? {
-        let entry = entry?;
-        let path = entry.path();
-        if path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
-            continue;
-        }
-
-        let session_id = path
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("")
-            .to_string();
-        if session_id.is_empty() {
-            continue;
-        }
-
-        if let Some(session) = parse_session_jsonl(&path, session_id) {
-            sessions.push(session);
-        }
-    }
-
-    // Sort by timestamp descending (newest first)
-    sessions.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
-
-    Ok(sessions)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::session_tail::TAIL_INITIAL_BYTES;
+    use crate::types::AwaitingInputReason;
     use std::io::Write;
     use tempfile::tempdir;
 
@@ -118,7 +88,9 @@ mod tests {
     #[test]
     fn parse_valid_session_with_user_message() {
         let dir = tempdir().unwrap();
-        let path = dir.path().join("abc12345-1234-1234-1234-123456789abc.jsonl");
+        let path = dir
+            .path()
+            .join("abc12345-1234-1234-1234-123456789abc.jsonl");
         let mut file = fs::File::create(&path).unwrap();
         writeln!(
             file,
@@ -126,8 +98,7 @@ mod tests {
         )
         .unwrap();
 
-        let result =
-            parse_session_jsonl(&path, "abc12345-1234-1234-1234-123456789abc".to_string());
+        let result = parse_session_jsonl(&path, "abc12345-1234-1234-1234-123456789abc".to_string());
         assert!(result.is_some());
         let session = result.unwrap();
         assert_eq!(session.session_id, "abc12345-1234-1234-1234-123456789abc");
@@ -272,4 +243,105 @@ mod tests {
         assert_eq!(session.label, "Valid message after garbage");
         assert_eq!(session.timestamp, "2024-01-15T10:00:00Z");
     }
+
+    // session tail tests
+
+    fn write_entries(entries: &[serde_json::Value]) -> (tempfile::TempDir, std::path::PathBuf) {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("session.jsonl");
+        let mut file = fs::File::create(&path).unwrap();
+        writeln!(
+            file,
+            r#"{{"type":"user","timestamp":"2024-01-15T10:00:00Z","message":{{"content":"Refactor the config loader"}}}}"#
+        )
+        .unwrap();
+        for entry in entries {
+            writeln!(file, "{entry}").unwrap();
+        }
+        (dir, path)
+    }
+
+    fn assistant(content: serde_json::Value) -> serde_json::Value {
+        serde_json::json!({ "type": "assistant", "message": { "role": "assistant", "content": content } })
+    }
+
+    #[test]
+    fn tail_reports_last_role_and_snippet() {
+        let (_dir, path) = write_entries(&[
+            assistant(
+                serde_json::json!([{ "type": "text", "text": "Done.\n\nThe loader now   caches." }]),
+            ),
+            serde_json::json!({ "type": "system", "subtype": "turn_duration" }),
+        ]);
+        let session = parse_session_jsonl(&path, "s1".to_string()).unwrap();
+        assert_eq!(session.last_message_role.as_deref(), Some("assistant"));
+        assert_eq!(
+            session.last_assistant_snippet.as_deref(),
+            Some("Done. The loader now caches.")
+        );
+        assert_eq!(session.awaiting_input, None);
+    }
+
+    #[test]
+    fn tail_flags_trailing_question() {
+        let (_dir, path) = write_entries(&[assistant(
+            serde_json::json!([{ "type": "text", "text": "Should I also update the tests?" }]),
+        )]);
+        let session = parse_session_jsonl(&path, "s1".to_string()).unwrap();
+        assert_eq!(session.awaiting_input, Some(AwaitingInputReason::Question));
+    }
+
+    #[test]
+    fn tail_records_pending_tool_use_without_flagging_it() {
+        let (_dir, path) = write_entries(&[
+            assistant(serde_json::json!([{ "type": "text", "text": "I'll run the migration." }])),
+            serde_json::json!({ "type": "assistant", "timestamp": "2024-01-15T10:02:00Z",
+                "message": { "role": "assistant", "content": [{ "type": "tool_use", "id": "t1",
+                    "name": "Bash", "input": { "command": "make migrate" } }] } }),
+        ]);
+        let session = parse_session_jsonl(&path, "s1".to_string()).unwrap();
+        // Whether this is a permission prompt is decided at lookup time.
+        assert_eq!(session.awaiting_input, None);
+        assert_eq!(
+            session.pending_tool_since.as_deref(),
+            Some("2024-01-15T10:02:00Z")
+        );
+        assert_eq!(
+            session.last_assistant_snippet.as_deref(),
+            Some("I'll run the migration.")
+        );
+
+        let (_dir, path) = write_entries(&[assistant(
+            serde_json::json!([{ "type": "tool_use", "id": "t1", "name": "AskUserQuestion", "input": {} }]),
+        )]);
+        let session = parse_session_jsonl(&path, "s1".to_string()).unwrap();
+        assert_eq!(session.awaiting_input, Some(AwaitingInputReason::Question));
+    }
+
+    #[test]
+    fn tail_user_turn_is_not_awaiting_input() {
+        let (_dir, path) = write_entries(&[
+            assistant(
+                serde_json::json!([{ "type": "tool_use", "id": "t1", "name": "Bash", "input": {} }]),
+            ),
+            serde_json::json!({ "type": "user", "message": { "role": "user",
+                "content": [{ "type": "tool_result", "tool_use_id": "t1", "content": "ok" }] } }),
+            serde_json::json!({ "type": "user", "isMeta": true, "message": { "content": "<caveat>" } }),
+        ]);
+        let session = parse_session_jsonl(&path, "s1".to_string()).unwrap();
+        assert_eq!(session.last_message_role.as_deref(), Some("user"));
+        assert_eq!(session.awaiting_input, None);
+    }
+
+    #[test]
+    fn tail_window_grows_past_large_trailing_entries() {
+        let padding = "x".repeat(TAIL_INITIAL_BYTES as usize);
+        let (_dir, path) = write_entries(&[
+            assistant(serde_json::json!([{ "type": "text", "text": "Want me to continue?" }])),
+            serde_json::json!({ "type": "file-history-snapshot", "snapshot": padding }),
+        ]);
+        let session = parse_session_jsonl(&path, "s1".to_string()).unwrap();
+        assert_eq!(session.last_message_role.as_deref(), Some("assistant"));
+        assert_eq!(session.awaiting_input, Some(AwaitingInputReason::Question));
+    }
 }
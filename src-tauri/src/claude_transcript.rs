@@ -0,0 +1,375 @@
+/// Full Claude session transcript parsing — ~/.claude/projects/<encoded-path>/<session>.jsonl
+use anyhow::{bail, Context, Result};
+use serde_json::Value;
+use std::fs;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+
+use crate::paths;
+use crate::types::{TokenUsage, TranscriptBlock, TranscriptMessage, TranscriptPage};
+
+const DEFAULT_PAGE_SIZE: usize = 200;
+
+/// Resolve the JSONL file for a Claude session in a project.
+pub(crate) fn claude_session_path(project_path: &str, session_id: &str) -> Result<PathBuf> {
+    if session_id.is_empty() || session_id.contains(['/', '\\']) || session_id.starts_with('.') {
+        bail!("Invalid session ID: {session_id}");
+    }
+    Ok(paths::claude_user_dir()
+        .join("projects")
+        .join(paths::encode_project_path(project_path))
+        .join(format!("{session_id}.jsonl")))
+}
+
+fn str_field(value: &Value, key: &str) -> Option<String> {
+    value
+        .get(key)
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+}
+
+fn u64_field(value: &Value, key: &str) -> u64 {
+    value.get(key).and_then(|v| v.as_u64()).unwrap_or(0)
+}
+
+pub(crate) fn parse_usage(usage: &Value) -> Option<TokenUsage> {
+    if !usage.is_object() {
+        return None;
+    }
+    Some(TokenUsage {
+        input_tokens: u64_field(usage, "input_tokens"),
+        output_tokens: u64_field(usage, "output_tokens"),
+        cache_creation_input_tokens: u64_field(usage, "cache_creation_input_tokens"),
+        cache_read_input_tokens: u64_field(usage, "cache_read_input_tokens"),
+    })
+}
+
+/// Flatten tool_result content (string or array of text/image blocks) to text.
+fn tool_result_text(content: Option<&Value>) -> String {
+    match content {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Array(items)) => items
+            .iter()
+            .map(|item| match item.get("type").and_then(|v| v.as_str()) {
+                Some("image") => "[image]".to_string(),
+                _ => str_field(item, "text").unwrap_or_default(),
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+fn parse_block(block: &Value) -> Option<TranscriptBlock> {
+    match block.get("type").and_then(|v| v.as_str())? {
+        "text" => Some(TranscriptBlock::Text {
+            text: str_field(block, "text")?,
+        }),
+        "thinking" => Some(TranscriptBlock::Thinking {
+            text: str_field(block, "thinking")?,
+        }),
+        "redacted_thinking" => Some(TranscriptBlock::Thinking {
+            text: "[redacted]".to_string(),
+        }),
+        "tool_use" => Some(TranscriptBlock::ToolUse {
+            id: str_field(block, "id").unwrap_or_default(),
+            name: str_field(block, "name").unwrap_or_default(),
+            input: block.get("input").cloned().unwrap_or(Value::Null),
+        }),
+        "tool_result" => Some(TranscriptBlock::ToolResult {
+            tool_use_id: str_field(block, "tool_use_id").unwrap_or_default(),
+            content: tool_result_text(block.get("content")),
+            is_error: block
+                .get("is_error")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+        }),
+        "image" => Some(TranscriptBlock::Image {
+            media_type: block
+                .get("source")
+                .and_then(|s| s.get("media_type"))
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+        }),
+        _ => None,
+    }
+}
+
+fn parse_content(content: Option<&Value>) -> Vec<TranscriptBlock> {
+    match content {
+        Some(Value::String(text)) => vec![TranscriptBlock::Text { text: text.clone() }],
+        Some(Value::Array(blocks)) => blocks.iter().filter_map(parse_block).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Convert one JSONL entry into a transcript message. Bookkeeping entries
+/// (file-history snapshots, queue operations, ...) return `None`.
+pub(crate) fn parse_transcript_entry(entry: &Value) -> Option<TranscriptMessage> {
+    let entry_type = entry.get("type").and_then(|v| v.as_str())?;
+    let mut message = TranscriptMessage {
+        uuid: str_field(entry, "uuid"),
+        parent_uuid: str_field(entry, "parentUuid"),
+        timestamp: str_field(entry, "timestamp"),
+        role: entry_type.to_string(),
+        is_meta: entry
+            .get("isMeta")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        is_sidechain: entry
+            .get("isSidechain")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        subtype: None,
+        model: None,
+        usage: None,
+        cwd: str_field(entry, "cwd"),
+        blocks: Vec::new(),
+    };
+
+    match entry_type {
+        "user" | "assistant" => {
+            let inner = entry.get("message")?;
+            message.blocks = parse_content(inner.get("content"));
+            if entry_type == "assistant" {
+                message.model = str_field(inner, "model");
+                message.usage = inner.get("usage").and_then(parse_usage);
+            }
+        }
+        "system" => {
+            message.subtype = str_field(entry, "subtype");
+            if let Some(text) = str_field(entry, "content") {
+                message.blocks.push(TranscriptBlock::Text { text });
+            }
+        }
+        "summary" => {
+            message.role = "system".to_string();
+            message.subtype = Some("summary".to_string());
+            message.blocks.push(TranscriptBlock::Text {
+                text: str_field(entry, "summary")?,
+            });
+        }
+        _ => return None,
+    }
+
+    Some(message)
+}
+
+/// Parse every message in a transcript file, skipping malformed or non-UTF-8 lines.
+pub(crate) fn read_transcript(path: &Path) -> Result<Vec<TranscriptMessage>> {
+    let file = fs::File::open(path)
+        .with_context(|| format!("Failed to open transcript {}", path.display()))?;
+    let reader = std::io::BufReader::new(file);
+    let mut messages = Vec::new();
+    for line in reader.split(b'\n') {
+        let line = line?;
+        let Ok(entry) = serde_json::from_slice::<Value>(&line) else {
+            continue;
+        };
+        if let Some(message) = parse_transcript_entry(&entry) {
+            messages.push(message);
+        }
+    }
+    Ok(messages)
+}
+
+fn paginate(
+    session_id: String,
+    messages: Vec<TranscriptMessage>,
+    offset: usize,
+    limit: usize,
+) -> TranscriptPage {
+    let total = messages.len();
+    let page: Vec<_> = messages.into_iter().skip(offset).take(limit).collect();
+    TranscriptPage {
+        session_id,
+        has_more: offset + page.len() < total,
+        messages: page,
+        offset,
+        total,
+    }
+}
+
+/// Load a page of a Claude session transcript, oldest message first.
+pub fn load_claude_transcript(
+    project_path: &str,
+    session_id: &str,
+    offset: Option<usize>,
+    limit: Option<usize>,
+) -> Result<TranscriptPage> {
+    let path = claude_session_path(project_path, session_id)?;
+    let messages = read_transcript(&path)?;
+    Ok(paginate(
+        session_id.to_string(),
+        messages,
+        offset.unwrap_or(0),
+        limit.unwrap_or(DEFAULT_PAGE_SIZE),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::io::Write;
+    use tempfile::tempdir;
+
+    #[test]
+    fn parses_user_string_content() {
+        let entry = json!({
+            "type": "user",
+            "uuid": "u1",
+            "parentUuid": null,
+            "timestamp": "2025-01-01T00:00:00Z",
+            "message": { "role": "user", "content": "Fix the login bug" }
+        });
+        let msg = parse_transcript_entry(&entry).unwrap();
+        assert_eq!(msg.role, "user");
+        assert_eq!(msg.uuid.as_deref(), Some("u1"));
+        assert!(msg.parent_uuid.is_none());
+        assert_eq!(
+            msg.blocks,
+            vec![TranscriptBlock::Text {
+                text: "Fix the login bug".into()
+            }]
+        );
+    }
+
+    #[test]
+    fn parses_assistant_blocks_model_and_usage() {
+        let entry = json!({
+            "type": "assistant",
+            "uuid": "a1",
+            "parentUuid": "u1",
+            "message": {
+                "model": "claude-sonnet-4-5-20250929",
+                "content": [
+                    { "type": "thinking", "thinking": "Look at auth.rs first", "signature": "x" },
+                    { "type": "text", "text": "Checking the handler." },
+                    { "type": "tool_use", "id": "toolu_1", "name": "Read", "input": { "file_path": "/repo/auth.rs" } }
+                ],
+                "usage": {
+                    "input_tokens": 12,
+                    "output_tokens": 80,
+                    "cache_creation_input_tokens": 1000,
+                    "cache_read_input_tokens": 5000
+                }
+            }
+        });
+        let msg = parse_transcript_entry(&entry).unwrap();
+        assert_eq!(msg.model.as_deref(), Some("claude-sonnet-4-5-20250929"));
+        assert_eq!(
+            msg.usage,
+            Some(TokenUsage {
+                input_tokens: 12,
+                output_tokens: 80,
+                cache_creation_input_tokens: 1000,
+                cache_read_input_tokens: 5000,
+            })
+        );
+        assert_eq!(msg.blocks.len(), 3);
+        assert!(
+            matches!(&msg.blocks[0], TranscriptBlock::Thinking { text } if text == "Look at auth.rs first")
+        );
+        assert!(matches!(
+            &msg.blocks[2],
+            TranscriptBlock::ToolUse { id, name, input } if id == "toolu_1" && name == "Read" && input["file_path"] == "/repo/auth.rs"
+        ));
+    }
+
+    #[test]
+    fn parses_tool_results_with_array_content() {
+        let entry = json!({
+            "type": "user",
+            "message": { "role": "user", "content": [
+                { "type": "tool_result", "tool_use_id": "toolu_1", "is_error": true, "content": [
+                    { "type": "text", "text": "No such file" },
+                    { "type": "image", "source": { "media_type": "image/png" } }
+                ] }
+            ] }
+        });
+        let msg = parse_transcript_entry(&entry).unwrap();
+        assert_eq!(
+            msg.blocks,
+            vec![TranscriptBlock::ToolResult {
+                tool_use_id: "toolu_1".into(),
+                content: "No such file\n[image]".into(),
+                is_error: true,
+            }]
+        );
+    }
+
+    #[test]
+    fn parses_meta_system_and_summary_entries() {
+        let meta = json!({
+            "type": "user",
+            "isMeta": true,
+            "message": { "role": "user", "content": "<local-command-stdout></local-command-stdout>" }
+        });
+        assert!(parse_transcript_entry(&meta).unwrap().is_meta);
+
+        let system = json!({
+            "type": "system",
+            "subtype": "compact_boundary",
+            "content": "Conversation compacted"
+        });
+        let msg = parse_transcript_entry(&system).unwrap();
+        assert_eq!(msg.role, "system");
+        assert_eq!(msg.subtype.as_deref(), Some("compact_boundary"));
+
+        let summary = json!({ "type": "summary", "summary": "Login bug fix", "leafUuid": "a1" });
+        let msg = parse_transcript_entry(&summary).unwrap();
+        assert_eq!(msg.role, "system");
+        assert_eq!(msg.subtype.as_deref(), Some("summary"));
+    }
+
+    #[test]
+    fn skips_bookkeeping_entries() {
+        let entry = json!({ "type": "file-history-snapshot", "messageId": "m1", "snapshot": {} });
+        assert!(parse_transcript_entry(&entry).is_none());
+    }
+
+    #[test]
+    fn read_transcript_skips_malformed_lines_and_paginates() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("s.jsonl");
+        let mut file = fs::File::create(&path).unwrap();
+        for i in 0..5 {
+            writeln!(
+                file,
+                r#"{{"type":"user","uuid":"u{i}","message":{{"role":"user","content":"message {i}"}}}}"#
+            )
+            .unwrap();
+        }
+        writeln!(file, "not json").unwrap();
+        file.write_all(b"{\"type\":\"user\",\"message\":\"\xff\xfe\"}\n")
+            .unwrap();
+        writeln!(
+            file,
+            r#"{{"type":"user","uuid":"u5","message":{{"role":"user","content":"after"}}}}"#
+        )
+        .unwrap();
+
+        let messages = read_transcript(&path).unwrap();
+        assert_eq!(messages.len(), 6);
+        assert_eq!(messages[5].uuid.as_deref(), Some("u5"));
+
+        let page = paginate("s".into(), messages.clone(), 1, 2);
+        assert_eq!(page.total, 6);
+        assert!(page.has_more);
+        assert_eq!(page.messages.len(), 2);
+        assert_eq!(page.messages[0].uuid.as_deref(), Some("u1"));
+
+        let last = paginate("s".into(), messages, 5, 10);
+        assert_eq!(last.messages.len(), 1);
+        assert!(!last.has_more);
+    }
+
+    #[test]
+    fn claude_session_path_rejects_traversal() {
+        assert!(claude_session_path("/repo", "../../etc/passwd").is_err());
+        assert!(claude_session_path("/repo", "").is_err());
+        let path = claude_session_path("/repo", "abc").unwrap();
+        assert!(path.ends_with("abc.jsonl"));
+    }
+}
@@ -1,16 +1,34 @@
 /// Codex CLI configuration management.
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
 
 use crate::paths::{self, remove_path_if_exists};
+use crate::types::IntegrationRemovalReport;
 
 #[cfg(not(windows))]
 const WORKBENCH_CODEX_NOTIFY_SCRIPT_NAME: &str = "workbench-codex-notify-bridge.sh";
 #[cfg(windows)]
 const WORKBENCH_CODEX_NOTIFY_SCRIPT_NAME: &str = "workbench-codex-notify-bridge.ps1";
 
+const DOC_FALLBACK_LINE: &str = "project_doc_fallback_filenames = [\"CLAUDE.md\"]";
+
+/// What `ensure_codex_config` changed in config.toml, so removing the
+/// integration can undo exactly that.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct CodexIntegrationState {
+    /// The user's own `notify` line that the bridge replaced.
+    replaced_notify: Option<String>,
+    added_doc_fallback: bool,
+}
+
+fn integration_state_path() -> PathBuf {
+    paths::workbench_config_dir().join("codex-integration.json")
+}
+
 fn workbench_codex_notify_script_path() -> PathBuf {
     paths::codex_config_dir().join(WORKBENCH_CODEX_NOTIFY_SCRIPT_NAME)
 }
@@ -60,7 +78,7 @@ fn link_or_copy_skill(source: &Path, dest: &Path) -> Result<()> {
     #[cfg(unix)]
     {
         std::os::unix::fs::symlink(source, dest)?;
-        return Ok(());
+        Ok(())
     }
     #[cfg(windows)]
     {
@@ -187,6 +205,21 @@ fn sync_claude_skills_into_agents() -> Result<()> {
     )
 }
 
+/// Re-link Claude user skills into `~/.agents/skills` after they change, but
+/// only while the Codex integration is installed and has created that directory.
+pub(crate) fn resync_claude_skills() -> Result<()> {
+    if !paths::agents_dir().join("skills").is_dir()
+        || !workbench_codex_notify_script_path().exists()
+    {
+        return Ok(());
+    }
+    sync_claude_skills_into_agents()
+}
+
+fn is_top_level_notify(line: &str) -> bool {
+    line.trim_start() == line && line.starts_with("notify =")
+}
+
 pub(crate) fn ensure_codex_notify_config(content: &str, script_path: &str) -> (String, bool) {
     let escaped_path = toml_escape_str(script_path);
     #[cfg(not(windows))]
@@ -205,8 +238,7 @@ pub(crate) fn ensure_codex_notify_config(content: &str, script_path: &str) -> (S
     let had_trailing_newline = content.ends_with('\n');
     let mut lines = Vec::new();
     for line in content.lines() {
-        let is_top_level_notify = line.trim_start() == line && line.starts_with("notify =");
-        if !replaced && is_top_level_notify {
+        if !replaced && is_top_level_notify(line) {
             lines.push(notify_line.clone());
             replaced = true;
         } else {
@@ -271,16 +303,21 @@ pub fn ensure_codex_config() -> Result<()> {
         String::new()
     };
 
+    let state_path = integration_state_path();
+    let recorded = paths::load_json::<Option<CodexIntegrationState>>(&state_path, None);
+    // A line that is already there may be the user's own; only record what we add
+    let mut state = recorded.clone().unwrap_or_default();
+
     let mut updated_content = content.clone();
     let mut changed = false;
 
     if !updated_content.contains("project_doc_fallback_filenames") {
-        let addition = if updated_content.is_empty() || updated_content.ends_with('\n') {
-            "project_doc_fallback_filenames = [\"CLAUDE.md\"]\n"
-        } else {
-            "\nproject_doc_fallback_filenames = [\"CLAUDE.md\"]\n"
-        };
-        updated_content.push_str(addition);
+        if !updated_content.is_empty() && !updated_content.ends_with('\n') {
+            updated_content.push('\n');
+        }
+        updated_content.push_str(DOC_FALLBACK_LINE);
+        updated_content.push('\n');
+        state.added_doc_fallback = true;
         changed = true;
     }
 
@@ -289,6 +326,13 @@ pub fn ensure_codex_config() -> Result<()> {
     let (with_notify, notify_changed) =
         ensure_codex_notify_config(&updated_content, &script_path_str);
     if notify_changed {
+        let previous = updated_content
+            .lines()
+            .find(|line| is_top_level_notify(line));
+        // A stale bridge line (e.g. from a moved home directory) isn't the user's
+        if !previous.is_some_and(|line| line.contains(WORKBENCH_CODEX_NOTIFY_SCRIPT_NAME)) {
+            state.replaced_notify = previous.map(str::to_string);
+        }
         updated_content = with_notify;
         changed = true;
     }
@@ -296,6 +340,9 @@ pub fn ensure_codex_config() -> Result<()> {
     if changed {
         paths::atomic_write(&config_path, &updated_content)?;
     }
+    if recorded.as_ref() != Some(&state) {
+        paths::save_json(&state_path, &state)?;
+    }
 
     // Ensure Codex can discover user skills from Claude skill directories.
     sync_claude_skills_into_agents()?;
@@ -303,6 +350,121 @@ pub fn ensure_codex_config() -> Result<()> {
     Ok(())
 }
 
+/// Remove the notify bridge and the doc fallback that Workbench added,
+/// putting back the `notify` line the bridge replaced.
+fn strip_codex_config(content: &str, state: &CodexIntegrationState) -> (String, Vec<String>) {
+    let mut changes = Vec::new();
+    let mut lines = Vec::new();
+    for line in content.lines() {
+        if is_top_level_notify(line) && line.contains(WORKBENCH_CODEX_NOTIFY_SCRIPT_NAME) {
+            match &state.replaced_notify {
+                Some(previous) => {
+                    lines.push(previous.as_str());
+                    changes.push(format!("Restored previous notify command: {previous}"));
+                }
+                None => changes.push("Removed the Workbench notify command".to_string()),
+            }
+        } else if state.added_doc_fallback && line.trim() == DOC_FALLBACK_LINE {
+            changes.push("Removed project_doc_fallback_filenames".to_string());
+        } else {
+            lines.push(line);
+        }
+    }
+    if changes.is_empty() {
+        return (content.to_string(), changes);
+    }
+
+    let mut updated = lines.join("\n");
+    if !updated.is_empty() && content.ends_with('\n') {
+        updated.push('\n');
+    }
+    (updated, changes)
+}
+
+/// Remove the links in `agents_dir/skills` that still point into
+/// `source_skills`, and the directory itself once nothing else is left in it.
+fn unlink_synced_skills(source_skills: &Path, agents_dir: &Path) -> Result<Vec<String>> {
+    let destination = agents_dir.join("skills");
+    if !destination.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut unlinked = Vec::new();
+    for entry in fs::read_dir(&destination)? {
+        let path = entry?.path();
+        let is_link = fs::symlink_metadata(&path).is_ok_and(|m| m.file_type().is_symlink());
+        if !is_link || !fs::read_link(&path).is_ok_and(|target| target.starts_with(source_skills)) {
+            continue;
+        }
+        remove_path_if_exists(&path)?;
+        unlinked.push(
+            path.file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned(),
+        );
+    }
+    unlinked.sort();
+
+    let mut changes = Vec::new();
+    if !unlinked.is_empty() {
+        changes.push(format!(
+            "Unlinked skills from {}: {}",
+            destination.display(),
+            unlinked.join(", ")
+        ));
+    }
+    if fs::read_dir(&destination)?.next().is_none() {
+        fs::remove_dir(&destination)?;
+        changes.push(format!("Removed empty {}", destination.display()));
+    }
+    Ok(changes)
+}
+
+/// Undo `ensure_codex_config`: strip what it added to config.toml, restore
+/// the replaced `notify` line, delete the bridge script and unlink synced skills.
+pub fn remove_codex_config() -> Result<IntegrationRemovalReport> {
+    let config_path = paths::codex_config_dir().join("config.toml");
+    let state_path = integration_state_path();
+    let recorded = paths::load_json::<Option<CodexIntegrationState>>(&state_path, None);
+    let mut changes = Vec::new();
+
+    if config_path.exists() {
+        let content = fs::read_to_string(&config_path)?;
+        let state = recorded.clone().unwrap_or_default();
+        let (updated, config_changes) = strip_codex_config(&content, &state);
+        if !config_changes.is_empty() {
+            paths::atomic_write(&config_path, &updated)?;
+            changes.extend(
+                config_changes
+                    .into_iter()
+                    .map(|change| format!("{change} in {}", config_path.display())),
+            );
+        }
+        // Without a record the line may have been the user's before Workbench ran
+        if recorded.is_none() && content.lines().any(|line| line.trim() == DOC_FALLBACK_LINE) {
+            changes.push(format!(
+                "Left project_doc_fallback_filenames in {}: no record shows whether Workbench added it",
+                config_path.display()
+            ));
+        }
+    }
+
+    let script_path = workbench_codex_notify_script_path();
+    if script_path.is_file() {
+        fs::remove_file(&script_path)?;
+        changes.push(format!("Deleted {}", script_path.display()));
+    }
+
+    changes.extend(unlink_synced_skills(
+        &paths::claude_user_dir().join("skills"),
+        &paths::agents_dir(),
+    )?);
+    remove_path_if_exists(&state_path)?;
+
+    Ok(IntegrationRemovalReport { changes })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -380,6 +542,57 @@ mod tests {
         assert!(result.contains("script.ps1"));
     }
 
+    // -----------------------------------------------------------------------
+    // strip_codex_config
+    // -----------------------------------------------------------------------
+
+    fn bridge_path() -> String {
+        format!("/home/me/.codex/{WORKBENCH_CODEX_NOTIFY_SCRIPT_NAME}")
+    }
+
+    #[test]
+    fn strip_restores_replaced_notify_line() {
+        let original = "model = \"o3\"\nnotify = [\"terminal-notifier\"]\n";
+        let (installed, _) = ensure_codex_notify_config(original, &bridge_path());
+        let state = CodexIntegrationState {
+            replaced_notify: Some("notify = [\"terminal-notifier\"]".to_string()),
+            added_doc_fallback: false,
+        };
+
+        let (result, changes) = strip_codex_config(&installed, &state);
+        assert_eq!(result, original);
+        assert_eq!(changes.len(), 1);
+        assert!(changes[0].starts_with("Restored previous notify command"));
+    }
+
+    #[test]
+    fn strip_removes_only_what_was_added() {
+        let content = format!(
+            "model = \"o3\"\n{DOC_FALLBACK_LINE}\nnotify = [\"bash\", \"{}\"]\n",
+            bridge_path()
+        );
+        let added = CodexIntegrationState {
+            replaced_notify: None,
+            added_doc_fallback: true,
+        };
+        let (result, changes) = strip_codex_config(&content, &added);
+        assert_eq!(result, "model = \"o3\"\n");
+        assert_eq!(changes.len(), 2);
+
+        // The user already had the fallback before Workbench touched the file
+        let kept = CodexIntegrationState::default();
+        let (result, _) = strip_codex_config(&content, &kept);
+        assert_eq!(result, format!("model = \"o3\"\n{DOC_FALLBACK_LINE}\n"));
+    }
+
+    #[test]
+    fn strip_ignores_unrelated_notify() {
+        let content = "notify = [\"terminal-notifier\"]\n[profiles.x]\nmodel = \"o3\"\n";
+        let (result, changes) = strip_codex_config(content, &CodexIntegrationState::default());
+        assert_eq!(result, content);
+        assert!(changes.is_empty());
+    }
+
     // -----------------------------------------------------------------------
     // sync_skills
     // -----------------------------------------------------------------------
@@ -478,7 +691,7 @@ mod tests {
             sync_skills(&source, &agents).unwrap();
 
             assert!(dest.join("skill-a").exists());
-            assert!(!fs::symlink_metadata(dest.join("skill-b")).is_ok());
+            assert!(fs::symlink_metadata(dest.join("skill-b")).is_err());
         }
 
         #[test]
@@ -539,5 +752,39 @@ mod tests {
             assert!(sync_skills(&source, &agents).is_ok());
             assert!(!agents.join("skills").exists());
         }
+
+        #[test]
+        fn unlink_removes_only_synced_links() {
+            let tmp = tempdir().unwrap();
+            let source = tmp.path().join("skills");
+            let agents = tmp.path().join("agents");
+            let dest = agents.join("skills");
+            let elsewhere = tmp.path().join("elsewhere");
+            fs::create_dir_all(&source).unwrap();
+            create_skill(&source, "skill-a");
+            create_skill(&elsewhere, "linked-elsewhere");
+            fs::create_dir_all(dest.join("user-skill")).unwrap();
+            sync_skills(&source, &agents).unwrap();
+            symlink(
+                elsewhere.join("linked-elsewhere"),
+                dest.join("linked-elsewhere"),
+            )
+            .unwrap();
+
+            let changes = unlink_synced_skills(&source, &agents).unwrap();
+            assert_eq!(changes.len(), 1);
+            assert!(changes[0].ends_with("skill-a"));
+            assert!(fs::symlink_metadata(dest.join("skill-a")).is_err());
+            assert!(dest.join("user-skill").is_dir());
+            assert!(dest.join("linked-elsewhere").exists());
+            assert!(source.join("skill-a").join("SKILL.md").is_file());
+
+            // Once only synced links remain the directory goes too
+            fs::remove_dir_all(dest.join("user-skill")).unwrap();
+            fs::remove_file(dest.join("linked-elsewhere")).unwrap();
+            sync_skills(&source, &agents).unwrap();
+            assert_eq!(unlink_synced_skills(&source, &agents).unwrap().len(), 2);
+            assert!(!dest.exists());
+        }
     }
 }
@@ -1,12 +1,12 @@
 /// Codex CLI session discovery.
-use anyhow::Result;
+use anyhow::{Context, Result};
 use std::fs;
 use std::io::BufRead;
 use std::path::{Path, PathBuf};
 
-use crate::paths;
+use crate::session_tail;
 use crate::session_utils;
-use crate::types::DiscoveredClaudeSession;
+use crate::types::{DiscoveredClaudeSession, TranscriptBlock, TranscriptMessage};
 
 const CODEX_JSONL_MAX_SCAN_LINES: usize = 200;
 
@@ -14,9 +14,7 @@ const CODEX_JSONL_MAX_SCAN_LINES: usize = 200;
 // Session discovery
 // ---------------------------------------------------------------------------
 
-fn codex_session_meta_candidates<'a>(
-    obj: &'a serde_json::Value,
-) -> [Option<&'a serde_json::Value>; 4] {
+fn codex_session_meta_candidates(obj: &serde_json::Value) -> [Option<&serde_json::Value>; 4] {
     [
         obj.pointer("/item/meta"),
         obj.get("meta"),
@@ -56,9 +54,8 @@ pub(crate) fn is_codex_bootstrap_message(text: &str) -> bool {
         || first_line.starts_with("Warning: apply_patch was requested via exec_command.")
 }
 
-fn codex_cwd_matches_project(cwd: &str, project_path: &Path) -> bool {
-    let cwd_canon = fs::canonicalize(cwd).unwrap_or_else(|_| PathBuf::from(cwd));
-    cwd_canon == *project_path
+fn canonical_cwd(cwd: &str) -> PathBuf {
+    fs::canonicalize(cwd).unwrap_or_else(|_| PathBuf::from(cwd))
 }
 
 fn is_codex_session_meta_row(obj: &serde_json::Value, line_index: usize) -> bool {
@@ -71,13 +68,56 @@ fn is_codex_session_meta_row(obj: &serde_json::Value, line_index: usize) -> bool
     obj.pointer("/item/type").and_then(|v| v.as_str()) == Some("session_meta")
 }
 
-fn parse_codex_session_jsonl(path: &Path, project_path: &Path) -> Option<DiscoveredClaudeSession> {
+/// The (canonicalized) cwd recorded in a Codex session's metadata, read
+/// without parsing the rest of the transcript.
+pub(crate) fn read_codex_session_cwd(path: &Path) -> Option<String> {
+    let file = fs::File::open(path).ok()?;
+    let reader = std::io::BufReader::new(file);
+    for (i, line) in reader.lines().enumerate().take(CODEX_JSONL_MAX_SCAN_LINES) {
+        let Ok(line) = line else { break };
+        let Ok(obj) = serde_json::from_str::<serde_json::Value>(&line) else {
+            continue;
+        };
+        if !is_codex_session_meta_row(&obj, i) {
+            continue;
+        }
+        if let Some(cwd) = codex_row_cwd(&obj, i) {
+            return Some(canonical_cwd(cwd).to_string_lossy().to_string());
+        }
+    }
+    None
+}
+
+/// The cwd set by a session metadata or `turn_context` row.
+fn codex_row_cwd(obj: &serde_json::Value, line_index: usize) -> Option<&str> {
+    if obj.get("type").and_then(|v| v.as_str()) == Some("turn_context") {
+        return obj.pointer("/payload/cwd").and_then(|v| v.as_str());
+    }
+    if !is_codex_session_meta_row(obj, line_index) {
+        return None;
+    }
+    codex_session_meta_candidates(obj)
+        .into_iter()
+        .flatten()
+        .find_map(|meta| meta.get("cwd").and_then(|v| v.as_str()))
+        .or_else(|| obj.get("cwd").and_then(|v| v.as_str()))
+}
+
+/// A parsed Codex session along with the (canonicalized) cwd it ran in.
+pub(crate) struct CodexSessionSummary {
+    pub cwd: String,
+    pub session: DiscoveredClaudeSession,
+}
+
+/// Parse a Codex session file without filtering by project. Returns `None`
+/// for files that never record a cwd.
+pub(crate) fn parse_codex_session_summary(path: &Path) -> Option<CodexSessionSummary> {
     let file = fs::File::open(path).ok()?;
     let reader = std::io::BufReader::new(file);
 
     let mut session_id = String::new();
     let mut timestamp = String::new();
-    let mut cwd_matches = false;
+    let mut cwd: Option<String> = None;
     let mut label = String::new();
 
     for (i, line) in reader.lines().enumerate() {
@@ -95,11 +135,8 @@ fn parse_codex_session_jsonl(path: &Path, project_path: &Path) -> Option<Discove
 
         if is_codex_session_meta_row(&obj, i) {
             for meta in codex_session_meta_candidates(&obj).into_iter().flatten() {
-                if let Some(cwd) = meta.get("cwd").and_then(|v| v.as_str()) {
-                    if !codex_cwd_matches_project(cwd, project_path) {
-                        return None;
-                    }
-                    cwd_matches = true;
+                if cwd.is_none() {
+                    cwd = meta.get("cwd").and_then(|v| v.as_str()).map(str::to_string);
                 }
                 if session_id.is_empty() {
                     if let Some(id) = meta.get("id").and_then(|v| v.as_str()) {
@@ -126,13 +163,8 @@ fn parse_codex_session_jsonl(path: &Path, project_path: &Path) -> Option<Discove
                     timestamp = ts.to_string();
                 }
             }
-            if !cwd_matches {
-                if let Some(cwd) = obj.get("cwd").and_then(|v| v.as_str()) {
-                    if !codex_cwd_matches_project(cwd, project_path) {
-                        return None;
-                    }
-                    cwd_matches = true;
-                }
+            if cwd.is_none() {
+                cwd = obj.get("cwd").and_then(|v| v.as_str()).map(str::to_string);
             }
         }
 
@@ -152,9 +184,7 @@ fn parse_codex_session_jsonl(path: &Path, project_path: &Path) -> Option<Discove
         }
     }
 
-    if !cwd_matches {
-        return None;
-    }
+    let cwd = cwd?;
 
     // Fall back to extracting session ID from filename if not found in content
     if session_id.is_empty() {
@@ -170,11 +200,20 @@ fn parse_codex_session_jsonl(path: &Path, project_path: &Path) -> Option<Discove
         label = session_utils::fallback_label(&session_id);
     }
 
-    Some(DiscoveredClaudeSession {
-        session_id,
-        label,
-        timestamp,
-        last_message_role: None,
+    let tail = session_tail::read_session_tail(path, parse_codex_transcript_entry);
+    Some(CodexSessionSummary {
+        cwd: canonical_cwd(&cwd).to_string_lossy().to_string(),
+        session: DiscoveredClaudeSession {
+            session_id,
+            label,
+            timestamp,
+            last_message_role: tail.last_message_role,
+            last_assistant_snippet: tail.last_assistant_snippet,
+            awaiting_input: tail.awaiting_input,
+            pending_tool_since: tail.pending_tool_since,
+            worktree_path: None,
+            branch: None,
+        },
     })
 }
 
@@ -244,33 +283,160 @@ pub(crate) fn collect_jsonl_files(dir: &Path, max_depth: u32) -> Vec<PathBuf> {
     results
 }
 
-/// Discover Codex CLI sessions by reading ~/.codex/sessions/**/*.jsonl
-/// and filtering by cwd match against the given project_path.
-pub fn discover_codex_sessions(project_path: &str) -> Result<Vec<DiscoveredClaudeSession>> {
-    let sessions_dir = paths::codex_sessions_dir();
-    if !sessions_dir.is_dir() {
-        return Ok(Vec::new());
-    }
+// ---------------------------------------------------------------------------
+// Transcript
+// ---------------------------------------------------------------------------
 
-    let canonical_project =
-        fs::canonicalize(project_path).unwrap_or_else(|_| PathBuf::from(project_path));
+fn codex_content_text(content: Option<&serde_json::Value>) -> String {
+    match content {
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(serde_json::Value::Array(items)) => items
+            .iter()
+            .filter_map(|item| item.get("text").and_then(|v| v.as_str()))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
 
-    let jsonl_files = collect_jsonl_files(&sessions_dir, 4); // year/month/day/file
+/// Convert one Codex `response_item` row into a transcript message. Event
+/// rows (`event_msg`) duplicate response items and are skipped.
+pub(crate) fn parse_codex_transcript_entry(obj: &serde_json::Value) -> Option<TranscriptMessage> {
+    if obj.get("type").and_then(|v| v.as_str()) != Some("response_item") {
+        return None;
+    }
+    let payload = obj.get("payload")?;
+    let str_field = |key: &str| {
+        payload
+            .get(key)
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+    };
+    let mut message = TranscriptMessage {
+        uuid: None,
+        parent_uuid: None,
+        timestamp: obj
+            .get("timestamp")
+            .and_then(|v| v.as_str())
+            .map(str::to_string),
+        role: "assistant".to_string(),
+        is_meta: false,
+        is_sidechain: false,
+        subtype: None,
+        model: None,
+        usage: None,
+        cwd: None,
+        blocks: Vec::new(),
+    };
 
-    let mut sessions = Vec::new();
-    for path in jsonl_files {
-        if let Some(session) = parse_codex_session_jsonl(&path, &canonical_project) {
-            sessions.push(session);
+    match payload.get("type").and_then(|v| v.as_str())? {
+        "message" => {
+            let role = str_field("role")?;
+            let text = codex_content_text(payload.get("content"));
+            if role == "user" {
+                message.is_meta = is_codex_bootstrap_message(text.trim());
+            } else if role != "assistant" {
+                // developer/system instructions
+                message.is_meta = true;
+            }
+            message.role = if role == "assistant" {
+                role
+            } else {
+                "user".to_string()
+            };
+            message.blocks.push(TranscriptBlock::Text {
+                text: strip_codex_request_prefix(&text),
+            });
         }
+        "reasoning" => {
+            let text = payload
+                .get("summary")
+                .and_then(|v| v.as_array())
+                .map(|items| {
+                    items
+                        .iter()
+                        .filter_map(|item| item.get("text").and_then(|v| v.as_str()))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                })
+                .unwrap_or_default();
+            if text.is_empty() {
+                return None;
+            }
+            message.blocks.push(TranscriptBlock::Thinking { text });
+        }
+        "function_call" => {
+            // Arguments are a JSON-encoded string.
+            let input = str_field("arguments")
+                .map(|raw| serde_json::from_str(&raw).unwrap_or(serde_json::Value::String(raw)))
+                .unwrap_or(serde_json::Value::Null);
+            message.blocks.push(TranscriptBlock::ToolUse {
+                id: str_field("call_id").unwrap_or_default(),
+                name: str_field("name").unwrap_or_default(),
+                input,
+            });
+        }
+        "custom_tool_call" => {
+            // Freeform tools such as apply_patch carry raw text input.
+            message.blocks.push(TranscriptBlock::ToolUse {
+                id: str_field("call_id").unwrap_or_default(),
+                name: str_field("name").unwrap_or_default(),
+                input: serde_json::Value::String(str_field("input").unwrap_or_default()),
+            });
+        }
+        "function_call_output" | "custom_tool_call_output" => {
+            let output = payload.get("output");
+            let content = match output {
+                Some(serde_json::Value::String(s)) => s.clone(),
+                Some(serde_json::Value::Object(o)) => o
+                    .get("content")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+                _ => String::new(),
+            };
+            message.role = "user".to_string();
+            message.blocks.push(TranscriptBlock::ToolResult {
+                tool_use_id: str_field("call_id").unwrap_or_default(),
+                content,
+                is_error: false,
+            });
+        }
+        _ => return None,
     }
 
-    sessions.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
-    Ok(sessions)
+    Some(message)
+}
+
+/// Parse a Codex session file into transcript messages, skipping malformed lines.
+pub(crate) fn read_codex_transcript(path: &Path) -> Result<Vec<TranscriptMessage>> {
+    let file = fs::File::open(path)
+        .with_context(|| format!("Failed to open Codex session {}", path.display()))?;
+    let reader = std::io::BufReader::new(file);
+    let mut messages = Vec::new();
+    let mut cwd: Option<String> = None;
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        let Ok(obj) = serde_json::from_str::<serde_json::Value>(&line) else {
+            continue;
+        };
+        // Response items carry no cwd; it comes from the session metadata
+        // and each turn's context.
+        if let Some(row_cwd) = codex_row_cwd(&obj, i) {
+            cwd = Some(row_cwd.to_string());
+        }
+        if let Some(mut message) = parse_codex_transcript_entry(&obj) {
+            message.cwd = cwd.clone();
+            messages.push(message);
+        }
+    }
+    Ok(messages)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::AwaitingInputReason;
     use std::fs;
     use std::path::Path;
     use tempfile::tempdir;
@@ -534,4 +700,156 @@ mod tests {
         let files = collect_jsonl_files(Path::new("/nonexistent/path"), 3);
         assert!(files.is_empty());
     }
+
+    // -----------------------------------------------------------------------
+    // parse_codex_transcript_entry
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn transcript_user_and_assistant_messages() {
+        let user = serde_json::json!({
+            "timestamp": "2025-01-01T00:00:00Z",
+            "type": "response_item",
+            "payload": { "type": "message", "role": "user",
+                "content": [{ "type": "input_text", "text": "## My request for Codex:\nFix the redirect" }] }
+        });
+        let msg = parse_codex_transcript_entry(&user).unwrap();
+        assert_eq!(msg.role, "user");
+        assert!(!msg.is_meta);
+        assert_eq!(msg.timestamp.as_deref(), Some("2025-01-01T00:00:00Z"));
+        assert!(
+            matches!(&msg.blocks[0], TranscriptBlock::Text { text } if text == "Fix the redirect")
+        );
+
+        let assistant = serde_json::json!({
+            "type": "response_item",
+            "payload": { "type": "message", "role": "assistant",
+                "content": [{ "type": "output_text", "text": "Done." }] }
+        });
+        assert_eq!(
+            parse_codex_transcript_entry(&assistant).unwrap().role,
+            "assistant"
+        );
+    }
+
+    #[test]
+    fn transcript_bootstrap_messages_are_meta() {
+        let obj = serde_json::json!({
+            "type": "response_item",
+            "payload": { "type": "message", "role": "user",
+                "content": [{ "type": "input_text", "text": "<environment_context>\n</environment_context>" }] }
+        });
+        assert!(parse_codex_transcript_entry(&obj).unwrap().is_meta);
+    }
+
+    #[test]
+    fn transcript_tool_calls_and_outputs() {
+        let call = serde_json::json!({
+            "type": "response_item",
+            "payload": { "type": "function_call", "name": "shell", "call_id": "c1",
+                "arguments": "{\"command\":[\"ls\"]}" }
+        });
+        match &parse_codex_transcript_entry(&call).unwrap().blocks[0] {
+            TranscriptBlock::ToolUse { id, name, input } => {
+                assert_eq!(id, "c1");
+                assert_eq!(name, "shell");
+                assert_eq!(input["command"][0], "ls");
+            }
+            other => panic!("unexpected block {other:?}"),
+        }
+
+        let patch = serde_json::json!({
+            "type": "response_item",
+            "payload": { "type": "custom_tool_call", "name": "apply_patch", "call_id": "c2",
+                "input": "*** Begin Patch" }
+        });
+        match &parse_codex_transcript_entry(&patch).unwrap().blocks[0] {
+            TranscriptBlock::ToolUse { input, .. } => assert_eq!(input, "*** Begin Patch"),
+            other => panic!("unexpected block {other:?}"),
+        }
+
+        let output = serde_json::json!({
+            "type": "response_item",
+            "payload": { "type": "function_call_output", "call_id": "c1", "output": "a.txt" }
+        });
+        match &parse_codex_transcript_entry(&output).unwrap().blocks[0] {
+            TranscriptBlock::ToolResult {
+                tool_use_id,
+                content,
+                ..
+            } => {
+                assert_eq!(tool_use_id, "c1");
+                assert_eq!(content, "a.txt");
+            }
+            other => panic!("unexpected block {other:?}"),
+        }
+    }
+
+    #[test]
+    fn transcript_skips_event_rows() {
+        let obj = serde_json::json!({
+            "type": "event_msg",
+            "payload": { "type": "user_message", "message": "hello there" }
+        });
+        assert!(parse_codex_transcript_entry(&obj).is_none());
+    }
+
+    #[test]
+    fn summary_reads_the_session_tail() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("rollout-1.jsonl");
+        let lines = [
+            serde_json::json!({ "type": "session_meta", "payload": {
+                "id": "codex-1", "timestamp": "2025-01-01T00:00:00Z", "cwd": "/work/app" } }),
+            serde_json::json!({ "type": "response_item", "payload": {
+                "type": "message", "role": "user",
+                "content": [{ "type": "input_text", "text": "Bump the tokio version" }] } }),
+            serde_json::json!({ "type": "response_item", "payload": {
+                "type": "message", "role": "assistant",
+                "content": [{ "type": "output_text", "text": "Should I run the tests too?" }] } }),
+        ];
+        let body: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+        fs::write(&path, body.join("\n") + "\n").unwrap();
+
+        let session = parse_codex_session_summary(&path).unwrap().session;
+        assert_eq!(session.last_message_role.as_deref(), Some("assistant"));
+        assert_eq!(
+            session.last_assistant_snippet.as_deref(),
+            Some("Should I run the tests too?")
+        );
+        assert_eq!(session.awaiting_input, Some(AwaitingInputReason::Question));
+    }
+
+    #[test]
+    fn transcript_messages_carry_the_current_cwd() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("rollout-1.jsonl");
+        let message = |text: &str| {
+            serde_json::json!({ "type": "response_item", "payload": {
+                "type": "message", "role": "assistant",
+                "content": [{ "type": "output_text", "text": text }] } })
+        };
+        let lines = [
+            serde_json::json!({ "type": "session_meta", "payload": {
+                "id": "codex-1", "cwd": "/work/app" } }),
+            message("In the app"),
+            serde_json::json!({ "type": "turn_context", "payload": { "cwd": "/work/app/web" } }),
+            message("In the web client"),
+        ];
+        let body: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+        fs::write(&path, body.join("\n") + "\n").unwrap();
+
+        let cwds: Vec<Option<String>> = read_codex_transcript(&path)
+            .unwrap()
+            .into_iter()
+            .map(|m| m.cwd)
+            .collect();
+        assert_eq!(
+            cwds,
+            [
+                Some("/work/app".to_string()),
+                Some("/work/app/web".to_string())
+            ]
+        );
+    }
 }
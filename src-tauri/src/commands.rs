@@ -2,23 +2,48 @@ use std::collections::HashSet;
 
 use tauri::{AppHandle, Emitter, State};
 
-use crate::claude_sessions;
+use crate::claude_definitions;
+use crate::claude_transcript;
 use crate::codex_config;
-use crate::codex_sessions;
 use crate::config;
+use crate::config_history;
+use crate::effective_settings;
 use crate::git;
+use crate::git_watcher::GitWatcher;
 use crate::github;
 use crate::github_poller::GitHubPoller;
-use crate::git_watcher::GitWatcher;
 use crate::hook_bridge::{HookBridgeState, HookLogEntry};
+use crate::hook_tester;
+use crate::mcp_health;
+use crate::mcp_servers;
+use crate::paths;
+use crate::permission_rules;
+use crate::plugins;
 use crate::pty::PtyManager;
+use crate::session_export;
+use crate::session_housekeeping;
+use crate::session_index::SessionIndex;
+use crate::session_providers;
+use crate::session_search::SessionSearch;
 use crate::settings;
-use crate::types::GitHubProjectStatusEvent;
+use crate::settings_schema;
+use crate::skills;
+use crate::touched_files;
 use crate::types::{
-    BranchInfo, CreateTerminalRequest, CreateTerminalResponse, CreateWorktreeRequest,
-    DiscoveredClaudeSession, GitHubRemote, GitHubRepo, GitInfo, HookScriptInfo, IntegrationStatus,
-    PluginInfo, ProjectConfig, SkillInfo, WorkbenchSettings, WorkspaceFile, WorktreeInfo,
+    default_automation_rules, AgentSession, ArchivedSession, AutomationRule, BranchInfo,
+    ClaudeDefinition, ClaudeDefinitionInfo, ClaudeDefinitionKind, ConfigDiffLine,
+    ConfigHistoryFile, ConfigVersion, CreateTerminalRequest, CreateTerminalResponse,
+    CreateWorktreeRequest, DiscoveredClaudeSession, EffectiveClaudeSettings,
+    GitHubProjectStatusEvent, GitHubRemote, GitHubRepo, GitInfo, HookScriptInfo, HookTestResult,
+    IntegrationRemovalReport, IntegrationStatus, MarketplaceInfo, McpHealthReport, McpScope,
+    McpServerConfig, McpServerEntry, PaneAgentActivity, PermissionEvaluation, PermissionLintIssue,
+    PluginInfo, PluginUninstallReport, ProjectConfig, SessionExportFormat,
+    SessionHousekeepingAction, SessionHousekeepingReport, SessionPrunePolicy, SessionSearchHit,
+    SessionTouchedFiles, SettingsSaveResult, SettingsValidationIssue, SkillDocument, SkillInfo,
+    SkillScope, TranscriptPage, UsageGroupBy, UsageRange, UsageSummary, WorkbenchSettings,
+    WorkspaceFile, WorktreeInfo,
 };
+use crate::usage_accounting::UsageTracker;
 
 #[tauri::command]
 pub fn list_projects() -> Result<Vec<ProjectConfig>, String> {
@@ -141,9 +166,23 @@ pub fn save_workspaces(
 
 #[tauri::command]
 pub fn discover_claude_sessions(
+    index: State<'_, SessionIndex>,
     project_path: String,
 ) -> Result<Vec<DiscoveredClaudeSession>, String> {
-    claude_sessions::discover_claude_sessions(&project_path).map_err(|e| e.to_string())
+    index
+        .claude_sessions(&project_path)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command(async)]
+pub fn load_claude_transcript(
+    project_path: String,
+    session_id: String,
+    offset: Option<usize>,
+    limit: Option<usize>,
+) -> Result<TranscriptPage, String> {
+    claude_transcript::load_claude_transcript(&project_path, &session_id, offset, limit)
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -154,24 +193,275 @@ pub fn load_claude_settings(
     settings::load_settings(&scope, project_path.as_deref()).map_err(|e| e.to_string())
 }
 
+/// Validates against the bundled schema first. Errors block the write unless
+/// `force` is set; warnings never do.
 #[tauri::command]
 pub fn save_claude_settings(
     scope: String,
     project_path: Option<String>,
     value: serde_json::Value,
-) -> Result<bool, String> {
+    force: Option<bool>,
+) -> Result<SettingsSaveResult, String> {
+    let issues = settings_schema::validate_settings(&value);
+    if settings_schema::has_errors(&issues) && !force.unwrap_or(false) {
+        return Ok(SettingsSaveResult {
+            saved: false,
+            issues,
+        });
+    }
     settings::save_settings(&scope, project_path.as_deref(), &value).map_err(|e| e.to_string())?;
+    Ok(SettingsSaveResult {
+        saved: true,
+        issues,
+    })
+}
+
+#[tauri::command]
+pub fn validate_claude_settings(value: serde_json::Value) -> Vec<SettingsValidationIssue> {
+    settings_schema::validate_settings(&value)
+}
+
+#[tauri::command]
+pub fn load_effective_claude_settings(
+    project_path: Option<String>,
+) -> Result<EffectiveClaudeSettings, String> {
+    effective_settings::load_effective_settings(project_path.as_deref()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn list_mcp_servers(project_path: Option<String>) -> Result<Vec<McpServerEntry>, String> {
+    mcp_servers::list_servers(project_path.as_deref()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn save_mcp_server(
+    scope: McpScope,
+    project_path: Option<String>,
+    name: String,
+    config: McpServerConfig,
+) -> Result<bool, String> {
+    mcp_servers::save_server(scope, project_path.as_deref(), &name, &config)
+        .map_err(|e| e.to_string())?;
     Ok(true)
 }
 
+#[tauri::command]
+pub fn remove_mcp_server(
+    scope: McpScope,
+    project_path: Option<String>,
+    name: String,
+) -> Result<bool, String> {
+    mcp_servers::remove_server(scope, project_path.as_deref(), &name).map_err(|e| e.to_string())
+}
+
+/// Copy a server definition between scopes, e.g. from Claude to Codex.
+#[tauri::command]
+pub fn sync_mcp_server(
+    project_path: Option<String>,
+    name: String,
+    from: McpScope,
+    to: McpScope,
+) -> Result<McpServerConfig, String> {
+    mcp_servers::sync_server(project_path.as_deref(), &name, from, to).map_err(|e| e.to_string())
+}
+
+#[tauri::command(async)]
+pub fn check_mcp_server(config: McpServerConfig, project_path: Option<String>) -> McpHealthReport {
+    mcp_health::check_server(&config, project_path.as_deref())
+}
+
+#[tauri::command]
+pub fn evaluate_claude_permission(
+    project_path: Option<String>,
+    tool_name: String,
+    tool_input: serde_json::Value,
+) -> Result<PermissionEvaluation, String> {
+    permission_rules::evaluate(project_path.as_deref(), &tool_name, &tool_input)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn lint_claude_permissions(
+    project_path: Option<String>,
+) -> Result<Vec<PermissionLintIssue>, String> {
+    permission_rules::lint(project_path.as_deref()).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub fn list_claude_plugins() -> Result<Vec<PluginInfo>, String> {
-    settings::list_plugins().map_err(|e| e.to_string())
+    plugins::list_plugins().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn list_plugin_marketplaces() -> Result<Vec<MarketplaceInfo>, String> {
+    plugins::list_marketplaces().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn add_plugin_marketplace(path: String) -> Result<MarketplaceInfo, String> {
+    plugins::add_marketplace(&path).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn remove_plugin_marketplace(name: String) -> Result<bool, String> {
+    plugins::remove_marketplace(&name).map_err(|e| e.to_string())
+}
+
+#[tauri::command(async)]
+pub fn install_claude_plugin(
+    marketplace: String,
+    plugin: String,
+    scope: String,
+    project_path: Option<String>,
+) -> Result<String, String> {
+    plugins::install_plugin(&marketplace, &plugin, &scope, project_path.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command(async)]
+pub fn uninstall_claude_plugin(
+    plugin_id: String,
+    project_path: Option<String>,
+) -> Result<PluginUninstallReport, String> {
+    plugins::uninstall_plugin(&plugin_id, project_path.as_deref()).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub fn list_claude_skills() -> Result<Vec<SkillInfo>, String> {
-    settings::list_skills().map_err(|e| e.to_string())
+pub fn set_claude_plugin_enabled(
+    plugin_id: String,
+    scope: String,
+    project_path: Option<String>,
+    enabled: bool,
+) -> Result<bool, String> {
+    plugins::set_plugin_enabled(&plugin_id, &scope, project_path.as_deref(), enabled)
+        .map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+#[tauri::command(async)]
+pub fn test_claude_hook(
+    event: String,
+    matcher: Option<String>,
+    command: String,
+    timeout: Option<u64>,
+    project_path: Option<String>,
+) -> Result<HookTestResult, String> {
+    hook_tester::test_hook(
+        &event,
+        matcher.as_deref(),
+        &command,
+        timeout,
+        project_path.as_deref(),
+    )
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn list_claude_skills(project_path: Option<String>) -> Result<Vec<SkillInfo>, String> {
+    skills::list_skills(project_path.as_deref()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_claude_skill(
+    scope: SkillScope,
+    project_path: Option<String>,
+    dir_name: String,
+) -> Result<SkillDocument, String> {
+    skills::get_skill(scope, project_path.as_deref(), &dir_name).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn validate_claude_skill(skill: SkillDocument) -> Vec<String> {
+    skills::validate_skill(&skill)
+}
+
+#[tauri::command]
+pub fn save_claude_skill(
+    scope: SkillScope,
+    project_path: Option<String>,
+    dir_name: Option<String>,
+    skill: SkillDocument,
+) -> Result<bool, String> {
+    skills::save_skill(scope, project_path.as_deref(), dir_name.as_deref(), &skill)
+        .map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+#[tauri::command]
+pub fn delete_claude_skill(
+    scope: SkillScope,
+    project_path: Option<String>,
+    dir_name: String,
+) -> Result<bool, String> {
+    skills::delete_skill(scope, project_path.as_deref(), &dir_name).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn copy_claude_skill(
+    project_path: Option<String>,
+    dir_name: String,
+    from: SkillScope,
+    to: SkillScope,
+    overwrite: Option<bool>,
+) -> Result<bool, String> {
+    skills::copy_skill(
+        project_path.as_deref(),
+        &dir_name,
+        from,
+        to,
+        overwrite.unwrap_or(false),
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+#[tauri::command]
+pub fn list_claude_definitions(
+    kind: ClaudeDefinitionKind,
+    project_path: Option<String>,
+) -> Result<Vec<ClaudeDefinitionInfo>, String> {
+    claude_definitions::list_definitions(kind, project_path.as_deref()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_claude_definition(
+    kind: ClaudeDefinitionKind,
+    scope: SkillScope,
+    project_path: Option<String>,
+    file_name: String,
+) -> Result<ClaudeDefinition, String> {
+    claude_definitions::get_definition(kind, scope, project_path.as_deref(), &file_name)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn save_claude_definition(
+    kind: ClaudeDefinitionKind,
+    scope: SkillScope,
+    project_path: Option<String>,
+    original_name: Option<String>,
+    definition: ClaudeDefinition,
+) -> Result<bool, String> {
+    claude_definitions::save_definition(
+        kind,
+        scope,
+        project_path.as_deref(),
+        original_name.as_deref(),
+        &definition,
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+#[tauri::command]
+pub fn delete_claude_definition(
+    kind: ClaudeDefinitionKind,
+    scope: SkillScope,
+    project_path: Option<String>,
+    file_name: String,
+) -> Result<bool, String> {
+    claude_definitions::delete_definition(kind, scope, project_path.as_deref(), &file_name)
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -209,11 +499,210 @@ pub fn list_branches(path: String) -> Result<Vec<BranchInfo>, String> {
     git::list_branches(&path).map_err(|e| e.to_string())
 }
 
-#[tauri::command]
+#[tauri::command(async)]
 pub fn discover_codex_sessions(
+    index: State<'_, SessionIndex>,
     project_path: String,
 ) -> Result<Vec<DiscoveredClaudeSession>, String> {
-    codex_sessions::discover_codex_sessions(&project_path).map_err(|e| e.to_string())
+    Ok(index.codex_sessions(&project_path))
+}
+
+/// Sessions from every registered agent provider (or just `providers`),
+/// newest first.
+#[tauri::command(async)]
+pub fn discover_agent_sessions(
+    index: State<'_, SessionIndex>,
+    project_path: String,
+    providers: Option<Vec<String>>,
+) -> Result<Vec<AgentSession>, String> {
+    Ok(session_providers::discover_all(
+        &index,
+        &project_path,
+        providers.as_deref(),
+    ))
+}
+
+#[tauri::command(async)]
+pub fn delete_agent_session(
+    index: State<'_, SessionIndex>,
+    provider: String,
+    project_path: String,
+    session_id: String,
+    open_session_ids: Vec<String>,
+) -> Result<bool, String> {
+    let report = session_housekeeping::housekeep_sessions(
+        &index,
+        &provider,
+        &project_path,
+        &[session_id],
+        &open_session_ids,
+        SessionHousekeepingAction::Delete,
+        false,
+    )
+    .map_err(|e| e.to_string())?;
+    match report.skipped.into_iter().next() {
+        Some(skipped) => Err(skipped.reason),
+        None => Ok(true),
+    }
+}
+
+/// Archive specific sessions into ~/.workbench/archive/sessions. With
+/// `dry_run` nothing is changed and the report previews the result.
+#[tauri::command(async)]
+pub fn archive_sessions(
+    index: State<'_, SessionIndex>,
+    provider: String,
+    project_path: String,
+    session_ids: Vec<String>,
+    open_session_ids: Vec<String>,
+    dry_run: bool,
+) -> Result<SessionHousekeepingReport, String> {
+    session_housekeeping::housekeep_sessions(
+        &index,
+        &provider,
+        &project_path,
+        &session_ids,
+        &open_session_ids,
+        SessionHousekeepingAction::Archive,
+        dry_run,
+    )
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command(async)]
+pub fn delete_sessions(
+    index: State<'_, SessionIndex>,
+    provider: String,
+    project_path: String,
+    session_ids: Vec<String>,
+    open_session_ids: Vec<String>,
+    dry_run: bool,
+) -> Result<SessionHousekeepingReport, String> {
+    session_housekeeping::housekeep_sessions(
+        &index,
+        &provider,
+        &project_path,
+        &session_ids,
+        &open_session_ids,
+        SessionHousekeepingAction::Delete,
+        dry_run,
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Archive or delete a project's sessions that are older or beyond the size
+/// budget in `policy`.
+#[tauri::command(async)]
+pub fn prune_sessions(
+    index: State<'_, SessionIndex>,
+    project_path: String,
+    policy: SessionPrunePolicy,
+    open_session_ids: Vec<String>,
+    action: SessionHousekeepingAction,
+    dry_run: bool,
+) -> Result<SessionHousekeepingReport, String> {
+    session_housekeeping::prune_sessions(
+        &index,
+        &project_path,
+        &policy,
+        &open_session_ids,
+        action,
+        dry_run,
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Sessions in the archive, optionally only those from `project_path`.
+#[tauri::command]
+pub fn list_archived_sessions(
+    project_path: Option<String>,
+) -> Result<Vec<ArchivedSession>, String> {
+    session_housekeeping::archived_sessions(project_path.as_deref()).map_err(|e| e.to_string())
+}
+
+/// Move archived sessions back into their agent's store.
+#[tauri::command(async)]
+pub fn restore_sessions(
+    provider: String,
+    session_ids: Vec<String>,
+    dry_run: bool,
+) -> Result<SessionHousekeepingReport, String> {
+    session_housekeeping::restore_sessions(&provider, &session_ids, dry_run)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command(async)]
+pub fn search_sessions(
+    search: State<'_, SessionSearch>,
+    query: String,
+    project_path: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<SessionSearchHit>, String> {
+    Ok(search.search(&query, project_path.as_deref(), limit))
+}
+
+#[tauri::command(async)]
+pub fn session_touched_files(
+    index: State<'_, SessionIndex>,
+    agent: String,
+    project_path: String,
+    session_id: String,
+) -> Result<SessionTouchedFiles, String> {
+    let messages = session_providers::provider(&agent)
+        .and_then(|p| p.load_transcript(&index, &project_path, &session_id))
+        .map_err(|e| e.to_string())?;
+    Ok(touched_files::session_touched_files(
+        &messages,
+        &session_id,
+        &project_path,
+    ))
+}
+
+/// Render a session transcript and, when `output_path` is given, write it
+/// there too. Returns the rendered document.
+#[tauri::command(async)]
+pub fn export_session(
+    index: State<'_, SessionIndex>,
+    agent: String,
+    project_path: String,
+    session_id: String,
+    format: SessionExportFormat,
+    output_path: Option<String>,
+) -> Result<String, String> {
+    let provider = session_providers::provider(&agent).map_err(|e| e.to_string())?;
+    let messages = provider
+        .load_transcript(&index, &project_path, &session_id)
+        .map_err(|e| e.to_string())?;
+    let settings = config::load_workbench_settings().map_err(|e| e.to_string())?;
+    let document = session_export::export_session(
+        provider.display_name(),
+        &messages,
+        &session_id,
+        &project_path,
+        format,
+        &settings.export_redaction_patterns,
+    );
+    if let Some(path) = output_path {
+        paths::atomic_write(std::path::Path::new(&path), &document)
+            .map_err(|e| format!("Failed to write {path}: {e}"))?;
+    }
+    Ok(document)
+}
+
+#[tauri::command(async)]
+pub fn usage_summary(
+    tracker: State<'_, UsageTracker>,
+    range: UsageRange,
+    group_by: UsageGroupBy,
+    project_path: Option<String>,
+) -> Result<UsageSummary, String> {
+    let settings = config::load_workbench_settings().map_err(|e| e.to_string())?;
+    Ok(tracker.summary(
+        &range,
+        group_by,
+        project_path.as_deref(),
+        &settings.model_prices,
+    ))
 }
 
 // Workbench settings commands
@@ -229,6 +718,39 @@ pub fn save_workbench_settings(settings: WorkbenchSettings) -> Result<bool, Stri
     Ok(true)
 }
 
+/// The rules Workbench ships with; saved rules with the same id override them.
+#[tauri::command]
+pub fn builtin_automation_rules() -> Vec<AutomationRule> {
+    default_automation_rules()
+}
+
+// Config history commands
+
+#[tauri::command]
+pub fn list_config_history() -> Result<Vec<ConfigHistoryFile>, String> {
+    config_history::list_files().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn list_config_versions(path: String) -> Result<Vec<ConfigVersion>, String> {
+    config_history::list_versions(&path).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn diff_config_versions(
+    path: String,
+    from: String,
+    to: Option<String>,
+) -> Result<Vec<ConfigDiffLine>, String> {
+    config_history::diff_versions(&path, &from, to.as_deref()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn restore_config_version(path: String, version: String) -> Result<bool, String> {
+    config_history::restore_version(&path, &version).map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
 // GitHub integration commands
 
 #[tauri::command(async)]
@@ -284,10 +806,7 @@ pub fn github_update_pr_branch(
 }
 
 #[tauri::command(async)]
-pub fn github_rerun_workflow(
-    project_path: String,
-    run_id: u64,
-) -> Result<bool, String> {
+pub fn github_rerun_workflow(project_path: String, run_id: u64) -> Result<bool, String> {
     github::rerun_workflow(&project_path, run_id).map_err(|e| e.to_string())?;
     Ok(true)
 }
@@ -315,11 +834,7 @@ pub fn github_merge_pr(
 }
 
 #[tauri::command(async)]
-pub fn delete_branch(
-    repo_path: String,
-    branch: String,
-    force: bool,
-) -> Result<bool, String> {
+pub fn delete_branch(repo_path: String, branch: String, force: bool) -> Result<bool, String> {
     git::delete_branch(&repo_path, &branch, force).map_err(|e| e.to_string())?;
     Ok(true)
 }
@@ -376,6 +891,16 @@ pub fn apply_codex_integration() -> Result<bool, String> {
     Ok(true)
 }
 
+#[tauri::command]
+pub fn remove_claude_integration() -> Result<IntegrationRemovalReport, String> {
+    settings::remove_workbench_hook_integration().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn remove_codex_integration() -> Result<IntegrationRemovalReport, String> {
+    codex_config::remove_codex_config().map_err(|e| e.to_string())
+}
+
 // Hook bridge log commands
 
 #[tauri::command]
@@ -384,13 +909,19 @@ pub fn get_hook_logs(hook_bridge: State<'_, HookBridgeState>) -> Result<Vec<Hook
 }
 
 #[tauri::command]
-pub fn clear_hook_logs(
-    hook_bridge: State<'_, HookBridgeState>,
-) -> Result<(), String> {
+pub fn clear_hook_logs(hook_bridge: State<'_, HookBridgeState>) -> Result<(), String> {
     hook_bridge.clear_logs();
     Ok(())
 }
 
+#[tauri::command]
+pub fn get_agent_activity(
+    pane_id: String,
+    hook_bridge: State<'_, HookBridgeState>,
+) -> Result<Option<PaneAgentActivity>, String> {
+    Ok(hook_bridge.get_agent_activity(&pane_id))
+}
+
 // Native terminal availability check
 
 #[tauri::command(async)]
@@ -2,8 +2,12 @@
 use anyhow::Result;
 use std::path::PathBuf;
 
+use crate::hook_automation;
 use crate::paths;
-use crate::types::{ProjectConfig, ProjectsFile, WorkbenchSettings, WorkspaceFile};
+use crate::types::{
+    default_automation_rules, default_export_redaction_patterns, default_model_prices,
+    ProjectConfig, ProjectsFile, WorkbenchSettings, WorkspaceFile,
+};
 
 fn config_path() -> PathBuf {
     paths::workbench_config_dir().join("projects.json")
@@ -45,11 +49,28 @@ fn settings_path() -> PathBuf {
 }
 
 pub fn load_workbench_settings() -> Result<WorkbenchSettings> {
-    paths::load_json_strict(&settings_path(), WorkbenchSettings::default())
+    let mut settings = paths::load_json_strict(&settings_path(), WorkbenchSettings::default())?;
+    // Older versions saved copies of the built-in rules; keep only real overrides
+    // so later changes to the built-ins still apply.
+    let builtins = default_automation_rules();
+    settings
+        .automation_rules
+        .retain(|rule| !builtins.contains(rule));
+    let patterns = default_export_redaction_patterns();
+    settings
+        .export_redaction_patterns
+        .retain(|pattern| !patterns.contains(pattern));
+    let prices = default_model_prices();
+    settings
+        .model_prices
+        .retain(|price| !prices.contains(price));
+    Ok(settings)
 }
 
 pub fn save_workbench_settings(settings: &WorkbenchSettings) -> Result<()> {
-    paths::save_json(&settings_path(), settings)
+    paths::save_json(&settings_path(), settings)?;
+    hook_automation::invalidate_rules();
+    Ok(())
 }
 
 #[cfg(test)]
@@ -0,0 +1,561 @@
+/// Version history for config files written through `paths::atomic_write`.
+/// Each file gets a directory under `~/.workbench/history/` holding its
+/// original path and one timestamped snapshot per write, oldest pruned first.
+/// Every config file Workbench writes is tracked: Claude settings at any
+/// scope, `~/.claude.json` and `.mcp.json` (MCP servers), Codex's
+/// `config.toml`, Workbench's own config and Trello project configs.
+/// Credentials never are, nor records Workbench keeps for itself, nor
+/// Claude's plugin registries, which Claude rewrites on its own.
+use anyhow::{bail, Context, Result};
+#[cfg(test)]
+use std::cell::RefCell;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::mcp_servers;
+use crate::paths;
+use crate::types::{ConfigDiffLine, ConfigDiffLineKind, ConfigHistoryFile, ConfigVersion};
+
+const SOURCE_FILE: &str = "source";
+const SNAPSHOT_EXT: &str = "snap";
+const MAX_VERSIONS_PER_FILE: usize = 50;
+/// Above this many line pairs the diff degrades to "everything changed".
+const MAX_DIFF_CELLS: usize = 4_000_000;
+const VERSION_FORMAT: &str = "%Y%m%dT%H%M%S%.3fZ";
+/// Files in `~/.workbench` that hold config. `codex-integration.json` is a
+/// record Workbench keeps for itself and `trello/credentials.json` a token.
+const TRACKED_WORKBENCH_FILES: &[&str] = &["projects.json", "settings.json", "workspaces.json"];
+/// Settings files in any `.claude` directory (user, project or local scope).
+const CLAUDE_SETTINGS_FILES: &[&str] = &["settings.json", "settings.local.json"];
+/// Project MCP server config.
+const PROJECT_MCP_FILE: &str = ".mcp.json";
+
+/// Where the tracked files and the history live.
+#[derive(Debug, Clone)]
+struct Locations {
+    history: PathBuf,
+    claude_user_dir: PathBuf,
+    claude_user_config: PathBuf,
+    codex_config_dir: PathBuf,
+    workbench_dir: PathBuf,
+}
+
+#[cfg(test)]
+thread_local! {
+    /// Redirects `paths::atomic_write` snapshots in this test thread.
+    static TEST_LOCATIONS: RefCell<Option<Locations>> = const { RefCell::new(None) };
+}
+
+fn locations() -> Locations {
+    #[cfg(test)]
+    if let Some(locations) = TEST_LOCATIONS.with(|l| l.borrow().clone()) {
+        return locations;
+    }
+    Locations {
+        history: paths::workbench_config_dir().join("history"),
+        claude_user_dir: paths::claude_user_dir(),
+        claude_user_config: mcp_servers::claude_user_config_path(),
+        codex_config_dir: paths::codex_config_dir(),
+        workbench_dir: paths::workbench_config_dir(),
+    }
+}
+
+fn history_dir() -> PathBuf {
+    locations().history
+}
+
+fn is_tracked_in(locations: &Locations, path: &Path) -> bool {
+    let Some(parent) = path.parent() else {
+        return false;
+    };
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default();
+    let in_claude_dir = parent.file_name().is_some_and(|n| n == ".claude");
+    path == locations.claude_user_dir.join("settings.json")
+        || (in_claude_dir && CLAUDE_SETTINGS_FILES.contains(&name))
+        || path == locations.claude_user_config
+        || name == PROJECT_MCP_FILE
+        || path == locations.codex_config_dir.join("config.toml")
+        || (parent == locations.workbench_dir && TRACKED_WORKBENCH_FILES.contains(&name))
+        // Project configs only; `trello/credentials.json` holds the API token.
+        || (parent == locations.workbench_dir.join("trello").join("projects")
+            && name.ends_with(".json"))
+}
+
+/// Tests write settings files in temp dirs all the time; only those that
+/// redirect the history get snapshots.
+fn recording_enabled() -> bool {
+    #[cfg(test)]
+    return TEST_LOCATIONS.with(|l| l.borrow().is_some());
+    #[cfg(not(test))]
+    true
+}
+
+fn is_tracked(path: &Path) -> bool {
+    is_tracked_in(&locations(), path)
+}
+
+/// Snapshots can hold tokens (MCP server env, for one), so only the owner
+/// may read them.
+fn create_private_dir(dir: &Path) -> Result<()> {
+    fs::create_dir_all(dir)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(dir, fs::Permissions::from_mode(0o700))?;
+    }
+    Ok(())
+}
+
+fn write_private(path: &Path, content: &[u8]) -> Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(path)?;
+    #[cfg(unix)]
+    {
+        // `mode` only applies when the file is created.
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    }
+    file.write_all(content)?;
+    Ok(())
+}
+
+/// FNV-1a; stable across Rust versions, unlike `DefaultHasher`.
+fn path_hash(path: &Path) -> u64 {
+    path.to_string_lossy()
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        })
+}
+
+/// `<file name>-<hash of full path>`, readable but unique per path.
+fn entry_dir(root: &Path, path: &Path) -> PathBuf {
+    let name: String = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    root.join(format!("{name}-{:016x}", path_hash(path)))
+}
+
+/// Snapshot ids, oldest first. Ids sort chronologically by construction:
+/// a timestamp, plus a zero-padded counter for writes within the same millisecond.
+fn version_ids(dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut ids: Vec<String> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            (path.extension().and_then(|e| e.to_str()) == Some(SNAPSHOT_EXT))
+                .then(|| path.file_stem()?.to_str().map(str::to_string))
+                .flatten()
+        })
+        .collect();
+    ids.sort();
+    ids
+}
+
+fn snapshot_path(dir: &Path, id: &str) -> PathBuf {
+    dir.join(format!("{id}.{SNAPSHOT_EXT}"))
+}
+
+fn write_snapshot(dir: &Path, content: &str) -> Result<()> {
+    let stamp = chrono::Utc::now().format(VERSION_FORMAT).to_string();
+    let mut id = stamp.clone();
+    let mut n = 1;
+    while snapshot_path(dir, &id).exists() {
+        id = format!("{stamp}-{n:03}");
+        n += 1;
+    }
+    write_private(&snapshot_path(dir, &id), content.as_bytes())
+}
+
+pub(crate) fn record_in(
+    root: &Path,
+    path: &Path,
+    previous: Option<&str>,
+    content: &str,
+) -> Result<()> {
+    let dir = entry_dir(root, path);
+    create_private_dir(root)?;
+    create_private_dir(&dir)?;
+    write_private(&dir.join(SOURCE_FILE), path.to_string_lossy().as_bytes())?;
+
+    let ids = version_ids(&dir);
+    let latest = ids
+        .last()
+        .and_then(|id| fs::read_to_string(snapshot_path(&dir, id)).ok());
+    // The first time Workbench touches a file, keep what was there before
+    if latest.is_none() {
+        if let Some(previous) = previous.filter(|p| *p != content) {
+            write_snapshot(&dir, previous)?;
+        }
+    }
+    if latest.as_deref() != Some(content) {
+        write_snapshot(&dir, content)?;
+    }
+
+    let ids = version_ids(&dir);
+    if ids.len() > MAX_VERSIONS_PER_FILE {
+        for id in &ids[..ids.len() - MAX_VERSIONS_PER_FILE] {
+            fs::remove_file(snapshot_path(&dir, id))?;
+        }
+    }
+    Ok(())
+}
+
+/// Whether `atomic_write` should read the old content before overwriting `path`.
+pub(crate) fn wants_previous(path: &Path) -> bool {
+    recording_enabled() && is_tracked(path)
+}
+
+/// Snapshot a write that just succeeded, and drop the history of files that
+/// are no longer tracked. Failures are logged, never surfaced: history must
+/// not make a config save fail.
+pub(crate) fn record(path: &Path, previous: Option<&str>, content: &str) {
+    let locations = locations();
+    if !recording_enabled() || !is_tracked_in(&locations, path) {
+        return;
+    }
+    if let Err(e) = record_in(&locations.history, path, previous, content) {
+        eprintln!(
+            "[ConfigHistory] Failed to snapshot {}: {e:#}",
+            path.display()
+        );
+    }
+    forget_untracked_in(&locations);
+}
+
+fn version_time(id: &str) -> String {
+    let stamp = id.split('-').next().unwrap_or(id);
+    chrono::NaiveDateTime::parse_from_str(stamp, VERSION_FORMAT)
+        .map(|t| t.and_utc().to_rfc3339())
+        .unwrap_or_default()
+}
+
+pub(crate) fn list_files_in(root: &Path) -> Result<Vec<ConfigHistoryFile>> {
+    let Ok(entries) = fs::read_dir(root) else {
+        return Ok(Vec::new());
+    };
+    let mut files = Vec::new();
+    for entry in entries.flatten() {
+        let dir = entry.path();
+        let Ok(source) = fs::read_to_string(dir.join(SOURCE_FILE)) else {
+            continue;
+        };
+        let ids = version_ids(&dir);
+        let Some(latest) = ids.last() else {
+            continue;
+        };
+        files.push(ConfigHistoryFile {
+            exists: Path::new(&source).exists(),
+            versions: ids.len(),
+            latest: version_time(latest),
+            path: source,
+        });
+    }
+    files.sort_by(|a, b| b.latest.cmp(&a.latest));
+    Ok(files)
+}
+
+/// Delete the history of files that are no longer tracked, such as
+/// credentials snapshotted before tracking was narrowed.
+fn forget_untracked_in(locations: &Locations) {
+    let Ok(entries) = fs::read_dir(&locations.history) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let dir = entry.path();
+        let Ok(source) = fs::read_to_string(dir.join(SOURCE_FILE)) else {
+            continue;
+        };
+        if !is_tracked_in(locations, Path::new(&source)) {
+            if let Err(e) = fs::remove_dir_all(&dir) {
+                eprintln!("[ConfigHistory] Failed to remove {}: {e}", dir.display());
+            }
+        }
+    }
+}
+
+pub fn list_files() -> Result<Vec<ConfigHistoryFile>> {
+    list_files_in(&history_dir())
+}
+
+/// Versions of `path`, newest first.
+pub(crate) fn list_versions_in(root: &Path, path: &Path) -> Result<Vec<ConfigVersion>> {
+    let dir = entry_dir(root, path);
+    let current = fs::read_to_string(path).ok();
+    let mut versions = Vec::new();
+    for id in version_ids(&dir).into_iter().rev() {
+        let content = fs::read_to_string(snapshot_path(&dir, &id))?;
+        versions.push(ConfigVersion {
+            created_at: version_time(&id),
+            size: content.len() as u64,
+            current: current.as_deref() == Some(content.as_str()),
+            id,
+        });
+    }
+    Ok(versions)
+}
+
+pub fn list_versions(path: &str) -> Result<Vec<ConfigVersion>> {
+    list_versions_in(&history_dir(), Path::new(path))
+}
+
+fn read_version(root: &Path, path: &Path, id: &str) -> Result<String> {
+    if id.is_empty() || id.contains(['/', '\\']) || id.contains("..") {
+        bail!("Invalid version id \"{id}\"");
+    }
+    let snapshot = snapshot_path(&entry_dir(root, path), id);
+    fs::read_to_string(&snapshot).with_context(|| format!("No version {id} of {}", path.display()))
+}
+
+/// Line diff from longest common subsequence; config files are small.
+pub(crate) fn diff_lines(old: &str, new: &str) -> Vec<ConfigDiffLine> {
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+    let line = |kind, text: &str| ConfigDiffLine {
+        kind,
+        text: text.to_string(),
+    };
+    if a.len().saturating_mul(b.len()) > MAX_DIFF_CELLS {
+        return a
+            .iter()
+            .map(|t| line(ConfigDiffLineKind::Removed, t))
+            .chain(b.iter().map(|t| line(ConfigDiffLineKind::Added, t)))
+            .collect();
+    }
+
+    // lcs[i][j] = common lines in a[i..] and b[j..]
+    let mut lcs = vec![vec![0u32; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut lines = Vec::new();
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            lines.push(line(ConfigDiffLineKind::Context, a[i]));
+            i += 1;
+            j += 1;
+        } else if i < a.len() && (j == b.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            lines.push(line(ConfigDiffLineKind::Removed, a[i]));
+            i += 1;
+        } else {
+            lines.push(line(ConfigDiffLineKind::Added, b[j]));
+            j += 1;
+        }
+    }
+    lines
+}
+
+/// Diff two versions; `to: None` compares against the file on disk.
+pub(crate) fn diff_versions_in(
+    root: &Path,
+    path: &Path,
+    from: &str,
+    to: Option<&str>,
+) -> Result<Vec<ConfigDiffLine>> {
+    let old = read_version(root, path, from)?;
+    let new = match to {
+        Some(id) => read_version(root, path, id)?,
+        None => fs::read_to_string(path).unwrap_or_default(),
+    };
+    Ok(diff_lines(&old, &new))
+}
+
+pub fn diff_versions(path: &str, from: &str, to: Option<&str>) -> Result<Vec<ConfigDiffLine>> {
+    diff_versions_in(&history_dir(), Path::new(path), from, to)
+}
+
+/// Write a version back; the restore itself becomes the newest version.
+pub fn restore_version(path: &str, id: &str) -> Result<()> {
+    let path = Path::new(path);
+    if !is_tracked(path) {
+        bail!("{} has no version history", path.display());
+    }
+    let content = read_version(&history_dir(), path, id)?;
+    paths::atomic_write(path, &content)?;
+    eprintln!("[ConfigHistory] Restored {} to {id}", path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn kinds(lines: &[ConfigDiffLine]) -> String {
+        lines
+            .iter()
+            .map(|l| match l.kind {
+                ConfigDiffLineKind::Context => ' ',
+                ConfigDiffLineKind::Added => '+',
+                ConfigDiffLineKind::Removed => '-',
+            })
+            .collect()
+    }
+
+    #[test]
+    fn records_original_then_each_distinct_write() {
+        let root = tempdir().unwrap();
+        let file = root.path().join("settings.json");
+
+        record_in(root.path(), &file, Some("original"), "one").unwrap();
+        record_in(root.path(), &file, Some("one"), "one").unwrap();
+        fs::write(&file, "two").unwrap();
+        record_in(root.path(), &file, Some("one"), "two").unwrap();
+
+        let versions = list_versions_in(root.path(), &file).unwrap();
+        assert_eq!(versions.len(), 3);
+        assert!(versions[0].current);
+        assert!(!versions[1].current);
+        assert!(versions[0].id > versions[2].id);
+        assert!(versions[0].created_at.starts_with("20"));
+        let oldest = read_version(root.path(), &file, &versions[2].id).unwrap();
+        assert_eq!(oldest, "original");
+
+        let files = list_files_in(root.path()).unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, file.to_string_lossy());
+        assert_eq!(files[0].versions, 3);
+        assert!(files[0].exists);
+    }
+
+    #[test]
+    fn prunes_oldest_versions() {
+        let root = tempdir().unwrap();
+        let file = root.path().join("config.toml");
+        for n in 0..MAX_VERSIONS_PER_FILE + 5 {
+            record_in(root.path(), &file, None, &format!("v{n}")).unwrap();
+        }
+        let versions = list_versions_in(root.path(), &file).unwrap();
+        assert_eq!(versions.len(), MAX_VERSIONS_PER_FILE);
+        let oldest = read_version(root.path(), &file, &versions.last().unwrap().id).unwrap();
+        assert_eq!(oldest, "v5");
+    }
+
+    #[test]
+    fn diffs_versions_and_the_current_file() {
+        let root = tempdir().unwrap();
+        let file = root.path().join("a.json");
+        record_in(root.path(), &file, None, "a\nb\nc\n").unwrap();
+        record_in(root.path(), &file, None, "a\nc\nd\n").unwrap();
+        fs::write(&file, "a\nc\nd\ne\n").unwrap();
+
+        let versions = list_versions_in(root.path(), &file).unwrap();
+        let (newer, older) = (&versions[0].id, &versions[1].id);
+        let diff = diff_versions_in(root.path(), &file, older, Some(newer)).unwrap();
+        assert_eq!(kinds(&diff), " - +");
+        assert_eq!(diff[1].text, "b");
+        assert_eq!(diff[3].text, "d");
+
+        let to_disk = diff_versions_in(root.path(), &file, newer, None).unwrap();
+        assert_eq!(kinds(&to_disk), "   +");
+
+        assert!(read_version(root.path(), &file, "../../etc/passwd").is_err());
+    }
+
+    #[test]
+    fn keeps_paths_with_the_same_name_apart() {
+        let root = tempdir().unwrap();
+        let a = Path::new("/one/settings.json");
+        let b = Path::new("/two/settings.json");
+        assert_ne!(entry_dir(root.path(), a), entry_dir(root.path(), b));
+    }
+
+    fn test_locations(home: &Path) -> Locations {
+        Locations {
+            history: home.join(".workbench/history"),
+            claude_user_dir: home.join(".claude"),
+            claude_user_config: home.join(".claude.json"),
+            codex_config_dir: home.join(".codex"),
+            workbench_dir: home.join(".workbench"),
+        }
+    }
+
+    #[test]
+    fn tracks_config_but_not_credentials_or_state() {
+        let home = tempdir().unwrap();
+        let locations = test_locations(home.path());
+        let tracked = |path: &str| is_tracked_in(&locations, &home.path().join(path));
+
+        assert!(tracked(".claude/settings.json"));
+        assert!(tracked("repo/.claude/settings.json"));
+        assert!(tracked("repo/.claude/settings.local.json"));
+        assert!(tracked(".claude.json"));
+        assert!(tracked("repo/.mcp.json"));
+        assert!(tracked(".codex/config.toml"));
+        assert!(tracked(".workbench/projects.json"));
+        assert!(tracked(".workbench/workspaces.json"));
+        assert!(tracked(".workbench/trello/projects/-repo.json"));
+        assert!(!tracked(".workbench/trello/credentials.json"));
+        assert!(!tracked(".workbench/codex-integration.json"));
+        assert!(!tracked(".workbench/history/x/source"));
+        assert!(!tracked(".claude/plugins/installed_plugins.json"));
+        assert!(!tracked("repo/.claude/agents/reviewer.md"));
+    }
+
+    #[test]
+    fn atomic_writes_to_tracked_files_are_snapshotted_privately() {
+        let home = tempdir().unwrap();
+        let locations = test_locations(home.path());
+        TEST_LOCATIONS.with(|l| *l.borrow_mut() = Some(locations.clone()));
+        let settings = home.path().join(".claude/settings.json");
+        let credentials = home.path().join(".workbench/trello/credentials.json");
+        fs::create_dir_all(settings.parent().unwrap()).unwrap();
+        fs::write(&settings, "{}").unwrap();
+
+        paths::atomic_write(&settings, "{\"model\": \"opus\"}").unwrap();
+        paths::atomic_write(&credentials, "{\"token\": \"secret\"}").unwrap();
+
+        let versions = list_versions_in(&locations.history, &settings).unwrap();
+        assert_eq!(versions.len(), 2);
+        assert!(versions[0].current);
+        assert!(list_versions_in(&locations.history, &credentials)
+            .unwrap()
+            .is_empty());
+        assert!(restore_version(&credentials.to_string_lossy(), "x").is_err());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let dir = entry_dir(&locations.history, &settings);
+            let snapshot = snapshot_path(&dir, &versions[0].id);
+            let mode = fs::metadata(snapshot).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        // History recorded before credentials were excluded is dropped on
+        // the next write, not by listing.
+        record_in(&locations.history, &credentials, None, "{}").unwrap();
+        assert_eq!(list_files_in(&locations.history).unwrap().len(), 2);
+        paths::atomic_write(&settings, "{}").unwrap();
+        assert_eq!(list_files_in(&locations.history).unwrap().len(), 1);
+        TEST_LOCATIONS.with(|l| *l.borrow_mut() = None);
+    }
+}
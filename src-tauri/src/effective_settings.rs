@@ -0,0 +1,378 @@
+/// Claude settings merged across every scope, the way Claude Code resolves
+/// them, with a record of which file supplied each value.
+use anyhow::Result;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::paths;
+use crate::settings;
+use crate::types::{EffectiveClaudeSettings, SettingProvenance, SettingsSourceInfo};
+
+pub(crate) const MANAGED_SCOPE: &str = "managed";
+
+/// Scopes from lowest to highest precedence. Managed (enterprise) settings
+/// cannot be overridden by anything else.
+const PRECEDENCE: &[&str] = &[
+    "user",
+    "user-local",
+    "project",
+    "project-local",
+    MANAGED_SCOPE,
+];
+
+#[cfg(target_os = "macos")]
+fn managed_settings_path() -> PathBuf {
+    PathBuf::from("/Library/Application Support/ClaudeCode/managed-settings.json")
+}
+
+#[cfg(windows)]
+fn managed_settings_path() -> PathBuf {
+    PathBuf::from(r"C:\ProgramData\ClaudeCode\managed-settings.json")
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn managed_settings_path() -> PathBuf {
+    PathBuf::from("/etc/claude-code/managed-settings.json")
+}
+
+/// Where a scope's settings live. The user directory and managed file are
+/// passed in so tests never read the real ones.
+fn scope_path(
+    user_dir: &Path,
+    managed_path: &Path,
+    scope: &str,
+    project_path: Option<&str>,
+) -> Result<PathBuf> {
+    if scope == MANAGED_SCOPE {
+        return Ok(managed_path.to_path_buf());
+    }
+    settings::settings_path_in(user_dir, scope, project_path)
+}
+
+/// Read one scope's file. Missing files contribute nothing; unreadable or
+/// invalid ones are reported on the source and skipped, as Claude does.
+fn read_source(scope: &str, path: &Path) -> (SettingsSourceInfo, Option<Value>) {
+    let mut info = SettingsSourceInfo {
+        scope: scope.to_string(),
+        path: path.to_string_lossy().to_string(),
+        exists: path.exists(),
+        error: None,
+    };
+    if !info.exists {
+        return (info, None);
+    }
+    let parsed = fs::read_to_string(path)
+        .map_err(anyhow::Error::from)
+        .and_then(|content| Ok(serde_json::from_str::<Value>(&content)?));
+    match parsed {
+        Ok(value) if value.is_object() => (info, Some(value)),
+        Ok(_) => {
+            info.error = Some("Settings file is not a JSON object".to_string());
+            (info, None)
+        }
+        Err(e) => {
+            info.error = Some(e.to_string());
+            (info, None)
+        }
+    }
+}
+
+/// Merge every settings scope for `project_path` (user scopes only when
+/// `None`) in Claude's precedence order.
+pub fn load_effective_settings(project_path: Option<&str>) -> Result<EffectiveClaudeSettings> {
+    load_effective_settings_in(
+        &paths::claude_user_dir(),
+        &managed_settings_path(),
+        project_path,
+    )
+}
+
+fn load_effective_settings_in(
+    user_dir: &Path,
+    managed_path: &Path,
+    project_path: Option<&str>,
+) -> Result<EffectiveClaudeSettings> {
+    let mut sources = Vec::new();
+    let mut layers = Vec::new();
+    for scope in PRECEDENCE {
+        if scope.starts_with("project") && project_path.is_none() {
+            continue;
+        }
+        let path = scope_path(user_dir, managed_path, scope, project_path)?;
+        let (info, value) = read_source(scope, &path);
+        sources.push(info);
+        if let Some(value) = value {
+            layers.push((*scope, value));
+        }
+    }
+    let (settings, provenance) = merge_layers(&layers);
+    Ok(EffectiveClaudeSettings {
+        settings,
+        sources,
+        provenance,
+    })
+}
+
+/// Merge `(scope, value)` layers given lowest precedence first. Objects merge
+/// key by key, arrays concatenate without duplicates, and any other value
+/// from a later layer replaces what came before.
+pub(crate) fn merge_layers(layers: &[(&str, Value)]) -> (Value, Vec<SettingProvenance>) {
+    let mut merged = Value::Object(Map::new());
+    let mut provenance = BTreeMap::new();
+    for (scope, value) in layers {
+        merge_into(&mut merged, value, "", scope, &mut provenance);
+    }
+    (merged, provenance.into_values().collect())
+}
+
+fn merge_into(
+    target: &mut Value,
+    source: &Value,
+    pointer: &str,
+    scope: &str,
+    provenance: &mut BTreeMap<String, SettingProvenance>,
+) {
+    match (&mut *target, source) {
+        (Value::Object(existing), Value::Object(incoming)) => {
+            for (key, value) in incoming {
+                let child = child_pointer(pointer, key);
+                match existing.get_mut(key) {
+                    Some(slot) => merge_into(slot, value, &child, scope, provenance),
+                    None => {
+                        existing.insert(key.clone(), value.clone());
+                        record_new(value, &child, scope, provenance);
+                    }
+                }
+            }
+        }
+        (Value::Array(existing), Value::Array(incoming)) => {
+            for value in incoming {
+                if existing.contains(value) {
+                    continue;
+                }
+                let child = child_pointer(pointer, &existing.len().to_string());
+                existing.push(value.clone());
+                record_new(value, &child, scope, provenance);
+            }
+        }
+        _ => {
+            let overridden = take_scopes_under(pointer, provenance);
+            *target = source.clone();
+            record_new(source, pointer, scope, provenance);
+            if let Some(entry) = provenance.get_mut(pointer) {
+                entry.overridden_scopes = overridden;
+            } else if !overridden.is_empty() {
+                provenance.insert(
+                    pointer.to_string(),
+                    SettingProvenance {
+                        pointer: pointer.to_string(),
+                        scope: scope.to_string(),
+                        overridden_scopes: overridden,
+                    },
+                );
+            }
+        }
+    }
+}
+
+/// Attribute every leaf of a freshly inserted value to `scope`. Empty objects
+/// and arrays count as leaves so they still show where they came from.
+fn record_new(
+    value: &Value,
+    pointer: &str,
+    scope: &str,
+    provenance: &mut BTreeMap<String, SettingProvenance>,
+) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, child) in map {
+                record_new(child, &child_pointer(pointer, key), scope, provenance);
+            }
+        }
+        Value::Array(items) if !items.is_empty() => {
+            for (i, child) in items.iter().enumerate() {
+                record_new(
+                    child,
+                    &child_pointer(pointer, &i.to_string()),
+                    scope,
+                    provenance,
+                );
+            }
+        }
+        _ => {
+            provenance.insert(
+                pointer.to_string(),
+                SettingProvenance {
+                    pointer: pointer.to_string(),
+                    scope: scope.to_string(),
+                    overridden_scopes: Vec::new(),
+                },
+            );
+        }
+    }
+}
+
+/// Remove the entries at or below `pointer`, returning the scopes they came
+/// from (including anything they had themselves overridden).
+fn take_scopes_under(
+    pointer: &str,
+    provenance: &mut BTreeMap<String, SettingProvenance>,
+) -> Vec<String> {
+    let prefix = format!("{pointer}/");
+    let keys: Vec<String> = provenance
+        .keys()
+        .filter(|k| k.as_str() == pointer || k.starts_with(&prefix))
+        .cloned()
+        .collect();
+    let mut scopes: Vec<String> = Vec::new();
+    for key in keys {
+        let Some(entry) = provenance.remove(&key) else {
+            continue;
+        };
+        for scope in entry.overridden_scopes.into_iter().chain([entry.scope]) {
+            if !scopes.contains(&scope) {
+                scopes.push(scope);
+            }
+        }
+    }
+    scopes
+}
+
+/// Append one reference token to a JSON pointer (RFC 6901 escaping).
+pub(crate) fn child_pointer(pointer: &str, token: &str) -> String {
+    format!("{pointer}/{}", token.replace('~', "~0").replace('/', "~1"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn entry<'a>(provenance: &'a [SettingProvenance], pointer: &str) -> &'a SettingProvenance {
+        provenance
+            .iter()
+            .find(|p| p.pointer == pointer)
+            .unwrap_or_else(|| panic!("no provenance for {pointer}"))
+    }
+
+    #[test]
+    fn later_scopes_override_scalars_and_are_flagged() {
+        let (merged, provenance) = merge_layers(&[
+            (
+                "user",
+                json!({"model": "sonnet", "env": {"A": "1", "B": "2"}}),
+            ),
+            ("project", json!({"model": "opus", "env": {"B": "3"}})),
+        ]);
+
+        assert_eq!(
+            merged,
+            json!({"model": "opus", "env": {"A": "1", "B": "3"}})
+        );
+        let model = entry(&provenance, "/model");
+        assert_eq!(model.scope, "project");
+        assert_eq!(model.overridden_scopes, ["user"]);
+        let a = entry(&provenance, "/env/A");
+        assert_eq!(a.scope, "user");
+        assert!(a.overridden_scopes.is_empty());
+        assert_eq!(entry(&provenance, "/env/B").overridden_scopes, ["user"]);
+    }
+
+    #[test]
+    fn arrays_concatenate_without_duplicates() {
+        let (merged, provenance) = merge_layers(&[
+            (
+                "user",
+                json!({"permissions": {"allow": ["Read", "Bash(git:*)"]}}),
+            ),
+            (
+                "project-local",
+                json!({"permissions": {"allow": ["Bash(git:*)", "Edit"]}}),
+            ),
+        ]);
+
+        assert_eq!(
+            merged["permissions"]["allow"],
+            json!(["Read", "Bash(git:*)", "Edit"])
+        );
+        assert_eq!(entry(&provenance, "/permissions/allow/1").scope, "user");
+        assert_eq!(
+            entry(&provenance, "/permissions/allow/2").scope,
+            "project-local"
+        );
+    }
+
+    #[test]
+    fn replacing_an_object_with_a_scalar_collects_every_overridden_scope() {
+        let (merged, provenance) = merge_layers(&[
+            ("user", json!({"sandbox": {"enabled": true}})),
+            ("user-local", json!({"sandbox": {"network": {"x": 1}}})),
+            ("managed", json!({"sandbox": false})),
+        ]);
+
+        assert_eq!(merged, json!({"sandbox": false}));
+        assert_eq!(provenance.len(), 1);
+        let sandbox = entry(&provenance, "/sandbox");
+        assert_eq!(sandbox.scope, "managed");
+        assert_eq!(sandbox.overridden_scopes, ["user", "user-local"]);
+    }
+
+    #[test]
+    fn pointers_escape_slashes_and_tildes() {
+        let (_, provenance) = merge_layers(&[("user", json!({"env": {"a/b~c": "1"}}))]);
+        assert_eq!(provenance[0].pointer, "/env/a~1b~0c");
+    }
+
+    #[test]
+    fn loads_project_scopes_and_reports_invalid_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().to_str().unwrap();
+        let claude_dir = dir.path().join(".claude");
+        fs::create_dir_all(&claude_dir).unwrap();
+        fs::write(claude_dir.join("settings.json"), r#"{"model": "opus"}"#).unwrap();
+        fs::write(claude_dir.join("settings.local.json"), "{ not json").unwrap();
+        let user_dir = dir.path().join("home");
+        fs::create_dir_all(&user_dir).unwrap();
+        fs::write(
+            user_dir.join("settings.json"),
+            r#"{"model": "sonnet", "cleanupPeriodDays": 30}"#,
+        )
+        .unwrap();
+        let managed = dir.path().join("managed-settings.json");
+        fs::write(&managed, r#"{"cleanupPeriodDays": 7}"#).unwrap();
+
+        let effective = load_effective_settings_in(&user_dir, &managed, Some(project)).unwrap();
+
+        let project_source = effective
+            .sources
+            .iter()
+            .find(|s| s.scope == "project")
+            .unwrap();
+        assert!(project_source.exists);
+        assert!(project_source.error.is_none());
+        let local_source = effective
+            .sources
+            .iter()
+            .find(|s| s.scope == "project-local")
+            .unwrap();
+        assert!(local_source.error.is_some());
+        assert_eq!(effective.settings["model"], "opus");
+        assert_eq!(entry(&effective.provenance, "/model").scope, "project");
+        assert_eq!(effective.settings["cleanupPeriodDays"], 7);
+        assert_eq!(
+            entry(&effective.provenance, "/cleanupPeriodDays").scope,
+            MANAGED_SCOPE
+        );
+    }
+
+    #[test]
+    fn user_only_view_skips_project_scopes() {
+        let dir = tempfile::tempdir().unwrap();
+        let managed = dir.path().join("managed-settings.json");
+        let effective = load_effective_settings_in(dir.path(), &managed, None).unwrap();
+        let scopes: Vec<&str> = effective.sources.iter().map(|s| s.scope.as_str()).collect();
+        assert_eq!(scopes, ["user", "user-local", "managed"]);
+    }
+}
@@ -0,0 +1,436 @@
+/// YAML frontmatter for Markdown files such as SKILL.md, subagents and slash
+/// commands. Supports the subset these files use: `key: value` scalars
+/// (plain, quoted, or `|`/`>` blocks), flow lists `[a, b]` and block lists.
+/// Entries keep their original lines so rewriting one key leaves the rest of
+/// the frontmatter byte-for-byte intact.
+use anyhow::{bail, Result};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum FrontmatterValue {
+    Scalar(String),
+    List(Vec<String>),
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    key: String,
+    value: FrontmatterValue,
+    /// Source lines, including trailing comments and blank lines
+    lines: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Frontmatter {
+    /// Comments or blank lines before the first key
+    leading: Vec<String>,
+    entries: Vec<Entry>,
+}
+
+/// Split a document into its frontmatter text and body. Documents without a
+/// leading `---` block have no frontmatter.
+pub(crate) fn split(content: &str) -> (Option<&str>, &str) {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let Some(rest) = content
+        .strip_prefix("---\n")
+        .or_else(|| content.strip_prefix("---\r\n"))
+    else {
+        return (None, content);
+    };
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            return (Some(&rest[..offset]), &rest[offset + line.len()..]);
+        }
+        offset += line.len();
+    }
+    (None, content)
+}
+
+/// Parse a whole document: frontmatter (empty when absent) and body.
+pub(crate) fn parse_document(content: &str) -> Result<(Frontmatter, String)> {
+    let (frontmatter, body) = split(content);
+    let frontmatter = match frontmatter {
+        Some(text) => parse(text)?,
+        None => Frontmatter::default(),
+    };
+    Ok((frontmatter, body.to_string()))
+}
+
+pub(crate) fn render_document(frontmatter: &Frontmatter, body: &str) -> String {
+    let mut out = String::from("---\n");
+    out.push_str(&frontmatter.render());
+    out.push_str("---\n");
+    out.push_str(body);
+    out
+}
+
+fn is_indented(line: &str) -> bool {
+    line.starts_with(' ') || line.starts_with('\t')
+}
+
+fn is_blank_or_comment(line: &str) -> bool {
+    let trimmed = line.trim();
+    trimmed.is_empty() || trimmed.starts_with('#')
+}
+
+pub(crate) fn parse(text: &str) -> Result<Frontmatter> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut frontmatter = Frontmatter::default();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        if is_blank_or_comment(line) {
+            match frontmatter.entries.last_mut() {
+                Some(entry) => entry.lines.push(line.to_string()),
+                None => frontmatter.leading.push(line.to_string()),
+            }
+            i += 1;
+            continue;
+        }
+        if is_indented(line) {
+            bail!("Line {}: unexpected indentation", i + 1);
+        }
+        let Some((key, rest)) = line.split_once(':') else {
+            bail!("Line {}: expected `key: value`", i + 1);
+        };
+        let key = key.trim().to_string();
+        if key.is_empty() {
+            bail!("Line {}: missing key", i + 1);
+        }
+        if frontmatter.entries.iter().any(|e| e.key == key) {
+            bail!("Line {}: duplicate key \"{key}\"", i + 1);
+        }
+
+        // Indented lines (and block list items) belong to this entry.
+        let start = i;
+        i += 1;
+        let belongs = |line: &str| is_indented(line) || line.starts_with("- ") || line == "-";
+        while i < lines.len() {
+            if belongs(lines[i]) {
+                i += 1;
+                continue;
+            }
+            // Blank lines inside a block scalar or list.
+            let next = lines[i..].iter().position(|l| !l.trim().is_empty());
+            match next {
+                Some(offset) if offset > 0 && belongs(lines[i + offset]) => i += offset,
+                _ => break,
+            }
+        }
+        let continuation = &lines[start + 1..i];
+        let value = parse_value(rest.trim(), continuation)
+            .map_err(|e| anyhow::anyhow!("Line {}: {e}", start + 1))?;
+        frontmatter.entries.push(Entry {
+            key,
+            value,
+            lines: lines[start..i].iter().map(|l| l.to_string()).collect(),
+        });
+    }
+    Ok(frontmatter)
+}
+
+fn parse_value(rest: &str, continuation: &[&str]) -> Result<FrontmatterValue, String> {
+    let rest = strip_comment(rest);
+    if rest.is_empty() {
+        let items: Vec<&str> = continuation
+            .iter()
+            .map(|l| l.trim())
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+            .collect();
+        if items.is_empty() {
+            return Ok(FrontmatterValue::Scalar(String::new()));
+        }
+        return items
+            .iter()
+            .map(|item| {
+                let item = item.strip_prefix('-').ok_or("expected a `- item` list")?;
+                unquote(strip_comment(item.trim()))
+            })
+            .collect::<Result<_, _>>()
+            .map(FrontmatterValue::List);
+    }
+    if let Some(style) = rest.strip_prefix(['|', '>']) {
+        if !matches!(style, "" | "-" | "+") {
+            return Err("unsupported block scalar indicator".to_string());
+        }
+        let block: Vec<&str> = continuation.iter().map(|l| l.trim()).collect();
+        let separator = if rest.starts_with('|') { "\n" } else { " " };
+        let text = block.join(separator);
+        return Ok(FrontmatterValue::Scalar(text.trim_end().to_string()));
+    }
+    if let Some(inner) = rest.strip_prefix('[') {
+        let inner = inner.strip_suffix(']').ok_or("unterminated flow list")?;
+        return split_flow(inner)
+            .into_iter()
+            .filter(|item| !item.trim().is_empty())
+            .map(|item| unquote(item.trim()))
+            .collect::<Result<_, _>>()
+            .map(FrontmatterValue::List);
+    }
+    let mut text = unquote(rest)?;
+    // Plain scalars may fold onto indented continuation lines.
+    for line in continuation {
+        let line = line.trim();
+        if !line.is_empty() {
+            text.push(' ');
+            text.push_str(line);
+        }
+    }
+    Ok(FrontmatterValue::Scalar(text))
+}
+
+/// Drop a trailing ` # comment` outside quotes.
+fn strip_comment(value: &str) -> &str {
+    let mut quote = None;
+    let bytes = value.as_bytes();
+    for (i, &b) in bytes.iter().enumerate() {
+        match quote {
+            Some(q) if b == q => quote = None,
+            Some(_) => {}
+            None if b == b'"' || b == b'\'' => quote = Some(b),
+            None if b == b'#' && (i == 0 || bytes[i - 1] == b' ') => {
+                return value[..i].trim_end();
+            }
+            None => {}
+        }
+    }
+    value
+}
+
+fn split_flow(inner: &str) -> Vec<&str> {
+    let mut items = Vec::new();
+    let mut quote = None;
+    let mut start = 0;
+    for (i, c) in inner.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == ',' => {
+                items.push(&inner[start..i]);
+                start = i + 1;
+            }
+            None => {}
+        }
+    }
+    items.push(&inner[start..]);
+    items
+}
+
+fn unquote(value: &str) -> Result<String, String> {
+    if let Some(inner) = value.strip_prefix('"') {
+        let inner = inner
+            .strip_suffix('"')
+            .ok_or("unterminated double-quoted string")?;
+        let mut out = String::new();
+        let mut chars = inner.chars();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                out.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('n') => out.push('\n'),
+                Some('t') => out.push('\t'),
+                Some(other) => out.push(other),
+                None => return Err("dangling escape".to_string()),
+            }
+        }
+        return Ok(out);
+    }
+    if let Some(inner) = value.strip_prefix('\'') {
+        let inner = inner
+            .strip_suffix('\'')
+            .ok_or("unterminated single-quoted string")?;
+        return Ok(inner.replace("''", "'"));
+    }
+    Ok(value.to_string())
+}
+
+/// Quote a scalar when writing it plain would change its meaning.
+fn format_scalar(value: &str) -> String {
+    let needs_quotes = value.is_empty()
+        || value != value.trim()
+        || value.contains(": ")
+        || value.contains(" #")
+        || value.contains('\n')
+        || value.ends_with(':')
+        || value.starts_with([
+            '[', ']', '{', '}', '&', '*', '!', '|', '>', '\'', '"', '%', '@', '`', '#', '-', '?',
+            ',',
+        ])
+        || matches!(
+            value.to_ascii_lowercase().as_str(),
+            "true" | "false" | "yes" | "no" | "null" | "~"
+        )
+        || value.parse::<f64>().is_ok();
+    if !needs_quotes {
+        return value.to_string();
+    }
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t");
+    format!("\"{escaped}\"")
+}
+
+impl Frontmatter {
+    pub fn get(&self, key: &str) -> Option<&FrontmatterValue> {
+        self.entries.iter().find(|e| e.key == key).map(|e| &e.value)
+    }
+
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        match self.get(key)? {
+            FrontmatterValue::Scalar(value) => Some(value),
+            FrontmatterValue::List(_) => None,
+        }
+    }
+
+    /// A list, or a comma-separated scalar such as `tools: Read, Grep`.
+    pub fn get_list(&self, key: &str) -> Vec<String> {
+        match self.get(key) {
+            Some(FrontmatterValue::List(items)) => items.clone(),
+            Some(FrontmatterValue::Scalar(value)) => value
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(str::to_string)
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Set a scalar, keeping the key's position if it already exists.
+    pub fn set_str(&mut self, key: &str, value: &str) {
+        if self.get_str(key) == Some(value) {
+            return;
+        }
+        let line = format!("{key}: {}", format_scalar(value));
+        match self.entries.iter_mut().find(|e| e.key == key) {
+            Some(entry) => {
+                // Keep comments and blank lines that trailed the old value.
+                let trailing: Vec<String> = entry
+                    .lines
+                    .iter()
+                    .skip(1)
+                    .rev()
+                    .take_while(|l| is_blank_or_comment(l))
+                    .cloned()
+                    .collect();
+                entry.value = FrontmatterValue::Scalar(value.to_string());
+                entry.lines = std::iter::once(line)
+                    .chain(trailing.into_iter().rev())
+                    .collect();
+            }
+            None => self.entries.push(Entry {
+                key: key.to_string(),
+                value: FrontmatterValue::Scalar(value.to_string()),
+                lines: vec![line],
+            }),
+        }
+    }
+
+    /// Set `key` to a comma-separated list, or remove it when empty.
+    pub fn set_list(&mut self, key: &str, items: &[String]) {
+        if self.get(key).is_some() && self.get_list(key) == items {
+            return;
+        }
+        if items.is_empty() {
+            self.remove(key);
+        } else {
+            self.set_str(key, &items.join(", "));
+        }
+    }
+
+    /// Set `key` when `value` is non-empty, otherwise remove it.
+    pub fn set_optional(&mut self, key: &str, value: Option<&str>) {
+        match value.map(str::trim).filter(|v| !v.is_empty()) {
+            Some(value) => self.set_str(key, value),
+            None => self.remove(key),
+        }
+    }
+
+    pub fn remove(&mut self, key: &str) {
+        self.entries.retain(|e| e.key != key);
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for line in self
+            .leading
+            .iter()
+            .chain(self.entries.iter().flat_map(|e| e.lines.iter()))
+        {
+            out.push_str(line);
+            out.push('\n');
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_scalars_lists_and_blocks() {
+        let doc = "---\nname: pdf-tools\ndescription: \"Fill forms: fast\" # note\nallowed-tools: [Read, 'Bash(git:*)']\ntools:\n  - Read\n  - Grep\nnotes: |\n  line one\n  line two\nsummary: folded\n  onto two lines\n---\n# Body\n";
+        let (fm, body) = parse_document(doc).unwrap();
+
+        assert_eq!(fm.get_str("name"), Some("pdf-tools"));
+        assert_eq!(fm.get_str("description"), Some("Fill forms: fast"));
+        assert_eq!(fm.get_list("allowed-tools"), ["Read", "Bash(git:*)"]);
+        assert_eq!(fm.get_list("tools"), ["Read", "Grep"]);
+        assert_eq!(fm.get_str("notes"), Some("line one\nline two"));
+        assert_eq!(fm.get_str("summary"), Some("folded onto two lines"));
+        assert_eq!(body, "# Body\n");
+    }
+
+    #[test]
+    fn comma_separated_scalars_read_as_lists() {
+        let (fm, _) = parse_document("---\ntools: Read, Grep,Glob\n---\n").unwrap();
+        assert_eq!(fm.get_list("tools"), ["Read", "Grep", "Glob"]);
+    }
+
+    #[test]
+    fn documents_without_frontmatter_are_all_body() {
+        let (fm, body) = parse_document("# Title\n---\n").unwrap();
+        assert!(fm.render().is_empty());
+        assert_eq!(body, "# Title\n---\n");
+    }
+
+    #[test]
+    fn rejects_malformed_frontmatter() {
+        assert!(parse("just text\n").is_err());
+        assert!(parse("name: a\nname: b\n").is_err());
+        assert!(parse("description: \"open\n").is_err());
+        assert!(parse("  indented: x\n").is_err());
+    }
+
+    #[test]
+    fn edits_preserve_other_entries() {
+        let doc = "---\n# leading comment\nname: old\ncustom:\n  - keep  # me\nmodel: sonnet\n---\nbody\n";
+        let (mut fm, body) = parse_document(doc).unwrap();
+
+        fm.set_str("name", "new: name");
+        fm.remove("model");
+        fm.set_list("tools", &["Read".to_string(), "Grep".to_string()]);
+
+        assert_eq!(
+            render_document(&fm, &body),
+            "---\n# leading comment\nname: \"new: name\"\ncustom:\n  - keep  # me\ntools: Read, Grep\n---\nbody\n"
+        );
+        let (reparsed, _) = parse_document(&render_document(&fm, &body)).unwrap();
+        assert_eq!(reparsed.get_str("name"), Some("new: name"));
+    }
+
+    #[test]
+    fn quotes_ambiguous_scalars() {
+        assert_eq!(format_scalar("plain text"), "plain text");
+        assert_eq!(format_scalar("true"), "\"true\"");
+        assert_eq!(format_scalar("1.5"), "\"1.5\"");
+        assert_eq!(format_scalar("- dash"), "\"- dash\"");
+        assert_eq!(format_scalar("say \"hi\"\n"), "\"say \\\"hi\\\"\\n\"");
+    }
+}
@@ -141,7 +141,9 @@ impl GitWatcher {
         // Watch .git/HEAD for branch switches
         let head = git_dir.join("HEAD");
         if head.exists() {
-            watcher.watcher().watch(&head, RecursiveMode::NonRecursive)?;
+            watcher
+                .watcher()
+                .watch(&head, RecursiveMode::NonRecursive)?;
         }
 
         // Watch .git/refs/ for new commits, branches, tags
@@ -153,7 +155,9 @@ impl GitWatcher {
         // Watch .git/index for staging changes
         let index = git_dir.join("index");
         if index.exists() {
-            watcher.watcher().watch(&index, RecursiveMode::NonRecursive)?;
+            watcher
+                .watcher()
+                .watch(&index, RecursiveMode::NonRecursive)?;
         }
 
         watched.insert(path);
@@ -225,7 +229,10 @@ fn normalize_project_paths(project_paths: Vec<String>) -> HashSet<PathBuf> {
         .collect()
 }
 
-fn watch_diff(current: &HashSet<PathBuf>, desired: &HashSet<PathBuf>) -> (Vec<PathBuf>, Vec<PathBuf>) {
+fn watch_diff(
+    current: &HashSet<PathBuf>,
+    desired: &HashSet<PathBuf>,
+) -> (Vec<PathBuf>, Vec<PathBuf>) {
     (
         desired.difference(current).cloned().collect(),
         current.difference(desired).cloned().collect(),
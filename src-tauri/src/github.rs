@@ -253,10 +253,7 @@ pub fn get_project_status(path: &str) -> GitHubProjectStatus {
         let handles: Vec<_> = prs
             .iter()
             .filter(|pr| pr.state == "OPEN")
-            .map(|pr| {
-                let path = path;
-                s.spawn(move || (pr.number, list_pr_checks(path, pr.number)))
-            })
+            .map(|pr| s.spawn(move || (pr.number, list_pr_checks(path, pr.number))))
             .collect();
         handles
             .into_iter()
@@ -417,8 +414,12 @@ pub fn list_repos() -> Result<Vec<GitHubRepo>> {
         ],
         &home.to_string_lossy(),
     )?;
-    serde_json::from_str(&json)
-        .map_err(|e| anyhow::anyhow!("Failed to parse repo list: {e}\nResponse: {}", &json[..json.len().min(500)]))
+    serde_json::from_str(&json).map_err(|e| {
+        anyhow::anyhow!(
+            "Failed to parse repo list: {e}\nResponse: {}",
+            &json[..json.len().min(500)]
+        )
+    })
 }
 
 pub fn checkout_pr(path: &str, pr_number: u64) -> Result<()> {
@@ -427,7 +428,10 @@ pub fn checkout_pr(path: &str, pr_number: u64) -> Result<()> {
 }
 
 pub fn fetch_pr_branch(path: &str, branch: &str) -> Result<()> {
-    crate::git::git_output(&["fetch", "origin", &format!("{}:{}", branch, branch)], path)?;
+    crate::git::git_output(
+        &["fetch", "origin", &format!("{}:{}", branch, branch)],
+        path,
+    )?;
     Ok(())
 }
 
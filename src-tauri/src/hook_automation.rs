@@ -0,0 +1,326 @@
+/// Automation rules evaluated against hook bridge events.
+use regex::Regex;
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+
+use crate::config;
+use crate::types::{default_automation_rules, AutomationAction, AutomationRule};
+
+/// A rule with its `input_pattern` compiled.
+pub struct CompiledRule {
+    rule: AutomationRule,
+    pattern: Option<Regex>,
+}
+
+/// Compiled effective rules, rebuilt after the settings change.
+static RULES: Mutex<Option<Arc<Vec<CompiledRule>>>> = Mutex::new(None);
+
+/// The built-in rules with the user's saved rules applied: a saved rule with a
+/// built-in id replaces that rule, any other saved rule is appended.
+pub fn effective_rules(saved: &[AutomationRule]) -> Vec<AutomationRule> {
+    let mut rules = default_automation_rules();
+    for rule in saved {
+        match rules.iter_mut().find(|builtin| builtin.id == rule.id) {
+            Some(builtin) => *builtin = rule.clone(),
+            None => rules.push(rule.clone()),
+        }
+    }
+    rules
+}
+
+/// Compile rule patterns once. Rules with an invalid pattern never match.
+pub fn compile_rules(rules: &[AutomationRule]) -> Vec<CompiledRule> {
+    rules
+        .iter()
+        .filter_map(|rule| {
+            let pattern = match rule.input_pattern.as_deref().map(Regex::new) {
+                None => None,
+                Some(Ok(regex)) => Some(regex),
+                Some(Err(e)) => {
+                    eprintln!(
+                        "[HookAutomation] Invalid pattern in rule {:?}: {e}",
+                        rule.name
+                    );
+                    return None;
+                }
+            };
+            Some(CompiledRule {
+                rule: rule.clone(),
+                pattern,
+            })
+        })
+        .collect()
+}
+
+/// The compiled effective rules, loading them from settings on first use.
+/// Falls back to the built-in defaults when the settings file cannot be read.
+pub fn load_rules() -> Arc<Vec<CompiledRule>> {
+    let mut cached = RULES.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(rules) = cached.as_ref() {
+        return Arc::clone(rules);
+    }
+    let saved = match config::load_workbench_settings() {
+        Ok(settings) => settings.automation_rules,
+        Err(e) => {
+            eprintln!("[HookAutomation] Failed to load settings: {e}");
+            Vec::new()
+        }
+    };
+    let rules = Arc::new(compile_rules(&effective_rules(&saved)));
+    *cached = Some(Arc::clone(&rules));
+    rules
+}
+
+/// Drop the cached rules so the next event picks up saved settings.
+pub fn invalidate_rules() {
+    *RULES.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Text that a rule's `input_pattern` is matched against: the Bash command
+/// when present, otherwise the tool input serialized as JSON.
+fn tool_input_text(payload: &Value) -> Option<String> {
+    let input = payload.get("tool_input")?;
+    if let Some(command) = input.get("command").and_then(|v| v.as_str()) {
+        return Some(command.to_string());
+    }
+    Some(input.to_string())
+}
+
+fn rule_matches(
+    compiled: &CompiledRule,
+    source: &str,
+    event_name: Option<&str>,
+    payload: &Value,
+) -> bool {
+    let rule = &compiled.rule;
+    if !rule.enabled {
+        return false;
+    }
+    if rule.source.as_deref().is_some_and(|s| s != source) {
+        return false;
+    }
+    if let Some(expected) = rule.event_name.as_deref() {
+        if event_name != Some(expected) {
+            return false;
+        }
+    }
+    if !rule.tool_names.is_empty() {
+        let Some(tool) = payload.get("tool_name").and_then(|v| v.as_str()) else {
+            return false;
+        };
+        if !rule.tool_names.iter().any(|name| name == tool) {
+            return false;
+        }
+    }
+    if let Some(regex) = &compiled.pattern {
+        let Some(text) = tool_input_text(payload) else {
+            return false;
+        };
+        if !regex.is_match(&text) {
+            return false;
+        }
+    }
+    true
+}
+
+/// Collect the actions of every rule matching an event, deduplicated and in
+/// rule order.
+pub fn matching_actions(
+    rules: &[CompiledRule],
+    source: &str,
+    event_name: Option<&str>,
+    payload: &Value,
+) -> Vec<AutomationAction> {
+    let mut actions: Vec<AutomationAction> = Vec::new();
+    for rule in rules {
+        if !rule_matches(rule, source, event_name, payload) {
+            continue;
+        }
+        for action in &rule.rule.actions {
+            if !actions.contains(action) {
+                actions.push(action.clone());
+            }
+        }
+    }
+    actions
+}
+
+/// Refresh trigger name reported to the frontend for a hook payload.
+pub fn refresh_trigger(payload: &Value) -> &'static str {
+    match payload.get("tool_name").and_then(|v| v.as_str()) {
+        Some("Write") => "post-tool-use-write",
+        Some("Edit") => "post-tool-use-edit",
+        Some("NotebookEdit") => "post-tool-use-notebook-edit",
+        Some("Bash") => "post-tool-use-bash",
+        _ => "automation-rule",
+    }
+}
+
+/// Substitute `{event}`, `{tool}` and `{pane}` placeholders in notification text.
+pub fn render_template(
+    template: &str,
+    event_name: Option<&str>,
+    payload: &Value,
+    pane_id: &str,
+) -> String {
+    let tool = payload
+        .get("tool_name")
+        .and_then(|v| v.as_str())
+        .unwrap_or("");
+    template
+        .replace("{event}", event_name.unwrap_or(""))
+        .replace("{tool}", tool)
+        .replace("{pane}", pane_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rule(actions: Vec<AutomationAction>) -> AutomationRule {
+        AutomationRule {
+            id: "r1".into(),
+            name: "Rule".into(),
+            enabled: true,
+            source: None,
+            event_name: None,
+            tool_names: vec![],
+            input_pattern: None,
+            actions,
+        }
+    }
+
+    #[test]
+    fn empty_matchers_match_any_event() {
+        let rules = vec![rule(vec![AutomationAction::RefreshProject])];
+        let actions = matching_actions(
+            &compile_rules(&rules),
+            "codex",
+            Some("agent-turn-complete"),
+            &json!({}),
+        );
+        assert_eq!(actions, vec![AutomationAction::RefreshProject]);
+    }
+
+    #[test]
+    fn disabled_rule_never_matches() {
+        let mut r = rule(vec![AutomationAction::RefreshProject]);
+        r.enabled = false;
+        assert!(
+            matching_actions(&compile_rules(&[r]), "claude", Some("Stop"), &json!({})).is_empty()
+        );
+    }
+
+    #[test]
+    fn source_and_event_must_match() {
+        let mut r = rule(vec![AutomationAction::RefreshProject]);
+        r.source = Some("claude".into());
+        r.event_name = Some("Stop".into());
+        let rules = vec![r];
+
+        assert_eq!(
+            matching_actions(&compile_rules(&rules), "claude", Some("Stop"), &json!({})).len(),
+            1
+        );
+        assert!(
+            matching_actions(&compile_rules(&rules), "codex", Some("Stop"), &json!({})).is_empty()
+        );
+        assert!(matching_actions(
+            &compile_rules(&rules),
+            "claude",
+            Some("Notification"),
+            &json!({})
+        )
+        .is_empty());
+        assert!(matching_actions(&compile_rules(&rules), "claude", None, &json!({})).is_empty());
+    }
+
+    #[test]
+    fn input_pattern_matches_serialized_input_for_non_bash_tools() {
+        let mut r = rule(vec![AutomationAction::RefreshProject]);
+        r.tool_names = vec!["Write".into()];
+        r.input_pattern = Some(r#"\.rs""#.into());
+        let rules = vec![r];
+
+        let payload = json!({
+            "tool_name": "Write",
+            "tool_input": { "file_path": "/repo/src/main.rs" }
+        });
+        assert_eq!(
+            matching_actions(&compile_rules(&rules), "claude", None, &payload).len(),
+            1
+        );
+
+        let payload = json!({
+            "tool_name": "Write",
+            "tool_input": { "file_path": "/repo/README.md" }
+        });
+        assert!(matching_actions(&compile_rules(&rules), "claude", None, &payload).is_empty());
+    }
+
+    #[test]
+    fn invalid_pattern_does_not_match() {
+        let mut r = rule(vec![AutomationAction::RefreshProject]);
+        r.input_pattern = Some("(unclosed".into());
+        let payload = json!({"tool_input": {"command": "(unclosed"}});
+        assert!(matching_actions(&compile_rules(&[r]), "claude", None, &payload).is_empty());
+    }
+
+    #[test]
+    fn actions_are_deduplicated_across_rules() {
+        let rules = vec![
+            rule(vec![AutomationAction::RefreshProject]),
+            rule(vec![
+                AutomationAction::RefreshProject,
+                AutomationAction::RunTask {
+                    task_name: "lint".into(),
+                },
+            ]),
+        ];
+        let actions = matching_actions(&compile_rules(&rules), "claude", None, &json!({}));
+        assert_eq!(actions.len(), 2);
+        assert_eq!(actions[0], AutomationAction::RefreshProject);
+    }
+
+    #[test]
+    fn saved_rules_override_builtins_by_id_and_append_the_rest() {
+        let builtins = default_automation_rules();
+        let mut disabled = builtins[0].clone();
+        disabled.enabled = false;
+        let mut custom = rule(vec![AutomationAction::RefreshProject]);
+        custom.id = "custom".into();
+
+        let rules = effective_rules(&[disabled, custom]);
+        assert_eq!(rules.len(), builtins.len() + 1);
+        assert_eq!(rules[0].id, builtins[0].id);
+        assert!(!rules[0].enabled);
+        assert_eq!(rules[1].id, builtins[1].id);
+        assert_eq!(rules.last().unwrap().id, "custom");
+    }
+
+    #[test]
+    fn render_template_substitutes_placeholders() {
+        let payload = json!({"tool_name": "Bash"});
+        let text = render_template(
+            "{event} ran {tool} in {pane}",
+            Some("PostToolUse"),
+            &payload,
+            "p1",
+        );
+        assert_eq!(text, "PostToolUse ran Bash in p1");
+    }
+
+    #[test]
+    fn refresh_trigger_names_match_tools() {
+        assert_eq!(
+            refresh_trigger(&json!({"tool_name": "Write"})),
+            "post-tool-use-write"
+        );
+        assert_eq!(
+            refresh_trigger(&json!({"tool_name": "Bash"})),
+            "post-tool-use-bash"
+        );
+        assert_eq!(refresh_trigger(&json!({})), "automation-rule");
+    }
+}
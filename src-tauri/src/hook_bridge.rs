@@ -1,14 +1,25 @@
-use std::collections::VecDeque;
-use std::io::{BufRead, BufReader, Read};
-use std::sync::{Arc, Mutex};
+use std::collections::{HashMap, VecDeque};
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::path::Path;
+use std::sync::{mpsc, Arc, Mutex};
 
+use anyhow::{anyhow, Result};
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
 
+use crate::agent_activity;
+use crate::config;
+use crate::git;
+use crate::hook_automation::{self, CompiledRule};
 use crate::pty::PtyManager;
 use crate::refresh_dispatcher::RefreshDispatcher;
+use crate::session_tail;
+use crate::trello_automation;
+use crate::types::{AutomationAction, AutomationRunTaskEvent, PaneAgentActivity};
 
 const MAX_LOG_ENTRIES: usize = 500;
 
@@ -25,11 +36,13 @@ pub struct HookLogEntry {
 }
 
 type LogBuffer = Arc<Mutex<VecDeque<HookLogEntry>>>;
+type ActivityMap = Arc<Mutex<HashMap<String, PaneAgentActivity>>>;
 
 #[derive(Clone)]
 pub struct HookBridgeState {
     socket_path: Option<String>,
     logs: LogBuffer,
+    activity: ActivityMap,
 }
 
 impl HookBridgeState {
@@ -50,6 +63,46 @@ impl HookBridgeState {
         let mut logs = self.logs.lock().unwrap_or_else(|e| e.into_inner());
         logs.clear();
     }
+
+    pub fn get_agent_activity(&self, pane_id: &str) -> Option<PaneAgentActivity> {
+        let activity = self.activity.lock().unwrap_or_else(|e| e.into_inner());
+        activity.get(pane_id).cloned()
+    }
+
+    /// Drop the activity tree of a closed pane.
+    pub fn forget_pane(&self, pane_id: &str) {
+        let mut activity = self.activity.lock().unwrap_or_else(|e| e.into_inner());
+        activity.remove(pane_id);
+    }
+}
+
+/// Forget a pane's activity tree once its terminal has exited or been killed.
+pub fn forget_pane_activity(handle: &AppHandle, pane_id: &str) {
+    if let Some(state) = handle.try_state::<HookBridgeState>() {
+        state.forget_pane(pane_id);
+    }
+}
+
+/// Apply a Claude hook to the pane's subagent/compaction tree, returning the
+/// updated snapshot when it changed. The tree is dropped once the session
+/// ends; the final snapshot is still returned for the frontend.
+fn update_agent_activity(
+    activity: &ActivityMap,
+    pane_id: &str,
+    hook: &Value,
+) -> Option<PaneAgentActivity> {
+    let mut map = activity.lock().unwrap_or_else(|e| e.into_inner());
+    let entry = map
+        .entry(pane_id.to_string())
+        .or_insert_with(|| PaneAgentActivity {
+            pane_id: pane_id.to_string(),
+            ..Default::default()
+        });
+    let changed = agent_activity::apply_claude_hook(entry, hook, &Utc::now().to_rfc3339());
+    if entry.ended_at.is_some() {
+        return map.remove(pane_id).filter(|_| changed);
+    }
+    changed.then(|| entry.clone())
 }
 
 fn push_log(logs: &LogBuffer, entry: HookLogEntry) {
@@ -63,8 +116,44 @@ fn push_log(logs: &LogBuffer, entry: HookLogEntry) {
 #[derive(Debug, Deserialize)]
 #[serde(untagged)]
 enum HookBridgeEnvelope {
-    Claude { pane_id: String, hook: Value },
-    Codex { pane_id: String, codex: Value },
+    Claude {
+        pane_id: String,
+        hook: Value,
+    },
+    Codex {
+        pane_id: String,
+        codex: Value,
+    },
+    /// Any other CLI agent (Gemini CLI, Aider, opencode, ...) reporting a
+    /// normalized lifecycle event through its own bridge script.
+    Agent {
+        pane_id: String,
+        agent: String,
+        event: AgentEventType,
+        #[serde(default)]
+        payload: Value,
+    },
+}
+
+/// Normalized lifecycle events for generic agents.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+enum AgentEventType {
+    TurnStarted,
+    ToolUsed,
+    NeedsInput,
+    TurnFinished,
+}
+
+impl AgentEventType {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::TurnStarted => "turn-started",
+            Self::ToolUsed => "tool-used",
+            Self::NeedsInput => "needs-input",
+            Self::TurnFinished => "turn-finished",
+        }
+    }
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -119,6 +208,16 @@ impl ClaudeHookEvent {
     }
 }
 
+/// Codex notify names its event in `type` (e.g. `agent-turn-complete`);
+/// older bridge payloads used `event`.
+fn codex_event_name(payload: &Value) -> Option<String> {
+    payload
+        .get("type")
+        .or_else(|| payload.get("event"))
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+}
+
 #[derive(Clone, Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct CodexNotifyEvent {
@@ -139,10 +238,7 @@ impl CodexNotifyEvent {
             .or_else(|| codex_payload.get("thread_id"))
             .and_then(|v| v.as_str())
             .map(|s| s.to_string());
-        let notify_event = codex_payload
-            .get("event")
-            .and_then(|v| v.as_str())
-            .map(|s| s.to_string());
+        let notify_event = codex_event_name(&codex_payload);
         let cwd = codex_payload
             .get("cwd")
             .and_then(|v| v.as_str())
@@ -158,58 +254,305 @@ impl CodexNotifyEvent {
     }
 }
 
-fn command_mentions_git_or_gh(command: &str) -> bool {
-    command
-        .split(|c: char| !c.is_ascii_alphanumeric() && c != '_')
-        .any(|token| token == "git" || token == "gh")
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AgentHookEvent {
+    pane_id: String,
+    agent: String,
+    event: AgentEventType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    session_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cwd: Option<String>,
+    payload: Value,
+}
+
+impl AgentHookEvent {
+    fn from_payload(pane_id: String, agent: String, event: AgentEventType, payload: Value) -> Self {
+        let session_id = payload
+            .get("session_id")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let tool_name = payload
+            .get("tool_name")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let cwd = payload
+            .get("cwd")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        Self {
+            pane_id,
+            agent,
+            event,
+            session_id,
+            tool_name,
+            cwd,
+            payload,
+        }
+    }
+}
+
+fn pane_project_path(handle: &AppHandle, pane_id: &str) -> Option<String> {
+    handle
+        .state::<PtyManager>()
+        .project_path_for_session(pane_id)
 }
 
-fn should_emit_project_refresh_for_hook(hook: &Value) -> bool {
-    if hook
-        .get("hook_event_name")
-        .and_then(|v| v.as_str())
-        != Some("PostToolUse")
-    {
-        return false;
-    }
-
-    match hook.get("tool_name").and_then(|v| v.as_str()) {
-        Some("Write" | "Edit" | "NotebookEdit") => true,
-        Some("Bash") => {
-            let Some(command) = hook
-                .get("tool_input")
-                .and_then(|v| v.get("command"))
-                .and_then(|v| v.as_str())
-            else {
-                return false;
+/// Resolve the main worktree for a project path, since project config
+/// (tasks, Trello links) is keyed by the main checkout.
+fn main_project_path(project_path: &str) -> String {
+    git::list_worktrees(project_path)
+        .ok()
+        .and_then(|worktrees| worktrees.into_iter().find(|wt| wt.is_main))
+        .map(|wt| wt.path)
+        .unwrap_or_else(|| project_path.to_string())
+}
+
+/// An automation action matched by a hook event, waiting for the worker.
+#[derive(Debug, Clone, PartialEq)]
+struct AutomationJob {
+    pane_id: String,
+    source: String,
+    event_name: Option<String>,
+    payload: Value,
+    action: AutomationAction,
+}
+
+fn run_automation_action(handle: &AppHandle, job: &AutomationJob) -> Result<()> {
+    let AutomationJob {
+        pane_id,
+        source,
+        event_name,
+        payload,
+        action,
+    } = job;
+    let event_name = event_name.as_deref();
+    match action {
+        AutomationAction::RefreshProject => {
+            let Some(project_path) = pane_project_path(handle, pane_id) else {
+                return Ok(());
+            };
+            let dispatcher = handle.state::<RefreshDispatcher>();
+            dispatcher.request_refresh(
+                handle,
+                project_path,
+                &format!("{source}-hook"),
+                hook_automation::refresh_trigger(payload),
+            );
+        }
+        AutomationAction::Notify { title, body } => {
+            handle
+                .notification()
+                .builder()
+                .title(hook_automation::render_template(
+                    title, event_name, payload, pane_id,
+                ))
+                .body(hook_automation::render_template(
+                    body, event_name, payload, pane_id,
+                ))
+                .show()?;
+        }
+        AutomationAction::RunTask { task_name } => {
+            let project_path = pane_project_path(handle, pane_id)
+                .ok_or_else(|| anyhow!("No project for pane {pane_id}"))?;
+            let main_path = main_project_path(&project_path);
+            let project = config::load_projects()?
+                .into_iter()
+                .find(|p| p.path == main_path || p.path == project_path)
+                .ok_or_else(|| anyhow!("No project configured at {main_path}"))?;
+            let task = project
+                .tasks
+                .into_iter()
+                .find(|t| t.name == *task_name)
+                .ok_or_else(|| anyhow!("Task \"{task_name}\" not found in {}", project.name))?;
+            handle.emit(
+                "automation:run-task",
+                AutomationRunTaskEvent {
+                    project_path: project.path,
+                    task,
+                },
+            )?;
+        }
+        AutomationAction::WriteToPane {
+            pane_id: target,
+            text,
+        } => {
+            handle.state::<PtyManager>().write(target, text)?;
+        }
+        AutomationAction::MoveTrelloCard { column_id } => {
+            let project_path = pane_project_path(handle, pane_id)
+                .ok_or_else(|| anyhow!("No project for pane {pane_id}"))?;
+            let branch = git::git_info(&project_path)?.branch;
+            trello_automation::move_card_for_branch(
+                &main_project_path(&project_path),
+                &branch,
+                column_id,
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Run queued automation actions one at a time until every sender is gone.
+/// Failures are logged to the hook log rather than stopping the queue.
+fn run_action_queue(
+    jobs: mpsc::Receiver<AutomationJob>,
+    logs: &LogBuffer,
+    run: impl Fn(&AutomationJob) -> Result<()>,
+    emit_log: impl Fn(HookLogEntry),
+) {
+    for job in jobs {
+        if let Err(e) = run(&job) {
+            let entry = HookLogEntry {
+                timestamp: Utc::now().to_rfc3339(),
+                level: "error".into(),
+                event_name: job.event_name.clone(),
+                pane_id: Some(job.pane_id.clone()),
+                source: Some(job.source.clone()),
+                summary: format!("Automation action failed: {e}"),
+                tool_name: None,
             };
-            command_mentions_git_or_gh(command)
+            push_log(logs, entry.clone());
+            emit_log(entry);
         }
-        _ => false,
     }
 }
 
-/// Emit a project refresh event. Caller must verify `should_emit_project_refresh_for_hook` first.
-fn emit_project_refresh_event(handle: &AppHandle, pane_id: &str, hook: &Value) {
-    let pty_manager = handle.state::<PtyManager>();
-    let Some(project_path) = pty_manager.project_path_for_session(pane_id) else {
-        return;
-    };
-    let dispatcher = handle.state::<RefreshDispatcher>();
+/// Start the thread that runs automation actions. Actions can block (a
+/// Trello request, loading projects.json), so bridge connections only queue
+/// them and move on to the next event.
+fn spawn_action_worker(handle: AppHandle, logs: LogBuffer) -> mpsc::Sender<AutomationJob> {
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        run_action_queue(
+            receiver,
+            &logs,
+            |job| run_automation_action(&handle, job),
+            |entry| {
+                let _ = handle.emit("hook-bridge:log", entry);
+            },
+        );
+    });
+    sender
+}
 
-    let trigger = match hook.get("tool_name").and_then(|v| v.as_str()) {
-        Some("Write") => "post-tool-use-write",
-        Some("Edit") => "post-tool-use-edit",
-        Some("NotebookEdit") => "post-tool-use-notebook-edit",
-        _ => "post-tool-use-bash",
-    };
+/// Destination for everything the bridge produces while processing a stream.
+/// The app is the real sink; replay tests substitute a recorder.
+trait HookSink {
+    fn emit_event<S: Serialize + Clone>(&self, event: &str, payload: S);
+
+    /// Hand an automation action to the worker without waiting for it.
+    fn queue_action(&self, job: AutomationJob);
+}
+
+struct AppSink<'a> {
+    handle: &'a AppHandle,
+    actions: &'a mpsc::Sender<AutomationJob>,
+}
+
+impl HookSink for AppSink<'_> {
+    fn emit_event<S: Serialize + Clone>(&self, event: &str, payload: S) {
+        let _ = self.handle.emit(event, payload);
+    }
+
+    fn queue_action(&self, job: AutomationJob) {
+        if self.actions.send(job).is_err() {
+            eprintln!("[HookBridge] Automation worker is gone; dropping action");
+        }
+    }
+}
+
+/// Queue the actions of every automation rule matching an event.
+/// Returns true if a project refresh was requested.
+fn run_automation_rules<K: HookSink>(
+    sink: &K,
+    rules: &[CompiledRule],
+    pane_id: &str,
+    source: &str,
+    event_name: Option<&str>,
+    payload: &Value,
+) -> bool {
+    let actions = hook_automation::matching_actions(rules, source, event_name, payload);
+    let refreshed = actions.contains(&AutomationAction::RefreshProject);
+    for action in actions {
+        sink.queue_action(AutomationJob {
+            pane_id: pane_id.to_string(),
+            source: source.to_string(),
+            event_name: event_name.map(str::to_string),
+            payload: payload.clone(),
+            action,
+        });
+    }
+    refreshed
+}
+
+/// When set to a directory, every bridge connection's raw line stream is saved
+/// there as a `.jsonl` fixture that the replay tests can consume.
+const CAPTURE_DIR_ENV: &str = "WORKBENCH_HOOK_CAPTURE_DIR";
+
+/// Reader that tees everything it reads into a capture file, if one is open.
+struct CaptureReader<R> {
+    inner: R,
+    file: Option<File>,
+}
+
+impl<R: Read> CaptureReader<R> {
+    fn new(inner: R, capture_dir: Option<&Path>, seq: usize) -> Self {
+        let file = capture_dir.and_then(|dir| match open_capture_file(dir, seq) {
+            Ok(file) => Some(file),
+            Err(e) => {
+                eprintln!("[HookBridge] Failed to open capture file: {e}");
+                None
+            }
+        });
+        Self { inner, file }
+    }
+}
 
-    dispatcher.request_refresh(handle, project_path, "claude-hook", trigger);
+impl<R: Read> Read for CaptureReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        if let Some(file) = self.file.as_mut() {
+            if let Err(e) = file.write_all(&buf[..n]) {
+                eprintln!("[HookBridge] Capture write failed, disabling capture: {e}");
+                self.file = None;
+            }
+        }
+        Ok(n)
+    }
+}
+
+fn open_capture_file(dir: &Path, seq: usize) -> Result<File> {
+    fs::create_dir_all(dir)?;
+    let name = format!("{}-{seq:05}.jsonl", Utc::now().format("%Y%m%dT%H%M%S%.3f"));
+    Ok(File::create(dir.join(name))?)
 }
 
 /// Process lines from a stream, dispatching hook events to the frontend.
 /// Shared between Unix socket and TCP implementations.
-fn handle_stream<R: Read>(reader: BufReader<R>, handle: &AppHandle, logs: &LogBuffer) {
+fn handle_stream<R: Read>(
+    reader: BufReader<R>,
+    handle: &AppHandle,
+    actions: &mpsc::Sender<AutomationJob>,
+    logs: &LogBuffer,
+    activity: &ActivityMap,
+) {
+    let rules = hook_automation::load_rules();
+    let sink = AppSink { handle, actions };
+    process_stream(reader, &sink, logs, activity, &rules);
+}
+
+fn process_stream<R: Read, K: HookSink>(
+    reader: BufReader<R>,
+    sink: &K,
+    logs: &LogBuffer,
+    activity: &ActivityMap,
+    rules: &[CompiledRule],
+) {
     for line in reader.lines() {
         let line = match line {
             Ok(line) => line,
@@ -224,7 +567,7 @@ fn handle_stream<R: Read>(reader: BufReader<R>, handle: &AppHandle, logs: &LogBu
                     tool_name: None,
                 };
                 push_log(logs, entry.clone());
-                let _ = handle.emit("hook-bridge:log", entry);
+                sink.emit_event("hook-bridge:log", entry);
                 break;
             }
         };
@@ -250,22 +593,25 @@ fn handle_stream<R: Read>(reader: BufReader<R>, handle: &AppHandle, logs: &LogBu
                     tool_name: None,
                 };
                 push_log(logs, entry.clone());
-                let _ = handle.emit("hook-bridge:log", entry);
+                sink.emit_event("hook-bridge:log", entry);
                 continue;
             }
         };
 
         match envelope {
             HookBridgeEnvelope::Claude { pane_id, hook } => {
-                let refreshed = should_emit_project_refresh_for_hook(&hook);
-                if refreshed {
-                    emit_project_refresh_event(handle, &pane_id, &hook);
-                }
-
                 let event_name = hook
                     .get("hook_event_name")
                     .and_then(|v| v.as_str())
                     .map(|s| s.to_string());
+                let refreshed = run_automation_rules(
+                    sink,
+                    rules,
+                    &pane_id,
+                    "claude",
+                    event_name.as_deref(),
+                    &hook,
+                );
                 let tool_name = hook
                     .get("tool_name")
                     .and_then(|v| v.as_str())
@@ -305,19 +651,36 @@ fn handle_stream<R: Read>(reader: BufReader<R>, handle: &AppHandle, logs: &LogBu
                     tool_name,
                 };
                 push_log(logs, log_entry.clone());
-                let _ = handle.emit("hook-bridge:log", log_entry);
+                sink.emit_event("hook-bridge:log", log_entry);
+
+                if let Some(snapshot) = update_agent_activity(activity, &pane_id, &hook) {
+                    sink.emit_event("claude:activity", snapshot);
+                }
 
                 let event = ClaudeHookEvent::from_payload(pane_id, hook);
-                let _ = handle.emit("claude:hook", event);
+                session_tail::observe_claude_hook(
+                    event.hook_event_name.as_deref(),
+                    event.session_id.as_deref(),
+                    &event.hook_payload,
+                );
+                sink.emit_event("claude:hook", event);
             }
             HookBridgeEnvelope::Codex { pane_id, codex } => {
-                let event_name = codex
-                    .get("event")
-                    .and_then(|v| v.as_str())
-                    .map(|s| s.to_string());
-                let summary = event_name
+                let event_name = codex_event_name(&codex);
+                let refreshed = run_automation_rules(
+                    sink,
+                    rules,
+                    &pane_id,
+                    "codex",
+                    event_name.as_deref(),
+                    &codex,
+                );
+                let mut summary = event_name
                     .clone()
                     .unwrap_or_else(|| "Codex notification".into());
+                if refreshed {
+                    summary.push_str(" → refreshed");
+                }
                 let log_entry = HookLogEntry {
                     timestamp: Utc::now().to_rfc3339(),
                     level: "event".into(),
@@ -328,10 +691,45 @@ fn handle_stream<R: Read>(reader: BufReader<R>, handle: &AppHandle, logs: &LogBu
                     tool_name: None,
                 };
                 push_log(logs, log_entry.clone());
-                let _ = handle.emit("hook-bridge:log", log_entry);
+                sink.emit_event("hook-bridge:log", log_entry);
 
                 let event = CodexNotifyEvent::from_payload(pane_id, codex);
-                let _ = handle.emit("codex:notify", event);
+                sink.emit_event("codex:notify", event);
+            }
+            HookBridgeEnvelope::Agent {
+                pane_id,
+                agent,
+                event,
+                payload,
+            } => {
+                let event = AgentHookEvent::from_payload(pane_id, agent, event, payload);
+                let refreshed = run_automation_rules(
+                    sink,
+                    rules,
+                    &event.pane_id,
+                    &event.agent,
+                    Some(event.event.as_str()),
+                    &event.payload,
+                );
+                let mut summary = match &event.tool_name {
+                    Some(tool) => format!("{}: {tool}", event.event.as_str()),
+                    None => event.event.as_str().to_string(),
+                };
+                if refreshed {
+                    summary.push_str(" → refreshed");
+                }
+                let log_entry = HookLogEntry {
+                    timestamp: Utc::now().to_rfc3339(),
+                    level: "event".into(),
+                    event_name: Some(event.event.as_str().to_string()),
+                    pane_id: Some(event.pane_id.clone()),
+                    source: Some(event.agent.clone()),
+                    summary,
+                    tool_name: event.tool_name.clone(),
+                };
+                push_log(logs, log_entry.clone());
+                sink.emit_event("hook-bridge:log", log_entry);
+                sink.emit_event("agent:event", event);
             }
         }
     }
@@ -340,7 +738,9 @@ fn handle_stream<R: Read>(reader: BufReader<R>, handle: &AppHandle, logs: &LogBu
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::AutomationRule;
     use serde_json::json;
+    use std::cell::RefCell;
 
     fn make_log_entry(summary: &str) -> HookLogEntry {
         HookLogEntry {
@@ -384,6 +784,7 @@ mod tests {
         let state = HookBridgeState {
             socket_path: None,
             logs: Arc::new(Mutex::new(VecDeque::new())),
+            activity: Arc::new(Mutex::new(HashMap::new())),
         };
         push_log(&state.logs, make_log_entry("test"));
         let result = state.get_logs();
@@ -396,6 +797,7 @@ mod tests {
         let state = HookBridgeState {
             socket_path: None,
             logs: Arc::new(Mutex::new(VecDeque::new())),
+            activity: Arc::new(Mutex::new(HashMap::new())),
         };
         push_log(&state.logs, make_log_entry("one"));
         push_log(&state.logs, make_log_entry("two"));
@@ -523,7 +925,18 @@ mod tests {
         assert!(event.cwd.is_none());
     }
 
-    // --- refresh trigger detection ---
+    // --- refresh trigger detection (default automation rules) ---
+
+    fn should_emit_project_refresh_for_hook(hook: &Value) -> bool {
+        let event_name = hook.get("hook_event_name").and_then(|v| v.as_str());
+        hook_automation::matching_actions(
+            &hook_automation::compile_rules(&crate::types::default_automation_rules()),
+            "claude",
+            event_name,
+            hook,
+        )
+        .contains(&AutomationAction::RefreshProject)
+    }
 
     #[test]
     fn refresh_trigger_post_tool_use_bash_git() {
@@ -593,7 +1006,10 @@ mod tests {
                 "tool_name": tool,
                 "tool_input": {}
             });
-            assert!(!should_emit_project_refresh_for_hook(&payload), "should reject {tool}");
+            assert!(
+                !should_emit_project_refresh_for_hook(&payload),
+                "should reject {tool}"
+            );
         }
     }
 
@@ -619,7 +1035,7 @@ mod tests {
                 assert_eq!(pane_id, "p1");
                 assert_eq!(hook.get("session_id").unwrap().as_str().unwrap(), "s1");
             }
-            HookBridgeEnvelope::Codex { .. } => panic!("Expected Claude variant"),
+            _ => panic!("Expected Claude variant"),
         }
     }
 
@@ -633,7 +1049,7 @@ mod tests {
                 assert_eq!(pane_id, "p2");
                 assert_eq!(codex.get("thread-id").unwrap().as_str().unwrap(), "t1");
             }
-            HookBridgeEnvelope::Claude { .. } => panic!("Expected Codex variant"),
+            _ => panic!("Expected Codex variant"),
         }
     }
 
@@ -648,23 +1064,384 @@ mod tests {
         let json_str = r#"{"pane_id": "p3"}"#;
         assert!(serde_json::from_str::<HookBridgeEnvelope>(json_str).is_err());
     }
+
+    #[test]
+    fn envelope_generic_agent() {
+        let json_str = r#"{"pane_id": "p4", "agent": "gemini", "event": "tool-used", "payload": {"tool_name": "write_file"}}"#;
+        let envelope: HookBridgeEnvelope = serde_json::from_str(json_str).unwrap();
+
+        match envelope {
+            HookBridgeEnvelope::Agent {
+                pane_id,
+                agent,
+                event,
+                payload,
+            } => {
+                assert_eq!(pane_id, "p4");
+                assert_eq!(agent, "gemini");
+                assert_eq!(event, AgentEventType::ToolUsed);
+                assert_eq!(payload["tool_name"], "write_file");
+            }
+            _ => panic!("Expected Agent variant"),
+        }
+    }
+
+    #[test]
+    fn envelope_generic_agent_payload_is_optional() {
+        let json_str = r#"{"pane_id": "p5", "agent": "aider", "event": "needs-input"}"#;
+        let envelope: HookBridgeEnvelope = serde_json::from_str(json_str).unwrap();
+        assert!(matches!(
+            envelope,
+            HookBridgeEnvelope::Agent {
+                event: AgentEventType::NeedsInput,
+                payload: Value::Null,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn envelope_generic_agent_rejects_unknown_event() {
+        let json_str = r#"{"pane_id": "p6", "agent": "aider", "event": "exploded"}"#;
+        assert!(serde_json::from_str::<HookBridgeEnvelope>(json_str).is_err());
+    }
+
+    // --- capture ---
+
+    #[test]
+    fn capture_reader_tees_stream_to_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = "{\"pane_id\":\"p1\",\"hook\":{}}\nsecond line\n";
+        let mut reader = CaptureReader::new(input.as_bytes(), Some(dir.path()), 7);
+        let mut read_back = String::new();
+        reader.read_to_string(&mut read_back).unwrap();
+        assert_eq!(read_back, input);
+
+        let files: Vec<_> = fs::read_dir(dir.path()).unwrap().flatten().collect();
+        assert_eq!(files.len(), 1);
+        let name = files[0].file_name().to_string_lossy().into_owned();
+        assert!(name.ends_with("-00007.jsonl"), "unexpected name {name}");
+        assert_eq!(fs::read_to_string(files[0].path()).unwrap(), input);
+    }
+
+    #[test]
+    fn capture_reader_without_dir_passes_through() {
+        let mut reader = CaptureReader::new("abc".as_bytes(), None, 0);
+        let mut read_back = String::new();
+        reader.read_to_string(&mut read_back).unwrap();
+        assert_eq!(read_back, "abc");
+        assert!(reader.file.is_none());
+    }
+
+    // --- replay ---
+
+    /// Records everything the bridge would send to the app.
+    #[derive(Default)]
+    struct RecordingSink {
+        events: RefCell<Vec<(String, Value)>>,
+        actions: RefCell<Vec<(String, AutomationAction)>>,
+    }
+
+    impl RecordingSink {
+        fn events_named(&self, name: &str) -> Vec<Value> {
+            self.events
+                .borrow()
+                .iter()
+                .filter(|(event, _)| event == name)
+                .map(|(_, payload)| payload.clone())
+                .collect()
+        }
+    }
+
+    impl HookSink for RecordingSink {
+        fn emit_event<S: Serialize + Clone>(&self, event: &str, payload: S) {
+            let payload = serde_json::to_value(payload).unwrap();
+            self.events.borrow_mut().push((event.to_string(), payload));
+        }
+
+        fn queue_action(&self, job: AutomationJob) {
+            self.actions.borrow_mut().push((job.pane_id, job.action));
+        }
+    }
+
+    /// Feed a captured fixture through the bridge with the default rules.
+    fn replay(fixture: &str, sink: &RecordingSink) -> Vec<HookLogEntry> {
+        let logs: LogBuffer = Arc::new(Mutex::new(VecDeque::new()));
+        let activity: ActivityMap = Arc::new(Mutex::new(HashMap::new()));
+        let rules = hook_automation::compile_rules(&crate::types::default_automation_rules());
+        process_stream(
+            BufReader::new(fixture.as_bytes()),
+            sink,
+            &logs,
+            &activity,
+            &rules,
+        );
+        let entries = logs.lock().unwrap().iter().cloned().collect();
+        entries
+    }
+
+    // Hand-written stand-ins for real captures; see "record real traffic" in
+    // the README for how to replace them.
+    const CLAUDE_SESSION: &str = include_str!("../tests/fixtures/hook_bridge/claude_session.jsonl");
+    const CODEX_SESSION: &str = include_str!("../tests/fixtures/hook_bridge/codex_session.jsonl");
+
+    #[test]
+    fn replay_claude_session_emits_hook_events_in_order() {
+        let sink = RecordingSink::default();
+        replay(CLAUDE_SESSION, &sink);
+
+        let hooks = sink.events_named("claude:hook");
+        let names: Vec<_> = hooks
+            .iter()
+            .map(|e| e["hookEventName"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "SessionStart",
+                "UserPromptSubmit",
+                "PostToolUse",
+                "Notification",
+                "PostToolUse",
+                "Stop"
+            ]
+        );
+        assert!(hooks.iter().all(|e| e["paneId"] == "pane-1"));
+        assert_eq!(
+            hooks[0]["sessionId"],
+            "8f2c1d9e-4b7a-4e3f-9a61-2d5c8b0e7f14"
+        );
+        assert_eq!(hooks[0]["source"], "startup");
+        assert_eq!(hooks[0]["cwd"], "/Users/dev/repo");
+    }
+
+    #[test]
+    fn replay_claude_session_refreshes_only_after_git_commands() {
+        let sink = RecordingSink::default();
+        let logs = replay(CLAUDE_SESSION, &sink);
+
+        assert_eq!(
+            *sink.actions.borrow(),
+            vec![("pane-1".to_string(), AutomationAction::RefreshProject)]
+        );
+
+        let summaries: Vec<_> = logs.iter().map(|l| l.summary.as_str()).collect();
+        assert_eq!(
+            summaries,
+            vec![
+                "SessionStart",
+                "UserPromptSubmit",
+                "PostToolUse: Bash — cargo test",
+                "Notification",
+                "PostToolUse: Bash — git add -A && git commit -m \"Fix parser test\" → refreshed",
+                "Stop",
+            ]
+        );
+        assert_eq!(sink.events_named("hook-bridge:log").len(), logs.len());
+    }
+
+    #[test]
+    fn replay_codex_session_logs_invalid_lines_and_continues() {
+        let sink = RecordingSink::default();
+        let logs = replay(CODEX_SESSION, &sink);
+
+        let notifies = sink.events_named("codex:notify");
+        assert_eq!(notifies.len(), 2);
+        assert_eq!(notifies[0]["notifyEvent"], "agent-turn-complete");
+        assert_eq!(
+            notifies[0]["sessionId"],
+            "0199a1b2-c3d4-7e5f-8a9b-0c1d2e3f4a5b"
+        );
+
+        let levels: Vec<_> = logs.iter().map(|l| l.level.as_str()).collect();
+        assert_eq!(levels, vec!["event", "error", "event"]);
+        assert!(logs[1].summary.starts_with("Invalid payload"));
+        assert!(sink.actions.borrow().is_empty());
+    }
+
+    #[test]
+    fn replay_codex_session_fires_rules_scoped_to_codex_events() {
+        let rule = AutomationRule {
+            id: "codex-turns".to_string(),
+            name: "Notify on Codex turns".to_string(),
+            enabled: true,
+            source: Some("codex".to_string()),
+            event_name: Some("agent-turn-complete".to_string()),
+            tool_names: vec![],
+            input_pattern: None,
+            actions: vec![AutomationAction::RefreshProject],
+        };
+        let sink = RecordingSink::default();
+        let logs: LogBuffer = Arc::new(Mutex::new(VecDeque::new()));
+        let activity: ActivityMap = Arc::new(Mutex::new(HashMap::new()));
+        process_stream(
+            BufReader::new(CODEX_SESSION.as_bytes()),
+            &sink,
+            &logs,
+            &activity,
+            &hook_automation::compile_rules(&[rule]),
+        );
+
+        assert_eq!(
+            *sink.actions.borrow(),
+            vec![("pane-2".to_string(), AutomationAction::RefreshProject); 2]
+        );
+        let logs = logs.lock().unwrap();
+        assert_eq!(logs[0].event_name.as_deref(), Some("agent-turn-complete"));
+        assert_eq!(logs[0].summary, "agent-turn-complete → refreshed");
+    }
+
+    const CLAUDE_SUBAGENTS: &str =
+        include_str!("../tests/fixtures/hook_bridge/claude_subagents.jsonl");
+
+    #[test]
+    fn replay_claude_subagents_builds_activity_tree() {
+        let sink = RecordingSink::default();
+        let logs: LogBuffer = Arc::new(Mutex::new(VecDeque::new()));
+        let activity: ActivityMap = Arc::new(Mutex::new(HashMap::new()));
+        process_stream(
+            BufReader::new(CLAUDE_SUBAGENTS.as_bytes()),
+            &sink,
+            &logs,
+            &activity,
+            &hook_automation::compile_rules(&crate::types::default_automation_rules()),
+        );
+
+        let snapshots = sink.events_named("claude:activity");
+        // SessionStart, 2x PreToolUse(Task), the first SubagentStop (oldest
+        // running), PostToolUse(Task) for the other, PreCompact and SessionEnd.
+        assert_eq!(snapshots.len(), 7);
+
+        let pane: PaneAgentActivity =
+            serde_json::from_value(snapshots.last().unwrap().clone()).unwrap();
+        assert_eq!(
+            pane.session_id.as_deref(),
+            Some("3c9e0f6a-1d2b-4c5e-8f7a-9b0c1d2e3f4a")
+        );
+        let descriptions: Vec<_> = pane
+            .subagents
+            .iter()
+            .map(|n| n.description.as_deref().unwrap())
+            .collect();
+        assert_eq!(
+            descriptions,
+            vec!["Find hook callers", "Review settings tests"]
+        );
+        assert!(pane
+            .subagents
+            .iter()
+            .all(|n| n.status == crate::types::SubagentStatus::Completed));
+        assert_eq!(pane.compactions.len(), 1);
+        assert_eq!(pane.compactions[0].trigger.as_deref(), Some("auto"));
+        assert_eq!(pane.end_reason.as_deref(), Some("prompt_input_exit"));
+
+        // SessionEnd drops the tree from the bridge state
+        let state = HookBridgeState {
+            socket_path: None,
+            logs,
+            activity,
+        };
+        assert!(state.get_agent_activity("pane-1").is_none());
+    }
+
+    #[test]
+    fn closing_a_pane_forgets_its_activity() {
+        let sink = RecordingSink::default();
+        let state = HookBridgeState {
+            socket_path: None,
+            logs: Arc::new(Mutex::new(VecDeque::new())),
+            activity: Arc::new(Mutex::new(HashMap::new())),
+        };
+        let start = CLAUDE_SUBAGENTS.lines().next().unwrap();
+        process_stream(
+            BufReader::new(start.as_bytes()),
+            &sink,
+            &state.logs,
+            &state.activity,
+            &[],
+        );
+        assert!(state.get_agent_activity("pane-1").is_some());
+
+        state.forget_pane("pane-1");
+        assert!(state.get_agent_activity("pane-1").is_none());
+    }
+
+    #[test]
+    fn action_queue_logs_failures_and_keeps_going() {
+        let logs: LogBuffer = Arc::new(Mutex::new(VecDeque::new()));
+        let (sender, receiver) = mpsc::channel();
+        for pane_id in ["pane-1", "pane-2"] {
+            sender
+                .send(AutomationJob {
+                    pane_id: pane_id.to_string(),
+                    source: "claude".to_string(),
+                    event_name: Some("Stop".to_string()),
+                    payload: json!({}),
+                    action: AutomationAction::RefreshProject,
+                })
+                .unwrap();
+        }
+        drop(sender);
+
+        let ran = RefCell::new(Vec::new());
+        let emitted = RefCell::new(Vec::new());
+        run_action_queue(
+            receiver,
+            &logs,
+            |job| {
+                ran.borrow_mut().push(job.pane_id.clone());
+                Err(anyhow!("action failed"))
+            },
+            |entry| emitted.borrow_mut().push(entry),
+        );
+
+        assert_eq!(*ran.borrow(), ["pane-1", "pane-2"]);
+        let logs = logs.lock().unwrap();
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[0].level, "error");
+        assert_eq!(logs[0].summary, "Automation action failed: action failed");
+        assert_eq!(logs[0].pane_id.as_deref(), Some("pane-1"));
+        assert_eq!(logs[0].event_name.as_deref(), Some("Stop"));
+        assert_eq!(emitted.borrow().len(), 2);
+    }
+
+    #[test]
+    fn agent_hook_event_extracts_common_fields() {
+        let event = AgentHookEvent::from_payload(
+            "p1".into(),
+            "opencode".into(),
+            AgentEventType::TurnFinished,
+            json!({"session_id": "ses_1", "cwd": "/repo"}),
+        );
+        assert_eq!(event.session_id.as_deref(), Some("ses_1"));
+        assert_eq!(event.cwd.as_deref(), Some("/repo"));
+        assert!(event.tool_name.is_none());
+
+        let value = serde_json::to_value(&event).unwrap();
+        assert_eq!(value["event"], "turn-finished");
+        assert_eq!(value["paneId"], "p1");
+    }
 }
 
 /// TCP-based hook bridge for all platforms.
 /// Binds to 127.0.0.1:0 (ephemeral port) so there are no port conflicts.
 /// Hook scripts connect via TCP using /dev/tcp (bash) or TcpClient (PowerShell).
 mod tcp {
-    use std::collections::VecDeque;
+    use std::collections::{HashMap, VecDeque};
     use std::io::BufReader;
     use std::net::TcpListener;
+    use std::path::PathBuf;
     use std::sync::{Arc, Mutex};
 
     use tauri::AppHandle;
 
-    use super::{handle_stream, HookBridgeState};
+    use super::{
+        handle_stream, spawn_action_worker, CaptureReader, HookBridgeState, CAPTURE_DIR_ENV,
+    };
 
     pub fn start(app_handle: AppHandle) -> HookBridgeState {
         let logs = Arc::new(Mutex::new(VecDeque::new()));
+        let activity = Arc::new(Mutex::new(HashMap::new()));
 
         let listener = match TcpListener::bind("127.0.0.1:0") {
             Ok(l) => l,
@@ -673,6 +1450,7 @@ mod tcp {
                 return HookBridgeState {
                     socket_path: None,
                     logs,
+                    activity,
                 };
             }
         };
@@ -684,16 +1462,23 @@ mod tcp {
                 return HookBridgeState {
                     socket_path: None,
                     logs,
+                    activity,
                 };
             }
         };
 
         let socket_path = format!("127.0.0.1:{}", addr.port());
+        let actions = spawn_action_worker(app_handle.clone(), logs.clone());
         let handle = app_handle.clone();
         let logs_clone = logs.clone();
+        let activity_clone = activity.clone();
+        let capture_dir = std::env::var_os(CAPTURE_DIR_ENV).map(PathBuf::from);
+        if let Some(dir) = &capture_dir {
+            eprintln!("[HookBridge] Capturing hook traffic to {}", dir.display());
+        }
 
         std::thread::spawn(move || {
-            for stream in listener.incoming() {
+            for (seq, stream) in listener.incoming().enumerate() {
                 let stream = match stream {
                     Ok(s) => s,
                     Err(e) => {
@@ -703,9 +1488,13 @@ mod tcp {
                 };
 
                 let handle = handle.clone();
+                let actions = actions.clone();
                 let logs = logs_clone.clone();
+                let activity = activity_clone.clone();
+                let capture_dir = capture_dir.clone();
                 std::thread::spawn(move || {
-                    handle_stream(BufReader::new(stream), &handle, &logs);
+                    let reader = CaptureReader::new(stream, capture_dir.as_deref(), seq);
+                    handle_stream(BufReader::new(reader), &handle, &actions, &logs, &activity);
                 });
             }
         });
@@ -713,6 +1502,7 @@ mod tcp {
         HookBridgeState {
             socket_path: Some(socket_path),
             logs,
+            activity,
         }
     }
 }
@@ -0,0 +1,365 @@
+/// Dry runs for Claude hook commands: build the JSON payload Claude would send
+/// for an event, pipe it to the command and interpret the result the way
+/// Claude does (exit code 2 blocks, stdout JSON may carry a decision).
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+use std::io::{Read, Write};
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::types::{HookTestOutcome, HookTestResult};
+
+/// Claude's default per-command timeout.
+const DEFAULT_TIMEOUT_SECS: u64 = 60;
+const MAX_OUTPUT_BYTES: usize = 64 * 1024;
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+const TEST_SESSION_ID: &str = "workbench-hook-test";
+
+/// The tool a `PreToolUse`/`PostToolUse` matcher would fire for. Regex
+/// matchers fall back to `Bash`.
+fn tool_for_matcher(matcher: Option<&str>) -> String {
+    let first = matcher.unwrap_or("").split('|').next().unwrap_or("").trim();
+    if !first.is_empty()
+        && first != "*"
+        && first.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    {
+        first.to_string()
+    } else {
+        "Bash".to_string()
+    }
+}
+
+fn tool_input(tool: &str, cwd: &Path) -> Value {
+    let file = cwd.join("example.txt").to_string_lossy().into_owned();
+    match tool {
+        "Bash" => json!({"command": "echo workbench-hook-test", "description": "Hook test"}),
+        "Read" => json!({"file_path": file}),
+        "Write" => json!({"file_path": file, "content": "hello\n"}),
+        "Edit" => json!({"file_path": file, "old_string": "hello", "new_string": "goodbye"}),
+        "MultiEdit" => json!({
+            "file_path": file,
+            "edits": [{"old_string": "hello", "new_string": "goodbye"}],
+        }),
+        "Glob" => json!({"pattern": "**/*.rs"}),
+        "Grep" => json!({"pattern": "TODO"}),
+        "WebFetch" => json!({"url": "https://example.com", "prompt": "Summarize the page"}),
+        "WebSearch" => json!({"query": "workbench"}),
+        "Task" => json!({
+            "description": "Hook test",
+            "prompt": "Say hello",
+            "subagent_type": "general-purpose",
+        }),
+        _ => json!({}),
+    }
+}
+
+/// Use the matcher when it names one of the event's sources, else the first.
+fn pick(matcher: Option<&str>, options: &[&str]) -> String {
+    matcher
+        .and_then(|m| options.iter().find(|o| **o == m.trim()))
+        .unwrap_or(&options[0])
+        .to_string()
+}
+
+/// The stdin payload Claude sends to hooks for `event`.
+pub(crate) fn synthetic_payload(event: &str, matcher: Option<&str>, cwd: &Path) -> Value {
+    let transcript = std::env::temp_dir().join(format!("{TEST_SESSION_ID}.jsonl"));
+    let mut payload = json!({
+        "session_id": TEST_SESSION_ID,
+        "transcript_path": transcript.to_string_lossy(),
+        "cwd": cwd.to_string_lossy(),
+        "hook_event_name": event,
+    });
+    let extra = match event {
+        "PreToolUse" | "PostToolUse" => {
+            let tool = tool_for_matcher(matcher);
+            let mut fields = json!({"tool_name": tool, "tool_input": tool_input(&tool, cwd)});
+            if event == "PostToolUse" {
+                fields["tool_response"] = json!({"success": true});
+            }
+            fields
+        }
+        "UserPromptSubmit" => json!({"prompt": "Test prompt from Workbench"}),
+        "Notification" => json!({"message": "Claude needs your permission to use Bash"}),
+        "Stop" | "SubagentStop" => json!({"stop_hook_active": false}),
+        "PreCompact" => json!({
+            "trigger": pick(matcher, &["manual", "auto"]),
+            "custom_instructions": "",
+        }),
+        "SessionStart" => json!({
+            "source": pick(matcher, &["startup", "resume", "clear", "compact"]),
+        }),
+        "SessionEnd" => json!({"reason": "other"}),
+        _ => json!({}),
+    };
+    if let (Some(base), Value::Object(extra)) = (payload.as_object_mut(), extra) {
+        base.extend(extra);
+    }
+    payload
+}
+
+fn shell_command(command: &str) -> Command {
+    #[cfg(windows)]
+    {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    }
+    #[cfg(not(windows))]
+    {
+        use std::os::unix::process::CommandExt;
+        let mut cmd = Command::new("sh");
+        // Its own process group, so whatever the hook starts can be killed
+        // along with it.
+        cmd.args(["-c", command]).process_group(0);
+        cmd
+    }
+}
+
+/// Kill the hook and, on Unix, everything left in its process group.
+fn kill_hook(child: &mut Child) {
+    #[cfg(unix)]
+    unsafe {
+        libc::killpg(child.id() as libc::pid_t, libc::SIGKILL);
+    }
+    let _ = child.kill();
+}
+
+fn capture(mut reader: impl Read + Send + 'static) -> (Arc<Mutex<Vec<u8>>>, JoinHandle<()>) {
+    let buf = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&buf);
+    let handle = thread::spawn(move || {
+        let mut chunk = [0u8; 4096];
+        while let Ok(n) = reader.read(&mut chunk) {
+            if n == 0 {
+                break;
+            }
+            let mut buf = sink.lock().unwrap_or_else(|e| e.into_inner());
+            if buf.len() < MAX_OUTPUT_BYTES {
+                buf.extend_from_slice(&chunk[..n]);
+            }
+        }
+    });
+    (buf, handle)
+}
+
+fn take_output(buf: &Arc<Mutex<Vec<u8>>>) -> String {
+    let buf = buf.lock().unwrap_or_else(|e| e.into_inner());
+    String::from_utf8_lossy(&buf).into_owned()
+}
+
+/// `decision`/`reason` from either the top-level fields or
+/// `hookSpecificOutput.permissionDecision`; `continue: false` reads as "stop".
+fn read_decision(output: &Value) -> (Option<String>, Option<String>) {
+    let text = |pointer: &str| {
+        output
+            .pointer(pointer)
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    };
+    if output.get("continue") == Some(&Value::Bool(false)) {
+        return (Some("stop".to_string()), text("/stopReason"));
+    }
+    if let Some(decision) = text("/hookSpecificOutput/permissionDecision") {
+        return (
+            Some(decision),
+            text("/hookSpecificOutput/permissionDecisionReason"),
+        );
+    }
+    (text("/decision"), text("/reason"))
+}
+
+fn outcome(exit_code: Option<i32>, timed_out: bool, decision: Option<&str>) -> HookTestOutcome {
+    if timed_out {
+        return HookTestOutcome::TimedOut;
+    }
+    match exit_code {
+        Some(0) if matches!(decision, Some("block" | "deny" | "stop")) => HookTestOutcome::Blocked,
+        Some(0) => HookTestOutcome::Success,
+        Some(2) => HookTestOutcome::Blocked,
+        _ => HookTestOutcome::Error,
+    }
+}
+
+/// Run `command` with the payload for `event`, killing it after `timeout` seconds.
+pub fn test_hook(
+    event: &str,
+    matcher: Option<&str>,
+    command: &str,
+    timeout: Option<u64>,
+    project_path: Option<&str>,
+) -> Result<HookTestResult> {
+    let cwd = project_path
+        .map(Path::new)
+        .map(Path::to_path_buf)
+        .unwrap_or_else(crate::paths::home_dir);
+    let payload = synthetic_payload(event, matcher, &cwd);
+    let limit = Duration::from_secs(timeout.unwrap_or(DEFAULT_TIMEOUT_SECS).max(1));
+
+    let started = Instant::now();
+    let mut child = shell_command(command)
+        .current_dir(&cwd)
+        .env("PATH", crate::paths::enriched_path())
+        .env("CLAUDE_PROJECT_DIR", &cwd)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to start hook command: {command}"))?;
+
+    let (stdout_buf, stdout_reader) = capture(child.stdout.take().context("stdout unavailable")?);
+    let (stderr_buf, stderr_reader) = capture(child.stderr.take().context("stderr unavailable")?);
+    if let Some(mut stdin) = child.stdin.take() {
+        // Hooks that ignore stdin may exit before reading it
+        let _ = stdin.write_all(payload.to_string().as_bytes());
+    }
+
+    let mut timed_out = false;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break Some(status);
+        }
+        if started.elapsed() >= limit {
+            timed_out = true;
+            kill_hook(&mut child);
+            let _ = child.wait();
+            break None;
+        }
+        thread::sleep(POLL_INTERVAL);
+    };
+    let duration_ms = started.elapsed().as_millis() as u64;
+    // Processes the hook left in the background keep its pipes open. They get
+    // the rest of the time limit, then the group is killed so the readers end.
+    let readers = [stdout_reader, stderr_reader];
+    while !readers.iter().all(JoinHandle::is_finished) && started.elapsed() < limit {
+        thread::sleep(POLL_INTERVAL);
+    }
+    if !readers.iter().all(JoinHandle::is_finished) {
+        kill_hook(&mut child);
+    }
+    for reader in readers {
+        let _ = reader.join();
+    }
+
+    let stdout = take_output(&stdout_buf);
+    let stderr = take_output(&stderr_buf);
+    let exit_code = status.and_then(|s| s.code());
+    let output = match exit_code {
+        Some(0) => serde_json::from_str::<Value>(stdout.trim())
+            .ok()
+            .filter(Value::is_object),
+        _ => None,
+    };
+    let (decision, reason) = output.as_ref().map(read_decision).unwrap_or_default();
+
+    Ok(HookTestResult {
+        outcome: outcome(exit_code, timed_out, decision.as_deref()),
+        exit_code,
+        stdout,
+        stderr,
+        output,
+        decision,
+        reason,
+        payload,
+        duration_ms,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn payload_follows_event_and_matcher() {
+        let cwd = Path::new("/work/app");
+        let pre = synthetic_payload("PreToolUse", Some("Edit|Write"), cwd);
+        assert_eq!(pre["hook_event_name"], "PreToolUse");
+        assert_eq!(pre["tool_name"], "Edit");
+        assert_eq!(pre["tool_input"]["file_path"], "/work/app/example.txt");
+        assert!(pre.get("tool_response").is_none());
+
+        let post = synthetic_payload("PostToolUse", Some("mcp__.*"), cwd);
+        assert_eq!(post["tool_name"], "Bash");
+        assert_eq!(post["tool_response"]["success"], true);
+
+        let start = synthetic_payload("SessionStart", Some("resume"), cwd);
+        assert_eq!(start["source"], "resume");
+        assert_eq!(
+            synthetic_payload("PreCompact", None, cwd)["trigger"],
+            "manual"
+        );
+    }
+
+    #[test]
+    fn pipes_payload_and_reads_json_decision() {
+        let dir = tempdir().unwrap();
+        let project = dir.path().to_str();
+        let result = test_hook(
+            "PreToolUse",
+            Some("Bash"),
+            r#"grep -q '"tool_name":"Bash"' && echo '{"hookSpecificOutput":{"hookEventName":"PreToolUse","permissionDecision":"deny","permissionDecisionReason":"no"}}'"#,
+            None,
+            project,
+        )
+        .unwrap();
+
+        assert_eq!(result.exit_code, Some(0));
+        assert_eq!(result.decision.as_deref(), Some("deny"));
+        assert_eq!(result.reason.as_deref(), Some("no"));
+        assert_eq!(result.outcome, HookTestOutcome::Blocked);
+    }
+
+    #[test]
+    fn reports_exit_codes_and_timeouts() {
+        let ok = test_hook("Stop", None, "echo done", None, None).unwrap();
+        assert_eq!(ok.outcome, HookTestOutcome::Success);
+        assert_eq!(ok.stdout.trim(), "done");
+        assert!(ok.output.is_none());
+
+        let blocked = test_hook("Stop", None, "echo keep going >&2; exit 2", None, None).unwrap();
+        assert_eq!(blocked.outcome, HookTestOutcome::Blocked);
+        assert_eq!(blocked.stderr.trim(), "keep going");
+
+        let failed = test_hook("Stop", None, "exit 1", None, None).unwrap();
+        assert_eq!(failed.outcome, HookTestOutcome::Error);
+
+        let slow = test_hook("Stop", None, "sleep 5", Some(1), None).unwrap();
+        assert_eq!(slow.outcome, HookTestOutcome::TimedOut);
+        assert!(slow.exit_code.is_none());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn kills_what_the_hook_left_running() {
+        let dir = tempdir().unwrap();
+        let pid_file = dir.path().join("pid");
+        let command = format!(
+            "sleep 30 & echo $! > '{}'; echo started",
+            pid_file.display()
+        );
+
+        let started = Instant::now();
+        let result = test_hook("Stop", None, &command, Some(1), None).unwrap();
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert_eq!(result.outcome, HookTestOutcome::Success);
+        assert_eq!(result.stdout.trim(), "started");
+
+        let pid = std::fs::read_to_string(&pid_file).unwrap();
+        let stat = format!("/proc/{}/stat", pid.trim());
+        let deadline = Instant::now() + Duration::from_secs(2);
+        let alive = loop {
+            // A killed orphan may linger as a zombie until init reaps it.
+            let running = std::fs::read_to_string(&stat)
+                .is_ok_and(|s| !s.rsplit(") ").next().is_some_and(|s| s.starts_with('Z')));
+            if !running || Instant::now() >= deadline {
+                break running;
+            }
+            thread::sleep(POLL_INTERVAL);
+        };
+        assert!(!alive, "sleep {} survived the hook test", pid.trim());
+    }
+}
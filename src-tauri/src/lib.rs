@@ -1,36 +1,61 @@
+mod agent_activity;
+mod claude_definitions;
 mod claude_sessions;
+mod claude_transcript;
 mod codex_config;
 mod codex_sessions;
 mod commands;
 mod config;
+mod config_history;
+mod effective_settings;
+mod frontmatter;
 mod git;
 mod git_commands;
 mod git_watcher;
 mod github;
 mod github_poller;
+mod hook_automation;
 mod hook_bridge;
+mod hook_tester;
+mod mcp_health;
+mod mcp_servers;
 mod menu;
 #[cfg(target_os = "macos")]
 mod native_terminal;
 #[cfg(target_os = "macos")]
 mod native_terminal_commands;
 mod paths;
+mod permission_rules;
+mod plugins;
 mod pty;
 mod refresh_dispatcher;
+mod session_export;
+mod session_housekeeping;
+mod session_index;
+mod session_providers;
+mod session_search;
+mod session_tail;
 mod session_utils;
 mod settings;
+mod settings_schema;
 mod shell_integration;
+mod skills;
+mod touched_files;
 mod trello;
-mod trello_commands;
 mod trello_automation;
+mod trello_commands;
 mod types;
+mod usage_accounting;
 
 use git_watcher::GitWatcher;
 use github_poller::GitHubPoller;
 use hook_bridge::HookBridgeState;
 use pty::PtyManager;
 use refresh_dispatcher::RefreshDispatcher;
+use session_index::SessionIndex;
+use session_search::SessionSearch;
 use tauri::Manager;
+use usage_accounting::UsageTracker;
 
 /// Build the invoke handler with all shared commands, plus native terminal
 /// commands on macOS. Uses a declarative macro to avoid duplicating the
@@ -48,10 +73,36 @@ macro_rules! build_invoke_handler {
             commands::load_workspaces,
             commands::save_workspaces,
             commands::discover_claude_sessions,
+            commands::load_claude_transcript,
             commands::load_claude_settings,
             commands::save_claude_settings,
+            commands::load_effective_claude_settings,
+            commands::validate_claude_settings,
+            commands::list_mcp_servers,
+            commands::save_mcp_server,
+            commands::remove_mcp_server,
+            commands::sync_mcp_server,
+            commands::check_mcp_server,
+            commands::evaluate_claude_permission,
+            commands::lint_claude_permissions,
             commands::list_claude_plugins,
+            commands::list_plugin_marketplaces,
+            commands::add_plugin_marketplace,
+            commands::remove_plugin_marketplace,
+            commands::install_claude_plugin,
+            commands::uninstall_claude_plugin,
+            commands::set_claude_plugin_enabled,
+            commands::test_claude_hook,
             commands::list_claude_skills,
+            commands::get_claude_skill,
+            commands::validate_claude_skill,
+            commands::save_claude_skill,
+            commands::delete_claude_skill,
+            commands::copy_claude_skill,
+            commands::list_claude_definitions,
+            commands::get_claude_definition,
+            commands::save_claude_definition,
+            commands::delete_claude_definition,
             commands::list_claude_hooks_scripts,
             commands::git_info,
             commands::list_worktrees,
@@ -59,8 +110,24 @@ macro_rules! build_invoke_handler {
             commands::remove_worktree,
             commands::list_branches,
             commands::discover_codex_sessions,
+            commands::discover_agent_sessions,
+            commands::delete_agent_session,
+            commands::archive_sessions,
+            commands::delete_sessions,
+            commands::prune_sessions,
+            commands::list_archived_sessions,
+            commands::restore_sessions,
+            commands::search_sessions,
+            commands::session_touched_files,
+            commands::export_session,
+            commands::usage_summary,
             commands::load_workbench_settings,
             commands::save_workbench_settings,
+            commands::builtin_automation_rules,
+            commands::list_config_history,
+            commands::list_config_versions,
+            commands::diff_config_versions,
+            commands::restore_config_version,
             commands::github_is_available,
             commands::github_get_remote,
             commands::github_set_tracked_projects,
@@ -79,8 +146,11 @@ macro_rules! build_invoke_handler {
             commands::check_codex_integration,
             commands::apply_claude_integration,
             commands::apply_codex_integration,
+            commands::remove_claude_integration,
+            commands::remove_codex_integration,
             commands::get_hook_logs,
             commands::clear_hook_logs,
+            commands::get_agent_activity,
             commands::is_native_terminal_available,
             git_commands::git_status,
             git_commands::git_log,
@@ -130,6 +200,8 @@ pub fn run() {
         .plugin(tauri_plugin_notification::init())
         .manage(PtyManager::new())
         .manage(RefreshDispatcher::new())
+        .manage(SessionSearch::new())
+        .manage(UsageTracker::new())
         .setup(|app| {
             let handle = app.handle().clone();
             menu::build(&handle).expect("failed to build menu");
@@ -139,6 +211,9 @@ pub fn run() {
             app.manage(git_watcher);
             let github_poller = GitHubPoller::new(app.handle().clone());
             app.manage(github_poller);
+            let session_index = SessionIndex::new();
+            session_index.start_watcher(app.handle().clone());
+            app.manage(session_index);
             Ok(())
         });
 
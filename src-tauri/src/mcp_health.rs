@@ -0,0 +1,349 @@
+/// Health check for stdio MCP servers: launch the server, run the
+/// `initialize` handshake, list its tools, then shut it down.
+use anyhow::{bail, Context, Result};
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::types::{McpHealthReport, McpServerConfig, McpToolInfo};
+
+const PROTOCOL_VERSION: &str = "2025-06-18";
+/// Servers launched through `npx`/`uvx` may download packages first.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(30);
+const MAX_STDERR_BYTES: usize = 16 * 1024;
+/// Guards against servers that hand out cursors forever.
+const MAX_TOOL_PAGES: usize = 20;
+
+pub fn check_server(config: &McpServerConfig, cwd: Option<&str>) -> McpHealthReport {
+    let started = Instant::now();
+    let stderr = Arc::new(Mutex::new(String::new()));
+    let mut report = McpHealthReport::default();
+
+    match run_check(config, cwd, &stderr, &mut report) {
+        Ok(()) => report.ok = true,
+        Err(e) => report.error = Some(format!("{e:#}")),
+    }
+
+    report.stderr = stderr.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    report.duration_ms = started.elapsed().as_millis() as u64;
+    report
+}
+
+/// Kills the server when the check finishes, however it finishes. On Unix
+/// the server leads its own process group, so anything it spawned (an `npx`
+/// wrapper's node child, say) is killed with it.
+struct ServerProcess(Child);
+
+impl Drop for ServerProcess {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        unsafe {
+            libc::killpg(self.0.id() as libc::pid_t, libc::SIGKILL);
+        }
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+fn run_check(
+    config: &McpServerConfig,
+    cwd: Option<&str>,
+    stderr_buf: &Arc<Mutex<String>>,
+    report: &mut McpHealthReport,
+) -> Result<()> {
+    let Some(command) = config.command.as_deref() else {
+        bail!("Only stdio MCP servers can be checked");
+    };
+
+    let mut cmd = Command::new(command);
+    cmd.args(&config.args)
+        .env("PATH", crate::paths::enriched_path())
+        .envs(&config.env)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if let Some(cwd) = cwd {
+        cmd.current_dir(cwd);
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+    let mut server = ServerProcess(
+        cmd.spawn()
+            .with_context(|| format!("Failed to start {command}"))?,
+    );
+
+    let mut stdin = server.0.stdin.take().context("Server stdin unavailable")?;
+    let stdout = server
+        .0
+        .stdout
+        .take()
+        .context("Server stdout unavailable")?;
+    let stderr = server
+        .0
+        .stderr
+        .take()
+        .context("Server stderr unavailable")?;
+
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        for line in BufReader::new(stdout).lines() {
+            let Ok(line) = line else { break };
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    let stderr_buf = Arc::clone(stderr_buf);
+    thread::spawn(move || {
+        let mut chunk = [0u8; 4096];
+        let mut reader = stderr;
+        while let Ok(n) = reader.read(&mut chunk) {
+            if n == 0 {
+                break;
+            }
+            let mut buf = stderr_buf.lock().unwrap_or_else(|e| e.into_inner());
+            if buf.len() < MAX_STDERR_BYTES {
+                buf.push_str(&String::from_utf8_lossy(&chunk[..n]));
+            }
+        }
+    });
+
+    let deadline = Instant::now() + HANDSHAKE_TIMEOUT;
+    let mut session = Session {
+        stdin: &mut stdin,
+        rx: &rx,
+        deadline,
+        next_id: 1,
+    };
+
+    let init = session.request(
+        "initialize",
+        json!({
+            "protocolVersion": PROTOCOL_VERSION,
+            "capabilities": {},
+            "clientInfo": {"name": "workbench", "version": env!("CARGO_PKG_VERSION")},
+        }),
+    )?;
+    report.protocol_version = init
+        .get("protocolVersion")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    report.server_name = init
+        .pointer("/serverInfo/name")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    report.server_version = init
+        .pointer("/serverInfo/version")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    session.send(&json!({"jsonrpc": "2.0", "method": "notifications/initialized"}))?;
+
+    if init.pointer("/capabilities/tools").is_none() {
+        return Ok(());
+    }
+    let mut cursor: Option<String> = None;
+    for _ in 0..MAX_TOOL_PAGES {
+        let params = match &cursor {
+            Some(cursor) => json!({"cursor": cursor}),
+            None => json!({}),
+        };
+        let page = session.request("tools/list", params)?;
+        report.tools.extend(parse_tools(&page));
+        cursor = page
+            .get("nextCursor")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        if cursor.is_none() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn parse_tools(page: &Value) -> Vec<McpToolInfo> {
+    page.get("tools")
+        .and_then(Value::as_array)
+        .map(|tools| {
+            tools
+                .iter()
+                .filter_map(|tool| {
+                    Some(McpToolInfo {
+                        name: tool.get("name")?.as_str()?.to_string(),
+                        description: tool
+                            .get("description")
+                            .and_then(Value::as_str)
+                            .map(str::to_string),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// JSON-RPC over newline-delimited stdio.
+struct Session<'a> {
+    stdin: &'a mut ChildStdin,
+    rx: &'a Receiver<String>,
+    deadline: Instant,
+    next_id: u64,
+}
+
+impl Session<'_> {
+    fn send(&mut self, message: &Value) -> Result<()> {
+        let mut line = serde_json::to_string(message)?;
+        line.push('\n');
+        self.stdin
+            .write_all(line.as_bytes())
+            .and_then(|_| self.stdin.flush())
+            .context("Server closed its input")
+    }
+
+    fn request(&mut self, method: &str, params: Value) -> Result<Value> {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.send(&json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params}))?;
+
+        loop {
+            let remaining = self.deadline.saturating_duration_since(Instant::now());
+            let line = match self.rx.recv_timeout(remaining) {
+                Ok(line) => line,
+                Err(RecvTimeoutError::Timeout) => {
+                    bail!("Timed out waiting for a response to {method}")
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    bail!("Server exited before responding to {method}")
+                }
+            };
+            // Servers sometimes log to stdout; anything that isn't JSON-RPC is skipped.
+            let Ok(message) = serde_json::from_str::<Value>(&line) else {
+                continue;
+            };
+            if message.get("method").is_some() {
+                if let Some(request_id) = message.get("id") {
+                    // Server-to-client request (ping, roots/list, ...): decline politely.
+                    self.send(&json!({
+                        "jsonrpc": "2.0",
+                        "id": request_id,
+                        "error": {"code": -32601, "message": "Method not supported"},
+                    }))?;
+                }
+                continue;
+            }
+            if message.get("id").and_then(Value::as_u64) != Some(id) {
+                continue;
+            }
+            if let Some(error) = message.get("error") {
+                let text = error
+                    .get("message")
+                    .and_then(Value::as_str)
+                    .unwrap_or("unknown error");
+                bail!("{method} failed: {text}");
+            }
+            return Ok(message.get("result").cloned().unwrap_or(Value::Null));
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    /// A tiny MCP server in shell: answers `initialize` and `tools/list`.
+    const FAKE_SERVER: &str = r#"
+echo "starting up" >&2
+while IFS= read -r line; do
+  case "$line" in
+    *'"initialize"'*)
+      echo 'not json, just a log line'
+      echo '{"jsonrpc":"2.0","id":1,"result":{"protocolVersion":"2025-06-18","capabilities":{"tools":{}},"serverInfo":{"name":"fake","version":"0.1.0"}}}'
+      ;;
+    *'"tools/list"'*)
+      echo '{"jsonrpc":"2.0","id":2,"result":{"tools":[{"name":"search","description":"Find things"},{"name":"fetch"}]}}'
+      ;;
+  esac
+done
+"#;
+
+    fn shell(script: &str) -> McpServerConfig {
+        McpServerConfig {
+            command: Some("sh".into()),
+            args: vec!["-c".into(), script.into()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn handshake_lists_tools() {
+        let report = check_server(&shell(FAKE_SERVER), None);
+
+        assert!(report.ok, "{:?}", report.error);
+        assert_eq!(report.server_name.as_deref(), Some("fake"));
+        assert_eq!(report.protocol_version.as_deref(), Some(PROTOCOL_VERSION));
+        let names: Vec<&str> = report.tools.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["search", "fetch"]);
+        assert_eq!(report.tools[0].description.as_deref(), Some("Find things"));
+    }
+
+    #[test]
+    fn reports_servers_that_exit_early() {
+        let report = check_server(&shell("echo boom >&2; exit 1"), None);
+
+        // Depending on timing the write or the read notices first.
+        assert!(!report.ok);
+        assert!(report.error.is_some());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn kills_what_the_server_spawned() {
+        let dir = tempfile::tempdir().unwrap();
+        let pid_file = dir.path().join("pid");
+        let script = format!(
+            "sleep 60 </dev/null >/dev/null 2>&1 & echo $! > '{}'; exit 1",
+            pid_file.display()
+        );
+        assert!(!check_server(&shell(&script), None).ok);
+
+        let pid = std::fs::read_to_string(&pid_file).unwrap();
+        let stat = format!("/proc/{}/stat", pid.trim());
+        let deadline = Instant::now() + Duration::from_secs(2);
+        let alive = loop {
+            // A killed orphan may linger as a zombie until init reaps it.
+            let running = std::fs::read_to_string(&stat)
+                .is_ok_and(|s| !s.rsplit(") ").next().is_some_and(|s| s.starts_with('Z')));
+            if !running || Instant::now() >= deadline {
+                break running;
+            }
+            thread::sleep(Duration::from_millis(20));
+        };
+        assert!(!alive, "sleep {} survived the check", pid.trim());
+    }
+
+    #[test]
+    fn reports_missing_commands_and_remote_servers() {
+        let missing = McpServerConfig {
+            command: Some("workbench-no-such-mcp-server".into()),
+            ..Default::default()
+        };
+        assert!(check_server(&missing, None)
+            .error
+            .unwrap()
+            .contains("Failed to start"));
+
+        let remote = McpServerConfig {
+            url: Some("https://example.com/mcp".into()),
+            ..Default::default()
+        };
+        assert!(check_server(&remote, None)
+            .error
+            .unwrap()
+            .contains("Only stdio"));
+    }
+}
@@ -0,0 +1,680 @@
+/// MCP server definitions across Claude's JSON configs and Codex's
+/// `config.toml`. Only the keys Workbench models are rewritten; anything else
+/// stored alongside a server definition is left as it was.
+use anyhow::{bail, Context, Result};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml_edit::{DocumentMut, Item, Table};
+
+use crate::paths;
+use crate::types::{McpScope, McpServerConfig, McpServerEntry};
+
+const CLAUDE_MANAGED_KEYS: &[&str] = &["type", "command", "args", "env", "url", "headers"];
+/// Attempts at saving a Claude config that keeps changing underneath us.
+const MAX_WRITE_ATTEMPTS: usize = 3;
+
+pub(crate) fn claude_user_config_path() -> PathBuf {
+    paths::home_dir().join(".claude.json")
+}
+
+fn codex_config_path() -> PathBuf {
+    paths::codex_config_dir().join("config.toml")
+}
+
+fn require_project(project_path: Option<&str>) -> Result<&str> {
+    project_path.context("This MCP scope needs a project")
+}
+
+pub(crate) fn scope_path(scope: McpScope, project_path: Option<&str>) -> Result<PathBuf> {
+    Ok(match scope {
+        McpScope::Project => Path::new(require_project(project_path)?).join(".mcp.json"),
+        McpScope::User | McpScope::Local => claude_user_config_path(),
+        McpScope::Codex => codex_config_path(),
+    })
+}
+
+/// Key path to the `mcpServers` object inside a Claude JSON config.
+fn claude_servers_key(scope: McpScope, project_path: Option<&str>) -> Result<Vec<String>> {
+    Ok(match scope {
+        McpScope::Project | McpScope::User => vec!["mcpServers".to_string()],
+        McpScope::Local => vec![
+            "projects".to_string(),
+            require_project(project_path)?.to_string(),
+            "mcpServers".to_string(),
+        ],
+        McpScope::Codex => bail!("Codex servers are not stored in JSON"),
+    })
+}
+
+fn read_text(path: &Path) -> Result<String> {
+    if !path.exists() {
+        return Ok(String::new());
+    }
+    fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))
+}
+
+fn read_json(path: &Path) -> Result<Value> {
+    parse_json(path, &read_text(path)?)
+}
+
+fn parse_json(path: &Path, content: &str) -> Result<Value> {
+    if content.trim().is_empty() {
+        return Ok(Value::Object(Map::new()));
+    }
+    serde_json::from_str(content).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+fn string_list(value: Option<&Value>) -> Vec<String> {
+    value
+        .and_then(Value::as_array)
+        .map(|items| items.iter().map(value_text).collect())
+        .unwrap_or_default()
+}
+
+fn string_map(value: Option<&Value>) -> BTreeMap<String, String> {
+    value
+        .and_then(Value::as_object)
+        .map(|map| {
+            map.iter()
+                .map(|(k, v)| (k.clone(), value_text(v)))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn value_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn claude_config_from_value(value: &Value) -> McpServerConfig {
+    McpServerConfig {
+        transport: value
+            .get("type")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        command: value
+            .get("command")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        args: string_list(value.get("args")),
+        env: string_map(value.get("env")),
+        url: value.get("url").and_then(Value::as_str).map(str::to_string),
+        headers: string_map(value.get("headers")),
+    }
+}
+
+fn codex_config_from_table(table: &Map<String, Value>) -> McpServerConfig {
+    let url = table.get("url").and_then(Value::as_str).map(str::to_string);
+    McpServerConfig {
+        transport: url.as_ref().map(|_| "http".to_string()),
+        command: table
+            .get("command")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        args: string_list(table.get("args")),
+        env: string_map(table.get("env")),
+        url,
+        headers: string_map(table.get("http_headers")),
+    }
+}
+
+/// Server tables under `mcp_servers`, whether written as
+/// `[mcp_servers.name]` sections, dotted keys or inline tables.
+fn codex_servers(content: &str) -> Result<BTreeMap<String, Map<String, Value>>> {
+    let doc: DocumentMut = content
+        .parse()
+        .context("Failed to parse Codex config.toml")?;
+    let Some(servers) = doc.get("mcp_servers").and_then(Item::as_table_like) else {
+        return Ok(BTreeMap::new());
+    };
+    Ok(servers
+        .iter()
+        .filter_map(|(name, item)| match toml_item_to_json(item) {
+            Value::Object(table) => Some((name.to_string(), table)),
+            _ => None,
+        })
+        .collect())
+}
+
+/// A TOML item as JSON. Dates and times are kept as their text.
+fn toml_item_to_json(item: &Item) -> Value {
+    match item {
+        Item::None => Value::Null,
+        Item::Value(value) => toml_value_to_json(value),
+        Item::Table(table) => Value::Object(
+            table
+                .iter()
+                .map(|(k, v)| (k.to_string(), toml_item_to_json(v)))
+                .collect(),
+        ),
+        Item::ArrayOfTables(tables) => Value::Array(
+            tables
+                .iter()
+                .map(|table| toml_item_to_json(&Item::Table(table.clone())))
+                .collect(),
+        ),
+    }
+}
+
+fn toml_value_to_json(value: &toml_edit::Value) -> Value {
+    match value {
+        toml_edit::Value::String(s) => Value::from(s.value().as_str()),
+        toml_edit::Value::Integer(n) => Value::from(*n.value()),
+        toml_edit::Value::Float(n) => serde_json::Number::from_f64(*n.value())
+            .map(Value::Number)
+            .unwrap_or(Value::Null),
+        toml_edit::Value::Boolean(b) => Value::Bool(*b.value()),
+        toml_edit::Value::Datetime(d) => Value::from(d.value().to_string()),
+        toml_edit::Value::Array(items) => {
+            Value::Array(items.iter().map(toml_value_to_json).collect())
+        }
+        toml_edit::Value::InlineTable(table) => Value::Object(
+            table
+                .iter()
+                .map(|(k, v)| (k.to_string(), toml_value_to_json(v)))
+                .collect(),
+        ),
+    }
+}
+
+fn read_scope(
+    scope: McpScope,
+    project_path: Option<&str>,
+) -> Result<Vec<(String, McpServerConfig)>> {
+    let path = scope_path(scope, project_path)?;
+    if scope == McpScope::Codex {
+        return Ok(codex_servers(&read_text(&path)?)?
+            .into_iter()
+            .map(|(name, table)| (name, codex_config_from_table(&table)))
+            .collect());
+    }
+    let doc = read_json(&path)?;
+    let mut servers = &doc;
+    for key in claude_servers_key(scope, project_path)? {
+        match servers.get(&key) {
+            Some(next) => servers = next,
+            None => return Ok(Vec::new()),
+        }
+    }
+    Ok(servers
+        .as_object()
+        .map(|map| {
+            map.iter()
+                .map(|(name, value)| (name.clone(), claude_config_from_value(value)))
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Servers from every scope that applies: project and local ones only when a
+/// project is given.
+pub fn list_servers(project_path: Option<&str>) -> Result<Vec<McpServerEntry>> {
+    let scopes: &[McpScope] = if project_path.is_some() {
+        &[
+            McpScope::Project,
+            McpScope::Local,
+            McpScope::User,
+            McpScope::Codex,
+        ]
+    } else {
+        &[McpScope::User, McpScope::Codex]
+    };
+    let mut entries = Vec::new();
+    for &scope in scopes {
+        let source_path = scope_path(scope, project_path)?
+            .to_string_lossy()
+            .to_string();
+        for (name, config) in read_scope(scope, project_path)? {
+            entries.push(McpServerEntry {
+                name,
+                scope,
+                config,
+                source_path: source_path.clone(),
+            });
+        }
+    }
+    Ok(entries)
+}
+
+pub fn get_server(
+    scope: McpScope,
+    project_path: Option<&str>,
+    name: &str,
+) -> Result<McpServerConfig> {
+    read_scope(scope, project_path)?
+        .into_iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, config)| config)
+        .with_context(|| format!("No MCP server named \"{name}\""))
+}
+
+fn validate(scope: McpScope, name: &str, config: &McpServerConfig) -> Result<()> {
+    if name.trim().is_empty() {
+        bail!("MCP server name cannot be empty");
+    }
+    if config.command.is_none() && config.url.is_none() {
+        bail!("MCP server \"{name}\" needs a command or a URL");
+    }
+    if scope == McpScope::Codex && config.transport.as_deref() == Some("sse") {
+        bail!("Codex does not support SSE MCP servers");
+    }
+    Ok(())
+}
+
+/// Add or replace a server definition.
+pub fn save_server(
+    scope: McpScope,
+    project_path: Option<&str>,
+    name: &str,
+    config: &McpServerConfig,
+) -> Result<()> {
+    validate(scope, name, config)?;
+    write_server(scope, project_path, name, Some(config)).map(|_| ())
+}
+
+/// Returns whether the server existed.
+pub fn remove_server(scope: McpScope, project_path: Option<&str>, name: &str) -> Result<bool> {
+    write_server(scope, project_path, name, None)
+}
+
+/// Copy a server definition from one scope to another, typically between
+/// Claude and Codex. Returns the definition that was written.
+pub fn sync_server(
+    project_path: Option<&str>,
+    name: &str,
+    from: McpScope,
+    to: McpScope,
+) -> Result<McpServerConfig> {
+    if from == to {
+        bail!("Source and destination scopes are the same");
+    }
+    let mut config = get_server(from, project_path, name)?;
+    if to == McpScope::Codex {
+        // Codex infers the transport from `command` / `url`.
+        config.transport = config.url.as_ref().map(|_| "http".to_string());
+    }
+    save_server(to, project_path, name, &config)?;
+    Ok(config)
+}
+
+fn write_server(
+    scope: McpScope,
+    project_path: Option<&str>,
+    name: &str,
+    config: Option<&McpServerConfig>,
+) -> Result<bool> {
+    let path = scope_path(scope, project_path)?;
+    if scope == McpScope::Codex {
+        let content = read_text(&path)?;
+        let (updated, existed) = write_codex_server(&content, name, config)?;
+        if updated != content {
+            paths::atomic_write(&path, &updated)?;
+        }
+        return Ok(existed);
+    }
+
+    // Claude rewrites ~/.claude.json while it runs, so the file is read
+    // again just before writing and the edit redone if it changed meanwhile.
+    // Keys keep their order, leaving everything outside the edited server
+    // as it was.
+    let key = claude_servers_key(scope, project_path)?;
+    for _ in 0..MAX_WRITE_ATTEMPTS {
+        let content = read_text(&path)?;
+        let mut doc = parse_json(&path, &content)?;
+        let existed = write_claude_server(&mut doc, &key, name, config)?;
+        if config.is_none() && !existed {
+            return Ok(false);
+        }
+        let updated = serde_json::to_string_pretty(&doc)?;
+        if read_text(&path)? == content {
+            paths::atomic_write(&path, &updated)?;
+            return Ok(existed);
+        }
+    }
+    bail!("{} kept changing while saving; try again", path.display())
+}
+
+fn write_claude_server(
+    doc: &mut Value,
+    key: &[String],
+    name: &str,
+    config: Option<&McpServerConfig>,
+) -> Result<bool> {
+    let Some(config) = config else {
+        let servers = key
+            .iter()
+            .try_fold(&mut *doc, |value, k| value.get_mut(k))
+            .and_then(Value::as_object_mut);
+        return Ok(servers.is_some_and(|servers| servers.remove(name).is_some()));
+    };
+
+    let mut target = doc;
+    for k in key {
+        let Value::Object(map) = target else {
+            bail!("Expected a JSON object above \"{k}\"");
+        };
+        target = map
+            .entry(k.clone())
+            .or_insert_with(|| Value::Object(Map::new()));
+    }
+    let Value::Object(servers) = target else {
+        bail!("mcpServers is not a JSON object");
+    };
+    let existed = servers.contains_key(name);
+    let entry = servers
+        .entry(name.to_string())
+        .or_insert_with(|| Value::Object(Map::new()));
+    if !entry.is_object() {
+        *entry = Value::Object(Map::new());
+    }
+    let fields = entry.as_object_mut().expect("just made an object");
+    for k in CLAUDE_MANAGED_KEYS {
+        fields.remove(*k);
+    }
+    if let Value::Object(new_fields) = serde_json::to_value(config)? {
+        fields.extend(new_fields);
+    }
+    Ok(existed)
+}
+
+/// The value Codex expects for each managed key, or `None` to drop it.
+fn codex_managed_values(config: &McpServerConfig) -> Vec<(&'static str, Option<toml_edit::Value>)> {
+    let strings = |items: &[String]| -> toml_edit::Value {
+        toml_edit::Value::Array(items.iter().map(String::as_str).collect())
+    };
+    let table = |map: &BTreeMap<String, String>| -> toml_edit::Value {
+        toml_edit::Value::InlineTable(
+            map.iter()
+                .map(|(k, v)| (k.as_str(), toml_edit::Value::from(v.as_str())))
+                .collect(),
+        )
+    };
+    vec![
+        (
+            "command",
+            config.command.as_deref().map(toml_edit::Value::from),
+        ),
+        (
+            "args",
+            (!config.args.is_empty()).then(|| strings(&config.args)),
+        ),
+        ("env", (!config.env.is_empty()).then(|| table(&config.env))),
+        ("url", config.url.as_deref().map(toml_edit::Value::from)),
+        (
+            "http_headers",
+            (!config.headers.is_empty()).then(|| table(&config.headers)),
+        ),
+    ]
+}
+
+/// Replace (or with `None`, remove) one server in a Codex config, keeping
+/// the position of an existing definition, its formatting and any keys
+/// Workbench does not manage. Returns the new content and whether the server
+/// existed.
+fn write_codex_server(
+    content: &str,
+    name: &str,
+    config: Option<&McpServerConfig>,
+) -> Result<(String, bool)> {
+    let mut doc: DocumentMut = content
+        .parse()
+        .context("Failed to parse Codex config.toml")?;
+    let existed = doc
+        .get("mcp_servers")
+        .and_then(Item::as_table_like)
+        .is_some_and(|servers| servers.contains_key(name));
+
+    let Some(config) = config else {
+        if !existed {
+            return Ok((content.to_string(), false));
+        }
+        if let Some(servers) = doc.get_mut("mcp_servers").and_then(Item::as_table_like_mut) {
+            servers.remove(name);
+        }
+        return Ok((doc.to_string(), true));
+    };
+
+    let servers = doc
+        .entry("mcp_servers")
+        .or_insert_with(|| {
+            let mut table = Table::new();
+            table.set_implicit(true);
+            Item::Table(table)
+        })
+        .as_table_like_mut()
+        .context("mcp_servers in config.toml is not a table")?;
+    let server = servers
+        .entry(name)
+        .or_insert_with(|| Item::Table(Table::new()));
+    if !server.is_table_like() {
+        *server = Item::Table(Table::new());
+    }
+    let fields = server.as_table_like_mut().expect("just made a table");
+    for (key, value) in codex_managed_values(config) {
+        match value {
+            Some(value) => match fields.get_mut(key) {
+                // Keep the comment and spacing around a value being replaced.
+                Some(Item::Value(old)) => {
+                    let decor = old.decor().clone();
+                    *old = value;
+                    *old.decor_mut() = decor;
+                }
+                _ => {
+                    fields.insert(key, Item::Value(value));
+                }
+            },
+            None => {
+                fields.remove(key);
+            }
+        }
+    }
+    Ok((doc.to_string(), existed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn stdio(command: &str, args: &[&str]) -> McpServerConfig {
+        McpServerConfig {
+            command: Some(command.to_string()),
+            args: args.iter().map(|a| a.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    const CODEX_CONFIG: &str = "\
+model = \"o3\"
+
+[mcp_servers.docs]
+command = \"npx\"
+args = [\"-y\", \"docs-server\"]
+startup_timeout_sec = 20
+
+[mcp_servers.docs.env]
+TOKEN = \"abc\"
+
+[mcp_servers]
+inline = { url = \"https://example.com/mcp\" }
+
+[profiles.fast]
+model = \"o4-mini\"
+";
+
+    #[test]
+    fn reads_codex_servers_in_every_form() {
+        let servers = codex_servers(CODEX_CONFIG).unwrap();
+        let docs = codex_config_from_table(&servers["docs"]);
+        assert_eq!(docs.command.as_deref(), Some("npx"));
+        assert_eq!(docs.args, ["-y", "docs-server"]);
+        assert_eq!(docs.env["TOKEN"], "abc");
+        let inline = codex_config_from_table(&servers["inline"]);
+        assert_eq!(inline.transport.as_deref(), Some("http"));
+        assert_eq!(inline.url.as_deref(), Some("https://example.com/mcp"));
+    }
+
+    #[test]
+    fn rewrites_a_codex_server_in_place() {
+        let mut config = stdio("uvx", &["docs-mcp"]);
+        config.env.insert("TOKEN".into(), "xyz".into());
+        let (updated, existed) = write_codex_server(CODEX_CONFIG, "docs", Some(&config)).unwrap();
+
+        assert!(existed);
+        assert_eq!(
+            updated,
+            "\
+model = \"o3\"
+
+[mcp_servers.docs]
+command = \"uvx\"
+args = [\"docs-mcp\"]
+startup_timeout_sec = 20
+env = { TOKEN = \"xyz\" }
+
+[mcp_servers]
+inline = { url = \"https://example.com/mcp\" }
+
+[profiles.fast]
+model = \"o4-mini\"
+"
+        );
+    }
+
+    #[test]
+    fn adds_and_removes_codex_servers() {
+        let (added, existed) =
+            write_codex_server("model = \"o3\"\n", "new", Some(&stdio("node", &["s.js"]))).unwrap();
+        assert!(!existed);
+        assert_eq!(
+            added,
+            "model = \"o3\"\n\n[mcp_servers.new]\ncommand = \"node\"\nargs = [\"s.js\"]\n"
+        );
+
+        let (removed, existed) = write_codex_server(CODEX_CONFIG, "inline", None).unwrap();
+        assert!(existed);
+        assert!(!removed.contains("inline"));
+        assert!(removed.contains("[mcp_servers.docs]"));
+        assert_eq!(
+            write_codex_server(CODEX_CONFIG, "missing", None).unwrap(),
+            (CODEX_CONFIG.to_string(), false)
+        );
+    }
+
+    #[test]
+    fn removes_subtables_and_dotted_keys_with_their_server() {
+        let content = "\
+mcp_servers.dotted.command = \"node\"
+mcp_servers.dotted.args = [\"d.js\"]
+
+[mcp_servers.docs]
+command = \"npx\"
+
+[mcp_servers.docs.env]
+TOKEN = \"abc\"
+
+[profiles.fast]
+model = \"o4-mini\"
+";
+        let servers = codex_servers(content).unwrap();
+        assert_eq!(codex_config_from_table(&servers["dotted"]).args, ["d.js"]);
+
+        let (removed, existed) = write_codex_server(content, "docs", None).unwrap();
+        assert!(existed);
+        assert!(!removed.contains("docs"));
+        assert!(!removed.contains("TOKEN"));
+        assert!(removed.contains("[profiles.fast]"));
+
+        let (updated, existed) =
+            write_codex_server(content, "dotted", Some(&stdio("deno", &[]))).unwrap();
+        assert!(existed);
+        assert!(updated.starts_with("mcp_servers.dotted.command = \"deno\"\n\n"));
+        assert_eq!(
+            codex_config_from_table(&codex_servers(&updated).unwrap()["dotted"]),
+            stdio("deno", &[])
+        );
+    }
+
+    #[test]
+    fn claude_edits_keep_unmanaged_keys() {
+        let mut doc = json!({
+            "numStartups": 3,
+            "projects": {"/p": {"mcpServers": {
+                "db": {"type": "stdio", "command": "old", "timeout": 5}
+            }}}
+        });
+        let key = claude_servers_key(McpScope::Local, Some("/p")).unwrap();
+
+        let existed =
+            write_claude_server(&mut doc, &key, "db", Some(&stdio("pg-mcp", &[]))).unwrap();
+
+        assert!(existed);
+        assert_eq!(
+            doc["projects"]["/p"]["mcpServers"]["db"],
+            json!({"command": "pg-mcp", "timeout": 5})
+        );
+        assert_eq!(doc["numStartups"], 3);
+        assert!(write_claude_server(&mut doc, &key, "db", None).unwrap());
+        assert!(!write_claude_server(&mut doc, &["mcpServers".into()], "db", None).unwrap());
+    }
+
+    #[test]
+    fn project_servers_round_trip_and_sync_to_codex() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().to_str().unwrap();
+        let mut config = stdio("npx", &["-y", "server"]);
+        config.transport = Some("stdio".into());
+
+        save_server(McpScope::Project, Some(project), "tools", &config).unwrap();
+        let written = read_json(&dir.path().join(".mcp.json")).unwrap();
+        assert_eq!(
+            written,
+            json!({"mcpServers": {"tools": {"type": "stdio", "command": "npx", "args": ["-y", "server"]}}})
+        );
+        assert_eq!(
+            get_server(McpScope::Project, Some(project), "tools").unwrap(),
+            config
+        );
+        assert!(remove_server(McpScope::Project, Some(project), "tools").unwrap());
+        assert!(get_server(McpScope::Project, Some(project), "tools").is_err());
+    }
+
+    #[test]
+    fn claude_config_keeps_its_key_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().to_str().unwrap();
+        let path = dir.path().join(".mcp.json");
+        fs::write(
+            &path,
+            "{\n  \"zeta\": 1,\n  \"mcpServers\": {\n    \"b\": {\n      \"command\": \"b\"\n    }\n  },\n  \"alpha\": 2\n}",
+        )
+        .unwrap();
+
+        save_server(McpScope::Project, Some(project), "a", &stdio("a", &[])).unwrap();
+
+        let written = fs::read_to_string(&path).unwrap();
+        let order: Vec<usize> = ["\"zeta\"", "\"mcpServers\"", "\"b\"", "\"a\"", "\"alpha\""]
+            .iter()
+            .map(|key| written.find(key).unwrap())
+            .collect();
+        assert!(order.windows(2).all(|pair| pair[0] < pair[1]), "{written}");
+    }
+
+    #[test]
+    fn rejects_incomplete_or_unsupported_definitions() {
+        assert!(validate(McpScope::User, "x", &McpServerConfig::default()).is_err());
+        assert!(validate(McpScope::User, " ", &stdio("node", &[])).is_err());
+        let sse = McpServerConfig {
+            transport: Some("sse".into()),
+            url: Some("https://example.com/sse".into()),
+            ..Default::default()
+        };
+        assert!(validate(McpScope::User, "x", &sse).is_ok());
+        assert!(validate(McpScope::Codex, "x", &sse).is_err());
+    }
+}
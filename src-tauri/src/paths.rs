@@ -16,6 +16,15 @@ pub fn workbench_config_dir() -> PathBuf {
     home_dir().join(".workbench")
 }
 
+pub fn workbench_cache_dir() -> PathBuf {
+    workbench_config_dir().join("cache")
+}
+
+/// Compressed copies of archived agent sessions, one subdirectory per provider.
+pub fn session_archive_dir() -> PathBuf {
+    workbench_config_dir().join("archive").join("sessions")
+}
+
 pub fn claude_user_dir() -> PathBuf {
     home_dir().join(".claude")
 }
@@ -99,10 +108,7 @@ pub fn enriched_path() -> OsString {
 /// Encode a project path for use as a filename-safe identifier.
 /// Replaces path separators and drive letter colons with `-`.
 pub fn encode_project_path(project_path: &str) -> String {
-    project_path
-        .replace('\\', "-")
-        .replace('/', "-")
-        .replace(':', "")
+    project_path.replace(['\\', '/'], "-").replace(':', "")
 }
 
 /// Write a script file to disk, creating parent dirs and setting executable
@@ -126,10 +132,16 @@ pub fn ensure_script(path: &Path, body: &str) -> Result<PathBuf> {
 
 /// Write content to a file atomically by writing to a temp file first,
 /// then renaming into place. This prevents data corruption if the app
-/// crashes mid-write.
+/// crashes mid-write. Writes to tracked config files are also snapshotted
+/// by `config_history`.
 pub fn atomic_write(path: &Path, content: &str) -> Result<()> {
     let dir = path.parent().context("Cannot determine parent directory")?;
     fs::create_dir_all(dir)?;
+    let previous = if crate::config_history::wants_previous(path) {
+        fs::read_to_string(path).ok()
+    } else {
+        None
+    };
 
     let temp_path = path.with_extension("tmp");
     fs::write(&temp_path, content).context("Failed to write temp file")?;
@@ -142,6 +154,7 @@ pub fn atomic_write(path: &Path, content: &str) -> Result<()> {
     }
 
     fs::rename(&temp_path, path).context("Failed to rename temp file into place")?;
+    crate::config_history::record(path, previous.as_deref(), content);
     Ok(())
 }
 
@@ -279,10 +292,7 @@ mod tests {
 
     #[test]
     fn encode_project_path_windows_drive_letter() {
-        assert_eq!(
-            encode_project_path("D:\\repos\\my-app"),
-            "D-repos-my-app"
-        );
+        assert_eq!(encode_project_path("D:\\repos\\my-app"), "D-repos-my-app");
     }
 
     #[test]
@@ -362,7 +372,7 @@ mod tests {
         assert!(!link.exists()); // but target does not
 
         remove_path_if_exists(&link).unwrap();
-        assert!(!fs::symlink_metadata(&link).is_ok()); // link removed
+        assert!(fs::symlink_metadata(&link).is_err()); // link removed
     }
 
     // --- copy_file ---
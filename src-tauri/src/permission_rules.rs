@@ -0,0 +1,923 @@
+/// Claude permission rules (`permissions.allow` / `ask` / `deny`): parsing,
+/// evaluation of a sample tool call against the rules merged from every
+/// settings scope, and a linter for rules that are shadowed or can never
+/// match.
+///
+/// Rules look like `Tool` or `Tool(specifier)`. Specifiers understood here:
+/// - `Bash(npm run test:*)` prefix, `Bash(git * main)` wildcard, or an exact command
+/// - `Read(src/**)`, `Edit(//etc/hosts)`, `Edit(~/.zshrc)` gitignore-style paths;
+///   `Edit(/src/**)` is relative to the settings file that holds the rule
+/// - `WebFetch(domain:example.com)`
+/// - `mcp__server` or `mcp__server__*` for every tool of an MCP server
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::effective_settings;
+use crate::paths;
+use crate::types::{
+    PermissionDecision, PermissionEvaluation, PermissionLintIssue, PermissionRuleRef,
+    SettingsIssueSeverity,
+};
+
+const KNOWN_TOOLS: &[&str] = &[
+    "Bash",
+    "BashOutput",
+    "Edit",
+    "ExitPlanMode",
+    "Glob",
+    "Grep",
+    "KillShell",
+    "LS",
+    "MultiEdit",
+    "NotebookEdit",
+    "NotebookRead",
+    "Read",
+    "Skill",
+    "SlashCommand",
+    "Task",
+    "TodoWrite",
+    "WebFetch",
+    "WebSearch",
+    "Write",
+];
+
+/// Tools that never need approval unless a rule says otherwise.
+const READ_ONLY_TOOLS: &[&str] = &[
+    "Read",
+    "Glob",
+    "Grep",
+    "LS",
+    "NotebookRead",
+    "TodoWrite",
+    "BashOutput",
+];
+
+/// `Edit(...)` rules govern every tool that modifies files.
+const EDIT_TOOLS: &[&str] = &["Edit", "MultiEdit", "Write", "NotebookEdit"];
+/// `Read(...)` rules govern every tool that reads files.
+const READ_TOOLS: &[&str] = &["Read", "Glob", "Grep", "LS", "NotebookRead"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PermissionRule {
+    pub tool: String,
+    pub specifier: Option<String>,
+}
+
+pub(crate) fn parse_rule(text: &str) -> Result<PermissionRule, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err("Empty rule".to_string());
+    }
+    let Some(open) = text.find('(') else {
+        if text.contains(')') {
+            return Err("Unbalanced parentheses".to_string());
+        }
+        return Ok(PermissionRule {
+            tool: text.to_string(),
+            specifier: None,
+        });
+    };
+    if !text.ends_with(')') {
+        return Err("Expected the rule to end with ')'".to_string());
+    }
+    let tool = text[..open].trim();
+    if tool.is_empty() {
+        return Err("Missing tool name before '('".to_string());
+    }
+    let specifier = &text[open + 1..text.len() - 1];
+    if specifier.trim().is_empty() {
+        return Err(format!(
+            "Empty specifier; use \"{tool}\" to match every use"
+        ));
+    }
+    Ok(PermissionRule {
+        tool: tool.to_string(),
+        specifier: Some(specifier.to_string()),
+    })
+}
+
+/// One rule from the merged settings, with where it came from.
+#[derive(Debug, Clone)]
+struct ScopedRule {
+    reference: PermissionRuleRef,
+    parsed: Result<PermissionRule, String>,
+}
+
+/// Rules from every scope in evaluation order: deny, then ask, then allow.
+fn collect_rules(
+    settings: &Value,
+    provenance: &[crate::types::SettingProvenance],
+) -> Vec<ScopedRule> {
+    let mut rules = Vec::new();
+    for (list, decision) in [
+        ("deny", PermissionDecision::Deny),
+        ("ask", PermissionDecision::Ask),
+        ("allow", PermissionDecision::Allow),
+    ] {
+        let Some(items) = settings
+            .pointer(&format!("/permissions/{list}"))
+            .and_then(Value::as_array)
+        else {
+            continue;
+        };
+        for (i, item) in items.iter().enumerate() {
+            let Some(text) = item.as_str() else { continue };
+            let pointer = format!("/permissions/{list}/{i}");
+            let scope = provenance
+                .iter()
+                .find(|p| p.pointer == pointer)
+                .map(|p| p.scope.clone())
+                .unwrap_or_default();
+            rules.push(ScopedRule {
+                reference: PermissionRuleRef {
+                    rule: text.to_string(),
+                    list: decision,
+                    scope,
+                },
+                parsed: parse_rule(text),
+            });
+        }
+    }
+    rules
+}
+
+/// Evaluation context: where relative path rules and inputs resolve.
+struct Context<'a> {
+    project_root: Option<&'a Path>,
+    home: PathBuf,
+    /// Base directory of each scope's settings file, for `/path` rules
+    settings_roots: BTreeMap<String, PathBuf>,
+}
+
+/// The directory a settings file's `/path` rules start from: its own
+/// directory, or the one above when it sits in a `.claude` directory
+/// (`<project>/.claude/settings.json` is relative to `<project>`).
+fn settings_root(settings_file: &Path) -> Option<PathBuf> {
+    let dir = settings_file.parent()?;
+    Some(match dir.file_name() {
+        Some(name) if name == ".claude" => dir.parent().unwrap_or(dir).to_path_buf(),
+        _ => dir.to_path_buf(),
+    })
+}
+
+/// A file rule whose path is relative to its settings file (`/src/**`, not
+/// `//etc/**`).
+fn settings_relative(rule: &PermissionRule) -> bool {
+    let tool = rule.tool.as_str();
+    (EDIT_TOOLS.contains(&tool) || READ_TOOLS.contains(&tool))
+        && rule
+            .specifier
+            .as_deref()
+            .is_some_and(|spec| spec.starts_with('/') && !spec.starts_with("//"))
+}
+
+impl Context<'_> {
+    fn resolve_input_path(&self, path: &str) -> PathBuf {
+        let path = Path::new(path);
+        match self.project_root {
+            Some(root) if path.is_relative() => root.join(path),
+            _ => path.to_path_buf(),
+        }
+    }
+
+    /// Expand a gitignore-style rule path from `scope` into an absolute glob.
+    fn resolve_rule_path(&self, pattern: &str, scope: &str) -> String {
+        let trimmed = |path: &Path| path.to_string_lossy().trim_end_matches('/').to_string();
+        let root = self.project_root.map(trimmed).unwrap_or_default();
+        if let Some(abs) = pattern.strip_prefix("//") {
+            format!("/{abs}")
+        } else if let Some(rest) = pattern.strip_prefix("~/") {
+            format!(
+                "{}/{rest}",
+                self.home.to_string_lossy().trim_end_matches('/')
+            )
+        } else if let Some(rest) = pattern.strip_prefix('/') {
+            let base = self
+                .settings_roots
+                .get(scope)
+                .map(|dir| trimmed(dir))
+                .unwrap_or(root);
+            format!("{base}/{rest}")
+        } else {
+            let rest = pattern.strip_prefix("./").unwrap_or(pattern);
+            if rest.contains('/') {
+                format!("{root}/{rest}")
+            } else {
+                // No slash: matches at any depth, as in .gitignore.
+                format!("{root}/**/{rest}")
+            }
+        }
+    }
+}
+
+fn tool_matches(rule_tool: &str, tool_name: &str) -> bool {
+    if rule_tool == tool_name {
+        return true;
+    }
+    if let Some(server) = rule_tool.strip_suffix("__*") {
+        return tool_name.starts_with(&format!("{server}__"));
+    }
+    if rule_tool.starts_with("mcp__") && rule_tool.matches("__").count() == 1 {
+        return tool_name.starts_with(&format!("{rule_tool}__"));
+    }
+    (rule_tool == "Edit" && EDIT_TOOLS.contains(&tool_name))
+        || (rule_tool == "Read" && READ_TOOLS.contains(&tool_name))
+}
+
+fn input_path(input: &Value) -> Option<&str> {
+    ["file_path", "notebook_path", "path"]
+        .iter()
+        .find_map(|key| input.get(*key).and_then(Value::as_str))
+}
+
+/// Whether `rule`, from `scope`, matches one call (for Bash, one simple
+/// command).
+fn rule_matches(
+    rule: &PermissionRule,
+    scope: &str,
+    tool_name: &str,
+    input: &Value,
+    command: Option<&str>,
+    ctx: &Context,
+) -> bool {
+    if !tool_matches(&rule.tool, tool_name) {
+        return false;
+    }
+    let Some(spec) = rule.specifier.as_deref() else {
+        return true;
+    };
+    match rule.tool.as_str() {
+        "Bash" => command.is_some_and(|command| bash_spec_matches(spec, command)),
+        "WebFetch" => {
+            let Some(domain) = spec.strip_prefix("domain:") else {
+                return false;
+            };
+            input
+                .get("url")
+                .and_then(Value::as_str)
+                .and_then(url_host)
+                .is_some_and(|host| {
+                    host.eq_ignore_ascii_case(domain)
+                        || host
+                            .to_ascii_lowercase()
+                            .ends_with(&format!(".{}", domain.to_ascii_lowercase()))
+                })
+        }
+        _ if EDIT_TOOLS.contains(&rule.tool.as_str())
+            || READ_TOOLS.contains(&rule.tool.as_str()) =>
+        {
+            input_path(input).is_some_and(|path| {
+                let path = ctx.resolve_input_path(path);
+                glob_match(
+                    &ctx.resolve_rule_path(spec, scope),
+                    &path.to_string_lossy(),
+                    false,
+                )
+            })
+        }
+        _ => input.as_object().is_some_and(|fields| {
+            fields
+                .values()
+                .filter_map(Value::as_str)
+                .any(|value| glob_match(spec, value, true))
+        }),
+    }
+}
+
+fn bash_spec_matches(spec: &str, command: &str) -> bool {
+    let command = command.trim();
+    if let Some(prefix) = spec.strip_suffix(":*") {
+        return command == prefix
+            || command
+                .strip_prefix(prefix)
+                .is_some_and(|rest| rest.starts_with(char::is_whitespace));
+    }
+    if spec.contains('*') {
+        return glob_match(spec, command, true);
+    }
+    command == spec
+}
+
+fn url_host(url: &str) -> Option<&str> {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host = authority.rsplit('@').next()?;
+    let host = host.split(':').next()?;
+    (!host.is_empty()).then_some(host)
+}
+
+/// Wildcard match. `**` matches anything; `*` matches anything except `/`
+/// unless `star_crosses_slash`; `?` matches one character.
+pub(crate) fn glob_match(pattern: &str, text: &str, star_crosses_slash: bool) -> bool {
+    let p: Vec<char> = pattern.chars().collect();
+    let t: Vec<char> = text.chars().collect();
+    // dp[j]: pattern prefix processed so far matches t[..j]
+    let mut dp = vec![false; t.len() + 1];
+    dp[0] = true;
+    let mut i = 0;
+    while i < p.len() {
+        let mut next = vec![false; t.len() + 1];
+        if p[i] == '*' {
+            let double = p.get(i + 1) == Some(&'*');
+            let crosses = double || star_crosses_slash;
+            // `**/` also matches zero directories.
+            let skip_slash = double && p.get(i + 2) == Some(&'/');
+            for j in 0..=t.len() {
+                next[j] = dp[j] || (j > 0 && next[j - 1] && (crosses || t[j - 1] != '/'));
+            }
+            if skip_slash {
+                let mut after = next.clone();
+                for j in 0..t.len() {
+                    if next[j] && t[j] == '/' {
+                        after[j + 1] = true;
+                    }
+                }
+                // Let `**/` match zero directories as well as "dir/".
+                for j in 0..=t.len() {
+                    after[j] = after[j] || dp[j];
+                }
+                dp = after;
+                i += 3;
+                continue;
+            }
+            dp = next;
+            i += if double { 2 } else { 1 };
+            continue;
+        }
+        for j in 1..=t.len() {
+            next[j] = dp[j - 1] && (p[i] == '?' && t[j - 1] != '/' || p[i] == t[j - 1]);
+        }
+        dp = next;
+        i += 1;
+    }
+    dp[t.len()]
+}
+
+/// Split a shell command into its simple commands on `&&`, `||`, `;`, `|`
+/// and newlines, ignoring separators inside quotes.
+pub(crate) fn split_shell_commands(command: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    let mut chars = command.chars().peekable();
+    while let Some(c) = chars.next() {
+        match quote {
+            Some(q) => {
+                if c == '\\' && q == '"' {
+                    current.push(c);
+                    if let Some(next) = chars.next() {
+                        current.push(next);
+                    }
+                    continue;
+                }
+                if c == q {
+                    quote = None;
+                }
+                current.push(c);
+            }
+            None => match c {
+                '\'' | '"' => {
+                    quote = Some(c);
+                    current.push(c);
+                }
+                '\\' => {
+                    current.push(c);
+                    if let Some(next) = chars.next() {
+                        current.push(next);
+                    }
+                }
+                ';' | '\n' => parts.push(std::mem::take(&mut current)),
+                '&' if chars.peek() == Some(&'&') => {
+                    chars.next();
+                    parts.push(std::mem::take(&mut current));
+                }
+                '|' => {
+                    if chars.peek() == Some(&'|') {
+                        chars.next();
+                    }
+                    parts.push(std::mem::take(&mut current));
+                }
+                _ => current.push(c),
+            },
+        }
+    }
+    parts.push(current);
+    parts
+        .into_iter()
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect()
+}
+
+fn default_decision(tool_name: &str, default_mode: Option<&str>) -> (PermissionDecision, String) {
+    match default_mode {
+        Some("bypassPermissions") => (
+            PermissionDecision::Allow,
+            "No rule matched; bypassPermissions mode allows everything".to_string(),
+        ),
+        Some("dontAsk") => (
+            PermissionDecision::Deny,
+            "No rule matched; dontAsk mode denies anything not pre-approved".to_string(),
+        ),
+        Some("acceptEdits") if EDIT_TOOLS.contains(&tool_name) => (
+            PermissionDecision::Allow,
+            "No rule matched; acceptEdits mode allows file edits".to_string(),
+        ),
+        _ if READ_ONLY_TOOLS.contains(&tool_name) => (
+            PermissionDecision::Allow,
+            format!("No rule matched; {tool_name} is read-only and needs no approval"),
+        ),
+        _ => (
+            PermissionDecision::Ask,
+            "No rule matched; Claude will ask".to_string(),
+        ),
+    }
+}
+
+fn evaluate_rules(
+    rules: &[ScopedRule],
+    tool_name: &str,
+    input: &Value,
+    default_mode: Option<&str>,
+    ctx: &Context,
+) -> PermissionEvaluation {
+    let commands: Vec<Option<String>> = if tool_name == "Bash" {
+        let command = input.get("command").and_then(Value::as_str).unwrap_or("");
+        let parts = split_shell_commands(command);
+        if parts.is_empty() {
+            vec![Some(String::new())]
+        } else {
+            parts.into_iter().map(Some).collect()
+        }
+    } else {
+        vec![None]
+    };
+
+    let find = |list: PermissionDecision, command: Option<&str>| {
+        rules.iter().find(|r| {
+            r.reference.list == list
+                && r.parsed.as_ref().is_ok_and(|rule| {
+                    rule_matches(rule, &r.reference.scope, tool_name, input, command, ctx)
+                })
+        })
+    };
+    let describe = |command: &Option<String>| match command {
+        Some(command) if commands.len() > 1 => format!(" for `{command}`"),
+        _ => String::new(),
+    };
+
+    for list in [PermissionDecision::Deny, PermissionDecision::Ask] {
+        for command in &commands {
+            if let Some(rule) = find(list, command.as_deref()) {
+                return PermissionEvaluation {
+                    decision: list,
+                    matched_rule: Some(rule.reference.clone()),
+                    reason: format!(
+                        "Matched {} rule \"{}\"{}",
+                        list.as_str(),
+                        rule.reference.rule,
+                        describe(command)
+                    ),
+                };
+            }
+        }
+    }
+
+    let mut allowed_by = None;
+    for command in &commands {
+        match find(PermissionDecision::Allow, command.as_deref()) {
+            Some(rule) => {
+                allowed_by.get_or_insert(rule);
+            }
+            None => {
+                let (decision, reason) = default_decision(tool_name, default_mode);
+                let reason = match command {
+                    Some(command) if commands.len() > 1 => {
+                        format!("`{command}` is not covered by an allow rule. {reason}")
+                    }
+                    _ => reason,
+                };
+                return PermissionEvaluation {
+                    decision,
+                    matched_rule: None,
+                    reason,
+                };
+            }
+        }
+    }
+    let rule = allowed_by.expect("at least one command");
+    PermissionEvaluation {
+        decision: PermissionDecision::Allow,
+        matched_rule: Some(rule.reference.clone()),
+        reason: format!("Matched allow rule \"{}\"", rule.reference.rule),
+    }
+}
+
+/// Would Claude allow this tool call in `project_path`?
+pub fn evaluate(
+    project_path: Option<&str>,
+    tool_name: &str,
+    input: &Value,
+) -> anyhow::Result<PermissionEvaluation> {
+    let effective = effective_settings::load_effective_settings(project_path)?;
+    let rules = collect_rules(&effective.settings, &effective.provenance);
+    let default_mode = effective
+        .settings
+        .pointer("/permissions/defaultMode")
+        .and_then(Value::as_str);
+    let settings_roots = effective
+        .sources
+        .iter()
+        .filter_map(|source| {
+            Some((
+                source.scope.clone(),
+                settings_root(Path::new(&source.path))?,
+            ))
+        })
+        .collect();
+    let ctx = Context {
+        project_root: project_path.map(Path::new),
+        home: paths::home_dir(),
+        settings_roots,
+    };
+    Ok(evaluate_rules(&rules, tool_name, input, default_mode, &ctx))
+}
+
+/// Whether every call matched by `narrow` is also matched by `broad`.
+fn covers(broad: &PermissionRule, narrow: &PermissionRule) -> bool {
+    if !tool_matches(&broad.tool, &narrow.tool) {
+        return false;
+    }
+    let Some(broad_spec) = broad.specifier.as_deref() else {
+        return true;
+    };
+    let Some(narrow_spec) = narrow.specifier.as_deref() else {
+        return false;
+    };
+    if broad_spec == narrow_spec {
+        return true;
+    }
+    if broad.tool == "Bash" {
+        let narrow_text = narrow_spec.strip_suffix(":*").unwrap_or(narrow_spec);
+        if narrow_text.contains('*') {
+            return false;
+        }
+        return bash_spec_matches(broad_spec, narrow_text);
+    }
+    // Path and other globs: a literal narrow spec the broad glob matches.
+    !narrow_spec.contains(['*', '?']) && glob_match(broad_spec, narrow_spec, false)
+}
+
+fn lint_rule(rule: &PermissionRule) -> Option<String> {
+    let tool = rule.tool.as_str();
+    if !tool.starts_with("mcp__") && !KNOWN_TOOLS.contains(&tool) {
+        let hint = KNOWN_TOOLS
+            .iter()
+            .find(|known| known.eq_ignore_ascii_case(tool))
+            .map(|known| format!(" (did you mean \"{known}\"?)"))
+            .unwrap_or_default();
+        return Some(format!(
+            "Unknown tool \"{tool}\"{hint}; this rule never matches"
+        ));
+    }
+    let spec = rule.specifier.as_deref()?;
+    if tool.starts_with("mcp__") {
+        return Some("MCP rules do not take a specifier; this rule never matches".to_string());
+    }
+    match tool {
+        "Bash" if spec.contains(":*") && !spec.ends_with(":*") => {
+            Some("\":*\" only works at the end of a Bash rule".to_string())
+        }
+        "WebFetch" if !spec.starts_with("domain:") => {
+            Some("WebFetch rules need the form WebFetch(domain:example.com)".to_string())
+        }
+        _ => None,
+    }
+}
+
+fn lint_rules(rules: &[ScopedRule]) -> Vec<PermissionLintIssue> {
+    let mut issues = Vec::new();
+    for (i, rule) in rules.iter().enumerate() {
+        let parsed = match &rule.parsed {
+            Ok(parsed) => parsed,
+            Err(message) => {
+                issues.push(PermissionLintIssue {
+                    rule: rule.reference.clone(),
+                    severity: SettingsIssueSeverity::Error,
+                    message: message.clone(),
+                    related: None,
+                });
+                continue;
+            }
+        };
+        if let Some(message) = lint_rule(parsed) {
+            issues.push(PermissionLintIssue {
+                rule: rule.reference.clone(),
+                severity: SettingsIssueSeverity::Warning,
+                message,
+                related: None,
+            });
+            continue;
+        }
+        // Rules earlier in `rules` are evaluated first (deny, ask, allow), so
+        // an earlier rule that covers this one decides every call it would.
+        // `/path` rules from different settings files start from different
+        // directories, so their text cannot be compared.
+        let shadow = rules[..i].iter().find(|earlier| {
+            earlier.parsed.as_ref().is_ok_and(|earlier_rule| {
+                let comparable = earlier.reference.scope == rule.reference.scope
+                    || !(settings_relative(earlier_rule) || settings_relative(parsed));
+                comparable && covers(earlier_rule, parsed)
+            })
+        });
+        if let Some(earlier) = shadow {
+            let message = if earlier.reference.list == rule.reference.list {
+                format!(
+                    "Redundant: already covered by \"{}\"",
+                    earlier.reference.rule
+                )
+            } else {
+                format!(
+                    "Never applies: {} rule \"{}\" takes precedence",
+                    earlier.reference.list.as_str(),
+                    earlier.reference.rule
+                )
+            };
+            issues.push(PermissionLintIssue {
+                rule: rule.reference.clone(),
+                severity: SettingsIssueSeverity::Warning,
+                message,
+                related: Some(earlier.reference.clone()),
+            });
+        }
+    }
+    issues
+}
+
+/// Problems with the permission rules merged from every scope.
+pub fn lint(project_path: Option<&str>) -> anyhow::Result<Vec<PermissionLintIssue>> {
+    let effective = effective_settings::load_effective_settings(project_path)?;
+    Ok(lint_rules(&collect_rules(
+        &effective.settings,
+        &effective.provenance,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rules(settings: Value) -> Vec<ScopedRule> {
+        let (merged, provenance) = effective_settings::merge_layers(&[("project", settings)]);
+        collect_rules(&merged, &provenance)
+    }
+
+    fn ctx() -> Context<'static> {
+        Context {
+            project_root: Some(Path::new("/repo")),
+            home: PathBuf::from("/home/me"),
+            settings_roots: BTreeMap::from([
+                ("user".to_string(), PathBuf::from("/home/me")),
+                ("project".to_string(), PathBuf::from("/repo")),
+            ]),
+        }
+    }
+
+    fn eval(rules: &[ScopedRule], tool: &str, input: Value) -> PermissionEvaluation {
+        evaluate_rules(rules, tool, &input, None, &ctx())
+    }
+
+    #[test]
+    fn parses_rules() {
+        assert_eq!(
+            parse_rule("Bash(git push:*)").unwrap(),
+            PermissionRule {
+                tool: "Bash".into(),
+                specifier: Some("git push:*".into())
+            }
+        );
+        assert_eq!(parse_rule("WebSearch").unwrap().specifier, None);
+        assert!(parse_rule("Bash(").is_err());
+        assert!(parse_rule("Bash()").is_err());
+        assert!(parse_rule("(ls)").is_err());
+        assert!(parse_rule("Bash)").is_err());
+    }
+
+    #[test]
+    fn deny_beats_ask_beats_allow() {
+        let rules = rules(json!({"permissions": {
+            "allow": ["Bash(git:*)"],
+            "ask": ["Bash(git push:*)"],
+            "deny": ["Bash(git push --force:*)"]
+        }}));
+
+        let status = eval(&rules, "Bash", json!({"command": "git status"}));
+        assert_eq!(status.decision, PermissionDecision::Allow);
+        assert_eq!(status.matched_rule.unwrap().rule, "Bash(git:*)");
+
+        let push = eval(&rules, "Bash", json!({"command": "git push origin main"}));
+        assert_eq!(push.decision, PermissionDecision::Ask);
+
+        let force = eval(&rules, "Bash", json!({"command": "git push --force"}));
+        assert_eq!(force.decision, PermissionDecision::Deny);
+        assert_eq!(force.matched_rule.unwrap().scope, "project");
+    }
+
+    #[test]
+    fn bash_prefixes_respect_word_boundaries_and_compound_commands() {
+        let rules = rules(json!({"permissions": {"allow": ["Bash(git:*)", "Bash(npm test)"]}}));
+
+        assert_eq!(
+            eval(&rules, "Bash", json!({"command": "gitk"})).decision,
+            PermissionDecision::Ask
+        );
+        assert_eq!(
+            eval(&rules, "Bash", json!({"command": "git add . && npm test"})).decision,
+            PermissionDecision::Allow
+        );
+        let chained = eval(&rules, "Bash", json!({"command": "git add . && rm -rf /"}));
+        assert_eq!(chained.decision, PermissionDecision::Ask);
+        assert!(chained.reason.contains("`rm -rf /`"));
+        assert_eq!(
+            eval(&rules, "Bash", json!({"command": "git log --grep='a;b'"})).decision,
+            PermissionDecision::Allow
+        );
+    }
+
+    #[test]
+    fn file_rules_use_gitignore_style_paths() {
+        let rules = rules(json!({"permissions": {
+            "deny": ["Read(*.env)", "Edit(//etc/**)", "Edit(~/.zshrc)"],
+            "allow": ["Edit(/src/**)"]
+        }}));
+
+        assert_eq!(
+            eval(&rules, "Read", json!({"file_path": "/repo/config/.env"})).decision,
+            PermissionDecision::Deny
+        );
+        assert_eq!(
+            eval(&rules, "Grep", json!({"path": "app/prod.env"})).decision,
+            PermissionDecision::Deny
+        );
+        assert_eq!(
+            eval(&rules, "Write", json!({"file_path": "/etc/hosts"})).decision,
+            PermissionDecision::Deny
+        );
+        assert_eq!(
+            eval(&rules, "Edit", json!({"file_path": "/home/me/.zshrc"})).decision,
+            PermissionDecision::Deny
+        );
+        assert_eq!(
+            eval(&rules, "MultiEdit", json!({"file_path": "src/lib/a.rs"})).decision,
+            PermissionDecision::Allow
+        );
+        assert_eq!(
+            eval(&rules, "Edit", json!({"file_path": "/repo/README.md"})).decision,
+            PermissionDecision::Ask
+        );
+        assert_eq!(
+            eval(&rules, "Read", json!({"file_path": "/repo/README.md"})).decision,
+            PermissionDecision::Allow
+        );
+    }
+
+    #[test]
+    fn slash_paths_start_from_the_rule_settings_file() {
+        let (merged, provenance) = effective_settings::merge_layers(&[
+            (
+                "user",
+                json!({"permissions": {"deny": ["Edit(/notes/**)"]}}),
+            ),
+            (
+                "project",
+                json!({"permissions": {"deny": ["Edit(/notes/a.md)"]}}),
+            ),
+        ]);
+        let rules = collect_rules(&merged, &provenance);
+
+        let home = eval(
+            &rules,
+            "Edit",
+            json!({"file_path": "/home/me/notes/todo.md"}),
+        );
+        assert_eq!(home.decision, PermissionDecision::Deny);
+        assert_eq!(home.matched_rule.unwrap().scope, "user");
+        let repo = eval(&rules, "Edit", json!({"file_path": "/repo/notes/todo.md"}));
+        assert_eq!(repo.decision, PermissionDecision::Ask);
+        // Same text, different roots: the project rule is not shadowed.
+        assert!(lint_rules(&rules).is_empty());
+
+        assert_eq!(
+            settings_root(Path::new("/repo/.claude/settings.local.json")),
+            Some(PathBuf::from("/repo"))
+        );
+        assert_eq!(
+            settings_root(Path::new("/etc/claude-code/managed-settings.json")),
+            Some(PathBuf::from("/etc/claude-code"))
+        );
+    }
+
+    #[test]
+    fn web_fetch_and_mcp_rules() {
+        let rules = rules(json!({"permissions": {
+            "allow": ["WebFetch(domain:docs.rs)", "mcp__github"],
+            "deny": ["mcp__db__drop_table"]
+        }}));
+
+        assert_eq!(
+            eval(&rules, "WebFetch", json!({"url": "https://docs.rs/serde"})).decision,
+            PermissionDecision::Allow
+        );
+        assert_eq!(
+            eval(&rules, "WebFetch", json!({"url": "https://evil-docs.rs/"})).decision,
+            PermissionDecision::Ask
+        );
+        assert_eq!(
+            eval(&rules, "mcp__github__create_issue", json!({})).decision,
+            PermissionDecision::Allow
+        );
+        assert_eq!(
+            eval(&rules, "mcp__db__drop_table", json!({})).decision,
+            PermissionDecision::Deny
+        );
+    }
+
+    #[test]
+    fn default_mode_decides_unmatched_calls() {
+        let edit = json!({"file_path": "/repo/a.rs"});
+        let ctx = ctx();
+        assert_eq!(
+            evaluate_rules(&[], "Edit", &edit, Some("acceptEdits"), &ctx).decision,
+            PermissionDecision::Allow
+        );
+        assert_eq!(
+            evaluate_rules(
+                &[],
+                "Bash",
+                &json!({"command": "ls"}),
+                Some("dontAsk"),
+                &ctx
+            )
+            .decision,
+            PermissionDecision::Deny
+        );
+        assert_eq!(
+            evaluate_rules(&[], "Glob", &json!({}), None, &ctx).decision,
+            PermissionDecision::Allow
+        );
+    }
+
+    #[test]
+    fn lints_shadowed_redundant_and_dead_rules() {
+        let rules = rules(json!({"permissions": {
+            "deny": ["Bash(git push:*)"],
+            "allow": [
+                "Bash(git:*)",
+                "Bash(git push origin:*)",
+                "Bash(git status)",
+                "bash(ls)",
+                "Bash(git:* --help)",
+                "WebFetch(docs.rs)",
+                "Read("
+            ]
+        }}));
+
+        let issues = lint_rules(&rules);
+        let summary: Vec<(&str, &str)> = issues
+            .iter()
+            .map(|i| (i.rule.rule.as_str(), i.message.as_str()))
+            .collect();
+
+        assert_eq!(summary.len(), 6, "{summary:?}");
+        assert_eq!(
+            summary[0],
+            (
+                "Bash(git push origin:*)",
+                "Never applies: deny rule \"Bash(git push:*)\" takes precedence"
+            )
+        );
+        assert_eq!(
+            summary[1],
+            (
+                "Bash(git status)",
+                "Redundant: already covered by \"Bash(git:*)\""
+            )
+        );
+        assert!(summary[2].1.contains("did you mean \"Bash\""));
+        assert!(summary[3].1.contains("only works at the end"));
+        assert!(summary[4].1.contains("domain:"));
+        assert_eq!(issues[5].severity, SettingsIssueSeverity::Error);
+    }
+
+    #[test]
+    fn glob_matching() {
+        assert!(glob_match("/repo/**/*.env", "/repo/.env", false));
+        assert!(glob_match("/repo/**/*.env", "/repo/a/b/prod.env", false));
+        assert!(!glob_match("/repo/*.env", "/repo/a/prod.env", false));
+        assert!(glob_match("/repo/src/**", "/repo/src/a/b.rs", false));
+        assert!(glob_match("git * main", "git push origin main", true));
+        assert!(!glob_match("a?c", "a/c", false));
+    }
+}
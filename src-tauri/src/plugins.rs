@@ -0,0 +1,836 @@
+/// Claude plugins: marketplaces registered in `~/.claude/plugins/known_marketplaces.json`,
+/// installs recorded in `installed_plugins.json` and copied to `cache/<marketplace>/<plugin>`,
+/// and the `enabledPlugins` settings key that switches an install on or off per scope.
+use anyhow::{bail, Context, Result};
+use serde_json::{json, Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config;
+use crate::paths;
+use crate::settings;
+use crate::types::{
+    MarketplaceInfo, MarketplacePluginInfo, PluginComponents, PluginInfo, PluginUninstallReport,
+};
+
+const KNOWN_MARKETPLACES_FILE: &str = "known_marketplaces.json";
+const INSTALLED_PLUGINS_FILE: &str = "installed_plugins.json";
+/// The only `installed_plugins.json` layout Workbench reads and writes.
+const INSTALLED_PLUGINS_VERSION: u64 = 1;
+const MANIFEST_DIR: &str = ".claude-plugin";
+const SETTINGS_SCOPES: &[&str] = &["user", "user-local", "project", "project-local"];
+
+fn plugins_dir() -> PathBuf {
+    paths::claude_user_dir().join("plugins")
+}
+
+/// Marketplace and plugin names become directory names and ids.
+fn check_name(kind: &str, name: &str) -> Result<()> {
+    if name.is_empty()
+        || name == "."
+        || name == ".."
+        || name.contains(['/', '\\', '@'])
+        || name.trim() != name
+    {
+        bail!("Invalid {kind} name \"{name}\"");
+    }
+    Ok(())
+}
+
+/// `name@marketplace`, the key Claude uses in `enabledPlugins`.
+fn plugin_id(plugin: &str, marketplace: &str) -> String {
+    format!("{plugin}@{marketplace}")
+}
+
+fn read_json(path: &Path) -> Option<Value> {
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn str_field(value: &Value, key: &str) -> String {
+    value
+        .get(key)
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string()
+}
+
+/// `.claude-plugin/plugin.json`, or a bare `plugin.json` from older installs.
+fn plugin_manifest(dir: &Path) -> Option<Value> {
+    read_json(&dir.join(MANIFEST_DIR).join("plugin.json"))
+        .or_else(|| read_json(&dir.join("plugin.json")))
+}
+
+fn markdown_names(dir: &Path, prefix: &str, recursive: bool, out: &mut Vec<String>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Some(file_name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        if path.is_dir() {
+            if recursive {
+                markdown_names(&path, &format!("{prefix}{file_name}/"), true, out);
+            }
+        } else if let Some(stem) = file_name.strip_suffix(".md") {
+            out.push(format!("{prefix}{stem}"));
+        }
+    }
+}
+
+fn object_keys(value: Option<&Value>) -> Vec<String> {
+    value
+        .and_then(|v| v.as_object())
+        .map(|map| map.keys().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// A manifest entry is either inline JSON or a path relative to the plugin root.
+fn inline_or_file(dir: &Path, value: Option<&Value>, default_file: &str) -> Option<Value> {
+    match value {
+        Some(Value::String(rel)) => read_json(&dir.join(rel.trim_start_matches("./"))),
+        Some(inline @ Value::Object(_)) => Some(inline.clone()),
+        _ => read_json(&dir.join(default_file)),
+    }
+}
+
+/// Skills, commands, agents, hook events and MCP servers bundled in a plugin directory.
+pub(crate) fn plugin_components(dir: &Path, manifest: Option<&Value>) -> PluginComponents {
+    let mut components = PluginComponents::default();
+
+    markdown_names(&dir.join("commands"), "", true, &mut components.commands);
+    markdown_names(&dir.join("agents"), "", false, &mut components.agents);
+    if let Ok(entries) = fs::read_dir(dir.join("skills")) {
+        for entry in entries.flatten() {
+            if entry.path().join("SKILL.md").is_file() {
+                components
+                    .skills
+                    .push(entry.file_name().to_string_lossy().into_owned());
+            }
+        }
+    }
+
+    let hooks = inline_or_file(
+        dir,
+        manifest.and_then(|m| m.get("hooks")),
+        "hooks/hooks.json",
+    );
+    components.hooks = object_keys(hooks.as_ref().and_then(|h| h.get("hooks")));
+
+    let mcp = inline_or_file(dir, manifest.and_then(|m| m.get("mcpServers")), ".mcp.json");
+    components.mcp_servers = match mcp {
+        Some(value) if value.get("mcpServers").is_some() => object_keys(value.get("mcpServers")),
+        other => object_keys(other.as_ref()),
+    };
+
+    components.skills.sort();
+    components.commands.sort();
+    components.agents.sort();
+    components
+}
+
+fn load_registry(path: &Path) -> Result<Map<String, Value>> {
+    match paths::load_json_strict(path, Value::Object(Map::new()))
+        .with_context(|| format!("Failed to read {}", path.display()))?
+    {
+        Value::Object(map) => Ok(map),
+        _ => bail!("{} is not a JSON object", path.display()),
+    }
+}
+
+/// The installed-plugins registry. Any layout but v1 is refused, so a file
+/// from a newer Claude is never misread or rewritten in the old shape.
+fn load_installed_registry(path: &Path) -> Result<Map<String, Value>> {
+    let registry = load_registry(path)?;
+    let version = registry.get("version");
+    if registry.is_empty() || version.and_then(Value::as_u64) == Some(INSTALLED_PLUGINS_VERSION) {
+        return Ok(registry);
+    }
+    bail!(
+        "{} has version {}; only version {INSTALLED_PLUGINS_VERSION} is supported",
+        path.display(),
+        version.map_or("(none)".to_string(), Value::to_string)
+    )
+}
+
+/// Installed entries keyed by plugin id, from the v1 `{version, plugins}` layout.
+fn installed_entries(plugins_dir: &Path) -> Result<Map<String, Value>> {
+    let registry = load_installed_registry(&plugins_dir.join(INSTALLED_PLUGINS_FILE))?;
+    Ok(registry
+        .get("plugins")
+        .and_then(|p| p.as_object())
+        .cloned()
+        .unwrap_or_default())
+}
+
+fn save_installed_entries(plugins_dir: &Path, entries: Map<String, Value>) -> Result<()> {
+    let path = plugins_dir.join(INSTALLED_PLUGINS_FILE);
+    let mut registry = load_installed_registry(&path)?;
+    registry.insert("version".to_string(), json!(INSTALLED_PLUGINS_VERSION));
+    registry.insert("plugins".to_string(), Value::Object(entries));
+    paths::save_json(&path, &Value::Object(registry))
+}
+
+fn plugin_info(dir: &Path, id: String, marketplace: Option<String>) -> PluginInfo {
+    let manifest = plugin_manifest(dir);
+    let fallback = id.split('@').next().unwrap_or(&id).to_string();
+    let value = manifest.clone().unwrap_or(Value::Null);
+    let name = value
+        .get("name")
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+        .unwrap_or(fallback);
+    PluginInfo {
+        name,
+        description: str_field(&value, "description"),
+        version: str_field(&value, "version"),
+        dir_name: id,
+        marketplace,
+        components: plugin_components(dir, manifest.as_ref()),
+    }
+}
+
+pub(crate) fn list_installed_in(plugins_dir: &Path) -> Result<Vec<PluginInfo>> {
+    let mut plugins = Vec::new();
+    for (id, entry) in installed_entries(plugins_dir)? {
+        let Some(install_path) = entry.get("installPath").and_then(|v| v.as_str()) else {
+            continue;
+        };
+        let marketplace = id.split_once('@').map(|(_, m)| m.to_string());
+        plugins.push(plugin_info(Path::new(install_path), id, marketplace));
+    }
+
+    // Older layouts kept a bare plugin.json directly in cache/<name>
+    let cache_dir = plugins_dir.join("cache");
+    if let Ok(entries) = fs::read_dir(&cache_dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !path.join("plugin.json").is_file() {
+                continue;
+            }
+            let dir_name = entry.file_name().to_string_lossy().into_owned();
+            if plugins.iter().any(|p| p.dir_name == dir_name) {
+                continue;
+            }
+            plugins.push(plugin_info(&path, dir_name, None));
+        }
+    }
+
+    plugins.sort_by(|a, b| a.dir_name.cmp(&b.dir_name));
+    Ok(plugins)
+}
+
+pub fn list_plugins() -> Result<Vec<PluginInfo>> {
+    list_installed_in(&plugins_dir())
+}
+
+fn marketplace_manifest(root: &Path) -> Result<Value> {
+    let path = root.join(MANIFEST_DIR).join("marketplace.json");
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("No marketplace manifest at {}", path.display()))?;
+    serde_json::from_str(&content).with_context(|| format!("Invalid JSON in {}", path.display()))
+}
+
+/// Directory of a plugin inside a local marketplace; `None` for GitHub or URL sources.
+fn plugin_source_dir(root: &Path, manifest: &Value, entry: &Value) -> Option<PathBuf> {
+    let source = entry.get("source")?.as_str()?;
+    let plugin_root = manifest
+        .get("metadata")
+        .and_then(|m| m.get("pluginRoot"))
+        .and_then(|v| v.as_str());
+    let base = match plugin_root {
+        Some(rel) if !source.starts_with("./") => root.join(rel.trim_start_matches("./")),
+        _ => root.to_path_buf(),
+    };
+    let dir = base.join(source.trim_start_matches("./"));
+    dir.is_dir().then_some(dir)
+}
+
+fn marketplace_root(entry: &Value) -> Option<PathBuf> {
+    entry
+        .get("installLocation")
+        .or_else(|| entry.get("source").and_then(|s| s.get("path")))
+        .and_then(|v| v.as_str())
+        .map(PathBuf::from)
+}
+
+fn read_marketplace(name: &str, root: &Path, installed: &Map<String, Value>) -> MarketplaceInfo {
+    let mut info = MarketplaceInfo {
+        name: name.to_string(),
+        path: root.to_string_lossy().into_owned(),
+        is_git: root.join(".git").exists(),
+        plugins: Vec::new(),
+        error: None,
+    };
+    let manifest = match marketplace_manifest(root) {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            info.error = Some(e.to_string());
+            return info;
+        }
+    };
+    let entries = manifest
+        .get("plugins")
+        .and_then(|p| p.as_array())
+        .cloned()
+        .unwrap_or_default();
+    for entry in entries {
+        let plugin_name = str_field(&entry, "name");
+        if plugin_name.is_empty() {
+            continue;
+        }
+        let id = plugin_id(&plugin_name, name);
+        let source_dir = plugin_source_dir(root, &manifest, &entry);
+        let plugin_manifest = source_dir.as_deref().and_then(plugin_manifest);
+        let describe = |key: &str| {
+            let from_entry = str_field(&entry, key);
+            if from_entry.is_empty() {
+                plugin_manifest
+                    .as_ref()
+                    .map(|m| str_field(m, key))
+                    .unwrap_or_default()
+            } else {
+                from_entry
+            }
+        };
+        info.plugins.push(MarketplacePluginInfo {
+            installed: installed.contains_key(&id),
+            id,
+            name: plugin_name,
+            marketplace: name.to_string(),
+            description: describe("description"),
+            version: describe("version"),
+            installable: source_dir.is_some(),
+            components: source_dir
+                .as_deref()
+                .map(|dir| plugin_components(dir, plugin_manifest.as_ref()))
+                .unwrap_or_default(),
+        });
+    }
+    info
+}
+
+pub(crate) fn list_marketplaces_in(plugins_dir: &Path) -> Result<Vec<MarketplaceInfo>> {
+    let known = load_registry(&plugins_dir.join(KNOWN_MARKETPLACES_FILE))?;
+    let installed = installed_entries(plugins_dir)?;
+    let mut marketplaces: Vec<MarketplaceInfo> = known
+        .iter()
+        .filter_map(|(name, entry)| {
+            marketplace_root(entry).map(|root| read_marketplace(name, &root, &installed))
+        })
+        .collect();
+    marketplaces.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(marketplaces)
+}
+
+pub fn list_marketplaces() -> Result<Vec<MarketplaceInfo>> {
+    list_marketplaces_in(&plugins_dir())
+}
+
+/// Register a local directory (or an already cloned git repo) as a marketplace.
+pub(crate) fn add_marketplace_in(plugins_dir: &Path, path: &Path) -> Result<MarketplaceInfo> {
+    let root = path
+        .canonicalize()
+        .with_context(|| format!("Marketplace directory {} not found", path.display()))?;
+    let manifest = marketplace_manifest(&root)?;
+    let name = str_field(&manifest, "name");
+    check_name("marketplace", &name)?;
+
+    let known_path = plugins_dir.join(KNOWN_MARKETPLACES_FILE);
+    let mut known = load_registry(&known_path)?;
+    if let Some(existing) = known.get(&name).and_then(marketplace_root) {
+        if existing != root {
+            bail!(
+                "A marketplace named \"{name}\" is already registered from {}",
+                existing.display()
+            );
+        }
+    }
+    let root_str = root.to_string_lossy().into_owned();
+    known.insert(
+        name.clone(),
+        json!({
+            "source": {"source": "directory", "path": root_str},
+            "installLocation": root_str,
+            "lastUpdated": chrono::Utc::now().to_rfc3339(),
+        }),
+    );
+    paths::save_json(&known_path, &Value::Object(known))?;
+    Ok(read_marketplace(
+        &name,
+        &root,
+        &installed_entries(plugins_dir)?,
+    ))
+}
+
+pub fn add_marketplace(path: &str) -> Result<MarketplaceInfo> {
+    add_marketplace_in(&plugins_dir(), Path::new(path))
+}
+
+/// Forget a marketplace. Its plugins must be uninstalled first so no
+/// `enabledPlugins` entry is left pointing at an unknown source.
+pub(crate) fn remove_marketplace_in(plugins_dir: &Path, name: &str) -> Result<bool> {
+    let known_path = plugins_dir.join(KNOWN_MARKETPLACES_FILE);
+    let mut known = load_registry(&known_path)?;
+    if !known.contains_key(name) {
+        return Ok(false);
+    }
+    let suffix = format!("@{name}");
+    let remaining: Vec<String> = installed_entries(plugins_dir)?
+        .keys()
+        .filter(|id| id.ends_with(&suffix))
+        .cloned()
+        .collect();
+    if !remaining.is_empty() {
+        bail!(
+            "Uninstall {} before removing marketplace \"{name}\"",
+            remaining.join(", ")
+        );
+    }
+    known.remove(name);
+    paths::save_json(&known_path, &Value::Object(known))?;
+    Ok(true)
+}
+
+pub fn remove_marketplace(name: &str) -> Result<bool> {
+    remove_marketplace_in(&plugins_dir(), name)
+}
+
+/// Copy a plugin out of a registered marketplace into the cache and record it.
+pub(crate) fn install_in(plugins_dir: &Path, marketplace: &str, plugin: &str) -> Result<String> {
+    check_name("marketplace", marketplace)?;
+    check_name("plugin", plugin)?;
+    let known = load_registry(&plugins_dir.join(KNOWN_MARKETPLACES_FILE))?;
+    let root = known
+        .get(marketplace)
+        .and_then(marketplace_root)
+        .with_context(|| format!("Unknown marketplace \"{marketplace}\""))?;
+    let manifest = marketplace_manifest(&root)?;
+    let entry = manifest
+        .get("plugins")
+        .and_then(|p| p.as_array())
+        .and_then(|plugins| {
+            plugins
+                .iter()
+                .find(|p| p.get("name").and_then(|n| n.as_str()) == Some(plugin))
+        })
+        .with_context(|| format!("Marketplace \"{marketplace}\" has no plugin \"{plugin}\""))?;
+    let source = plugin_source_dir(&root, &manifest, entry).with_context(|| {
+        format!("Plugin \"{plugin}\" is not a local directory in \"{marketplace}\"")
+    })?;
+
+    let dest = plugins_dir.join("cache").join(marketplace).join(plugin);
+    paths::remove_path_if_exists(&dest)?;
+    paths::copy_dir_skip_symlinks(&source, &dest)?;
+
+    let version = {
+        let from_entry = str_field(entry, "version");
+        if from_entry.is_empty() {
+            plugin_manifest(&dest)
+                .map(|m| str_field(&m, "version"))
+                .unwrap_or_default()
+        } else {
+            from_entry
+        }
+    };
+    let id = plugin_id(plugin, marketplace);
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut entries = installed_entries(plugins_dir)?;
+    let installed_at = entries
+        .get(&id)
+        .and_then(|e| e.get("installedAt"))
+        .cloned()
+        .unwrap_or_else(|| json!(now));
+    entries.insert(
+        id.clone(),
+        json!({
+            "version": if version.is_empty() { "unknown".to_string() } else { version },
+            "installedAt": installed_at,
+            "lastUpdated": now,
+            "installPath": dest.to_string_lossy(),
+            "gitCommitSha": Value::Null,
+            "isLocal": true,
+        }),
+    );
+    save_installed_entries(plugins_dir, entries)?;
+    Ok(id)
+}
+
+/// Delete an installed plugin's files and registry entry.
+pub(crate) fn uninstall_in(plugins_dir: &Path, id: &str) -> Result<bool> {
+    let mut entries = installed_entries(plugins_dir)?;
+    let Some(entry) = entries.remove(id) else {
+        return Ok(false);
+    };
+    let cache_dir = plugins_dir.join("cache");
+    if let Some(install_path) = entry.get("installPath").and_then(|v| v.as_str()) {
+        let install_path = PathBuf::from(install_path);
+        // Never delete anything Claude did not put in the cache
+        if install_path.starts_with(&cache_dir) {
+            paths::remove_path_if_exists(&install_path)?;
+            if let Some(parent) = install_path.parent() {
+                if parent != cache_dir && fs::read_dir(parent)?.next().is_none() {
+                    fs::remove_dir(parent)?;
+                }
+            }
+        }
+    }
+    save_installed_entries(plugins_dir, entries)?;
+    Ok(true)
+}
+
+/// Set `enabledPlugins[id]`, migrating the legacy `enabledPlugins`/`disabledPlugins`
+/// arrays to Claude's object form. Returns whether the settings changed.
+pub(crate) fn set_enabled_value(settings: &mut Value, id: &str, enabled: bool) -> bool {
+    let Some(obj) = settings.as_object_mut() else {
+        return false;
+    };
+    let mut changed = false;
+    let mut map = match obj.remove("enabledPlugins") {
+        Some(Value::Object(map)) => map,
+        Some(Value::Array(items)) => {
+            changed = true;
+            items
+                .iter()
+                .filter_map(|v| v.as_str())
+                .map(|name| (name.to_string(), json!(true)))
+                .collect()
+        }
+        _ => Map::new(),
+    };
+    if let Some(Value::Array(disabled)) = obj.remove("disabledPlugins") {
+        changed = true;
+        for name in disabled.iter().filter_map(|v| v.as_str()) {
+            map.insert(name.to_string(), json!(false));
+        }
+    }
+    if map.get(id) != Some(&json!(enabled)) {
+        map.insert(id.to_string(), json!(enabled));
+        changed = true;
+    }
+    obj.insert("enabledPlugins".to_string(), Value::Object(map));
+    changed
+}
+
+/// Drop every mention of `id` from `enabledPlugins` and `disabledPlugins`.
+pub(crate) fn remove_enabled_value(settings: &mut Value, id: &str) -> bool {
+    let Some(obj) = settings.as_object_mut() else {
+        return false;
+    };
+    let mut changed = false;
+    for key in ["enabledPlugins", "disabledPlugins"] {
+        match obj.get_mut(key) {
+            Some(Value::Object(map)) => changed |= map.remove(id).is_some(),
+            Some(Value::Array(items)) => {
+                let before = items.len();
+                items.retain(|v| v.as_str() != Some(id));
+                changed |= items.len() != before;
+            }
+            _ => {}
+        }
+    }
+    changed
+}
+
+fn scope_project<'a>(scope: &str, project_path: Option<&'a str>) -> Option<&'a str> {
+    if scope.starts_with("project") {
+        project_path
+    } else {
+        None
+    }
+}
+
+pub fn set_plugin_enabled(
+    id: &str,
+    scope: &str,
+    project_path: Option<&str>,
+    enabled: bool,
+) -> Result<()> {
+    let project = scope_project(scope, project_path);
+    if scope.starts_with("project") && project.is_none() {
+        bail!("Project scope needs a project path");
+    }
+    let mut value = settings::load_settings(scope, project)?;
+    if set_enabled_value(&mut value, id, enabled) {
+        settings::save_settings(scope, project, &value)?;
+    }
+    Ok(())
+}
+
+/// Install from a marketplace and enable the plugin in `scope`.
+pub fn install_plugin(
+    marketplace: &str,
+    plugin: &str,
+    scope: &str,
+    project_path: Option<&str>,
+) -> Result<String> {
+    let id = install_in(&plugins_dir(), marketplace, plugin)?;
+    set_plugin_enabled(&id, scope, project_path, true)?;
+    eprintln!("[Plugins] Installed {id} ({scope})");
+    Ok(id)
+}
+
+/// Drop `id` from one settings file. Returns whether the file changed.
+fn clear_enabled_file(path: &Path, id: &str) -> Result<bool> {
+    if !path.exists() {
+        return Ok(false);
+    }
+    let content = fs::read_to_string(path)?;
+    let mut value: Value = serde_json::from_str(&content)?;
+    if !remove_enabled_value(&mut value, id) {
+        return Ok(false);
+    }
+    paths::atomic_write(path, &serde_json::to_string_pretty(&value)?)?;
+    Ok(true)
+}
+
+/// Clear `id` from the user scopes and from the project scopes of every
+/// project given. Files that cannot be updated are reported as remaining.
+fn clear_enabled_in(
+    user_dir: &Path,
+    projects: &[String],
+    id: &str,
+    report: &mut PluginUninstallReport,
+) {
+    let targets = SETTINGS_SCOPES.iter().flat_map(|scope| {
+        let projects: Vec<Option<&str>> = if scope.starts_with("project") {
+            projects.iter().map(|p| Some(p.as_str())).collect()
+        } else {
+            vec![None]
+        };
+        projects
+            .into_iter()
+            .filter_map(|project| settings::settings_path_in(user_dir, scope, project).ok())
+    });
+    for path in targets {
+        let shown = path.to_string_lossy().to_string();
+        match clear_enabled_file(&path, id) {
+            Ok(true) => report.cleared.push(shown),
+            Ok(false) => {}
+            Err(e) => {
+                eprintln!("[Plugins] Could not clear {id} from {shown}: {e:#}");
+                report.remaining.push(shown);
+            }
+        }
+    }
+}
+
+/// Uninstall and clear the plugin from `enabledPlugins` in the user scopes
+/// and in every project Workbench knows about. Projects outside Workbench
+/// are not searched.
+pub fn uninstall_plugin(id: &str, project_path: Option<&str>) -> Result<PluginUninstallReport> {
+    let removed = uninstall_in(&plugins_dir(), id)?;
+    let mut projects: Vec<String> = project_path.map(str::to_string).into_iter().collect();
+    match config::load_projects() {
+        Ok(known) => {
+            for project in known {
+                if !projects.contains(&project.path) {
+                    projects.push(project.path);
+                }
+            }
+        }
+        Err(e) => eprintln!("[Plugins] Failed to load projects: {e:#}"),
+    }
+    let mut report = PluginUninstallReport {
+        removed,
+        cleared: Vec::new(),
+        remaining: Vec::new(),
+    };
+    clear_enabled_in(&paths::claude_user_dir(), &projects, id, &mut report);
+    if removed {
+        eprintln!("[Plugins] Uninstalled {id}");
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    /// A marketplace with one local plugin and one GitHub-sourced plugin.
+    fn marketplace(root: &Path) {
+        write(
+            &root.join(".claude-plugin/marketplace.json"),
+            r#"{
+                "name": "team-tools",
+                "metadata": {"pluginRoot": "./plugins"},
+                "plugins": [
+                    {"name": "formatter", "source": "formatter", "description": "Formats code"},
+                    {"name": "remote", "source": {"source": "github", "repo": "acme/remote"}}
+                ]
+            }"#,
+        );
+        let plugin = root.join("plugins/formatter");
+        write(
+            &plugin.join(".claude-plugin/plugin.json"),
+            r#"{"name": "formatter", "version": "1.2.0"}"#,
+        );
+        write(&plugin.join("commands/format.md"), "Format");
+        write(&plugin.join("commands/git/fixup.md"), "Fixup");
+        write(&plugin.join("agents/reviewer.md"), "Review");
+        write(
+            &plugin.join("skills/style/SKILL.md"),
+            "---\nname: style\n---\n",
+        );
+        write(
+            &plugin.join("hooks/hooks.json"),
+            r#"{"hooks": {"PostToolUse": [], "Stop": []}}"#,
+        );
+        write(&plugin.join(".mcp.json"), r#"{"mcpServers": {"lint": {}}}"#);
+    }
+
+    #[test]
+    fn lists_components_of_marketplace_plugins() {
+        let home = tempdir().unwrap();
+        let source = tempdir().unwrap();
+        marketplace(source.path());
+
+        let added = add_marketplace_in(home.path(), source.path()).unwrap();
+        assert_eq!(added.name, "team-tools");
+        assert!(!added.is_git);
+        assert_eq!(added.plugins.len(), 2);
+
+        let formatter = &added.plugins[0];
+        assert_eq!(formatter.id, "formatter@team-tools");
+        assert_eq!(formatter.version, "1.2.0");
+        assert!(formatter.installable);
+        assert_eq!(formatter.components.commands, ["format", "git/fixup"]);
+        assert_eq!(formatter.components.agents, ["reviewer"]);
+        assert_eq!(formatter.components.skills, ["style"]);
+        assert_eq!(formatter.components.hooks, ["PostToolUse", "Stop"]);
+        assert_eq!(formatter.components.mcp_servers, ["lint"]);
+        assert!(!added.plugins[1].installable);
+
+        let listed = list_marketplaces_in(home.path()).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert!(listed[0].error.is_none());
+    }
+
+    #[test]
+    fn install_and_uninstall_round_trip() {
+        let home = tempdir().unwrap();
+        let source = tempdir().unwrap();
+        marketplace(source.path());
+        add_marketplace_in(home.path(), source.path()).unwrap();
+
+        let id = install_in(home.path(), "team-tools", "formatter").unwrap();
+        assert_eq!(id, "formatter@team-tools");
+        let install_dir = home.path().join("cache/team-tools/formatter");
+        assert!(install_dir.join("commands/format.md").is_file());
+
+        let installed = list_installed_in(home.path()).unwrap();
+        assert_eq!(installed.len(), 1);
+        assert_eq!(installed[0].dir_name, id);
+        assert_eq!(installed[0].marketplace.as_deref(), Some("team-tools"));
+        assert_eq!(installed[0].components.agents, ["reviewer"]);
+        assert!(list_marketplaces_in(home.path()).unwrap()[0].plugins[0].installed);
+
+        assert!(install_in(home.path(), "team-tools", "remote").is_err());
+        assert!(remove_marketplace_in(home.path(), "team-tools").is_err());
+
+        assert!(uninstall_in(home.path(), &id).unwrap());
+        assert!(!home.path().join("cache/team-tools").exists());
+        assert!(list_installed_in(home.path()).unwrap().is_empty());
+        assert!(!uninstall_in(home.path(), &id).unwrap());
+        assert!(remove_marketplace_in(home.path(), "team-tools").unwrap());
+        assert!(list_marketplaces_in(home.path()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn refuses_unknown_registry_versions() {
+        let home = tempdir().unwrap();
+        let registry = home.path().join(INSTALLED_PLUGINS_FILE);
+        write(
+            &registry,
+            r#"{"version": 2, "plugins": {"a@m": [{"scope": "user"}]}}"#,
+        );
+
+        assert!(installed_entries(home.path()).is_err());
+        assert!(save_installed_entries(home.path(), Map::new()).is_err());
+        assert!(fs::read_to_string(&registry)
+            .unwrap()
+            .contains("\"version\": 2"));
+
+        write(&registry, r#"{"plugins": {}}"#);
+        assert!(installed_entries(home.path()).is_err());
+    }
+
+    #[test]
+    fn uninstall_clears_every_known_project() {
+        let home = tempdir().unwrap();
+        let user_dir = home.path().join(".claude");
+        let (a, b) = (home.path().join("a"), home.path().join("b"));
+        let enabled = r#"{"enabledPlugins": {"p@m": true, "q@m": true}}"#;
+        write(&user_dir.join("settings.json"), enabled);
+        write(&a.join(".claude/settings.json"), enabled);
+        write(
+            &a.join(".claude/settings.local.json"),
+            r#"{"model": "opus"}"#,
+        );
+        write(&b.join(".claude/settings.local.json"), "{ not json");
+        let projects = [a, b].map(|p| p.to_string_lossy().to_string());
+
+        let mut report = PluginUninstallReport {
+            removed: true,
+            cleared: Vec::new(),
+            remaining: Vec::new(),
+        };
+        clear_enabled_in(&user_dir, &projects, "p@m", &mut report);
+
+        assert_eq!(report.cleared.len(), 2, "{:?}", report.cleared);
+        assert_eq!(report.remaining.len(), 1);
+        assert!(report.remaining[0].ends_with("settings.local.json"));
+        let project = fs::read_to_string(home.path().join("a/.claude/settings.json")).unwrap();
+        assert!(!project.contains("p@m") && project.contains("q@m"));
+    }
+
+    #[test]
+    fn lists_legacy_cache_plugins() {
+        let home = tempdir().unwrap();
+        write(
+            &home.path().join("cache/old/plugin.json"),
+            r#"{"name": "old", "description": "Legacy", "version": "0.1.0"}"#,
+        );
+        let installed = list_installed_in(home.path()).unwrap();
+        assert_eq!(installed.len(), 1);
+        assert_eq!(installed[0].dir_name, "old");
+        assert_eq!(installed[0].description, "Legacy");
+        assert!(installed[0].marketplace.is_none());
+    }
+
+    #[test]
+    fn enabled_plugins_migrates_legacy_arrays() {
+        let mut settings = json!({"enabledPlugins": ["a"], "disabledPlugins": ["b"]});
+        assert!(set_enabled_value(&mut settings, "c@m", false));
+        assert_eq!(
+            settings,
+            json!({"enabledPlugins": {"a": true, "b": false, "c@m": false}})
+        );
+        assert!(!set_enabled_value(&mut settings, "c@m", false));
+        assert!(set_enabled_value(&mut settings, "c@m", true));
+
+        assert!(remove_enabled_value(&mut settings, "c@m"));
+        assert!(!remove_enabled_value(&mut settings, "c@m"));
+        assert_eq!(settings, json!({"enabledPlugins": {"a": true, "b": false}}));
+
+        let mut legacy = json!({"disabledPlugins": ["x", "y"]});
+        assert!(remove_enabled_value(&mut legacy, "x"));
+        assert_eq!(legacy, json!({"disabledPlugins": ["y"]}));
+    }
+
+    #[test]
+    fn rejects_unsafe_names() {
+        assert!(check_name("plugin", "../evil").is_err());
+        assert!(check_name("plugin", "a@b").is_err());
+        assert!(check_name("plugin", "").is_err());
+        assert!(check_name("plugin", "formatter").is_ok());
+    }
+}
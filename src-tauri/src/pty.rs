@@ -196,6 +196,7 @@ impl PtyManager {
             .cloned()
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn spawn(
         &self,
         session_id: String,
@@ -208,7 +209,8 @@ impl PtyManager {
         app_handle: AppHandle,
     ) -> Result<()> {
         let pty_system = native_pty_system();
-        let resolved_project_path = resolve_repo_root(&project_path).unwrap_or(project_path.clone());
+        let resolved_project_path =
+            resolve_repo_root(&project_path).unwrap_or(project_path.clone());
 
         let size = PtySize {
             rows,
@@ -375,9 +377,8 @@ impl PtyManager {
                         };
 
                         if valid_up_to > 0 {
-                            let data = unsafe {
-                                std::str::from_utf8_unchecked(&chunk[..valid_up_to])
-                            };
+                            let data =
+                                unsafe { std::str::from_utf8_unchecked(&chunk[..valid_up_to]) };
                             if !send_output_chunk(&data_tx, data.to_string()) {
                                 break; // emitter gone
                             }
@@ -407,12 +408,9 @@ impl PtyManager {
             let mut batch = String::new();
             let mut last_emit = Instant::now();
 
-            loop {
-                // Block until the reader pushes data (or closes the channel).
-                match data_rx.recv() {
-                    Ok(data) => batch.push_str(&data),
-                    Err(_) => break, // reader EOF / closed
-                }
+            // Block until the reader pushes data; stops on reader EOF / closed.
+            while let Ok(data) = data_rx.recv() {
+                batch.push_str(&data);
 
                 // Drain everything the reader has queued so far.
                 while let Ok(data) = data_rx.try_recv() {
@@ -478,6 +476,7 @@ impl PtyManager {
                     .unwrap_or(1)
             };
 
+            crate::hook_bridge::forget_pane_activity(&handle, &sid);
             let _ = handle.emit(
                 "terminal:exit",
                 TerminalExitEvent {
@@ -545,6 +544,7 @@ impl PtyManager {
             .map(|s| if s.success() { 0 } else { 1 })
             .unwrap_or(1);
 
+        crate::hook_bridge::forget_pane_activity(app_handle, session_id);
         let _ = app_handle.emit(
             "terminal:exit",
             TerminalExitEvent {
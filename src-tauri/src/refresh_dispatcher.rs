@@ -245,11 +245,8 @@ mod tests {
 
         let first =
             dispatcher.enqueue_request(project_path.clone(), "git-watcher", "git-dir-change");
-        let second = dispatcher.enqueue_request(
-            project_path.clone(),
-            "claude-hook",
-            "post-tool-use-bash",
-        );
+        let second =
+            dispatcher.enqueue_request(project_path.clone(), "claude-hook", "post-tool-use-bash");
 
         assert_eq!(first, 1);
         assert_eq!(second, 2);
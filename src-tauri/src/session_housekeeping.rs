@@ -0,0 +1,701 @@
+/// Archive, delete and prune agent sessions so the Claude and Codex stores do
+/// not grow forever, and restore archived ones. Sessions referenced by an open
+/// or saved terminal pane are never touched, and every operation supports a
+/// dry run.
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{bail, Context, Result};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+use crate::paths;
+use crate::session_index::SessionIndex;
+use crate::session_providers::{self, AgentSessionProvider};
+use crate::types::{
+    AgentSession, ArchivedFile, ArchivedSession, HousekeptSession, SessionHousekeepingAction,
+    SessionHousekeepingReport, SessionPrunePolicy, SkippedSession, WorkspaceFile,
+};
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+const REFERENCED_REASON: &str = "Open in a terminal pane";
+const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+/// Every archived session, so archives can be listed and restored.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ArchiveManifest {
+    sessions: Vec<ArchivedSession>,
+}
+
+impl ArchiveManifest {
+    fn load(archive_root: &Path) -> Result<Self> {
+        paths::load_json_strict(&archive_root.join(MANIFEST_FILE_NAME), Self::default())
+    }
+
+    fn save(&self, archive_root: &Path) -> Result<()> {
+        paths::save_json(&archive_root.join(MANIFEST_FILE_NAME), self)
+    }
+
+    fn position(&self, provider: &str, session_id: &str) -> Option<usize> {
+        self.sessions
+            .iter()
+            .position(|s| s.provider == provider && s.session_id == session_id)
+    }
+}
+
+struct Candidate {
+    provider: &'static dyn AgentSessionProvider,
+    session: AgentSession,
+    files: Vec<PathBuf>,
+    size_bytes: u64,
+    modified: Option<SystemTime>,
+}
+
+/// Session IDs pointed at by any pane in the saved workspaces.
+fn referenced_session_ids(file: &WorkspaceFile) -> HashSet<String> {
+    file.workspaces
+        .iter()
+        .flat_map(|ws| &ws.terminal_tabs)
+        .flat_map(|tab| &tab.panes)
+        .filter_map(|pane| pane.claude_session_id.clone())
+        .collect()
+}
+
+fn path_size(path: &Path) -> u64 {
+    let Ok(meta) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !meta.is_dir() {
+        return meta.len();
+    }
+    fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| path_size(&entry.path()))
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+/// Every discovered session for the given providers with its on-disk
+/// footprint, newest first.
+fn candidates(
+    index: &SessionIndex,
+    project_path: &str,
+    providers: &[&'static dyn AgentSessionProvider],
+) -> Vec<Candidate> {
+    let mut candidates: Vec<Candidate> = Vec::new();
+    for provider in providers {
+        let sessions = match provider.discover(index, project_path) {
+            Ok(sessions) => sessions,
+            Err(e) => {
+                eprintln!(
+                    "[SessionHousekeeping] {} discovery failed: {e}",
+                    provider.name()
+                );
+                continue;
+            }
+        };
+        for session in sessions {
+            let Ok(files) = provider.session_files(index, project_path, &session.session_id) else {
+                continue;
+            };
+            candidates.push(Candidate {
+                provider: *provider,
+                size_bytes: files.iter().map(|f| path_size(f)).sum(),
+                modified: files
+                    .iter()
+                    .filter_map(|f| fs::metadata(f).and_then(|m| m.modified()).ok())
+                    .max(),
+                files,
+                session,
+            });
+        }
+    }
+    candidates.sort_by_key(|c| Reverse(c.modified));
+    candidates
+}
+
+/// Indices of the candidates (sorted newest first) that fall outside the policy.
+fn select_for_prune(
+    candidates: &[Candidate],
+    policy: &SessionPrunePolicy,
+    now: SystemTime,
+) -> Vec<usize> {
+    let cutoff = policy
+        .older_than_days
+        .map(|days| now - Duration::from_secs(u64::from(days) * SECONDS_PER_DAY));
+    let mut total: u64 = 0;
+    let mut selected = Vec::new();
+    for (i, candidate) in candidates.iter().enumerate() {
+        total += candidate.size_bytes;
+        let too_old = cutoff.is_some_and(|cutoff| candidate.modified.is_some_and(|m| m < cutoff));
+        let over_budget = policy.max_total_bytes.is_some_and(|max| total > max);
+        if too_old || over_budget {
+            selected.push(i);
+        }
+    }
+    selected
+}
+
+/// Where the archived copy of `path` goes: its location relative to the
+/// provider's store, under `archive_root/<provider>/`.
+fn archive_destination(
+    archive_root: &Path,
+    provider: &str,
+    store_dir: &Path,
+    path: &Path,
+) -> PathBuf {
+    let relative = path
+        .strip_prefix(store_dir)
+        .unwrap_or_else(|_| Path::new(path.file_name().unwrap_or_default()));
+    archive_root.join(provider).join(relative)
+}
+
+fn gzip_file(src: &Path, dest: &Path) -> Result<()> {
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut input =
+        fs::File::open(src).with_context(|| format!("Failed to open {}", src.display()))?;
+    let output = fs::File::create_new(dest)
+        .with_context(|| format!("Failed to create {}", dest.display()))?;
+    let mut encoder = GzEncoder::new(output, Compression::default());
+    io::copy(&mut input, &mut encoder)?;
+    encoder.finish()?;
+    Ok(())
+}
+
+fn gunzip_file(src: &Path, dest: &Path) -> Result<()> {
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let input = fs::File::open(src).with_context(|| format!("Failed to open {}", src.display()))?;
+    let mut output = fs::File::create_new(dest)
+        .with_context(|| format!("Failed to create {}", dest.display()))?;
+    io::copy(&mut GzDecoder::new(input), &mut output)?;
+    Ok(())
+}
+
+fn gz_path(dest: &Path) -> PathBuf {
+    let mut name = dest.file_name().unwrap_or_default().to_os_string();
+    name.push(".gz");
+    dest.with_file_name(name)
+}
+
+/// Compress `src` (a file or a directory tree) into `dest`, adding `.gz` to
+/// each file name and recording every file written in `files`.
+fn archive_path(src: &Path, dest: &Path, files: &mut Vec<ArchivedFile>) -> Result<()> {
+    let meta = fs::symlink_metadata(src)?;
+    if meta.is_dir() {
+        for entry in fs::read_dir(src)? {
+            let entry = entry?;
+            archive_path(&entry.path(), &dest.join(entry.file_name()), files)?;
+        }
+        return Ok(());
+    }
+    if meta.file_type().is_symlink() {
+        return Ok(());
+    }
+    let archive = gz_path(dest);
+    gzip_file(src, &archive)?;
+    files.push(ArchivedFile {
+        original: src.to_string_lossy().to_string(),
+        archive: archive.to_string_lossy().to_string(),
+    });
+    Ok(())
+}
+
+/// Compress a candidate's files into `archive_root`. Refuses to replace an
+/// earlier archive of the same files; on failure nothing is left behind.
+fn archive_candidate(
+    candidate: &Candidate,
+    project_path: &str,
+    archive_root: &Path,
+) -> Result<ArchivedSession> {
+    let provider = candidate.provider.name();
+    let store_dir = candidate.provider.store_dir();
+    let targets: Vec<(&PathBuf, PathBuf)> = candidate
+        .files
+        .iter()
+        .map(|file| {
+            let dest = archive_destination(archive_root, provider, &store_dir, file);
+            (file, dest)
+        })
+        .collect();
+    // Files inside directories are checked as they are written (`create_new`).
+    for (file, dest) in &targets {
+        let archive = gz_path(dest);
+        if file.is_file() && archive.exists() {
+            bail!("Already archived at {}", archive.display());
+        }
+    }
+
+    let mut files = Vec::new();
+    let archived = targets
+        .iter()
+        .try_for_each(|(file, dest)| archive_path(file, dest, &mut files));
+    if let Err(e) = archived {
+        for file in &files {
+            let _ = fs::remove_file(&file.archive);
+        }
+        return Err(e);
+    }
+    Ok(ArchivedSession {
+        provider: provider.to_string(),
+        session_id: candidate.session.session_id.clone(),
+        label: candidate.session.label.clone(),
+        project_path: project_path.to_string(),
+        size_bytes: candidate.size_bytes,
+        modified_at: modified_at(candidate.modified),
+        archived_at: chrono::Utc::now().to_rfc3339(),
+        files,
+    })
+}
+
+/// Restoring never replaces files that exist again, e.g. a resumed session.
+fn check_restorable(session: &ArchivedSession) -> Result<()> {
+    match session
+        .files
+        .iter()
+        .find(|f| Path::new(&f.original).exists())
+    {
+        Some(file) => bail!("{} already exists", file.original),
+        None => Ok(()),
+    }
+}
+
+/// Decompress an archived session's files back to where they came from,
+/// then remove the archived copies.
+fn restore_files(session: &ArchivedSession) -> Result<()> {
+    check_restorable(session)?;
+    let mut restored: Vec<&Path> = Vec::new();
+    for file in &session.files {
+        if let Err(e) = gunzip_file(Path::new(&file.archive), Path::new(&file.original)) {
+            for path in restored {
+                let _ = fs::remove_file(path);
+            }
+            return Err(e);
+        }
+        restored.push(Path::new(&file.original));
+    }
+    for file in &session.files {
+        paths::remove_path_if_exists(Path::new(&file.archive))?;
+    }
+    Ok(())
+}
+
+fn modified_at(modified: Option<SystemTime>) -> Option<String> {
+    modified.map(|m| chrono::DateTime::<chrono::Utc>::from(m).to_rfc3339())
+}
+
+fn apply(
+    candidate: &Candidate,
+    action: SessionHousekeepingAction,
+    index: &SessionIndex,
+    project_path: &str,
+    archive_root: &Path,
+) -> Result<()> {
+    let session_id = &candidate.session.session_id;
+    match action {
+        SessionHousekeepingAction::Delete => {
+            candidate.provider.delete(index, project_path, session_id)
+        }
+        SessionHousekeepingAction::Archive => {
+            let mut manifest = ArchiveManifest::load(archive_root)?;
+            let archived = archive_candidate(candidate, project_path, archive_root)?;
+            let delete = candidate.provider.delete(index, project_path, session_id);
+            if delete.is_ok() {
+                // A stale entry left by archive files removed by hand.
+                if let Some(pos) = manifest.position(&archived.provider, session_id) {
+                    manifest.sessions.remove(pos);
+                }
+                manifest.sessions.push(archived);
+                return manifest.save(archive_root);
+            }
+            for file in &archived.files {
+                let _ = fs::remove_file(&file.archive);
+            }
+            delete
+        }
+        SessionHousekeepingAction::Restore => bail!("Restore archived sessions by ID"),
+    }
+}
+
+fn run(
+    selected: Vec<Candidate>,
+    mut skipped: Vec<SkippedSession>,
+    action: SessionHousekeepingAction,
+    dry_run: bool,
+    index: &SessionIndex,
+    project_path: &str,
+    open_session_ids: &[String],
+) -> Result<SessionHousekeepingReport> {
+    let mut referenced = referenced_session_ids(&config::load_workspaces()?);
+    // Panes opened or resumed since the workspaces were last saved.
+    referenced.extend(open_session_ids.iter().cloned());
+    let archive_root = paths::session_archive_dir();
+    let mut sessions = Vec::new();
+    for candidate in selected {
+        let skip = |reason: String| SkippedSession {
+            provider: candidate.provider.name().to_string(),
+            session_id: candidate.session.session_id.clone(),
+            reason,
+        };
+        if referenced.contains(&candidate.session.session_id) {
+            skipped.push(skip(REFERENCED_REASON.to_string()));
+            continue;
+        }
+        if !dry_run {
+            if let Err(e) = apply(&candidate, action, index, project_path, &archive_root) {
+                eprintln!(
+                    "[SessionHousekeeping] Failed to {action:?} {}: {e}",
+                    candidate.session.session_id
+                );
+                skipped.push(skip(e.to_string()));
+                continue;
+            }
+        }
+        sessions.push(HousekeptSession {
+            provider: candidate.provider.name().to_string(),
+            session_id: candidate.session.session_id.clone(),
+            label: candidate.session.label.clone(),
+            size_bytes: candidate.size_bytes,
+            modified_at: modified_at(candidate.modified),
+            paths: candidate
+                .files
+                .iter()
+                .map(|f| f.to_string_lossy().to_string())
+                .collect(),
+        });
+    }
+    Ok(SessionHousekeepingReport {
+        action,
+        dry_run,
+        total_bytes: sessions.iter().map(|s| s.size_bytes).sum(),
+        sessions,
+        skipped,
+        archive_dir: (action == SessionHousekeepingAction::Archive)
+            .then(|| archive_root.to_string_lossy().to_string()),
+    })
+}
+
+/// Archive or delete specific sessions of one provider. `open_session_ids`
+/// are the sessions of the frontend's live panes.
+pub fn housekeep_sessions(
+    index: &SessionIndex,
+    provider: &str,
+    project_path: &str,
+    session_ids: &[String],
+    open_session_ids: &[String],
+    action: SessionHousekeepingAction,
+    dry_run: bool,
+) -> Result<SessionHousekeepingReport> {
+    let provider = session_providers::provider(provider)?;
+    let mut found = candidates(index, project_path, &[provider]);
+    found.retain(|c| session_ids.contains(&c.session.session_id));
+    let skipped = session_ids
+        .iter()
+        .filter(|id| !found.iter().any(|c| &c.session.session_id == *id))
+        .map(|id| SkippedSession {
+            provider: provider.name().to_string(),
+            session_id: id.clone(),
+            reason: "Session not found".to_string(),
+        })
+        .collect();
+    run(
+        found,
+        skipped,
+        action,
+        dry_run,
+        index,
+        project_path,
+        open_session_ids,
+    )
+}
+
+/// Archive or delete every session in a project, across all providers, that
+/// falls outside `policy`.
+pub fn prune_sessions(
+    index: &SessionIndex,
+    project_path: &str,
+    policy: &SessionPrunePolicy,
+    open_session_ids: &[String],
+    action: SessionHousekeepingAction,
+    dry_run: bool,
+) -> Result<SessionHousekeepingReport> {
+    if action == SessionHousekeepingAction::Restore {
+        bail!("Pruning can only archive or delete sessions");
+    }
+    let all = candidates(index, project_path, session_providers::providers());
+    let selected: HashSet<usize> = select_for_prune(&all, policy, SystemTime::now())
+        .into_iter()
+        .collect();
+    let selected = all
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| selected.contains(i))
+        .map(|(_, candidate)| candidate)
+        .collect();
+    run(
+        selected,
+        Vec::new(),
+        action,
+        dry_run,
+        index,
+        project_path,
+        open_session_ids,
+    )
+}
+
+/// Archived sessions, optionally only those archived from `project_path`.
+pub fn archived_sessions(project_path: Option<&str>) -> Result<Vec<ArchivedSession>> {
+    let manifest = ArchiveManifest::load(&paths::session_archive_dir())?;
+    Ok(manifest
+        .sessions
+        .into_iter()
+        .filter(|s| project_path.is_none_or(|p| s.project_path == p))
+        .collect())
+}
+
+/// Decompress archived sessions of one provider back into its store and
+/// drop them from the archive. Sessions whose files exist again are skipped.
+pub fn restore_sessions(
+    provider: &str,
+    session_ids: &[String],
+    dry_run: bool,
+) -> Result<SessionHousekeepingReport> {
+    restore_from(
+        &paths::session_archive_dir(),
+        provider,
+        session_ids,
+        dry_run,
+    )
+}
+
+fn restore_from(
+    archive_root: &Path,
+    provider: &str,
+    session_ids: &[String],
+    dry_run: bool,
+) -> Result<SessionHousekeepingReport> {
+    let mut manifest = ArchiveManifest::load(archive_root)?;
+    let mut sessions = Vec::new();
+    let mut skipped = Vec::new();
+    for session_id in session_ids {
+        let skip = |reason: String| SkippedSession {
+            provider: provider.to_string(),
+            session_id: session_id.clone(),
+            reason,
+        };
+        let Some(pos) = manifest.position(provider, session_id) else {
+            skipped.push(skip("Not in the archive".to_string()));
+            continue;
+        };
+        let archived = &manifest.sessions[pos];
+        let result = if dry_run {
+            check_restorable(archived)
+        } else {
+            restore_files(archived)
+        };
+        if let Err(e) = result {
+            skipped.push(skip(e.to_string()));
+            continue;
+        }
+        sessions.push(HousekeptSession {
+            provider: archived.provider.clone(),
+            session_id: archived.session_id.clone(),
+            label: archived.label.clone(),
+            size_bytes: archived.size_bytes,
+            modified_at: archived.modified_at.clone(),
+            paths: archived.files.iter().map(|f| f.original.clone()).collect(),
+        });
+        if !dry_run {
+            manifest.sessions.remove(pos);
+            manifest.save(archive_root)?;
+        }
+    }
+    Ok(SessionHousekeepingReport {
+        action: SessionHousekeepingAction::Restore,
+        dry_run,
+        total_bytes: sessions.iter().map(|s| s.size_bytes).sum(),
+        sessions,
+        skipped,
+        archive_dir: Some(archive_root.to_string_lossy().to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session_providers::ClaudeSessionProvider;
+    use crate::types::{TerminalPaneSnapshot, TerminalTabSnapshot, WorkspaceSnapshot};
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    fn candidate(id: &str, size_bytes: u64, age_days: u64, now: SystemTime) -> Candidate {
+        Candidate {
+            provider: &ClaudeSessionProvider,
+            session: AgentSession {
+                provider: "claude".to_string(),
+                session_id: id.to_string(),
+                label: id.to_string(),
+                timestamp: String::new(),
+                last_message_role: None,
+                last_assistant_snippet: None,
+                awaiting_input: None,
+                worktree_path: None,
+                branch: None,
+            },
+            files: Vec::new(),
+            size_bytes,
+            modified: Some(now - Duration::from_secs(age_days * SECONDS_PER_DAY)),
+        }
+    }
+
+    #[test]
+    fn prune_selects_by_age_and_size() {
+        let now = SystemTime::now();
+        let candidates = vec![
+            candidate("new", 400, 1, now),
+            candidate("mid", 400, 10, now),
+            candidate("old", 100, 40, now),
+        ];
+
+        let by_age = SessionPrunePolicy {
+            older_than_days: Some(30),
+            max_total_bytes: None,
+        };
+        assert_eq!(select_for_prune(&candidates, &by_age, now), [2]);
+
+        let by_size = SessionPrunePolicy {
+            older_than_days: None,
+            max_total_bytes: Some(500),
+        };
+        assert_eq!(select_for_prune(&candidates, &by_size, now), [1, 2]);
+
+        assert!(select_for_prune(&candidates, &SessionPrunePolicy::default(), now).is_empty());
+    }
+
+    #[test]
+    fn collects_session_ids_referenced_by_panes() {
+        let pane = |id: &str, session: Option<&str>| TerminalPaneSnapshot {
+            id: id.to_string(),
+            startup_command: None,
+            session_type: None,
+            claude_session_id: session.map(str::to_string),
+        };
+        let file = WorkspaceFile {
+            workspaces: vec![WorkspaceSnapshot {
+                id: "ws-1".to_string(),
+                project_path: "/repo".to_string(),
+                project_name: "repo".to_string(),
+                terminal_tabs: vec![TerminalTabSnapshot {
+                    id: "tab-1".to_string(),
+                    label: "Claude".to_string(),
+                    split: "horizontal".to_string(),
+                    panes: vec![pane("p1", Some("s1")), pane("p2", None)],
+                    session_type: Some("claude".to_string()),
+                }],
+                active_terminal_tab_id: "tab-1".to_string(),
+                worktree_path: None,
+                branch: None,
+            }],
+            selected_id: None,
+        };
+        assert_eq!(
+            referenced_session_ids(&file),
+            HashSet::from(["s1".to_string()])
+        );
+    }
+
+    #[test]
+    fn archive_destination_mirrors_the_store_layout() {
+        let dest = archive_destination(
+            Path::new("/archive"),
+            "claude",
+            Path::new("/home/u/.claude/projects"),
+            Path::new("/home/u/.claude/projects/-repo/abc.jsonl"),
+        );
+        assert_eq!(dest, Path::new("/archive/claude/-repo/abc.jsonl"));
+    }
+
+    #[test]
+    fn archives_files_and_directories_as_gzip() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = dir.path().join("store");
+        fs::create_dir_all(store.join("abc/subagents")).unwrap();
+        fs::write(store.join("abc.jsonl"), "{\"type\":\"user\"}\n").unwrap();
+        fs::write(store.join("abc/subagents/agent-1.jsonl"), "{}\n").unwrap();
+
+        let archive = dir.path().join("archive");
+        let mut files = Vec::new();
+        archive_path(
+            &store.join("abc.jsonl"),
+            &archive.join("abc.jsonl"),
+            &mut files,
+        )
+        .unwrap();
+        archive_path(&store.join("abc"), &archive.join("abc"), &mut files).unwrap();
+        assert_eq!(files.len(), 2);
+
+        let mut text = String::new();
+        GzDecoder::new(fs::File::open(archive.join("abc.jsonl.gz")).unwrap())
+            .read_to_string(&mut text)
+            .unwrap();
+        assert_eq!(text, "{\"type\":\"user\"}\n");
+        assert!(archive.join("abc/subagents/agent-1.jsonl.gz").is_file());
+        assert_eq!(path_size(&store), 16 + 3);
+    }
+
+    #[test]
+    fn archived_sessions_restore_without_overwriting() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = dir.path().join("store");
+        fs::create_dir_all(store.join("abc/subagents")).unwrap();
+        fs::write(store.join("abc.jsonl"), "{\"type\":\"user\"}\n").unwrap();
+        fs::write(store.join("abc/subagents/agent-1.jsonl"), "{}\n").unwrap();
+        let archive = dir.path().join("archive");
+        let mut abc = candidate("abc", 19, 1, SystemTime::now());
+        abc.files = vec![store.join("abc.jsonl"), store.join("abc")];
+
+        let archived = archive_candidate(&abc, "/repo", &archive).unwrap();
+        assert_eq!(archived.files.len(), 2);
+        let again = archive_candidate(&abc, "/repo", &archive).unwrap_err();
+        assert!(again.to_string().starts_with("Already archived at"));
+        ArchiveManifest {
+            sessions: vec![archived],
+        }
+        .save(&archive)
+        .unwrap();
+
+        // The originals are still there, so restoring would overwrite them.
+        let ids = ["abc".to_string(), "gone".to_string()];
+        let report = restore_from(&archive, "claude", &ids, false).unwrap();
+        assert!(report.sessions.is_empty());
+        assert!(report.skipped[0].reason.ends_with("already exists"));
+        assert_eq!(report.skipped[1].reason, "Not in the archive");
+
+        fs::remove_file(store.join("abc.jsonl")).unwrap();
+        fs::remove_dir_all(store.join("abc")).unwrap();
+        let report = restore_from(&archive, "claude", &ids[..1], false).unwrap();
+        assert_eq!(report.sessions.len(), 1);
+        assert_eq!(
+            fs::read_to_string(store.join("abc.jsonl")).unwrap(),
+            "{\"type\":\"user\"}\n"
+        );
+        assert!(store.join("abc/subagents/agent-1.jsonl").is_file());
+        assert!(ArchiveManifest::load(&archive).unwrap().sessions.is_empty());
+        assert!(!archive.join("claude/abc.jsonl.gz").exists());
+        // Once restored, the session can be archived again.
+        assert!(archive_candidate(&abc, "/repo", &archive).is_ok());
+    }
+}
@@ -0,0 +1,1244 @@
+/// Persistent index of parsed Claude and Codex session files, stored in
+/// ~/.workbench/cache/session-index.json. A file is re-parsed only when its
+/// mtime or size changes, and a filesystem watcher keeps the index warm and
+/// tells the frontend about new activity via `sessions:changed`.
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::UNIX_EPOCH;
+
+use anyhow::{bail, Result};
+use chrono::Utc;
+use notify::RecursiveMode;
+use notify_debouncer_mini::{new_debouncer, DebouncedEventKind, Debouncer};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::claude_sessions;
+use crate::claude_transcript;
+use crate::codex_sessions;
+use crate::git;
+use crate::paths;
+use crate::session_tail;
+use crate::types::{DiscoveredClaudeSession, SessionsChangedEvent};
+
+/// Bump when the session types gain fields so cached entries are re-parsed.
+const INDEX_VERSION: u32 = 4;
+const INDEX_FILE_NAME: &str = "session-index.json";
+pub(crate) const CODEX_MAX_DEPTH: u32 = 4; // year/month/day/file
+
+type FileWatcher = Debouncer<notify::RecommendedWatcher>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SessionKind {
+    Claude,
+    Codex,
+}
+
+impl SessionKind {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Claude => "claude",
+            Self::Codex => "codex",
+        }
+    }
+}
+
+/// A checkout a project's sessions may have run in: the project directory
+/// itself or one of its git worktrees.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SessionLocation {
+    pub path: String,
+    pub branch: Option<String>,
+}
+
+impl SessionLocation {
+    /// Set a session's `worktree_path` and `branch` to this location.
+    fn tag(&self, worktree_path: &mut Option<String>, branch: &mut Option<String>) {
+        *worktree_path = Some(self.path.clone());
+        *branch = self.branch.clone();
+    }
+}
+
+fn canonical_path(path: &str) -> String {
+    fs::canonicalize(path)
+        .unwrap_or_else(|_| PathBuf::from(path))
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Every worktree of the project's repository, with `project_path` first.
+/// Falls back to just `project_path` outside a git repository.
+pub(crate) fn session_locations(project_path: &str) -> Vec<SessionLocation> {
+    let worktrees = git::list_worktrees(project_path).unwrap_or_default();
+    let project = canonical_path(project_path);
+    let mut locations = vec![SessionLocation {
+        path: project_path.to_string(),
+        branch: worktrees
+            .iter()
+            .find(|w| canonical_path(&w.path) == project)
+            .and_then(|w| (!w.branch.is_empty()).then(|| w.branch.clone())),
+    }];
+    locations.extend(
+        worktrees
+            .into_iter()
+            .filter(|w| canonical_path(&w.path) != project)
+            .map(|w| SessionLocation {
+                branch: (!w.branch.is_empty()).then_some(w.branch),
+                path: w.path,
+            }),
+    );
+    locations
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct FileStamp {
+    modified_ms: u64,
+    size: u64,
+}
+
+impl FileStamp {
+    pub(crate) fn of(path: &Path) -> Option<Self> {
+        let meta = fs::metadata(path).ok()?;
+        let modified_ms = meta
+            .modified()
+            .ok()?
+            .duration_since(UNIX_EPOCH)
+            .ok()?
+            .as_millis() as u64;
+        Some(Self {
+            modified_ms,
+            size: meta.len(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IndexEntry {
+    stamp: FileStamp,
+    /// `None` when the file holds no usable session, so it is not re-parsed
+    /// on every lookup.
+    session: Option<IndexedSession>,
+}
+
+/// A parsed session in its provider's own type.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+enum IndexedSession {
+    Claude(DiscoveredClaudeSession),
+    /// Codex sessions all share one directory tree, so they carry the
+    /// (canonicalized) cwd they are matched to projects by.
+    Codex {
+        cwd: String,
+        session: DiscoveredClaudeSession,
+    },
+}
+
+impl IndexEntry {
+    fn claude(&self) -> Option<&DiscoveredClaudeSession> {
+        match &self.session {
+            Some(IndexedSession::Claude(session)) => Some(session),
+            _ => None,
+        }
+    }
+
+    fn codex(&self) -> Option<(&str, &DiscoveredClaudeSession)> {
+        match &self.session {
+            Some(IndexedSession::Codex { cwd, session }) => Some((cwd, session)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IndexFile {
+    version: u32,
+    entries: HashMap<String, IndexEntry>,
+}
+
+impl Default for IndexFile {
+    fn default() -> Self {
+        Self {
+            version: INDEX_VERSION,
+            entries: HashMap::new(),
+        }
+    }
+}
+
+struct IndexState {
+    cache_path: PathBuf,
+    file: IndexFile,
+    dirty: bool,
+}
+
+impl IndexState {
+    fn load(cache_path: PathBuf) -> Self {
+        let file: IndexFile = paths::load_json(&cache_path, IndexFile::default());
+        let file = if file.version == INDEX_VERSION {
+            file
+        } else {
+            IndexFile::default()
+        };
+        Self {
+            cache_path,
+            file,
+            dirty: false,
+        }
+    }
+
+    fn save_if_dirty(&mut self) {
+        if !self.dirty {
+            return;
+        }
+        match paths::save_json(&self.cache_path, &self.file) {
+            Ok(()) => self.dirty = false,
+            Err(e) => eprintln!("[SessionIndex] Failed to save index: {e}"),
+        }
+    }
+
+    fn is_fresh(&self, key: &str, stamp: &FileStamp) -> bool {
+        self.file
+            .entries
+            .get(key)
+            .is_some_and(|entry| entry.stamp == *stamp)
+    }
+
+    /// Return the entry for `path`, re-parsing only if the file changed.
+    fn entry(&mut self, kind: SessionKind, path: &Path) -> Option<&IndexEntry> {
+        let stamp = FileStamp::of(path)?;
+        let key = path.to_string_lossy().to_string();
+        if !self.is_fresh(&key, &stamp) {
+            let entry = parse_entry(kind, path, stamp);
+            self.file.entries.insert(key.clone(), entry);
+            self.dirty = true;
+        }
+        self.file.entries.get(&key)
+    }
+
+    /// Drop entries under `dir` whose files are no longer in `present`.
+    fn prune(&mut self, dir: &Path, present: &HashSet<String>) {
+        let before = self.file.entries.len();
+        self.file
+            .entries
+            .retain(|key, _| !Path::new(key).starts_with(dir) || present.contains(key));
+        if self.file.entries.len() != before {
+            self.dirty = true;
+        }
+    }
+
+    fn claude_sessions_in(&mut self, sessions_dir: &Path) -> Result<Vec<DiscoveredClaudeSession>> {
+        if !sessions_dir.is_dir() {
+            return Ok(Vec::new());
+        }
+        let mut present = HashSet::new();
+        let mut sessions = Vec::new();
+        for entry in fs::read_dir(sessions_dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
+                continue;
+            }
+            present.insert(path.to_string_lossy().to_string());
+            if let Some(session) = self
+                .entry(SessionKind::Claude, &path)
+                .and_then(|e| e.claude().cloned())
+            {
+                sessions.push(session);
+            }
+        }
+        self.prune(sessions_dir, &present);
+        self.save_if_dirty();
+        sessions.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        Ok(sessions)
+    }
+
+    /// Claude sessions from each location's project directory, tagged with
+    /// the location they ran in.
+    fn claude_sessions_at(
+        &mut self,
+        projects_dir: &Path,
+        locations: &[SessionLocation],
+    ) -> Result<Vec<DiscoveredClaudeSession>> {
+        let mut sessions = Vec::new();
+        for location in locations {
+            let dir = projects_dir.join(paths::encode_project_path(&location.path));
+            sessions.extend(self.claude_sessions_in(&dir)?.into_iter().map(|mut s| {
+                location.tag(&mut s.worktree_path, &mut s.branch);
+                s
+            }));
+        }
+        sessions.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        Ok(sessions)
+    }
+
+    fn codex_sessions_in(
+        &mut self,
+        sessions_dir: &Path,
+        locations: &[SessionLocation],
+    ) -> Vec<DiscoveredClaudeSession> {
+        if !sessions_dir.is_dir() {
+            return Vec::new();
+        }
+        let by_cwd: HashMap<String, &SessionLocation> = locations
+            .iter()
+            .map(|l| (canonical_path(&l.path), l))
+            .collect();
+        let mut present = HashSet::new();
+        let mut sessions = Vec::new();
+        for path in codex_files(sessions_dir) {
+            present.insert(path.to_string_lossy().to_string());
+            let Some((cwd, session)) = self
+                .entry(SessionKind::Codex, &path)
+                .and_then(|e| e.codex())
+            else {
+                continue;
+            };
+            if let Some(location) = by_cwd.get(cwd) {
+                let mut session = session.clone();
+                location.tag(&mut session.worktree_path, &mut session.branch);
+                sessions.push(session);
+            }
+        }
+        self.prune(sessions_dir, &present);
+        self.save_if_dirty();
+        sessions.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        sessions
+    }
+
+    fn codex_session_path_in(&mut self, files: &[PathBuf], session_id: &str) -> Option<PathBuf> {
+        let found = files
+            .iter()
+            .find(|path| {
+                self.entry(SessionKind::Codex, path)
+                    .and_then(|e| e.codex())
+                    .is_some_and(|(_, s)| s.session_id == session_id)
+            })
+            .cloned();
+        self.save_if_dirty();
+        found
+    }
+}
+
+fn parse_entry(kind: SessionKind, path: &Path, stamp: FileStamp) -> IndexEntry {
+    let session = match kind {
+        SessionKind::Claude => {
+            let session_id = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("")
+                .to_string();
+            if session_id.is_empty() {
+                None
+            } else {
+                claude_sessions::parse_session_jsonl(path, session_id).map(IndexedSession::Claude)
+            }
+        }
+        SessionKind::Codex => {
+            codex_sessions::parse_codex_session_summary(path).map(|summary| IndexedSession::Codex {
+                cwd: summary.cwd,
+                session: summary.session,
+            })
+        }
+    };
+    IndexEntry { stamp, session }
+}
+
+fn codex_files(sessions_dir: &Path) -> Vec<PathBuf> {
+    codex_sessions::collect_jsonl_files(sessions_dir, CODEX_MAX_DEPTH)
+}
+
+/// Whether a rollout file is named after `session_id`, either exactly or as
+/// the `-<id>` suffix of `rollout-<timestamp>-<id>.jsonl`.
+fn rollout_named_after(path: &Path, session_id: &str) -> bool {
+    path.file_stem()
+        .and_then(|stem| stem.to_str())
+        .and_then(|stem| stem.strip_suffix(session_id))
+        .is_some_and(|prefix| prefix.is_empty() || prefix.ends_with('-'))
+}
+
+pub(crate) fn claude_projects_dir() -> PathBuf {
+    paths::claude_user_dir().join("projects")
+}
+
+fn kind_for_path(path: &Path) -> Option<SessionKind> {
+    if path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
+        return None;
+    }
+    if path.starts_with(claude_projects_dir()) {
+        Some(SessionKind::Claude)
+    } else if path.starts_with(paths::codex_sessions_dir()) {
+        Some(SessionKind::Codex)
+    } else {
+        None
+    }
+}
+
+struct CacheEntry<T> {
+    stamp: FileStamp,
+    value: Option<T>,
+    last_used: u64,
+}
+
+/// In-memory cache of values derived from session files, rebuilt only when a
+/// file's mtime or size changes. Files that fail to parse are cached as `None`.
+/// A bounded cache evicts the least recently used file once it is full.
+pub(crate) struct FileCache<T> {
+    entries: HashMap<String, CacheEntry<T>>,
+    capacity: Option<usize>,
+    clock: u64,
+}
+
+impl<T> Default for FileCache<T> {
+    fn default() -> Self {
+        Self {
+            entries: HashMap::new(),
+            capacity: None,
+            clock: 0,
+        }
+    }
+}
+
+impl<T> FileCache<T> {
+    pub(crate) fn bounded(capacity: usize) -> Self {
+        Self {
+            capacity: Some(capacity.max(1)),
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn get_or_build(
+        &mut self,
+        path: &Path,
+        build: impl FnOnce(&Path) -> Option<T>,
+    ) -> Option<&T> {
+        let stamp = FileStamp::of(path)?;
+        let key = path.to_string_lossy().to_string();
+        self.clock += 1;
+        match self.entries.get_mut(&key) {
+            Some(entry) if entry.stamp == stamp => entry.last_used = self.clock,
+            _ => {
+                let value = build(path);
+                self.entries.insert(
+                    key.clone(),
+                    CacheEntry {
+                        stamp,
+                        value,
+                        last_used: self.clock,
+                    },
+                );
+                self.evict_over_capacity();
+            }
+        }
+        self.entries.get(&key)?.value.as_ref()
+    }
+
+    fn evict_over_capacity(&mut self) {
+        let Some(capacity) = self.capacity else {
+            return;
+        };
+        while self.entries.len() > capacity {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone());
+            match oldest {
+                Some(key) => self.entries.remove(&key),
+                None => break,
+            };
+        }
+    }
+
+    /// Cached value for `path` without checking the file for changes.
+    pub(crate) fn get(&self, path: &Path) -> Option<&T> {
+        self.entries
+            .get(path.to_string_lossy().as_ref())?
+            .value
+            .as_ref()
+    }
+
+    /// Forget files that no longer exist.
+    pub(crate) fn prune_missing(&mut self) {
+        self.entries.retain(|key, _| Path::new(key).exists());
+    }
+}
+
+/// Session files to scan for one project, or for every project.
+pub(crate) struct SessionFiles {
+    claude: Vec<PathBuf>,
+    codex: Vec<PathBuf>,
+}
+
+impl SessionFiles {
+    pub(crate) fn for_project(project_path: Option<&str>) -> Self {
+        Self::in_dirs(
+            &claude_projects_dir(),
+            &paths::codex_sessions_dir(),
+            project_path,
+        )
+    }
+
+    pub(crate) fn in_dirs(
+        claude_projects: &Path,
+        codex_sessions_dir: &Path,
+        project_path: Option<&str>,
+    ) -> Self {
+        let codex = codex_files(codex_sessions_dir);
+        match project_path {
+            Some(project) => {
+                // Codex files are not grouped by project, so keep only those
+                // whose metadata names the project before anything parses them.
+                let cwd = canonical_path(project);
+                Self {
+                    claude: codex_sessions::collect_jsonl_files(
+                        &claude_projects.join(paths::encode_project_path(project)),
+                        1,
+                    ),
+                    codex: codex
+                        .into_iter()
+                        .filter(|path| {
+                            codex_sessions::read_codex_session_cwd(path).as_deref()
+                                == Some(cwd.as_str())
+                        })
+                        .collect(),
+                }
+            }
+            None => Self {
+                // projects/<encoded>/<session>.jsonl
+                claude: codex_sessions::collect_jsonl_files(claude_projects, 2),
+                codex,
+            },
+        }
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (SessionKind, &Path)> {
+        self.claude
+            .iter()
+            .map(|p| (SessionKind::Claude, p.as_path()))
+            .chain(self.codex.iter().map(|p| (SessionKind::Codex, p.as_path())))
+    }
+}
+
+/// A session directory that did not exist yet, and the ancestor watched
+/// (non-recursively) until it is created.
+struct PendingDir {
+    kind: SessionKind,
+    dir: PathBuf,
+    watched: PathBuf,
+}
+
+/// Where to watch for `dir`: the directory itself once it exists, otherwise
+/// its nearest existing ancestor so its creation is noticed.
+fn watch_target(dir: &Path) -> Option<(PathBuf, RecursiveMode)> {
+    if dir.is_dir() {
+        return Some((dir.to_path_buf(), RecursiveMode::Recursive));
+    }
+    let ancestor = dir.ancestors().skip(1).find(|p| p.is_dir())?;
+    Some((ancestor.to_path_buf(), RecursiveMode::NonRecursive))
+}
+
+/// How deep session files sit below each agent's session directory.
+fn session_depth(kind: SessionKind) -> u32 {
+    match kind {
+        SessionKind::Claude => 2, // projects/<encoded>/<session>.jsonl
+        SessionKind::Codex => CODEX_MAX_DEPTH,
+    }
+}
+
+/// Start watching a session directory, or the nearest ancestor that exists
+/// if it has not been created yet.
+fn watch_dir(watcher: &mut FileWatcher, kind: SessionKind, dir: PathBuf) -> Option<PendingDir> {
+    let (target, mode) = watch_target(&dir)?;
+    if let Err(err) = watcher.watcher().watch(&target, mode) {
+        eprintln!("[SessionIndex] Failed to watch {}: {err}", target.display());
+        return None;
+    }
+    (mode == RecursiveMode::NonRecursive).then_some(PendingDir {
+        kind,
+        dir,
+        watched: target,
+    })
+}
+
+#[derive(Clone)]
+pub struct SessionIndex {
+    state: Arc<Mutex<IndexState>>,
+    watcher: Arc<Mutex<Option<FileWatcher>>>,
+    pending: Arc<Mutex<Vec<PendingDir>>>,
+}
+
+impl SessionIndex {
+    pub fn new() -> Self {
+        let cache_path = paths::workbench_cache_dir().join(INDEX_FILE_NAME);
+        Self {
+            state: Arc::new(Mutex::new(IndexState::load(cache_path))),
+            watcher: Arc::new(Mutex::new(None)),
+            pending: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Parse new or changed files without holding the state lock, so other
+    /// lookups and the watcher don't wait while transcripts are read.
+    fn warm(&self, kind: SessionKind, files: &[PathBuf]) {
+        let stale: Vec<(&PathBuf, FileStamp)> = {
+            let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            files
+                .iter()
+                .filter_map(|path| {
+                    let stamp = FileStamp::of(path)?;
+                    let fresh = state.is_fresh(path.to_string_lossy().as_ref(), &stamp);
+                    (!fresh).then_some((path, stamp))
+                })
+                .collect()
+        };
+        if stale.is_empty() {
+            return;
+        }
+        let parsed: Vec<(String, IndexEntry)> = stale
+            .into_iter()
+            .map(|(path, stamp)| {
+                let key = path.to_string_lossy().to_string();
+                (key, parse_entry(kind, path, stamp))
+            })
+            .collect();
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.file.entries.extend(parsed);
+        state.dirty = true;
+    }
+
+    /// Claude sessions from the project and all of its worktrees.
+    pub fn claude_sessions(&self, project_path: &str) -> Result<Vec<DiscoveredClaudeSession>> {
+        let locations = session_locations(project_path);
+        let projects_dir = claude_projects_dir();
+        let files: Vec<PathBuf> = locations
+            .iter()
+            .flat_map(|l| {
+                let dir = projects_dir.join(paths::encode_project_path(&l.path));
+                codex_sessions::collect_jsonl_files(&dir, 1)
+            })
+            .collect();
+        self.warm(SessionKind::Claude, &files);
+        let mut sessions = {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            state.claude_sessions_at(&projects_dir, &locations)?
+        };
+        // Permission prompts depend on hook state and elapsed time, so they
+        // are decided per lookup rather than stored in the index.
+        let now = Utc::now();
+        for s in &mut sessions {
+            session_tail::resolve_pending_permission(
+                &s.session_id,
+                s.pending_tool_since.as_deref(),
+                &mut s.awaiting_input,
+                now,
+            );
+        }
+        Ok(sessions)
+    }
+
+    /// Codex sessions whose cwd is the project or one of its worktrees.
+    pub fn codex_sessions(&self, project_path: &str) -> Vec<DiscoveredClaudeSession> {
+        let locations = session_locations(project_path);
+        let sessions_dir = paths::codex_sessions_dir();
+        self.warm(SessionKind::Codex, &codex_files(&sessions_dir));
+        let mut sessions = {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            state.codex_sessions_in(&sessions_dir, &locations)
+        };
+        let now = Utc::now();
+        for s in &mut sessions {
+            session_tail::resolve_pending_permission(
+                &s.session_id,
+                s.pending_tool_since.as_deref(),
+                &mut s.awaiting_input,
+                now,
+            );
+        }
+        sessions
+    }
+
+    /// Locate the transcript file for a session. Claude files are named by
+    /// session ID and may live under any of the project's worktrees; Codex
+    /// files are found through the index.
+    pub fn session_path(
+        &self,
+        kind: SessionKind,
+        project_path: &str,
+        session_id: &str,
+    ) -> Result<PathBuf> {
+        let path = match kind {
+            SessionKind::Claude => {
+                let path = claude_transcript::claude_session_path(project_path, session_id)?;
+                if path.is_file() {
+                    path
+                } else {
+                    session_locations(project_path)
+                        .iter()
+                        .filter_map(|l| {
+                            claude_transcript::claude_session_path(&l.path, session_id).ok()
+                        })
+                        .find(|p| p.is_file())
+                        .unwrap_or(path)
+                }
+            }
+            SessionKind::Codex => {
+                match self.codex_session_path(&paths::codex_sessions_dir(), session_id) {
+                    Some(path) => path,
+                    None => bail!("Codex session not found: {session_id}"),
+                }
+            }
+        };
+        if !path.is_file() {
+            bail!("Session file not found: {}", path.display());
+        }
+        Ok(path)
+    }
+
+    /// Find a Codex session file by ID. Rollout files are named after their
+    /// session, and indexed files already know theirs, so only the remaining
+    /// files are parsed, and only if neither of those matches.
+    fn codex_session_path(&self, sessions_dir: &Path, session_id: &str) -> Option<PathBuf> {
+        if session_id.is_empty() {
+            return None;
+        }
+        let files = codex_files(sessions_dir);
+        let (named, others): (Vec<PathBuf>, Vec<PathBuf>) = files
+            .into_iter()
+            .partition(|path| rollout_named_after(path, session_id));
+        let indexed = |state: &IndexState, path: &PathBuf| {
+            state
+                .file
+                .entries
+                .get(path.to_string_lossy().as_ref())
+                .and_then(|e| e.codex())
+                .is_some_and(|(_, s)| s.session_id == session_id)
+        };
+        let known: Vec<PathBuf> = {
+            let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            others
+                .iter()
+                .filter(|path| indexed(&state, path))
+                .cloned()
+                .collect()
+        };
+        for candidates in [named, known, others] {
+            self.warm(SessionKind::Codex, &candidates);
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(path) = state.codex_session_path_in(&candidates, session_id) {
+                return Some(path);
+            }
+        }
+        None
+    }
+
+    /// Drop a deleted session file from the index.
+    pub(crate) fn forget(&self, path: &Path) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if state
+            .file
+            .entries
+            .remove(path.to_string_lossy().as_ref())
+            .is_some()
+        {
+            state.dirty = true;
+        }
+        state.save_if_dirty();
+    }
+
+    /// Re-index changed files so the next lookup is instant.
+    fn refresh_paths(&self, changed: &[(SessionKind, PathBuf)]) {
+        for kind in [SessionKind::Claude, SessionKind::Codex] {
+            let files: Vec<PathBuf> = changed
+                .iter()
+                .filter(|(k, path)| *k == kind && path.exists())
+                .map(|(_, path)| path.clone())
+                .collect();
+            self.warm(kind, &files);
+        }
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        for (_, path) in changed {
+            if !path.exists()
+                && state
+                    .file
+                    .entries
+                    .remove(path.to_string_lossy().as_ref())
+                    .is_some()
+            {
+                state.dirty = true;
+            }
+        }
+        state.save_if_dirty();
+    }
+
+    /// Move watches down to session directories created since startup.
+    /// Returns the session files already inside newly watched directories.
+    fn advance_pending(&self) -> Vec<(SessionKind, PathBuf)> {
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        if pending.is_empty() {
+            return Vec::new();
+        }
+        let mut guard = self.watcher.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(watcher) = guard.as_mut() else {
+            return Vec::new();
+        };
+
+        let mut found = Vec::new();
+        let mut still_pending = Vec::new();
+        let mut released = Vec::new();
+        for entry in pending.drain(..) {
+            if watch_target(&entry.dir).is_some_and(|(target, _)| target == entry.watched) {
+                still_pending.push(entry);
+                continue;
+            }
+            released.push(entry.watched.clone());
+            match watch_dir(watcher, entry.kind, entry.dir.clone()) {
+                Some(next) => still_pending.push(next),
+                None => found.extend(
+                    codex_sessions::collect_jsonl_files(&entry.dir, session_depth(entry.kind))
+                        .into_iter()
+                        .map(|path| (entry.kind, path)),
+                ),
+            }
+        }
+        // Both agents may have been waiting on the same ancestor.
+        for dir in released {
+            if !still_pending.iter().any(|p| p.watched == dir) {
+                let _ = watcher.watcher().unwatch(&dir);
+            }
+        }
+        *pending = still_pending;
+        found
+    }
+
+    /// Watch the Claude and Codex session directories, re-indexing changed
+    /// files and emitting `sessions:changed` once per agent per batch.
+    pub fn start_watcher(&self, app_handle: AppHandle) {
+        let index = self.clone();
+        let debouncer = new_debouncer(
+            std::time::Duration::from_millis(500),
+            move |events: Result<Vec<notify_debouncer_mini::DebouncedEvent>, notify::Error>| {
+                let events = match events {
+                    Ok(e) => e,
+                    Err(err) => {
+                        eprintln!("[SessionIndex] watcher error: {err}");
+                        return;
+                    }
+                };
+                let mut changed: Vec<(SessionKind, PathBuf)> = events
+                    .iter()
+                    .filter(|event| event.kind == DebouncedEventKind::Any)
+                    .filter_map(|event| Some((kind_for_path(&event.path)?, event.path.clone())))
+                    .collect();
+                changed.extend(index.advance_pending());
+                if changed.is_empty() {
+                    return;
+                }
+                index.refresh_paths(&changed);
+
+                for kind in [SessionKind::Claude, SessionKind::Codex] {
+                    let paths: Vec<String> = changed
+                        .iter()
+                        .filter(|(k, _)| *k == kind)
+                        .map(|(_, p)| p.to_string_lossy().to_string())
+                        .collect();
+                    if !paths.is_empty() {
+                        let _ = app_handle.emit(
+                            "sessions:changed",
+                            SessionsChangedEvent {
+                                agent: kind.as_str().to_string(),
+                                paths,
+                            },
+                        );
+                    }
+                }
+            },
+        );
+
+        let mut debouncer = match debouncer {
+            Ok(d) => d,
+            Err(err) => {
+                eprintln!("[SessionIndex] Failed to create debouncer: {err}");
+                return;
+            }
+        };
+        let pending: Vec<PendingDir> = [
+            (SessionKind::Claude, claude_projects_dir()),
+            (SessionKind::Codex, paths::codex_sessions_dir()),
+        ]
+        .into_iter()
+        .filter_map(|(kind, dir)| watch_dir(&mut debouncer, kind, dir))
+        .collect();
+        *self.pending.lock().unwrap_or_else(|e| e.into_inner()) = pending;
+        *self.watcher.lock().unwrap_or_else(|e| e.into_inner()) = Some(debouncer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::tempdir;
+
+    fn write_claude_session(dir: &Path, id: &str, text: &str) -> PathBuf {
+        let path = dir.join(format!("{id}.jsonl"));
+        let mut file = fs::File::create(&path).unwrap();
+        writeln!(
+            file,
+            r#"{{"type":"user","timestamp":"2025-01-0{}T00:00:00Z","message":{{"content":"{text}"}}}}"#,
+            id.len() % 9
+        )
+        .unwrap();
+        path
+    }
+
+    fn write_codex_session(dir: &Path, name: &str, cwd: &Path, text: &str) -> PathBuf {
+        fs::create_dir_all(dir).unwrap();
+        let path = dir.join(name);
+        let id = name.trim_end_matches(".jsonl");
+        let mut file = fs::File::create(&path).unwrap();
+        writeln!(
+            file,
+            r#"{{"type":"session_meta","payload":{{"id":"{id}","timestamp":"2025-01-01T00:00:00Z","cwd":"{}"}}}}"#,
+            cwd.display()
+        )
+        .unwrap();
+        writeln!(
+            file,
+            r#"{{"type":"event_msg","payload":{{"type":"user_message","text":"{text}"}}}}"#
+        )
+        .unwrap();
+        path
+    }
+
+    fn location(path: &Path, branch: Option<&str>) -> SessionLocation {
+        SessionLocation {
+            path: path.to_string_lossy().to_string(),
+            branch: branch.map(str::to_string),
+        }
+    }
+
+    fn state(dir: &Path) -> IndexState {
+        IndexState::load(dir.join("cache").join(INDEX_FILE_NAME))
+    }
+
+    #[test]
+    fn claude_sessions_are_cached_and_persisted() {
+        let dir = tempdir().unwrap();
+        let sessions_dir = dir.path().join("projects").join("-repo");
+        fs::create_dir_all(&sessions_dir).unwrap();
+        write_claude_session(&sessions_dir, "sess-a", "Fix the login bug");
+
+        let mut index = state(dir.path());
+        let sessions = index.claude_sessions_in(&sessions_dir).unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].label, "Fix the login bug");
+        assert!(!index.dirty, "index should be saved after lookup");
+
+        // A fresh load reads the persisted entry.
+        let reloaded = state(dir.path());
+        assert_eq!(reloaded.file.entries.len(), 1);
+    }
+
+    #[test]
+    fn unchanged_files_are_not_reparsed() {
+        let dir = tempdir().unwrap();
+        let sessions_dir = dir.path().join("projects").join("-repo");
+        fs::create_dir_all(&sessions_dir).unwrap();
+        let path = write_claude_session(&sessions_dir, "sess-a", "Original label");
+
+        let mut index = state(dir.path());
+        index.claude_sessions_in(&sessions_dir).unwrap();
+
+        // Tamper with the cached label; an unchanged file must keep serving it.
+        let key = path.to_string_lossy().to_string();
+        let Some(IndexedSession::Claude(cached)) =
+            &mut index.file.entries.get_mut(&key).unwrap().session
+        else {
+            panic!("expected a Claude entry");
+        };
+        cached.label = "Cached label".into();
+        let sessions = index.claude_sessions_in(&sessions_dir).unwrap();
+        assert_eq!(sessions[0].label, "Cached label");
+
+        // Changing the file (size differs) forces a re-parse.
+        write_claude_session(&sessions_dir, "sess-a", "A much longer replacement label");
+        let sessions = index.claude_sessions_in(&sessions_dir).unwrap();
+        assert_eq!(sessions[0].label, "A much longer replacement label");
+    }
+
+    #[test]
+    fn deleted_files_are_pruned() {
+        let dir = tempdir().unwrap();
+        let sessions_dir = dir.path().join("projects").join("-repo");
+        fs::create_dir_all(&sessions_dir).unwrap();
+        let path = write_claude_session(&sessions_dir, "sess-a", "First session");
+        write_claude_session(&sessions_dir, "sess-bb", "Second session");
+
+        let mut index = state(dir.path());
+        assert_eq!(index.claude_sessions_in(&sessions_dir).unwrap().len(), 2);
+
+        fs::remove_file(&path).unwrap();
+        assert_eq!(index.claude_sessions_in(&sessions_dir).unwrap().len(), 1);
+        assert_eq!(index.file.entries.len(), 1);
+    }
+
+    #[test]
+    fn codex_sessions_filter_by_cwd_from_index() {
+        let dir = tempdir().unwrap();
+        let project_a = dir.path().join("a");
+        let project_b = dir.path().join("b");
+        fs::create_dir_all(&project_a).unwrap();
+        fs::create_dir_all(&project_b).unwrap();
+        let sessions_dir = dir.path().join("sessions");
+        let day = sessions_dir.join("2025").join("01").join("01");
+        write_codex_session(&day, "rollout-a.jsonl", &project_a, "Rename the loader");
+        write_codex_session(&day, "rollout-b.jsonl", &project_b, "Add the tests");
+
+        let mut index = state(dir.path());
+        let a = index.codex_sessions_in(&sessions_dir, &[location(&project_a, None)]);
+        assert_eq!(a.len(), 1);
+        assert_eq!(a[0].label, "Rename the loader");
+
+        // Both files are indexed even though only one matched.
+        assert_eq!(index.file.entries.len(), 2);
+        let b = index.codex_sessions_in(&sessions_dir, &[location(&project_b, None)]);
+        assert_eq!(b[0].label, "Add the tests");
+    }
+
+    #[test]
+    fn sessions_are_tagged_with_their_worktree() {
+        let dir = tempdir().unwrap();
+        let repo = dir.path().join("repo");
+        let feature = dir.path().join("repo-feature-x");
+        fs::create_dir_all(&repo).unwrap();
+        fs::create_dir_all(&feature).unwrap();
+        let locations = [
+            location(&repo, Some("main")),
+            location(&feature, Some("feature-x")),
+        ];
+
+        let projects_dir = dir.path().join("projects");
+        for (path, id, text) in [
+            (&repo, "sess-a", "Main work"),
+            (&feature, "sess-bb", "Feature work"),
+        ] {
+            let sessions_dir =
+                projects_dir.join(paths::encode_project_path(path.to_str().unwrap()));
+            fs::create_dir_all(&sessions_dir).unwrap();
+            write_claude_session(&sessions_dir, id, text);
+        }
+        let codex_dir = dir.path().join("sessions");
+        let day = codex_dir.join("2025").join("01").join("01");
+        write_codex_session(&day, "rollout-a.jsonl", &feature, "Codex feature work");
+
+        let mut index = state(dir.path());
+        let claude = index.claude_sessions_at(&projects_dir, &locations).unwrap();
+        let tags: Vec<(&str, Option<&str>)> = claude
+            .iter()
+            .map(|s| (s.label.as_str(), s.branch.as_deref()))
+            .collect();
+        assert_eq!(
+            tags,
+            [
+                ("Feature work", Some("feature-x")),
+                ("Main work", Some("main"))
+            ]
+        );
+        assert_eq!(claude[0].worktree_path.as_deref(), feature.to_str());
+
+        let codex = index.codex_sessions_in(&codex_dir, &locations);
+        assert_eq!(codex.len(), 1);
+        assert_eq!(codex[0].branch.as_deref(), Some("feature-x"));
+    }
+
+    #[test]
+    fn codex_session_path_found_by_id() {
+        let dir = tempdir().unwrap();
+        let sessions_dir = dir.path().join("sessions");
+        let day = sessions_dir.join("2025").join("01").join("01");
+        let a = write_codex_session(&day, "rollout-a.jsonl", dir.path(), "First task here");
+        let b = write_codex_session(&day, "rollout-b.jsonl", dir.path(), "Second task here");
+        let index = SessionIndex {
+            state: Arc::new(Mutex::new(state(dir.path()))),
+            watcher: Arc::new(Mutex::new(None)),
+            pending: Arc::new(Mutex::new(Vec::new())),
+        };
+
+        // The file named after the session is found without parsing the rest.
+        assert_eq!(
+            index.codex_session_path(&sessions_dir, "rollout-b"),
+            Some(b)
+        );
+        let a_key = a.to_string_lossy().to_string();
+        assert!(!index
+            .state
+            .lock()
+            .unwrap()
+            .file
+            .entries
+            .contains_key(&a_key));
+
+        // An ID that no file name carries falls back to every file.
+        assert_eq!(index.codex_session_path(&sessions_dir, "missing"), None);
+        assert!(index
+            .state
+            .lock()
+            .unwrap()
+            .file
+            .entries
+            .contains_key(&a_key));
+    }
+
+    #[test]
+    fn rollout_name_matches_whole_id_suffix_only() {
+        let path = Path::new("/s/rollout-2025-01-01T10-00-00-0199a1b2-c3d4.jsonl");
+        assert!(rollout_named_after(path, "0199a1b2-c3d4"));
+        assert!(!rollout_named_after(path, "c3d4-extra"));
+        assert!(!rollout_named_after(path, "99a1b2-c3d4"));
+        assert!(rollout_named_after(
+            Path::new("/s/rollout-b.jsonl"),
+            "rollout-b"
+        ));
+    }
+
+    #[test]
+    fn codex_session_path_rejects_empty_id() {
+        let dir = tempdir().unwrap();
+        let sessions_dir = dir.path().join("sessions");
+        let day = sessions_dir.join("2025").join("01").join("01");
+        write_codex_session(&day, "rollout-a.jsonl", dir.path(), "First task here");
+        let index = SessionIndex {
+            state: Arc::new(Mutex::new(state(dir.path()))),
+            watcher: Arc::new(Mutex::new(None)),
+            pending: Arc::new(Mutex::new(Vec::new())),
+        };
+
+        assert_eq!(index.codex_session_path(&sessions_dir, ""), None);
+        assert!(index.state.lock().unwrap().file.entries.is_empty());
+    }
+
+    #[test]
+    fn warm_indexes_only_new_or_changed_files() {
+        let dir = tempdir().unwrap();
+        let sessions_dir = dir.path().join("projects").join("-repo");
+        fs::create_dir_all(&sessions_dir).unwrap();
+        let a = write_claude_session(&sessions_dir, "sess-a", "First session");
+        let index = SessionIndex {
+            state: Arc::new(Mutex::new(state(dir.path()))),
+            watcher: Arc::new(Mutex::new(None)),
+            pending: Arc::new(Mutex::new(Vec::new())),
+        };
+        index.warm(SessionKind::Claude, std::slice::from_ref(&a));
+
+        let key = a.to_string_lossy().to_string();
+        let mut state = index.state.lock().unwrap();
+        assert!(state.dirty);
+        state.file.entries.get_mut(&key).unwrap().session = None;
+        drop(state);
+
+        // An unchanged file keeps its entry; a new one is parsed.
+        let b = write_claude_session(&sessions_dir, "sess-bb", "Second session");
+        index.warm(SessionKind::Claude, &[a, b.clone()]);
+        let state = index.state.lock().unwrap();
+        assert!(state.file.entries[&key].session.is_none());
+        let entry = &state.file.entries[b.to_string_lossy().as_ref()];
+        assert_eq!(entry.claude().unwrap().label, "Second session");
+    }
+
+    #[test]
+    fn missing_session_dirs_watch_their_nearest_ancestor() {
+        let dir = tempdir().unwrap();
+        let sessions = dir.path().join(".codex").join("sessions");
+        assert_eq!(
+            watch_target(&sessions),
+            Some((dir.path().to_path_buf(), RecursiveMode::NonRecursive))
+        );
+
+        fs::create_dir_all(dir.path().join(".codex")).unwrap();
+        assert_eq!(
+            watch_target(&sessions),
+            Some((dir.path().join(".codex"), RecursiveMode::NonRecursive))
+        );
+
+        fs::create_dir_all(&sessions).unwrap();
+        assert_eq!(
+            watch_target(&sessions),
+            Some((sessions, RecursiveMode::Recursive))
+        );
+    }
+
+    #[test]
+    fn bounded_file_cache_evicts_least_recently_used() {
+        let dir = tempdir().unwrap();
+        let files: Vec<PathBuf> = ["a", "b", "c"]
+            .iter()
+            .map(|name| {
+                let path = dir.path().join(format!("{name}.jsonl"));
+                fs::write(&path, name).unwrap();
+                path
+            })
+            .collect();
+
+        let mut cache = FileCache::bounded(2);
+        let mut builds = 0;
+        let mut build = |p: &Path| {
+            builds += 1;
+            Some(p.to_path_buf())
+        };
+        cache.get_or_build(&files[0], &mut build);
+        cache.get_or_build(&files[1], &mut build);
+        // Touch `a` so `b` is the oldest when `c` arrives.
+        cache.get_or_build(&files[0], &mut build);
+        cache.get_or_build(&files[2], &mut build);
+        assert_eq!(builds, 3);
+
+        assert!(cache.get(&files[0]).is_some());
+        assert!(cache.get(&files[1]).is_none());
+        assert!(cache.get(&files[2]).is_some());
+    }
+
+    #[test]
+    fn project_scope_keeps_only_codex_files_from_the_project() {
+        let dir = tempdir().unwrap();
+        let project = dir.path().join("repo");
+        let other = dir.path().join("other");
+        fs::create_dir_all(&project).unwrap();
+        fs::create_dir_all(&other).unwrap();
+        let day = dir
+            .path()
+            .join("sessions")
+            .join("2025")
+            .join("01")
+            .join("01");
+        let ours = write_codex_session(&day, "rollout-a.jsonl", &project, "Ours here");
+        write_codex_session(&day, "rollout-b.jsonl", &other, "Theirs here");
+
+        let files = SessionFiles::in_dirs(
+            &dir.path().join("projects"),
+            &dir.path().join("sessions"),
+            project.to_str(),
+        );
+        let codex: Vec<&Path> = files
+            .iter()
+            .filter(|(kind, _)| *kind == SessionKind::Codex)
+            .map(|(_, path)| path)
+            .collect();
+        assert_eq!(codex, [ours.as_path()]);
+    }
+
+    #[test]
+    fn stale_index_version_is_discarded() {
+        let dir = tempdir().unwrap();
+        let cache_path = dir.path().join("cache").join(INDEX_FILE_NAME);
+        fs::create_dir_all(cache_path.parent().unwrap()).unwrap();
+        fs::write(
+            &cache_path,
+            r#"{"version":0,"entries":{"/x.jsonl":{"stamp":{"modifiedMs":1,"size":1},"session":null}}}"#,
+        )
+        .unwrap();
+        assert!(IndexState::load(cache_path).file.entries.is_empty());
+    }
+}
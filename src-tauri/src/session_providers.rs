@@ -0,0 +1,267 @@
+/// Agent session stores behind one interface. Each CLI that keeps session
+/// transcripts on disk gets an `AgentSessionProvider`; adding a new agent
+/// means implementing the trait and listing it in `PROVIDERS`.
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+
+use crate::claude_transcript;
+use crate::codex_sessions;
+use crate::paths;
+use crate::session_index::{self, SessionIndex, SessionKind};
+use crate::types::{AgentSession, TranscriptMessage};
+
+pub(crate) trait AgentSessionProvider: Sync {
+    /// Stable identifier, matching the pane session type (`"claude"`, `"codex"`).
+    fn name(&self) -> &'static str;
+
+    /// Human-readable agent name for titles and exports.
+    fn display_name(&self) -> &'static str;
+
+    /// Root directory the agent keeps its session files under.
+    fn store_dir(&self) -> PathBuf;
+
+    /// Sessions that ran in `project_path`, newest first.
+    fn discover(&self, index: &SessionIndex, project_path: &str) -> Result<Vec<AgentSession>>;
+
+    /// The transcript file backing a session.
+    fn session_path(
+        &self,
+        index: &SessionIndex,
+        project_path: &str,
+        session_id: &str,
+    ) -> Result<PathBuf>;
+
+    /// Parse a transcript file into provider-neutral messages.
+    fn read_transcript(&self, path: &Path) -> Result<Vec<TranscriptMessage>>;
+
+    fn load_transcript(
+        &self,
+        index: &SessionIndex,
+        project_path: &str,
+        session_id: &str,
+    ) -> Result<Vec<TranscriptMessage>> {
+        self.read_transcript(&self.session_path(index, project_path, session_id)?)
+    }
+
+    /// CLI command that resumes the session, or `None` if the ID is not resumable.
+    /// Pane startup commands are owned by the frontend workspace store.
+    #[allow(dead_code)]
+    fn resume_command(&self, session_id: &str, use_happy: bool) -> Option<String>;
+
+    /// Every file or directory belonging to a session, transcript first.
+    fn session_files(
+        &self,
+        index: &SessionIndex,
+        project_path: &str,
+        session_id: &str,
+    ) -> Result<Vec<PathBuf>> {
+        Ok(vec![self.session_path(index, project_path, session_id)?])
+    }
+
+    /// Remove the session's files from disk and from the index.
+    fn delete(&self, index: &SessionIndex, project_path: &str, session_id: &str) -> Result<()> {
+        for path in self.session_files(index, project_path, session_id)? {
+            paths::remove_path_if_exists(&path)
+                .with_context(|| format!("Failed to delete {}", path.display()))?;
+            index.forget(&path);
+        }
+        Ok(())
+    }
+}
+
+pub(crate) struct ClaudeSessionProvider;
+
+impl AgentSessionProvider for ClaudeSessionProvider {
+    fn name(&self) -> &'static str {
+        SessionKind::Claude.as_str()
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Claude"
+    }
+
+    fn store_dir(&self) -> PathBuf {
+        session_index::claude_projects_dir()
+    }
+
+    fn discover(&self, index: &SessionIndex, project_path: &str) -> Result<Vec<AgentSession>> {
+        Ok(index
+            .claude_sessions(project_path)?
+            .into_iter()
+            .map(|s| AgentSession {
+                provider: self.name().to_string(),
+                session_id: s.session_id,
+                label: s.label,
+                timestamp: s.timestamp,
+                last_message_role: s.last_message_role,
+                last_assistant_snippet: s.last_assistant_snippet,
+                awaiting_input: s.awaiting_input,
+                worktree_path: s.worktree_path,
+                branch: s.branch,
+            })
+            .collect())
+    }
+
+    fn session_path(
+        &self,
+        index: &SessionIndex,
+        project_path: &str,
+        session_id: &str,
+    ) -> Result<PathBuf> {
+        index.session_path(SessionKind::Claude, project_path, session_id)
+    }
+
+    fn read_transcript(&self, path: &Path) -> Result<Vec<TranscriptMessage>> {
+        claude_transcript::read_transcript(path)
+    }
+
+    fn resume_command(&self, session_id: &str, use_happy: bool) -> Option<String> {
+        if !is_uuid(session_id) {
+            return None;
+        }
+        let binary = if use_happy { "happy" } else { "claude" };
+        Some(format!("{binary} --resume {session_id}"))
+    }
+
+    /// Includes the sibling `<session-id>/` directory holding subagent
+    /// transcripts and spilled tool results.
+    fn session_files(
+        &self,
+        index: &SessionIndex,
+        project_path: &str,
+        session_id: &str,
+    ) -> Result<Vec<PathBuf>> {
+        let path = self.session_path(index, project_path, session_id)?;
+        let sidecar = path.with_extension("");
+        let mut files = vec![path];
+        if sidecar.is_dir() {
+            files.push(sidecar);
+        }
+        Ok(files)
+    }
+}
+
+pub(crate) struct CodexSessionProvider;
+
+impl AgentSessionProvider for CodexSessionProvider {
+    fn name(&self) -> &'static str {
+        SessionKind::Codex.as_str()
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Codex"
+    }
+
+    fn store_dir(&self) -> PathBuf {
+        paths::codex_sessions_dir()
+    }
+
+    fn discover(&self, index: &SessionIndex, project_path: &str) -> Result<Vec<AgentSession>> {
+        Ok(index
+            .codex_sessions(project_path)
+            .into_iter()
+            .map(|s| AgentSession {
+                provider: self.name().to_string(),
+                session_id: s.session_id,
+                label: s.label,
+                timestamp: s.timestamp,
+                last_message_role: s.last_message_role,
+                last_assistant_snippet: s.last_assistant_snippet,
+                awaiting_input: s.awaiting_input,
+                worktree_path: s.worktree_path,
+                branch: s.branch,
+            })
+            .collect())
+    }
+
+    fn session_path(
+        &self,
+        index: &SessionIndex,
+        project_path: &str,
+        session_id: &str,
+    ) -> Result<PathBuf> {
+        index.session_path(SessionKind::Codex, project_path, session_id)
+    }
+
+    fn read_transcript(&self, path: &Path) -> Result<Vec<TranscriptMessage>> {
+        codex_sessions::read_codex_transcript(path)
+    }
+
+    fn resume_command(&self, session_id: &str, _use_happy: bool) -> Option<String> {
+        Some(format!("codex resume {session_id}"))
+    }
+}
+
+static PROVIDERS: &[&dyn AgentSessionProvider] = &[&ClaudeSessionProvider, &CodexSessionProvider];
+
+/// Every registered provider, in display order.
+pub(crate) fn providers() -> &'static [&'static dyn AgentSessionProvider] {
+    PROVIDERS
+}
+
+pub(crate) fn provider(name: &str) -> Result<&'static dyn AgentSessionProvider> {
+    match PROVIDERS.iter().find(|p| p.name() == name) {
+        Some(provider) => Ok(*provider),
+        None => bail!("Unknown session provider: {name}"),
+    }
+}
+
+/// Sessions from the named providers (all of them when `names` is `None`),
+/// merged newest first. A failing provider is logged and skipped.
+pub fn discover_all(
+    index: &SessionIndex,
+    project_path: &str,
+    names: Option<&[String]>,
+) -> Vec<AgentSession> {
+    let mut sessions: Vec<AgentSession> = providers()
+        .iter()
+        .filter(|p| names.is_none_or(|names| names.iter().any(|n| n == p.name())))
+        .flat_map(|p| match p.discover(index, project_path) {
+            Ok(sessions) => sessions,
+            Err(e) => {
+                eprintln!("[SessionProviders] {} discovery failed: {e}", p.name());
+                Vec::new()
+            }
+        })
+        .collect();
+    sessions.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    sessions
+}
+
+fn is_uuid(value: &str) -> bool {
+    value.len() == 36
+        && value.char_indices().all(|(i, c)| match i {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SESSION: &str = "0b1c2d3e-4f50-6172-8394-a5b6c7d8e9f0";
+
+    #[test]
+    fn looks_up_providers_by_name() {
+        let names: Vec<&str> = providers().iter().map(|p| p.name()).collect();
+        assert_eq!(names, ["claude", "codex"]);
+        assert_eq!(provider("codex").unwrap().display_name(), "Codex");
+        assert!(provider("gemini").is_err());
+    }
+
+    #[test]
+    fn resume_commands_per_provider() {
+        let claude = provider("claude").unwrap();
+        assert_eq!(
+            claude.resume_command(SESSION, false),
+            Some(format!("claude --resume {SESSION}"))
+        );
+        assert_eq!(claude.resume_command("not-a-uuid", false), None);
+        assert_eq!(
+            provider("codex").unwrap().resume_command("thread-1", true),
+            Some("codex resume thread-1".to_string())
+        );
+    }
+}
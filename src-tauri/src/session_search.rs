@@ -0,0 +1,534 @@
+/// Full-text search over Claude and Codex session transcripts. Each session
+/// file gets its own small inverted index (token → message indices) built on
+/// first search and rebuilt only when the file's mtime or size changes. Only
+/// the most recently searched sessions are kept in memory.
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::io::BufRead;
+use std::path::Path;
+use std::sync::Mutex;
+
+use serde_json::Value;
+
+use crate::claude_sessions;
+use crate::claude_transcript;
+use crate::codex_sessions;
+use crate::session_index::{FileCache, SessionFiles, SessionKind};
+use crate::types::{
+    DiscoveredClaudeSession, SessionSearchHit, SessionSearchMatchKind, TranscriptBlock,
+    TranscriptMessage,
+};
+
+const DEFAULT_RESULT_LIMIT: usize = 50;
+/// Cap on indexed text per message so huge Write payloads don't bloat memory.
+const MAX_CHUNK_CHARS: usize = 16_000;
+const SNIPPET_CONTEXT_CHARS: usize = 80;
+/// Sessions whose index is kept in memory; older ones are rebuilt on demand.
+const MAX_INDEXED_SESSIONS: usize = 256;
+
+struct SearchChunk {
+    kind: SessionSearchMatchKind,
+    tool_name: Option<String>,
+    timestamp: Option<String>,
+    text: String,
+}
+
+struct SearchDoc {
+    agent: SessionKind,
+    session_id: String,
+    project_path: Option<String>,
+    label: String,
+    timestamp: String,
+    chunks: Vec<SearchChunk>,
+    /// Lowercased token → indices into `chunks`.
+    tokens: BTreeMap<String, BTreeSet<usize>>,
+}
+
+fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|t| !t.is_empty())
+        .map(str::to_lowercase)
+}
+
+/// Join every string value in a tool input, so keys like `file_path` or
+/// `command` don't need to be known up front.
+fn flatten_strings(value: &Value, out: &mut Vec<String>) {
+    match value {
+        Value::String(s) => out.push(s.clone()),
+        Value::Array(items) => items.iter().for_each(|v| flatten_strings(v, out)),
+        Value::Object(map) => map.values().for_each(|v| flatten_strings(v, out)),
+        _ => {}
+    }
+}
+
+fn truncate_chars(text: String) -> String {
+    match text.char_indices().nth(MAX_CHUNK_CHARS) {
+        Some((idx, _)) => text[..idx].to_string(),
+        None => text,
+    }
+}
+
+/// Split transcript messages into searchable chunks: user prompts, assistant
+/// text and tool inputs. Thinking, tool results and CLI-injected meta
+/// messages are not indexed.
+fn chunks_from_messages(messages: Vec<TranscriptMessage>) -> Vec<SearchChunk> {
+    let mut chunks = Vec::new();
+    for message in messages {
+        if message.is_meta {
+            continue;
+        }
+        let text_kind = match message.role.as_str() {
+            "user" => SessionSearchMatchKind::User,
+            "assistant" => SessionSearchMatchKind::Assistant,
+            _ => continue,
+        };
+        for block in message.blocks {
+            let (kind, tool_name, text) = match block {
+                TranscriptBlock::Text { text } => (text_kind, None, text),
+                TranscriptBlock::ToolUse { name, input, .. } => {
+                    let mut parts = Vec::new();
+                    flatten_strings(&input, &mut parts);
+                    (
+                        SessionSearchMatchKind::ToolInput,
+                        Some(name),
+                        parts.join("\n"),
+                    )
+                }
+                _ => continue,
+            };
+            if text.trim().is_empty() {
+                continue;
+            }
+            chunks.push(SearchChunk {
+                kind,
+                tool_name,
+                timestamp: message.timestamp.clone(),
+                text: truncate_chars(text),
+            });
+        }
+    }
+    chunks
+}
+
+fn index_tokens(chunks: &[SearchChunk]) -> BTreeMap<String, BTreeSet<usize>> {
+    let mut tokens: BTreeMap<String, BTreeSet<usize>> = BTreeMap::new();
+    for (idx, chunk) in chunks.iter().enumerate() {
+        for token in tokenize(&chunk.text) {
+            tokens.entry(token).or_default().insert(idx);
+        }
+    }
+    tokens
+}
+
+/// Read a Claude session, recording the first `cwd` it ran in.
+fn read_claude_doc(path: &Path) -> Option<(Option<String>, Vec<TranscriptMessage>)> {
+    let file = fs::File::open(path).ok()?;
+    let mut cwd = None;
+    let mut messages = Vec::new();
+    for line in std::io::BufReader::new(file).lines() {
+        let Ok(line) = line else { break };
+        let Ok(entry) = serde_json::from_str::<Value>(&line) else {
+            continue;
+        };
+        if cwd.is_none() {
+            cwd = entry
+                .get("cwd")
+                .and_then(|v| v.as_str())
+                .map(str::to_string);
+        }
+        if let Some(message) = claude_transcript::parse_transcript_entry(&entry) {
+            messages.push(message);
+        }
+    }
+    Some((cwd, messages))
+}
+
+fn build_doc(kind: SessionKind, path: &Path) -> Option<SearchDoc> {
+    let (session_id, label, timestamp, project_path, messages) = match kind {
+        SessionKind::Claude => {
+            let session_id = path.file_stem()?.to_str()?.to_string();
+            let session = claude_sessions::parse_session_jsonl(path, session_id)?;
+            let (cwd, messages) = read_claude_doc(path)?;
+            let DiscoveredClaudeSession {
+                session_id,
+                label,
+                timestamp,
+                ..
+            } = session;
+            (session_id, label, timestamp, cwd, messages)
+        }
+        SessionKind::Codex => {
+            let summary = codex_sessions::parse_codex_session_summary(path)?;
+            let messages = codex_sessions::read_codex_transcript(path).ok()?;
+            let DiscoveredClaudeSession {
+                session_id,
+                label,
+                timestamp,
+                ..
+            } = summary.session;
+            (session_id, label, timestamp, Some(summary.cwd), messages)
+        }
+    };
+    let chunks = chunks_from_messages(messages);
+    let tokens = index_tokens(&chunks);
+    Some(SearchDoc {
+        agent: kind,
+        session_id,
+        project_path,
+        label,
+        timestamp,
+        chunks,
+        tokens,
+    })
+}
+
+/// Chunk indices containing any token that starts with `term`.
+fn chunks_for_term(doc: &SearchDoc, term: &str) -> BTreeSet<usize> {
+    doc.tokens
+        .range(term.to_string()..)
+        .take_while(|(token, _)| token.starts_with(term))
+        .flat_map(|(_, chunks)| chunks.iter().copied())
+        .collect()
+}
+
+/// A window of text around the first occurrence of any term, whitespace collapsed.
+fn snippet(text: &str, terms: &[String]) -> String {
+    let lower = text.to_lowercase();
+    let byte_pos = terms.iter().filter_map(|t| lower.find(t.as_str())).min();
+    let chars: Vec<char> = text.chars().collect();
+    let center = byte_pos
+        .map(|pos| lower[..pos].chars().count())
+        .unwrap_or(0)
+        .min(chars.len());
+    let start = center.saturating_sub(SNIPPET_CONTEXT_CHARS);
+    let end = (center + SNIPPET_CONTEXT_CHARS * 2).min(chars.len());
+    let body: String = chars[start..end].iter().collect();
+    let body = body.split_whitespace().collect::<Vec<_>>().join(" ");
+    format!(
+        "{}{}{}",
+        if start > 0 { "…" } else { "" },
+        body,
+        if end < chars.len() { "…" } else { "" }
+    )
+}
+
+/// Match a document against all terms. Every term must appear somewhere in
+/// the session; the reported chunk is the one covering the most terms.
+fn match_doc(doc: &SearchDoc, terms: &[String]) -> Option<(usize, SessionSearchHit)> {
+    let mut hits_per_chunk: HashMap<usize, usize> = HashMap::new();
+    for term in terms {
+        let chunks = chunks_for_term(doc, term);
+        if chunks.is_empty() {
+            return None;
+        }
+        for idx in chunks {
+            *hits_per_chunk.entry(idx).or_default() += 1;
+        }
+    }
+    let (&best_idx, &best_terms) = hits_per_chunk
+        .iter()
+        .max_by(|(ia, ca), (ib, cb)| ca.cmp(cb).then(ib.cmp(ia)))?;
+    let chunk = &doc.chunks[best_idx];
+    Some((
+        best_terms,
+        SessionSearchHit {
+            agent: doc.agent.as_str().to_string(),
+            session_id: doc.session_id.clone(),
+            project_path: doc.project_path.clone(),
+            label: doc.label.clone(),
+            timestamp: doc.timestamp.clone(),
+            kind: chunk.kind,
+            tool_name: chunk.tool_name.clone(),
+            matched_at: chunk.timestamp.clone(),
+            snippet: snippet(&chunk.text, terms),
+            match_count: hits_per_chunk.len(),
+        },
+    ))
+}
+
+struct SearchState {
+    docs: FileCache<SearchDoc>,
+}
+
+impl Default for SearchState {
+    fn default() -> Self {
+        Self {
+            docs: FileCache::bounded(MAX_INDEXED_SESSIONS),
+        }
+    }
+}
+
+impl SearchState {
+    fn search(&mut self, files: &SessionFiles, query: &str, limit: usize) -> Vec<SessionSearchHit> {
+        let mut terms: Vec<String> = tokenize(query).collect();
+        terms.sort();
+        terms.dedup();
+        if terms.is_empty() {
+            return Vec::new();
+        }
+
+        let mut hits = Vec::new();
+        for (kind, path) in files.iter() {
+            let Some(doc) = self.docs.get_or_build(path, |p| build_doc(kind, p)) else {
+                continue;
+            };
+            if let Some(hit) = match_doc(doc, &terms) {
+                hits.push(hit);
+            }
+        }
+
+        self.docs.prune_missing();
+
+        hits.sort_by(|(ta, a), (tb, b)| {
+            tb.cmp(ta)
+                .then(b.match_count.cmp(&a.match_count))
+                .then(b.timestamp.cmp(&a.timestamp))
+        });
+        hits.into_iter().take(limit).map(|(_, hit)| hit).collect()
+    }
+}
+
+pub struct SessionSearch {
+    state: Mutex<SearchState>,
+}
+
+impl SessionSearch {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(SearchState::default()),
+        }
+    }
+
+    /// Search sessions for one project, or every project when `project_path` is `None`.
+    pub fn search(
+        &self,
+        query: &str,
+        project_path: Option<&str>,
+        limit: Option<usize>,
+    ) -> Vec<SessionSearchHit> {
+        let files = SessionFiles::for_project(project_path);
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.search(&files, query, limit.unwrap_or(DEFAULT_RESULT_LIMIT))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paths;
+    use std::io::Write;
+    use tempfile::tempdir;
+
+    fn write_lines(path: &Path, lines: &[Value]) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let mut file = fs::File::create(path).unwrap();
+        for line in lines {
+            writeln!(file, "{line}").unwrap();
+        }
+    }
+
+    fn claude_session(root: &Path, project: &str, id: &str, lines: &[Value]) {
+        let path = root
+            .join("projects")
+            .join(paths::encode_project_path(project))
+            .join(format!("{id}.jsonl"));
+        write_lines(&path, lines);
+    }
+
+    fn claude_user(text: &str, ts: &str) -> Value {
+        serde_json::json!({
+            "type": "user", "timestamp": ts, "cwd": "/work/app",
+            "message": { "role": "user", "content": text }
+        })
+    }
+
+    fn claude_assistant(blocks: Value, ts: &str) -> Value {
+        serde_json::json!({
+            "type": "assistant", "timestamp": ts,
+            "message": { "role": "assistant", "model": "claude-sonnet-4-5", "content": blocks }
+        })
+    }
+
+    fn search(root: &Path, project: Option<&str>, query: &str) -> Vec<SessionSearchHit> {
+        let scope = SessionFiles::in_dirs(&root.join("projects"), &root.join("sessions"), project);
+        SearchState::default().search(&scope, query, 10)
+    }
+
+    #[test]
+    fn matches_user_prompts_assistant_text_and_tool_inputs() {
+        let dir = tempdir().unwrap();
+        claude_session(
+            dir.path(),
+            "/work/app",
+            "sess-auth",
+            &[
+                claude_user("Please fix the auth redirect loop", "2025-01-01T00:00:00Z"),
+                claude_assistant(
+                    serde_json::json!([
+                        { "type": "text", "text": "The middleware drops the return URL." },
+                        { "type": "tool_use", "id": "t1", "name": "Edit",
+                          "input": { "file_path": "src/middleware.ts", "old_string": "a", "new_string": "b" } }
+                    ]),
+                    "2025-01-01T00:01:00Z",
+                ),
+            ],
+        );
+
+        let hits = search(dir.path(), Some("/work/app"), "redirect");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].agent, "claude");
+        assert_eq!(hits[0].session_id, "sess-auth");
+        assert_eq!(hits[0].kind, SessionSearchMatchKind::User);
+        assert_eq!(hits[0].project_path.as_deref(), Some("/work/app"));
+        assert!(hits[0].snippet.contains("auth redirect"));
+
+        let hits = search(dir.path(), Some("/work/app"), "middleware");
+        assert_eq!(hits[0].match_count, 2);
+
+        let hits = search(dir.path(), Some("/work/app"), "middleware.ts");
+        assert_eq!(hits[0].kind, SessionSearchMatchKind::ToolInput);
+        assert_eq!(hits[0].tool_name.as_deref(), Some("Edit"));
+    }
+
+    #[test]
+    fn all_terms_must_match_and_prefixes_are_allowed() {
+        let dir = tempdir().unwrap();
+        claude_session(
+            dir.path(),
+            "/work/app",
+            "sess-1",
+            &[claude_user(
+                "Refactor the redirect handling",
+                "2025-01-01T00:00:00Z",
+            )],
+        );
+        assert_eq!(search(dir.path(), None, "redir").len(), 1);
+        assert_eq!(search(dir.path(), None, "REDIRECT handling").len(), 1);
+        assert!(search(dir.path(), None, "redirect login").is_empty());
+        assert!(search(dir.path(), None, "  ").is_empty());
+    }
+
+    #[test]
+    fn scopes_to_project_or_searches_everything() {
+        let dir = tempdir().unwrap();
+        claude_session(
+            dir.path(),
+            "/work/app",
+            "sess-a",
+            &[claude_user(
+                "Update the changelog please",
+                "2025-01-01T00:00:00Z",
+            )],
+        );
+        claude_session(
+            dir.path(),
+            "/work/other",
+            "sess-b",
+            &[claude_user(
+                "Write the changelog entry",
+                "2025-01-02T00:00:00Z",
+            )],
+        );
+
+        let hits = search(dir.path(), Some("/work/app"), "changelog");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].session_id, "sess-a");
+
+        let hits = search(dir.path(), None, "changelog");
+        assert_eq!(hits.len(), 2);
+        // Equal relevance falls back to newest first.
+        assert_eq!(hits[0].session_id, "sess-b");
+    }
+
+    #[test]
+    fn meta_messages_are_not_indexed() {
+        let dir = tempdir().unwrap();
+        let mut meta = claude_user(
+            "Caveat: secretword in local command output",
+            "2025-01-01T00:00:00Z",
+        );
+        meta["isMeta"] = Value::Bool(true);
+        claude_session(
+            dir.path(),
+            "/work/app",
+            "sess-1",
+            &[
+                claude_user("A normal prompt here", "2025-01-01T00:00:00Z"),
+                meta,
+            ],
+        );
+        assert!(search(dir.path(), None, "secretword").is_empty());
+    }
+
+    #[test]
+    fn searches_codex_sessions_by_cwd() {
+        let dir = tempdir().unwrap();
+        let project = dir.path().join("repo");
+        fs::create_dir_all(&project).unwrap();
+        let path = dir.path().join("sessions/2025/01/01/rollout-1.jsonl");
+        write_lines(
+            &path,
+            &[
+                serde_json::json!({ "type": "session_meta", "payload": {
+                    "id": "codex-1", "timestamp": "2025-01-01T00:00:00Z", "cwd": project } }),
+                serde_json::json!({ "type": "response_item", "payload": {
+                    "type": "message", "role": "user",
+                    "content": [{ "type": "input_text", "text": "Bump the tokio version" }] } }),
+                serde_json::json!({ "type": "response_item", "payload": {
+                    "type": "function_call", "name": "shell", "call_id": "c1",
+                    "arguments": "{\"command\":[\"cargo\",\"update\",\"-p\",\"tokio\"]}" } }),
+            ],
+        );
+
+        let project_str = project.to_str().unwrap();
+        let hits = search(dir.path(), Some(project_str), "cargo update");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].agent, "codex");
+        assert_eq!(hits[0].session_id, "codex-1");
+        assert_eq!(hits[0].kind, SessionSearchMatchKind::ToolInput);
+        assert_eq!(hits[0].tool_name.as_deref(), Some("shell"));
+
+        assert!(search(dir.path(), Some("/elsewhere"), "tokio").is_empty());
+    }
+
+    #[test]
+    fn changed_files_are_reindexed() {
+        let dir = tempdir().unwrap();
+        let scope = || {
+            SessionFiles::in_dirs(
+                &dir.path().join("projects"),
+                &dir.path().join("sessions"),
+                None,
+            )
+        };
+        claude_session(
+            dir.path(),
+            "/work/app",
+            "sess-1",
+            &[claude_user("First prompt text", "2025-01-01T00:00:00Z")],
+        );
+        let mut state = SearchState::default();
+        assert!(state.search(&scope(), "pagination", 10).is_empty());
+
+        claude_session(
+            dir.path(),
+            "/work/app",
+            "sess-1",
+            &[
+                claude_user("First prompt text", "2025-01-01T00:00:00Z"),
+                claude_user("Now add pagination to the list", "2025-01-01T00:05:00Z"),
+            ],
+        );
+        assert_eq!(state.search(&scope(), "pagination", 10).len(), 1);
+    }
+
+    #[test]
+    fn snippet_centers_on_first_match() {
+        let text = format!("{} needle {}", "a ".repeat(100), "b ".repeat(100));
+        let s = snippet(&text, &["needle".to_string()]);
+        assert!(s.starts_with('…'));
+        assert!(s.ends_with('…'));
+        assert!(s.contains("needle"));
+        assert_eq!(snippet("short text", &["zzz".to_string()]), "short text");
+    }
+}
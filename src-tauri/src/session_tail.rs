@@ -0,0 +1,228 @@
+/// Reads how a Claude or Codex session ended from the tail of its JSONL, and
+/// decides whether it is waiting on the user.
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use crate::session_utils;
+use crate::types::{AwaitingInputReason, TranscriptBlock, TranscriptMessage};
+
+/// Tail window sizes for finding the last message; doubled until a
+/// conversational entry is found or the cap is reached.
+pub(crate) const TAIL_INITIAL_BYTES: u64 = 64 * 1024;
+const TAIL_MAX_BYTES: u64 = 4 * 1024 * 1024;
+const SNIPPET_MAX_CHARS: usize = 160;
+/// Tools that exist to ask the user something.
+const QUESTION_TOOLS: &[&str] = &["AskUserQuestion"];
+/// Without hook events, a tool call still unanswered after this long is
+/// assumed to be stuck on a permission prompt rather than running.
+const PENDING_TOOL_PERMISSION_SECS: i64 = 120;
+
+/// Sessions the hook bridge has seen, and whether each currently shows a
+/// permission prompt.
+static PERMISSION_PROMPTS: Mutex<BTreeMap<String, bool>> = Mutex::new(BTreeMap::new());
+
+/// How a session's conversation ended.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct SessionTail {
+    pub last_message_role: Option<String>,
+    pub last_assistant_snippet: Option<String>,
+    pub awaiting_input: Option<AwaitingInputReason>,
+    /// Timestamp of a trailing tool call that has no result yet.
+    pub pending_tool_since: Option<String>,
+}
+
+fn assistant_text(message: &TranscriptMessage) -> Option<&str> {
+    message.blocks.iter().rev().find_map(|block| match block {
+        TranscriptBlock::Text { text } if !text.trim().is_empty() => Some(text.as_str()),
+        _ => None,
+    })
+}
+
+/// Scan tail lines newest-first. Returns `None` if no conversational entry
+/// is in the window.
+fn scan_tail(
+    lines: &[String],
+    parse: fn(&Value) -> Option<TranscriptMessage>,
+) -> Option<SessionTail> {
+    let mut tail: Option<SessionTail> = None;
+    let messages = lines
+        .iter()
+        .rev()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .filter_map(|entry| parse(&entry))
+        .filter(|m| {
+            matches!(m.role.as_str(), "user" | "assistant") && !m.is_meta && !m.is_sidechain
+        });
+    for message in messages {
+        let Some(found) = tail.as_mut() else {
+            let mut first = SessionTail {
+                last_message_role: Some(message.role.clone()),
+                ..Default::default()
+            };
+            if message.role == "assistant" {
+                if let Some(TranscriptBlock::ToolUse { name, .. }) = message.blocks.last() {
+                    if QUESTION_TOOLS.contains(&name.as_str()) {
+                        first.awaiting_input = Some(AwaitingInputReason::Question);
+                    } else {
+                        first.pending_tool_since = message.timestamp.clone();
+                    }
+                } else if let Some(text) = assistant_text(&message) {
+                    if text.trim_end().ends_with('?') {
+                        first.awaiting_input = Some(AwaitingInputReason::Question);
+                    }
+                    first.last_assistant_snippet =
+                        Some(session_utils::truncate_snippet(text, SNIPPET_MAX_CHARS));
+                    return Some(first);
+                }
+            }
+            tail = Some(first);
+            continue;
+        };
+
+        if message.role == "assistant" {
+            if let Some(text) = assistant_text(&message) {
+                found.last_assistant_snippet =
+                    Some(session_utils::truncate_snippet(text, SNIPPET_MAX_CHARS));
+                break;
+            }
+        }
+    }
+    tail
+}
+
+/// Read the end of a session file, growing the window until the last
+/// message is found, so large transcripts are never scanned in full.
+pub(crate) fn read_session_tail(
+    path: &Path,
+    parse: fn(&Value) -> Option<TranscriptMessage>,
+) -> SessionTail {
+    let mut window = TAIL_INITIAL_BYTES;
+    loop {
+        let Ok((lines, whole_file)) = session_utils::read_tail_lines(path, window) else {
+            return SessionTail::default();
+        };
+        if let Some(tail) = scan_tail(&lines, parse) {
+            return tail;
+        }
+        if whole_file || window >= TAIL_MAX_BYTES {
+            return SessionTail::default();
+        }
+        window *= 2;
+    }
+}
+
+/// Track Claude permission prompts from hook events. A `Notification` of
+/// type `permission_prompt` opens one; the tool finishing, a new prompt or
+/// the turn ending closes it.
+pub(crate) fn observe_claude_hook(
+    event_name: Option<&str>,
+    session_id: Option<&str>,
+    payload: &Value,
+) {
+    let (Some(event_name), Some(session_id)) = (event_name, session_id) else {
+        return;
+    };
+    let mut prompts = PERMISSION_PROMPTS.lock().unwrap_or_else(|e| e.into_inner());
+    match event_name {
+        "SessionEnd" => {
+            prompts.remove(session_id);
+        }
+        "Notification" => {
+            let kind = payload.get("notification_type").and_then(|v| v.as_str());
+            let message = payload
+                .get("message")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default();
+            if kind == Some("permission_prompt") || message.contains("needs your permission") {
+                prompts.insert(session_id.to_string(), true);
+            }
+        }
+        _ => {
+            prompts.insert(session_id.to_string(), false);
+        }
+    }
+}
+
+/// Decide whether a trailing tool call is waiting on a permission prompt and
+/// set `awaiting_input` if so. Sessions reporting hook events are flagged only
+/// while the bridge has seen an open prompt; others once the call has gone
+/// unanswered for a while.
+pub(crate) fn resolve_pending_permission(
+    session_id: &str,
+    pending_tool_since: Option<&str>,
+    awaiting_input: &mut Option<AwaitingInputReason>,
+    now: DateTime<Utc>,
+) {
+    if awaiting_input.is_some() {
+        return;
+    }
+    let Some(since) = pending_tool_since else {
+        return;
+    };
+    let prompts = PERMISSION_PROMPTS.lock().unwrap_or_else(|e| e.into_inner());
+    let waiting = match prompts.get(session_id) {
+        Some(open) => *open,
+        None => DateTime::parse_from_rfc3339(since).is_ok_and(|t| {
+            (now - t.with_timezone(&Utc)).num_seconds() >= PENDING_TOOL_PERMISSION_SECS
+        }),
+    };
+    if waiting {
+        *awaiting_input = Some(AwaitingInputReason::Permission);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use serde_json::json;
+
+    fn awaiting(
+        id: &str,
+        pending_since: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Option<AwaitingInputReason> {
+        let mut awaiting_input = None;
+        let since = pending_since.to_rfc3339();
+        resolve_pending_permission(id, Some(&since), &mut awaiting_input, now);
+        awaiting_input
+    }
+
+    #[test]
+    fn unhooked_pending_tool_is_a_permission_prompt_once_old() {
+        let now = Utc::now();
+        assert_eq!(
+            awaiting("tail-fresh", now - Duration::seconds(5), now),
+            None
+        );
+        assert_eq!(
+            awaiting("tail-stale", now - Duration::minutes(10), now),
+            Some(AwaitingInputReason::Permission)
+        );
+    }
+
+    #[test]
+    fn hooked_sessions_follow_permission_notifications() {
+        let now = Utc::now();
+        let id = "tail-hooked";
+        observe_claude_hook(Some("PreToolUse"), Some(id), &json!({}));
+
+        // A long-running tool is not a prompt while hooks say nothing.
+        assert_eq!(awaiting(id, now - Duration::minutes(10), now), None);
+
+        let prompt = json!({ "notification_type": "permission_prompt",
+            "message": "Claude needs your permission to use Bash" });
+        observe_claude_hook(Some("Notification"), Some(id), &prompt);
+        assert_eq!(
+            awaiting(id, now, now),
+            Some(AwaitingInputReason::Permission)
+        );
+
+        observe_claude_hook(Some("PostToolUse"), Some(id), &json!({}));
+        assert_eq!(awaiting(id, now, now), None);
+    }
+}
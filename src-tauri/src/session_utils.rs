@@ -1,4 +1,7 @@
 /// Shared helpers for Claude and Codex session JSONL parsing.
+use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
 
 const SESSION_LABEL_MAX_LENGTH: usize = 80;
 const MIN_USER_MESSAGE_LENGTH: usize = 5;
@@ -49,6 +52,41 @@ pub fn extract_text_from_content(content: Option<&serde_json::Value>) -> Option<
     }
 }
 
+/// Collapse whitespace and cap at `max_chars` characters, adding "..." when cut.
+pub fn truncate_snippet(text: &str, max_chars: usize) -> String {
+    let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    match collapsed.char_indices().nth(max_chars.saturating_sub(3)) {
+        Some((idx, _)) if collapsed.chars().count() > max_chars => {
+            format!("{}...", &collapsed[..idx])
+        }
+        _ => collapsed,
+    }
+}
+
+/// Read the complete lines within the last `max_bytes` of a file, oldest
+/// first. Returns the lines and whether the window reached the file start.
+/// A partial first line (cut by the window) is dropped.
+pub fn read_tail_lines(path: &Path, max_bytes: u64) -> std::io::Result<(Vec<String>, bool)> {
+    let mut file = fs::File::open(path)?;
+    let len = file.metadata()?.len();
+    let start = len.saturating_sub(max_bytes);
+    // Include the preceding byte to tell whether the window starts mid-line.
+    let read_from = start.saturating_sub(1);
+    file.seek(SeekFrom::Start(read_from))?;
+    let mut buf = Vec::with_capacity((len - read_from) as usize);
+    file.read_to_end(&mut buf)?;
+    let (starts_mid_line, body) = match buf.split_first() {
+        Some((&first, rest)) if start > 0 => (first != b'\n', rest),
+        _ => (false, buf.as_slice()),
+    };
+    let text = String::from_utf8_lossy(body);
+    let mut lines: Vec<String> = text.lines().map(str::to_string).collect();
+    if starts_mid_line && !lines.is_empty() {
+        lines.remove(0);
+    }
+    Ok((lines, start == 0))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -79,7 +117,9 @@ mod tests {
 
     #[test]
     fn skippable_starts_with_base_directory() {
-        assert!(is_skippable_user_message("Base directory: /home/user/project"));
+        assert!(is_skippable_user_message(
+            "Base directory: /home/user/project"
+        ));
     }
 
     #[test]
@@ -176,4 +216,37 @@ mod tests {
         let val = json!([{"type": "image", "url": "http://example.com/img.png"}]);
         assert_eq!(extract_text_from_content(Some(&val)), None);
     }
+
+    // truncate_snippet tests
+
+    #[test]
+    fn snippet_collapses_whitespace() {
+        assert_eq!(truncate_snippet("a\n\n  b\tc", 20), "a b c");
+    }
+
+    #[test]
+    fn snippet_truncates_on_char_boundary() {
+        assert_eq!(truncate_snippet("héllo wörld", 8), "héllo...");
+        assert_eq!(truncate_snippet("exactly8", 8), "exactly8");
+    }
+
+    // read_tail_lines tests
+
+    #[test]
+    fn tail_lines_drops_partial_first_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("t.jsonl");
+        fs::write(&path, "first line\nsecond\nthird\n").unwrap();
+
+        let (lines, whole) = read_tail_lines(&path, 13).unwrap();
+        assert_eq!(lines, ["second", "third"]);
+        assert!(!whole);
+
+        let (lines, _) = read_tail_lines(&path, 10).unwrap();
+        assert_eq!(lines, ["third"]);
+
+        let (lines, whole) = read_tail_lines(&path, 1024).unwrap();
+        assert_eq!(lines, ["first line", "second", "third"]);
+        assert!(whole);
+    }
 }
@@ -4,7 +4,7 @@ use std::fs;
 use std::path::{Path, PathBuf};
 
 use crate::paths;
-use crate::types::{HookScriptInfo, PluginInfo, SkillInfo};
+use crate::types::{HookScriptInfo, IntegrationRemovalReport};
 
 #[cfg(not(windows))]
 const WORKBENCH_HOOK_SCRIPT_NAME: &str = "workbench-hook-bridge.sh";
@@ -16,12 +16,26 @@ const WORKBENCH_HOOK_EVENTS: &[(&str, Option<&str>)] = &[
     ("Stop", None),
     ("Notification", None),
     ("PostToolUse", Some("Bash")),
+    ("PreToolUse", Some("Task")),
+    ("PostToolUse", Some("Task")),
+    ("SubagentStop", None),
+    ("PreCompact", None),
+    ("SessionEnd", None),
 ];
 
 pub(crate) fn settings_path(scope: &str, project_path: Option<&str>) -> Result<PathBuf> {
+    settings_path_in(&paths::claude_user_dir(), scope, project_path)
+}
+
+/// `settings_path` with the user-level Claude directory given explicitly.
+pub(crate) fn settings_path_in(
+    user_dir: &Path,
+    scope: &str,
+    project_path: Option<&str>,
+) -> Result<PathBuf> {
     match scope {
-        "user" => Ok(paths::claude_user_dir().join("settings.json")),
-        "user-local" => Ok(paths::claude_user_dir().join("settings.local.json")),
+        "user" => Ok(user_dir.join("settings.json")),
+        "user-local" => Ok(user_dir.join("settings.local.json")),
         "project" => {
             let base = project_path
                 .map(PathBuf::from)
@@ -55,94 +69,6 @@ pub fn save_settings(scope: &str, project_path: Option<&str>, value: &Value) ->
     Ok(())
 }
 
-pub fn list_plugins() -> Result<Vec<PluginInfo>> {
-    let cache_dir = paths::claude_user_dir().join("plugins").join("cache");
-    if !cache_dir.exists() {
-        return Ok(Vec::new());
-    }
-
-    let mut plugins = Vec::new();
-    for entry in fs::read_dir(&cache_dir)? {
-        let entry = entry?;
-        let path = entry.path();
-        if !path.is_dir() {
-            continue;
-        }
-        let manifest = path.join("plugin.json");
-        if !manifest.exists() {
-            continue;
-        }
-        let content = fs::read_to_string(&manifest)?;
-        let value: Value = serde_json::from_str(&content)?;
-        let name = value
-            .get("name")
-            .and_then(|v| v.as_str())
-            .unwrap_or_else(|| {
-                path.file_name()
-                    .and_then(|n| n.to_str())
-                    .unwrap_or("unknown")
-            })
-            .to_string();
-        let description = value
-            .get("description")
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_string();
-        let version = value
-            .get("version")
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_string();
-        let dir_name = path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("")
-            .to_string();
-
-        plugins.push(PluginInfo {
-            name,
-            description,
-            version,
-            dir_name,
-        });
-    }
-    Ok(plugins)
-}
-
-pub fn list_skills() -> Result<Vec<SkillInfo>> {
-    let skills_dir = paths::claude_user_dir().join("skills");
-    if !skills_dir.exists() {
-        return Ok(Vec::new());
-    }
-
-    let mut skills = Vec::new();
-    for entry in fs::read_dir(&skills_dir)? {
-        let entry = entry?;
-        let path = entry.path();
-        if !path.is_dir() {
-            continue;
-        }
-        let skill_md = path.join("SKILL.md");
-        if !skill_md.exists() {
-            continue;
-        }
-        let dir_name = path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("")
-            .to_string();
-        let content = fs::read_to_string(&skill_md).unwrap_or_default();
-        let description = content.lines().take(3).collect::<Vec<_>>().join(" ");
-
-        skills.push(SkillInfo {
-            name: dir_name.clone(),
-            dir_name,
-            description,
-        });
-    }
-    Ok(skills)
-}
-
 pub fn list_hooks_scripts() -> Result<Vec<HookScriptInfo>> {
     let hooks_dir = paths::claude_user_dir().join("hooks");
     if !hooks_dir.exists() {
@@ -417,7 +343,7 @@ pub fn check_workbench_hook_integration() -> crate::types::IntegrationStatus {
 
     let needs_changes = !script_exists || !missing_events.is_empty();
     let description = if needs_changes {
-        "Workbench will install a hook script and register it in your Claude Code settings (~/.claude/settings.json) for the following events: SessionStart, UserPromptSubmit, Stop, Notification, and PostToolUse (Bash only). This enables session activity tracking and immediate git/GitHub refresh after git or gh commands.".to_string()
+        "Workbench will install a hook script and register it in your Claude Code settings (~/.claude/settings.json) for the following events: SessionStart, UserPromptSubmit, Stop, Notification, PostToolUse (Bash and Task), PreToolUse (Task only), SubagentStop, PreCompact, and SessionEnd. This enables session activity tracking, subagent and compaction tracking, and immediate git/GitHub refresh after git or gh commands.".to_string()
     } else {
         String::new()
     };
@@ -460,6 +386,106 @@ pub fn ensure_workbench_hook_integration() -> Result<()> {
     Ok(())
 }
 
+fn is_workbench_hook_command(command: &str) -> bool {
+    let normalized = command.to_ascii_lowercase().replace('\\', "/");
+    normalized.contains(&WORKBENCH_HOOK_SCRIPT_NAME.to_ascii_lowercase())
+        || is_legacy_hook_command(command)
+}
+
+/// Remove every hook command that runs a Workbench bridge script, dropping
+/// matcher entries and events only when nothing else is left in them.
+/// Returns the names of the events that lost at least one hook.
+fn remove_workbench_hooks(hooks_obj: &mut serde_json::Map<String, Value>) -> Vec<String> {
+    let mut touched = Vec::new();
+    for (event_name, entries) in hooks_obj.iter_mut() {
+        let Some(arr) = entries.as_array_mut() else {
+            continue;
+        };
+        let mut removed_any = false;
+        arr.retain_mut(|entry| {
+            // Legacy flat entries carry the command at the top level
+            if entry
+                .get("command")
+                .and_then(|v| v.as_str())
+                .is_some_and(is_workbench_hook_command)
+            {
+                removed_any = true;
+                return false;
+            }
+            let Some(hooks) = entry.get_mut("hooks").and_then(|v| v.as_array_mut()) else {
+                return true;
+            };
+            let hook_count = hooks.len();
+            hooks.retain(|hook| {
+                !hook
+                    .get("command")
+                    .and_then(|v| v.as_str())
+                    .is_some_and(is_workbench_hook_command)
+            });
+            if hooks.len() == hook_count {
+                return true;
+            }
+            removed_any = true;
+            !hooks.is_empty()
+        });
+        if removed_any {
+            touched.push(event_name.clone());
+        }
+    }
+    hooks_obj.retain(|event_name, entries| {
+        !(touched.contains(event_name) && entries.as_array().is_some_and(Vec::is_empty))
+    });
+    touched
+}
+
+/// Undo `ensure_workbench_hook_integration`: strip the bridge hooks from
+/// `~/.claude/settings.json` and delete the bridge scripts.
+pub fn remove_workbench_hook_integration() -> Result<IntegrationRemovalReport> {
+    let settings_path = paths::claude_user_dir().join("settings.json");
+    let mut changes = Vec::new();
+
+    if settings_path.exists() {
+        let raw = fs::read_to_string(&settings_path)?;
+        let mut settings: Value = serde_json::from_str(&raw)?;
+        if let Some(root) = settings.as_object_mut() {
+            let touched = root
+                .get_mut("hooks")
+                .and_then(|v| v.as_object_mut())
+                .map(remove_workbench_hooks)
+                .unwrap_or_default();
+            if !touched.is_empty() {
+                if root
+                    .get("hooks")
+                    .and_then(|v| v.as_object())
+                    .is_some_and(|hooks| hooks.is_empty())
+                {
+                    root.remove("hooks");
+                }
+                let content = serde_json::to_string_pretty(&settings)?;
+                paths::atomic_write(&settings_path, &content)?;
+                changes.push(format!(
+                    "Removed Workbench hooks from {} ({})",
+                    settings_path.display(),
+                    touched.join(", ")
+                ));
+            }
+        }
+    }
+
+    let hooks_dir = paths::claude_user_dir().join("hooks");
+    let scripts =
+        std::iter::once(WORKBENCH_HOOK_SCRIPT_NAME).chain(LEGACY_HOOK_SCRIPTS.iter().copied());
+    for name in scripts {
+        let path = hooks_dir.join(name);
+        if path.is_file() {
+            fs::remove_file(&path)?;
+            changes.push(format!("Deleted {}", path.display()));
+        }
+    }
+
+    Ok(IntegrationRemovalReport { changes })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -757,4 +783,55 @@ mod tests {
             Some("/usr/local/bin/other-hook")
         );
     }
+
+    // --- remove_workbench_hooks ---
+
+    #[test]
+    fn remove_workbench_hooks_strips_only_bridge_commands() {
+        let command = hook_command_for_script(&workbench_hook_script_path());
+        let mut hooks_obj = serde_json::Map::new();
+        for (event, matcher) in WORKBENCH_HOOK_EVENTS {
+            ensure_event_hooks(&mut hooks_obj, event, &command, *matcher);
+        }
+        // A user hook sharing the Bash matcher entry, and one on its own
+        hooks_obj["PostToolUse"][0]["hooks"]
+            .as_array_mut()
+            .unwrap()
+            .push(serde_json::json!({ "type": "command", "command": "lint.sh" }));
+        hooks_obj["Stop"]
+            .as_array_mut()
+            .unwrap()
+            .push(serde_json::json!({ "hooks": [{ "type": "command", "command": "say done" }] }));
+
+        let touched = remove_workbench_hooks(&mut hooks_obj);
+
+        assert!(touched.contains(&"SessionEnd".to_string()));
+        let mut events: Vec<_> = hooks_obj.keys().cloned().collect();
+        events.sort();
+        assert_eq!(events, ["PostToolUse", "Stop"]);
+        assert_eq!(
+            hooks_obj["PostToolUse"],
+            serde_json::json!([
+                { "matcher": "Bash", "hooks": [{ "type": "command", "command": "lint.sh" }] }
+            ])
+        );
+        assert_eq!(
+            hooks_obj["Stop"],
+            serde_json::json!([{ "hooks": [{ "type": "command", "command": "say done" }] }])
+        );
+        assert!(remove_workbench_hooks(&mut hooks_obj).is_empty());
+    }
+
+    #[test]
+    fn remove_workbench_hooks_leaves_unrelated_empty_events() {
+        let mut hooks_obj = serde_json::Map::new();
+        hooks_obj.insert("Stop".to_string(), serde_json::json!([]));
+        hooks_obj.insert(
+            "SessionStart".to_string(),
+            serde_json::json!([{ "command": "~/.claude/hooks/workbench-hook-bridge.py" }]),
+        );
+
+        assert_eq!(remove_workbench_hooks(&mut hooks_obj), ["SessionStart"]);
+        assert_eq!(hooks_obj.keys().collect::<Vec<_>>(), ["Stop"]);
+    }
 }
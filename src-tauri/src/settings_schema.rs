@@ -0,0 +1,306 @@
+/// Validation of Claude `settings.json` against the bundled schema.
+///
+/// Only the JSON Schema keywords the bundled schema uses are supported:
+/// `$ref` (local definitions), `type`, `enum`, `properties`,
+/// `additionalProperties`, `required`, `items`, `anyOf`, `minimum` and
+/// `maximum`. Keys an object schema does not declare are reported as
+/// warnings rather than errors, since Claude Code adds settings faster than
+/// the schema can track them.
+use serde_json::Value;
+use std::sync::OnceLock;
+
+use crate::effective_settings::child_pointer;
+use crate::types::{SettingsIssueSeverity, SettingsValidationIssue};
+
+static SCHEMA: OnceLock<Value> = OnceLock::new();
+
+fn schema() -> &'static Value {
+    SCHEMA.get_or_init(|| {
+        serde_json::from_str(include_str!("../schemas/claude-settings.schema.json"))
+            .expect("bundled settings schema is valid JSON")
+    })
+}
+
+/// Every problem found in `value`, errors and warnings alike.
+pub fn validate_settings(value: &Value) -> Vec<SettingsValidationIssue> {
+    let mut issues = Vec::new();
+    validate(schema(), schema(), value, "", &mut issues);
+    // Objects keep their file order, so sort for a stable report.
+    issues.sort_by(|a, b| a.pointer.cmp(&b.pointer));
+    issues
+}
+
+pub fn has_errors(issues: &[SettingsValidationIssue]) -> bool {
+    issues
+        .iter()
+        .any(|i| i.severity == SettingsIssueSeverity::Error)
+}
+
+fn issue(
+    issues: &mut Vec<SettingsValidationIssue>,
+    severity: SettingsIssueSeverity,
+    pointer: &str,
+    message: String,
+) {
+    issues.push(SettingsValidationIssue {
+        pointer: pointer.to_string(),
+        severity,
+        message,
+    });
+}
+
+fn resolve<'a>(root: &'a Value, schema: &'a Value) -> &'a Value {
+    match schema
+        .get("$ref")
+        .and_then(Value::as_str)
+        .and_then(|r| r.strip_prefix('#'))
+    {
+        Some(pointer) => root.pointer(pointer).unwrap_or(&Value::Bool(true)),
+        None => schema,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn matches_type(expected: &str, value: &Value) -> bool {
+    let actual = type_name(value);
+    actual == expected || (expected == "number" && actual == "integer")
+}
+
+fn validate(
+    root: &Value,
+    schema: &Value,
+    value: &Value,
+    pointer: &str,
+    issues: &mut Vec<SettingsValidationIssue>,
+) {
+    let schema = resolve(root, schema);
+
+    if let Some(branches) = schema.get("anyOf").and_then(Value::as_array) {
+        let mut best: Option<Vec<SettingsValidationIssue>> = None;
+        for branch in branches {
+            let mut branch_issues = Vec::new();
+            validate(root, branch, value, pointer, &mut branch_issues);
+            if !has_errors(&branch_issues) {
+                issues.extend(branch_issues);
+                return;
+            }
+            if best.as_ref().is_none_or(|b| branch_issues.len() < b.len()) {
+                best = Some(branch_issues);
+            }
+        }
+        issues.extend(best.unwrap_or_default());
+        return;
+    }
+
+    if let Some(expected) = schema.get("type") {
+        let allowed: Vec<&str> = match expected {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !allowed.is_empty() && !allowed.iter().any(|t| matches_type(t, value)) {
+            issue(
+                issues,
+                SettingsIssueSeverity::Error,
+                pointer,
+                format!(
+                    "Expected {}, found {}",
+                    allowed.join(" or "),
+                    type_name(value)
+                ),
+            );
+            return;
+        }
+    }
+
+    if let Some(options) = schema.get("enum").and_then(Value::as_array) {
+        if !options.contains(value) {
+            let listed: Vec<String> = options.iter().map(Value::to_string).collect();
+            issue(
+                issues,
+                SettingsIssueSeverity::Error,
+                pointer,
+                format!("Must be one of {}, found {value}", listed.join(", ")),
+            );
+            return;
+        }
+    }
+
+    if let Some(n) = value.as_f64() {
+        if let Some(min) = schema.get("minimum").and_then(Value::as_f64) {
+            if n < min {
+                issue(
+                    issues,
+                    SettingsIssueSeverity::Error,
+                    pointer,
+                    format!("Must be at least {min}, found {n}"),
+                );
+            }
+        }
+        if let Some(max) = schema.get("maximum").and_then(Value::as_f64) {
+            if n > max {
+                issue(
+                    issues,
+                    SettingsIssueSeverity::Error,
+                    pointer,
+                    format!("Must be at most {max}, found {n}"),
+                );
+            }
+        }
+    }
+
+    if let Value::Object(map) = value {
+        if let Some(required) = schema.get("required").and_then(Value::as_array) {
+            for key in required.iter().filter_map(Value::as_str) {
+                if !map.contains_key(key) {
+                    issue(
+                        issues,
+                        SettingsIssueSeverity::Error,
+                        pointer,
+                        format!("Missing required key \"{key}\""),
+                    );
+                }
+            }
+        }
+        let properties = schema.get("properties").and_then(Value::as_object);
+        let additional = schema.get("additionalProperties");
+        for (key, child) in map {
+            let child_ptr = child_pointer(pointer, key);
+            if let Some(child_schema) = properties.and_then(|p| p.get(key)) {
+                validate(root, child_schema, child, &child_ptr, issues);
+            } else if let Some(extra @ Value::Object(_)) = additional {
+                validate(root, extra, child, &child_ptr, issues);
+            } else if additional == Some(&Value::Bool(false)) {
+                issue(
+                    issues,
+                    SettingsIssueSeverity::Error,
+                    &child_ptr,
+                    format!("Key \"{key}\" is not allowed here"),
+                );
+            } else if properties.is_some() {
+                issue(
+                    issues,
+                    SettingsIssueSeverity::Warning,
+                    &child_ptr,
+                    format!("Unknown key \"{key}\""),
+                );
+            }
+        }
+    }
+
+    if let (Value::Array(items), Some(item_schema)) = (value, schema.get("items")) {
+        for (i, item) in items.iter().enumerate() {
+            validate(
+                root,
+                item_schema,
+                item,
+                &child_pointer(pointer, &i.to_string()),
+                issues,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn pointers(issues: &[SettingsValidationIssue], severity: SettingsIssueSeverity) -> Vec<&str> {
+        issues
+            .iter()
+            .filter(|i| i.severity == severity)
+            .map(|i| i.pointer.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn accepts_typical_settings() {
+        let issues = validate_settings(&json!({
+            "model": "opus",
+            "env": {"FOO": "bar"},
+            "permissions": {"allow": ["Bash(git:*)"], "defaultMode": "acceptEdits"},
+            "enabledPlugins": {"formatter@local": true},
+            "hooks": {
+                "Stop": [{"hooks": [{"type": "command", "command": "notify.sh", "timeout": 5}]}],
+                "PostToolUse": [{"matcher": "Bash", "hooks": [{"type": "command", "command": "x"}]}]
+            }
+        }));
+        assert!(issues.is_empty(), "{issues:?}");
+    }
+
+    #[test]
+    fn reports_type_and_enum_errors_with_pointers() {
+        let issues = validate_settings(&json!({
+            "permissions": {"allow": "Bash", "defaultMode": "yolo"},
+            "env": {"PORT": 8080},
+            "hooks": {"Stop": [{"hooks": [{"command": "x"}]}]}
+        }));
+        let errors = pointers(&issues, SettingsIssueSeverity::Error);
+        assert_eq!(
+            errors,
+            [
+                "/env/PORT",
+                "/hooks/Stop/0/hooks/0",
+                "/permissions/allow",
+                "/permissions/defaultMode"
+            ]
+        );
+        assert!(has_errors(&issues));
+    }
+
+    #[test]
+    fn unknown_keys_are_warnings() {
+        let issues = validate_settings(&json!({
+            "someFutureSetting": true,
+            "permissions": {"alow": []},
+            "hooks": {"PreToolUs": []}
+        }));
+        assert!(!has_errors(&issues));
+        assert_eq!(
+            pointers(&issues, SettingsIssueSeverity::Warning),
+            [
+                "/hooks/PreToolUs",
+                "/permissions/alow",
+                "/someFutureSetting"
+            ]
+        );
+    }
+
+    #[test]
+    fn any_of_accepts_either_form() {
+        assert!(validate_settings(&json!({"enabledPlugins": ["a", "b"]})).is_empty());
+        let issues = validate_settings(&json!({"enabledPlugins": {"a": "yes"}}));
+        assert_eq!(
+            pointers(&issues, SettingsIssueSeverity::Error),
+            ["/enabledPlugins/a"]
+        );
+    }
+
+    #[test]
+    fn range_and_required_checks() {
+        let issues = validate_settings(&json!({
+            "cleanupPeriodDays": -1,
+            "statusLine": {"type": "command"}
+        }));
+        let messages: Vec<&str> = issues.iter().map(|i| i.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            [
+                "Must be at least 0, found -1",
+                "Missing required key \"command\""
+            ]
+        );
+    }
+}
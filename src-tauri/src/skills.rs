@@ -0,0 +1,380 @@
+/// Claude skills: `<skills dir>/<name>/SKILL.md` with YAML frontmatter
+/// (`name`, `description`, `allowed-tools`), at user scope (`~/.claude/skills`)
+/// or project scope (`<project>/.claude/skills`).
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::codex_config;
+use crate::frontmatter::{self, Frontmatter};
+use crate::paths;
+use crate::permission_rules;
+use crate::types::{SkillDocument, SkillInfo, SkillScope};
+
+const SKILL_FILE: &str = "SKILL.md";
+const MAX_NAME_LEN: usize = 64;
+const MAX_DESCRIPTION_LEN: usize = 1024;
+const RESERVED_NAME_WORDS: &[&str] = &["anthropic", "claude"];
+
+pub(crate) fn skills_dir(scope: SkillScope, project_path: Option<&str>) -> Result<PathBuf> {
+    match scope {
+        SkillScope::User => Ok(paths::claude_user_dir().join("skills")),
+        SkillScope::Project => {
+            let project = project_path.context("Project skills need a project path")?;
+            Ok(Path::new(project).join(".claude").join("skills"))
+        }
+    }
+}
+
+/// Problems Claude would reject or that make the skill undiscoverable.
+pub fn validate_skill(skill: &SkillDocument) -> Vec<String> {
+    let mut issues = Vec::new();
+    let name = skill.name.trim();
+    if name.is_empty() {
+        issues.push("Name is required".to_string());
+    } else {
+        if name.len() > MAX_NAME_LEN {
+            issues.push(format!("Name must be at most {MAX_NAME_LEN} characters"));
+        }
+        if !name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        {
+            issues.push("Name may only contain lowercase letters, digits and hyphens".to_string());
+        }
+        if let Some(word) = RESERVED_NAME_WORDS.iter().find(|w| name.contains(**w)) {
+            issues.push(format!(
+                "Name must not contain the reserved word \"{word}\""
+            ));
+        }
+    }
+    let description = skill.description.trim();
+    if description.is_empty() {
+        issues.push(
+            "Description is required; Claude uses it to decide when to load the skill".to_string(),
+        );
+    } else if description.chars().count() > MAX_DESCRIPTION_LEN {
+        issues.push(format!(
+            "Description must be at most {MAX_DESCRIPTION_LEN} characters"
+        ));
+    }
+    for tool in &skill.allowed_tools {
+        if let Err(e) = permission_rules::parse_rule(tool) {
+            issues.push(format!("Allowed tool \"{tool}\": {e}"));
+        }
+    }
+    issues
+}
+
+/// A directory name must stay inside the skills directory.
+fn check_dir_name(dir_name: &str) -> Result<()> {
+    if dir_name.is_empty() || dir_name == "." || dir_name == ".." || dir_name.contains(['/', '\\'])
+    {
+        bail!("Invalid skill directory name \"{dir_name}\"");
+    }
+    Ok(())
+}
+
+fn read_document(skill_md: &Path) -> Result<(Frontmatter, String)> {
+    let content = fs::read_to_string(skill_md)
+        .with_context(|| format!("Failed to read {}", skill_md.display()))?;
+    frontmatter::parse_document(&content)
+}
+
+fn document_from(frontmatter: &Frontmatter, body: String) -> SkillDocument {
+    SkillDocument {
+        name: frontmatter.get_str("name").unwrap_or_default().to_string(),
+        description: frontmatter
+            .get_str("description")
+            .unwrap_or_default()
+            .to_string(),
+        allowed_tools: frontmatter.get_list("allowed-tools"),
+        body,
+    }
+}
+
+fn list_in(dir: &Path, scope: SkillScope) -> Result<Vec<SkillInfo>> {
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut skills = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let skill_md = path.join(SKILL_FILE);
+        if !path.is_dir() || !skill_md.is_file() {
+            continue;
+        }
+        let dir_name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("")
+            .to_string();
+        let (skill, issues) = match read_document(&skill_md) {
+            Ok((frontmatter, body)) => {
+                let skill = document_from(&frontmatter, body);
+                let issues = validate_skill(&skill);
+                (skill, issues)
+            }
+            Err(e) => (SkillDocument::default(), vec![format!("{e:#}")]),
+        };
+        skills.push(SkillInfo {
+            name: if skill.name.is_empty() {
+                dir_name.clone()
+            } else {
+                skill.name
+            },
+            dir_name,
+            description: skill.description,
+            scope,
+            allowed_tools: skill.allowed_tools,
+            path: skill_md.to_string_lossy().to_string(),
+            issues,
+        });
+    }
+    skills.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(skills)
+}
+
+/// User skills, then project skills when a project is given.
+pub fn list_skills(project_path: Option<&str>) -> Result<Vec<SkillInfo>> {
+    let mut skills = list_in(&skills_dir(SkillScope::User, None)?, SkillScope::User)?;
+    if project_path.is_some() {
+        skills.extend(list_in(
+            &skills_dir(SkillScope::Project, project_path)?,
+            SkillScope::Project,
+        )?);
+    }
+    Ok(skills)
+}
+
+pub fn get_skill(
+    scope: SkillScope,
+    project_path: Option<&str>,
+    dir_name: &str,
+) -> Result<SkillDocument> {
+    check_dir_name(dir_name)?;
+    let skill_md = skills_dir(scope, project_path)?
+        .join(dir_name)
+        .join(SKILL_FILE);
+    let (frontmatter, body) = read_document(&skill_md)?;
+    Ok(document_from(&frontmatter, body))
+}
+
+fn write_skill(dir: &Path, dir_name: Option<&str>, skill: &SkillDocument) -> Result<()> {
+    let issues = validate_skill(skill);
+    if !issues.is_empty() {
+        bail!("{}", issues.join("; "));
+    }
+    let skill_dir = match dir_name {
+        Some(dir_name) => {
+            check_dir_name(dir_name)?;
+            dir.join(dir_name)
+        }
+        None => {
+            let skill_dir = dir.join(skill.name.trim());
+            if skill_dir.exists() {
+                bail!("A skill named \"{}\" already exists", skill.name.trim());
+            }
+            skill_dir
+        }
+    };
+    let skill_md = skill_dir.join(SKILL_FILE);
+
+    // Keep any frontmatter keys Workbench does not manage.
+    let mut frontmatter = if skill_md.is_file() {
+        read_document(&skill_md)?.0
+    } else {
+        Frontmatter::default()
+    };
+    frontmatter.set_str("name", skill.name.trim());
+    frontmatter.set_str("description", skill.description.trim());
+    frontmatter.set_list("allowed-tools", &skill.allowed_tools);
+    paths::atomic_write(
+        &skill_md,
+        &frontmatter::render_document(&frontmatter, &skill.body),
+    )
+}
+
+/// Keep Codex's `~/.agents/skills` links current after a user skill changes.
+fn after_change(scope: SkillScope) {
+    if scope != SkillScope::User {
+        return;
+    }
+    if let Err(e) = codex_config::resync_claude_skills() {
+        eprintln!("[Skills] Failed to resync Codex skills: {e}");
+    }
+}
+
+/// Create a skill (`dir_name` = None, directory named after the skill) or
+/// rewrite the SKILL.md of an existing one.
+pub fn save_skill(
+    scope: SkillScope,
+    project_path: Option<&str>,
+    dir_name: Option<&str>,
+    skill: &SkillDocument,
+) -> Result<()> {
+    write_skill(&skills_dir(scope, project_path)?, dir_name, skill)?;
+    after_change(scope);
+    Ok(())
+}
+
+/// Remove a skill directory. Returns false if it did not exist.
+pub fn delete_skill(scope: SkillScope, project_path: Option<&str>, dir_name: &str) -> Result<bool> {
+    check_dir_name(dir_name)?;
+    let skill_dir = skills_dir(scope, project_path)?.join(dir_name);
+    if fs::symlink_metadata(&skill_dir).is_err() {
+        return Ok(false);
+    }
+    paths::remove_path_if_exists(&skill_dir)?;
+    after_change(scope);
+    Ok(true)
+}
+
+fn copy_between(from_dir: &Path, to_dir: &Path, dir_name: &str, overwrite: bool) -> Result<()> {
+    check_dir_name(dir_name)?;
+    let source = from_dir.join(dir_name);
+    if !source.join(SKILL_FILE).is_file() {
+        bail!("Skill \"{dir_name}\" not found in {}", from_dir.display());
+    }
+    let dest = to_dir.join(dir_name);
+    if fs::symlink_metadata(&dest).is_ok() {
+        if !overwrite {
+            bail!(
+                "Skill \"{dir_name}\" already exists in {}",
+                to_dir.display()
+            );
+        }
+        paths::remove_path_if_exists(&dest)?;
+    }
+    // Resolve a linked skill so the copy holds real files.
+    let source = fs::canonicalize(&source)?;
+    paths::copy_dir_skip_symlinks(&source, &dest)
+}
+
+/// Copy a skill directory from one scope to the other.
+pub fn copy_skill(
+    project_path: Option<&str>,
+    dir_name: &str,
+    from: SkillScope,
+    to: SkillScope,
+    overwrite: bool,
+) -> Result<()> {
+    if from == to {
+        bail!("Source and destination scopes are the same");
+    }
+    copy_between(
+        &skills_dir(from, project_path)?,
+        &skills_dir(to, project_path)?,
+        dir_name,
+        overwrite,
+    )?;
+    after_change(to);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn skill(name: &str) -> SkillDocument {
+        SkillDocument {
+            name: name.to_string(),
+            description: "Extract text from PDFs. Use when the user mentions PDFs.".to_string(),
+            allowed_tools: vec!["Read".to_string(), "Bash(pdftotext:*)".to_string()],
+            body: "# PDF\n\nRun pdftotext.\n".to_string(),
+        }
+    }
+
+    #[test]
+    fn validates_frontmatter() {
+        assert!(validate_skill(&skill("pdf-tools")).is_empty());
+
+        let mut bad = skill("PDF Tools");
+        bad.description = " ".to_string();
+        bad.allowed_tools.push("Bash(".to_string());
+        let issues = validate_skill(&bad);
+        assert_eq!(issues.len(), 3, "{issues:?}");
+
+        let issues = validate_skill(&skill("claude-helper"));
+        assert!(issues[0].contains("reserved word"));
+        assert!(!validate_skill(&skill(&"a".repeat(65))).is_empty());
+    }
+
+    #[test]
+    fn create_list_edit_and_delete() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+
+        write_skill(dir, None, &skill("pdf-tools")).unwrap();
+        assert!(write_skill(dir, None, &skill("pdf-tools"))
+            .unwrap_err()
+            .to_string()
+            .contains("already exists"));
+
+        let listed = list_in(dir, SkillScope::User).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].allowed_tools, ["Read", "Bash(pdftotext:*)"]);
+        assert!(listed[0].issues.is_empty());
+
+        // Unmanaged keys survive an edit.
+        let skill_md = dir.join("pdf-tools/SKILL.md");
+        let content = fs::read_to_string(&skill_md).unwrap();
+        fs::write(
+            &skill_md,
+            content.replacen("---\n", "---\nlicense: MIT\n", 1),
+        )
+        .unwrap();
+        let mut edited = skill("pdf-tools");
+        edited.description = "Updated".to_string();
+        edited.allowed_tools.clear();
+        write_skill(dir, Some("pdf-tools"), &edited).unwrap();
+
+        let content = fs::read_to_string(&skill_md).unwrap();
+        assert_eq!(
+            content,
+            "---\nlicense: MIT\nname: pdf-tools\ndescription: Updated\n---\n# PDF\n\nRun pdftotext.\n"
+        );
+
+        assert!(write_skill(dir, Some("../escape"), &edited).is_err());
+    }
+
+    #[test]
+    fn lists_invalid_skills_with_issues() {
+        let tmp = tempfile::tempdir().unwrap();
+        let skill_dir = tmp.path().join("legacy");
+        fs::create_dir_all(&skill_dir).unwrap();
+        fs::write(
+            skill_dir.join(SKILL_FILE),
+            "# Legacy\nNo frontmatter here.\n",
+        )
+        .unwrap();
+        fs::create_dir_all(tmp.path().join("not-a-skill")).unwrap();
+
+        let listed = list_in(tmp.path(), SkillScope::Project).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].name, "legacy");
+        assert_eq!(listed[0].scope, SkillScope::Project);
+        assert!(listed[0]
+            .issues
+            .iter()
+            .any(|i| i.starts_with("Name is required")));
+    }
+
+    #[test]
+    fn copies_between_scopes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let user = tmp.path().join("user");
+        let project = tmp.path().join("project");
+        write_skill(&user, None, &skill("pdf-tools")).unwrap();
+        fs::write(user.join("pdf-tools/reference.md"), "extra").unwrap();
+
+        copy_between(&user, &project, "pdf-tools", false).unwrap();
+        assert_eq!(
+            fs::read_to_string(project.join("pdf-tools/reference.md")).unwrap(),
+            "extra"
+        );
+        assert!(copy_between(&user, &project, "pdf-tools", false).is_err());
+        copy_between(&user, &project, "pdf-tools", true).unwrap();
+        assert!(copy_between(&user, &project, "missing", false).is_err());
+    }
+}
@@ -0,0 +1,395 @@
+/// Files written by an agent session, derived from its transcript's edit
+/// tool calls and intersected with the project's current `git status`.
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::git;
+use crate::types::{
+    GitFileStatus, SessionTouchedFiles, TouchedFile, TranscriptBlock, TranscriptMessage,
+};
+
+/// Claude tools that write a single file named by `file_path` / `notebook_path`.
+const CLAUDE_EDIT_TOOLS: &[&str] = &["Write", "Edit", "MultiEdit", "NotebookEdit"];
+const APPLY_PATCH_TOOL: &str = "apply_patch";
+const PATCH_MARKER: &str = "*** Begin Patch";
+/// Codex tools that run shell commands, which may embed an apply_patch call.
+const SHELL_TOOLS: &[&str] = &["shell", "exec_command", "local_shell"];
+
+#[derive(Debug, PartialEq)]
+struct Touch {
+    path: String,
+    tool: String,
+    timestamp: Option<String>,
+    /// Directory the agent was in when it made the call.
+    cwd: Option<String>,
+}
+
+/// Paths named by an apply_patch envelope (`*** Add File: ...` etc.).
+fn patch_paths(patch: &str) -> Vec<String> {
+    const HEADERS: &[&str] = &[
+        "*** Add File: ",
+        "*** Update File: ",
+        "*** Delete File: ",
+        "*** Move to: ",
+    ];
+    patch
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim_start();
+            HEADERS
+                .iter()
+                .find_map(|h| line.strip_prefix(h))
+                .map(|p| p.trim().to_string())
+        })
+        .filter(|p| !p.is_empty())
+        .collect()
+}
+
+/// Shell command text, whether given as an argv array or a single string.
+fn command_text(input: &Value) -> Option<String> {
+    match input.get("command").or_else(|| input.get("cmd"))? {
+        Value::String(s) => Some(s.clone()),
+        Value::Array(args) => Some(
+            args.iter()
+                .filter_map(|a| a.as_str())
+                .collect::<Vec<_>>()
+                .join("\n"),
+        ),
+        _ => None,
+    }
+}
+
+/// The apply_patch envelope carried by a tool call, either as the
+/// apply_patch tool's own input or embedded in a shell command.
+pub(crate) fn apply_patch_text(name: &str, input: &Value) -> Option<String> {
+    if name == APPLY_PATCH_TOOL {
+        // Freeform tool input is the raw patch; the function-call form wraps it.
+        return match input {
+            Value::String(s) => Some(s.clone()),
+            _ => input
+                .get("input")
+                .or_else(|| input.get("patch"))
+                .and_then(|v| v.as_str())
+                .map(str::to_string),
+        };
+    }
+    if SHELL_TOOLS.contains(&name) {
+        let command = command_text(input)?;
+        return command
+            .find(PATCH_MARKER)
+            .map(|at| command[at..].to_string());
+    }
+    None
+}
+
+fn paths_for_tool(name: &str, input: &Value) -> Vec<String> {
+    if CLAUDE_EDIT_TOOLS.contains(&name) {
+        return input
+            .get("file_path")
+            .or_else(|| input.get("notebook_path"))
+            .and_then(|v| v.as_str())
+            .map(|p| vec![p.to_string()])
+            .unwrap_or_default();
+    }
+    apply_patch_text(name, input)
+        .map(|patch| patch_paths(&patch))
+        .unwrap_or_default()
+}
+
+/// Every file write in a transcript, skipping tool calls whose result was an error.
+fn touches_from_messages(messages: &[TranscriptMessage]) -> Vec<Touch> {
+    let failed: HashSet<&str> = messages
+        .iter()
+        .flat_map(|m| &m.blocks)
+        .filter_map(|block| match block {
+            TranscriptBlock::ToolResult {
+                tool_use_id,
+                is_error: true,
+                ..
+            } => Some(tool_use_id.as_str()),
+            _ => None,
+        })
+        .collect();
+
+    let mut touches = Vec::new();
+    for message in messages {
+        for block in &message.blocks {
+            let TranscriptBlock::ToolUse { id, name, input } = block else {
+                continue;
+            };
+            if failed.contains(id.as_str()) {
+                continue;
+            }
+            for path in paths_for_tool(name, input) {
+                touches.push(Touch {
+                    path,
+                    tool: name.clone(),
+                    timestamp: message.timestamp.clone(),
+                    cwd: message.cwd.clone(),
+                });
+            }
+        }
+    }
+    touches
+}
+
+/// Resolve `path` against the session cwd and express it relative to the
+/// repository root when it lies inside it.
+fn normalize_path(path: &str, cwd: &Path, repo_root: Option<&Path>) -> String {
+    let absolute = if Path::new(path).is_absolute() {
+        PathBuf::from(path)
+    } else {
+        cwd.join(path)
+    };
+    let Some(root) = repo_root else {
+        return absolute.to_string_lossy().to_string();
+    };
+    if let Ok(relative) = absolute.strip_prefix(root) {
+        return relative.to_string_lossy().to_string();
+    }
+    // The repo root from git is canonical; the transcript path may not be.
+    let canonical = absolute
+        .parent()
+        .and_then(|parent| std::fs::canonicalize(parent).ok())
+        .map(|parent| parent.join(absolute.file_name().unwrap_or_default()));
+    match canonical.as_deref().and_then(|c| c.strip_prefix(root).ok()) {
+        Some(relative) => relative.to_string_lossy().to_string(),
+        None => absolute.to_string_lossy().to_string(),
+    }
+}
+
+/// Path a porcelain status entry currently refers to (the target of a rename).
+fn status_path(status: &GitFileStatus) -> &str {
+    status
+        .path
+        .rsplit_once(" -> ")
+        .map_or(status.path.as_str(), |(_, to)| to)
+}
+
+/// `default_cwd` applies to touches whose transcript entry recorded no cwd.
+fn aggregate(
+    touches: Vec<Touch>,
+    default_cwd: &Path,
+    repo_root: Option<&Path>,
+    statuses: &[GitFileStatus],
+) -> Vec<TouchedFile> {
+    let status_by_path: HashMap<&str, &GitFileStatus> =
+        statuses.iter().map(|s| (status_path(s), s)).collect();
+
+    let mut files: Vec<TouchedFile> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for touch in touches {
+        let cwd = touch.cwd.as_deref().map_or(default_cwd, Path::new);
+        let path = normalize_path(&touch.path, cwd, repo_root);
+        let idx = *index.entry(path.clone()).or_insert_with(|| {
+            files.push(TouchedFile {
+                git_status: status_by_path.get(path.as_str()).map(|s| (*s).clone()),
+                path,
+                tools: Vec::new(),
+                edit_count: 0,
+                first_touched_at: None,
+                last_touched_at: None,
+            });
+            files.len() - 1
+        });
+        let file = &mut files[idx];
+        if !file.tools.contains(&touch.tool) {
+            file.tools.push(touch.tool);
+        }
+        file.edit_count += 1;
+        if file.first_touched_at.is_none() {
+            file.first_touched_at = touch.timestamp.clone();
+        }
+        if touch.timestamp.is_some() {
+            file.last_touched_at = touch.timestamp;
+        }
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    files
+}
+
+/// List the files a session wrote. `project_path` is the project or worktree
+/// root whose `git status` the files are matched against; relative paths
+/// resolve against the cwd recorded in the transcript, falling back to it.
+pub fn session_touched_files(
+    messages: &[TranscriptMessage],
+    session_id: &str,
+    project_path: &str,
+) -> SessionTouchedFiles {
+    // Not being a git repository is fine; the files just carry no status.
+    let repo_root = git::git_info(project_path)
+        .ok()
+        .map(|info| PathBuf::from(info.repo_root));
+    let statuses = git::git_status(project_path)
+        .map(|status| status.files)
+        .unwrap_or_default();
+
+    let files = aggregate(
+        touches_from_messages(messages),
+        Path::new(project_path),
+        repo_root.as_deref(),
+        &statuses,
+    );
+    let changed_paths = files
+        .iter()
+        .filter(|f| f.git_status.is_some())
+        .map(|f| f.path.clone())
+        .collect();
+
+    SessionTouchedFiles {
+        session_id: session_id.to_string(),
+        repo_root: repo_root.map(|r| r.to_string_lossy().to_string()),
+        files,
+        changed_paths,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn message(timestamp: &str, blocks: Vec<TranscriptBlock>) -> TranscriptMessage {
+        TranscriptMessage {
+            uuid: None,
+            parent_uuid: None,
+            timestamp: Some(timestamp.to_string()),
+            role: "assistant".to_string(),
+            is_meta: false,
+            is_sidechain: false,
+            subtype: None,
+            model: None,
+            usage: None,
+            cwd: None,
+            blocks,
+        }
+    }
+
+    fn tool_use(id: &str, name: &str, input: Value) -> TranscriptBlock {
+        TranscriptBlock::ToolUse {
+            id: id.to_string(),
+            name: name.to_string(),
+            input,
+        }
+    }
+
+    fn status(path: &str) -> GitFileStatus {
+        GitFileStatus {
+            path: path.to_string(),
+            status: "modified".to_string(),
+            staged: false,
+            unstaged: true,
+        }
+    }
+
+    #[test]
+    fn patch_paths_reads_envelope_headers() {
+        let patch = "*** Begin Patch\n*** Update File: src/a.rs\n@@\n-x\n+y\n*** Add File: src/b.rs\n+new\n*** Delete File: old.txt\n*** Update File: src/c.rs\n*** Move to: src/d.rs\n*** End Patch";
+        assert_eq!(
+            patch_paths(patch),
+            ["src/a.rs", "src/b.rs", "old.txt", "src/c.rs", "src/d.rs"]
+        );
+    }
+
+    #[test]
+    fn collects_claude_edit_tools_and_skips_failures() {
+        let messages = vec![
+            message(
+                "t1",
+                vec![
+                    tool_use("1", "Edit", json!({ "file_path": "/repo/src/a.rs" })),
+                    tool_use("2", "Write", json!({ "file_path": "/repo/src/b.rs" })),
+                    tool_use("3", "Read", json!({ "file_path": "/repo/src/c.rs" })),
+                    tool_use(
+                        "4",
+                        "NotebookEdit",
+                        json!({ "notebook_path": "/repo/nb.ipynb" }),
+                    ),
+                ],
+            ),
+            message(
+                "t2",
+                vec![TranscriptBlock::ToolResult {
+                    tool_use_id: "2".to_string(),
+                    content: "File has not been read yet".to_string(),
+                    is_error: true,
+                }],
+            ),
+        ];
+        let paths: Vec<String> = touches_from_messages(&messages)
+            .into_iter()
+            .map(|t| t.path)
+            .collect();
+        assert_eq!(paths, ["/repo/src/a.rs", "/repo/nb.ipynb"]);
+    }
+
+    #[test]
+    fn collects_codex_apply_patch_forms() {
+        let patch = "*** Begin Patch\n*** Update File: src/lib.rs\n*** End Patch";
+        let messages = vec![message(
+            "t1",
+            vec![
+                tool_use("1", "apply_patch", Value::String(patch.to_string())),
+                tool_use("2", "apply_patch", json!({ "input": patch })),
+                tool_use("3", "shell", json!({ "command": ["apply_patch", patch] })),
+                tool_use("4", "shell", json!({ "command": ["ls", "-la"] })),
+            ],
+        )];
+        let touches = touches_from_messages(&messages);
+        assert_eq!(touches.len(), 3);
+        assert!(touches.iter().all(|t| t.path == "src/lib.rs"));
+    }
+
+    #[test]
+    fn aggregates_counts_timestamps_and_git_status() {
+        let touches = vec![
+            Touch {
+                path: "/repo/src/a.rs".into(),
+                tool: "Edit".into(),
+                timestamp: Some("t1".into()),
+                cwd: None,
+            },
+            // Resolved against the cwd the agent was in, not the project root.
+            Touch {
+                path: "a.rs".into(),
+                tool: "apply_patch".into(),
+                timestamp: Some("t3".into()),
+                cwd: Some("/repo/src".into()),
+            },
+            Touch {
+                path: "/repo/README.md".into(),
+                tool: "Write".into(),
+                timestamp: Some("t2".into()),
+                cwd: None,
+            },
+            Touch {
+                path: "/elsewhere/notes.md".into(),
+                tool: "Write".into(),
+                timestamp: None,
+                cwd: None,
+            },
+        ];
+        let statuses = vec![status("src/a.rs"), status("old.rs -> README.md")];
+        let files = aggregate(
+            touches,
+            Path::new("/repo"),
+            Some(Path::new("/repo")),
+            &statuses,
+        );
+
+        let paths: Vec<&str> = files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, ["/elsewhere/notes.md", "README.md", "src/a.rs"]);
+
+        let a = &files[2];
+        assert_eq!(a.edit_count, 2);
+        assert_eq!(a.tools, ["Edit", "apply_patch"]);
+        assert_eq!(a.first_touched_at.as_deref(), Some("t1"));
+        assert_eq!(a.last_touched_at.as_deref(), Some("t3"));
+        assert!(a.git_status.is_some());
+
+        assert!(files[1].git_status.is_some(), "rename target matches");
+        assert!(files[0].git_status.is_none());
+    }
+}
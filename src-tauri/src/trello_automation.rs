@@ -40,9 +40,40 @@ where
     Ok(Some(card_id))
 }
 
-fn resolve_merge_action(config: &TrelloProjectConfig, branch: &str) -> Option<(String, MergeAction)> {
-    let link = config.task_links.iter().find(|task| task.branch == branch)?;
-    let board = config.boards.iter().find(|board| board.board_id == link.board_id)?;
+/// Move the card linked to `branch` to `column_id`. Returns the moved card ID,
+/// or `None` when Trello is not connected or the branch has no linked card.
+pub fn move_card_for_branch(
+    project_path: &str,
+    branch: &str,
+    column_id: &str,
+) -> Result<Option<String>> {
+    let Some(creds) = config::load_credentials()? else {
+        return Ok(None);
+    };
+    let project_config = config::load_project_config(project_path)?;
+    let Some(link) = project_config
+        .task_links
+        .iter()
+        .find(|task| task.branch == branch)
+    else {
+        return Ok(None);
+    };
+    tauri::async_runtime::block_on(api::move_card(&creds, &link.card_id, column_id))?;
+    Ok(Some(link.card_id.clone()))
+}
+
+fn resolve_merge_action(
+    config: &TrelloProjectConfig,
+    branch: &str,
+) -> Option<(String, MergeAction)> {
+    let link = config
+        .task_links
+        .iter()
+        .find(|task| task.branch == branch)?;
+    let board = config
+        .boards
+        .iter()
+        .find(|board| board.board_id == link.board_id)?;
     let action = board.merge_action.clone()?;
     Some((link.card_id.clone(), action))
 }
@@ -66,10 +97,10 @@ fn execute_action(creds: &TrelloCredentials, card_id: &str, action: &MergeAction
 mod tests {
     use std::cell::{Cell, RefCell};
 
-    use anyhow::anyhow;
     use crate::trello::types::{
         BoardConfig, MergeAction, TaskLink, TrelloCredentials, TrelloProjectConfig,
     };
+    use anyhow::anyhow;
 
     use super::{apply_merge_action_for_branch_with, resolve_merge_action};
 
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use serde::{Deserialize, Serialize};
 
@@ -116,6 +116,8 @@ pub struct WorkspaceFile {
 
 // Claude CLI session discovery types
 
+/// A session listed from an agent's store. Claude and Codex discovery both
+/// return this shape.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DiscoveredClaudeSession {
@@ -124,6 +126,361 @@ pub struct DiscoveredClaudeSession {
     pub timestamp: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub last_message_role: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_assistant_snippet: Option<String>,
+    /// Set when the last turn ended waiting on the user.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub awaiting_input: Option<AwaitingInputReason>,
+    /// Timestamp of a trailing tool call that has no result yet.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pending_tool_since: Option<String>,
+    /// Checkout the session ran in: the project itself or one of its worktrees.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub worktree_path: Option<String>,
+    /// Branch checked out in `worktree_path` at discovery time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+}
+
+/// A session from any agent's store, tagged with the provider that found it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentSession {
+    /// Provider name, e.g. "claude" or "codex".
+    pub provider: String,
+    pub session_id: String,
+    pub label: String,
+    pub timestamp: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_message_role: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_assistant_snippet: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub awaiting_input: Option<AwaitingInputReason>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub worktree_path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AwaitingInputReason {
+    /// The assistant's final message asks a question.
+    Question,
+    /// A tool call is waiting on a permission prompt, as reported by hooks
+    /// or inferred from a call left unanswered for a while.
+    Permission,
+}
+
+// Claude transcript types
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_input_tokens: u64,
+    pub cache_read_input_tokens: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum TranscriptBlock {
+    Text {
+        text: String,
+    },
+    Thinking {
+        text: String,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+        input: serde_json::Value,
+    },
+    ToolResult {
+        tool_use_id: String,
+        content: String,
+        is_error: bool,
+    },
+    Image {
+        media_type: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptMessage {
+    pub uuid: Option<String>,
+    pub parent_uuid: Option<String>,
+    pub timestamp: Option<String>,
+    /// "user", "assistant" or "system".
+    pub role: String,
+    /// Injected by the CLI (command output, caveats) rather than typed by the user.
+    pub is_meta: bool,
+    /// Part of a Task subagent's conversation rather than the main thread.
+    pub is_sidechain: bool,
+    /// System entry subtype, e.g. "compact_boundary" or "summary".
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subtype: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<TokenUsage>,
+    /// Working directory the agent was in, which relative tool paths resolve against.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+    pub blocks: Vec<TranscriptBlock>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptPage {
+    pub session_id: String,
+    pub messages: Vec<TranscriptMessage>,
+    pub offset: usize,
+    pub total: usize,
+    pub has_more: bool,
+}
+
+// Session search types
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SessionSearchMatchKind {
+    User,
+    Assistant,
+    ToolInput,
+}
+
+/// Best-matching message of one session for a search query.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionSearchHit {
+    /// "claude" or "codex"
+    pub agent: String,
+    pub session_id: String,
+    pub project_path: Option<String>,
+    pub label: String,
+    pub timestamp: String,
+    pub kind: SessionSearchMatchKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_name: Option<String>,
+    pub matched_at: Option<String>,
+    pub snippet: String,
+    /// Number of messages in the session matching at least one query term.
+    pub match_count: usize,
+}
+
+// Files touched by an agent session
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TouchedFile {
+    /// Relative to the repository root when inside it, otherwise absolute.
+    pub path: String,
+    /// Distinct tools that wrote the file, in first-use order.
+    pub tools: Vec<String>,
+    pub edit_count: usize,
+    pub first_touched_at: Option<String>,
+    pub last_touched_at: Option<String>,
+    /// Current status when the file still has uncommitted changes.
+    pub git_status: Option<GitFileStatus>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionTouchedFiles {
+    pub session_id: String,
+    pub repo_root: Option<String>,
+    pub files: Vec<TouchedFile>,
+    /// Touched paths that still show up in `git status`, ready to stage.
+    pub changed_paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SessionExportFormat {
+    Markdown,
+    Html,
+}
+
+// Session housekeeping types
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SessionHousekeepingAction {
+    /// Compress into ~/.workbench/archive/sessions, then remove the original.
+    Archive,
+    Delete,
+    /// Decompress an archived session back into its agent's store.
+    Restore,
+}
+
+/// One file of an archived session and its compressed copy.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedFile {
+    pub original: String,
+    pub archive: String,
+}
+
+/// A session moved into the archive, with what restoring it needs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedSession {
+    pub provider: String,
+    pub session_id: String,
+    pub label: String,
+    /// Project the session was archived from.
+    pub project_path: String,
+    pub size_bytes: u64,
+    pub modified_at: Option<String>,
+    pub archived_at: String,
+    pub files: Vec<ArchivedFile>,
+}
+
+/// Which sessions `prune_sessions` selects. A session matching either limit
+/// is selected; with neither set nothing is.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionPrunePolicy {
+    /// Sessions whose files were last modified more than this many days ago.
+    #[serde(default)]
+    pub older_than_days: Option<u32>,
+    /// Keep the newest sessions up to this many bytes; select the rest.
+    #[serde(default)]
+    pub max_total_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HousekeptSession {
+    pub provider: String,
+    pub session_id: String,
+    pub label: String,
+    pub size_bytes: u64,
+    pub modified_at: Option<String>,
+    pub paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedSession {
+    pub provider: String,
+    pub session_id: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionHousekeepingReport {
+    pub action: SessionHousekeepingAction,
+    /// Nothing was changed on disk; `sessions` is what would be affected.
+    pub dry_run: bool,
+    pub sessions: Vec<HousekeptSession>,
+    pub skipped: Vec<SkippedSession>,
+    pub total_bytes: u64,
+    /// Where archived copies went (or would go).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archive_dir: Option<String>,
+}
+
+// Token usage accounting types
+
+/// USD prices per million tokens for models whose name starts with `model`.
+/// The longest matching prefix wins.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelPrice {
+    pub model: String,
+    pub input_per_mtok: f64,
+    pub output_per_mtok: f64,
+    #[serde(default)]
+    pub cache_write_per_mtok: f64,
+    #[serde(default)]
+    pub cache_read_per_mtok: f64,
+}
+
+fn model_price(
+    model: &str,
+    input: f64,
+    output: f64,
+    cache_write: f64,
+    cache_read: f64,
+) -> ModelPrice {
+    ModelPrice {
+        model: model.to_string(),
+        input_per_mtok: input,
+        output_per_mtok: output,
+        cache_write_per_mtok: cache_write,
+        cache_read_per_mtok: cache_read,
+    }
+}
+
+pub(crate) fn default_model_prices() -> Vec<ModelPrice> {
+    vec![
+        model_price("claude-opus-4-5", 5.0, 25.0, 6.25, 0.5),
+        model_price("claude-opus-4", 15.0, 75.0, 18.75, 1.5),
+        model_price("claude-sonnet-4", 3.0, 15.0, 3.75, 0.3),
+        model_price("claude-3-7-sonnet", 3.0, 15.0, 3.75, 0.3),
+        model_price("claude-haiku-4-5", 1.0, 5.0, 1.25, 0.1),
+        model_price("claude-3-5-haiku", 0.8, 4.0, 1.0, 0.08),
+        model_price("gpt-5", 1.25, 10.0, 0.0, 0.125),
+    ]
+}
+
+/// Inclusive `YYYY-MM-DD` bounds (UTC). Unset bounds are open.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageRange {
+    #[serde(default)]
+    pub from: Option<String>,
+    #[serde(default)]
+    pub to: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum UsageGroupBy {
+    Session,
+    Project,
+    Day,
+    Model,
+    Agent,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageBucket {
+    pub key: String,
+    /// Session label when grouping by session.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agent: Option<String>,
+    /// Project (or worktree) directory the session ran in.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_path: Option<String>,
+    pub usage: TokenUsage,
+    pub cost_usd: f64,
+    /// Number of model responses counted.
+    pub message_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageSummary {
+    pub group_by: UsageGroupBy,
+    pub buckets: Vec<UsageBucket>,
+    pub total: TokenUsage,
+    pub total_cost_usd: f64,
+    /// Models seen in range with no entry in the price table; their cost counts as zero.
+    pub unpriced_models: Vec<String>,
 }
 
 // Git types
@@ -303,6 +660,106 @@ fn default_agent_actions() -> Vec<AgentAction> {
     ]
 }
 
+/// Action run by an automation rule when a hook event matches.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum AutomationAction {
+    /// Request a git/GitHub refresh for the pane's project.
+    RefreshProject,
+    /// Show a desktop notification. `{event}`, `{tool}` and `{pane}` are substituted.
+    Notify { title: String, body: String },
+    /// Run a named `ProjectTask` from the pane's project in a new tab.
+    RunTask { task_name: String },
+    /// Write text into another terminal pane.
+    WriteToPane { pane_id: String, text: String },
+    /// Move the Trello card linked to the pane's branch to another column.
+    MoveTrelloCard { column_id: String },
+}
+
+/// A user-defined rule evaluated against every hook bridge event.
+/// Unset matchers match anything; all set matchers must match.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutomationRule {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Event source: "claude" or "codex".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_names: Vec<String>,
+    /// Regex matched against the tool input (the Bash command, or the input as JSON).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_pattern: Option<String>,
+    #[serde(default)]
+    pub actions: Vec<AutomationAction>,
+}
+
+pub(crate) fn default_automation_rules() -> Vec<AutomationRule> {
+    vec![
+        AutomationRule {
+            id: "builtin-refresh-file-edits".to_string(),
+            name: "Refresh after file edits".to_string(),
+            enabled: true,
+            source: Some("claude".to_string()),
+            event_name: Some("PostToolUse".to_string()),
+            tool_names: vec![
+                "Write".to_string(),
+                "Edit".to_string(),
+                "NotebookEdit".to_string(),
+            ],
+            input_pattern: None,
+            actions: vec![AutomationAction::RefreshProject],
+        },
+        AutomationRule {
+            id: "builtin-refresh-git-commands".to_string(),
+            name: "Refresh after git/gh commands".to_string(),
+            enabled: true,
+            source: Some("claude".to_string()),
+            event_name: Some("PostToolUse".to_string()),
+            tool_names: vec!["Bash".to_string()],
+            input_pattern: Some(r"\b(git|gh)\b".to_string()),
+            actions: vec![AutomationAction::RefreshProject],
+        },
+        AutomationRule {
+            id: "builtin-refresh-agent-turns".to_string(),
+            name: "Refresh when other agents finish a turn".to_string(),
+            enabled: true,
+            source: None,
+            event_name: Some("turn-finished".to_string()),
+            tool_names: vec![],
+            input_pattern: None,
+            actions: vec![AutomationAction::RefreshProject],
+        },
+    ]
+}
+
+pub(crate) fn default_export_redaction_patterns() -> Vec<String> {
+    [
+        r"sk-ant-[A-Za-z0-9_-]{20,}",
+        r"sk-[A-Za-z0-9_-]{20,}",
+        r"gh[pousr]_[A-Za-z0-9]{30,}",
+        r"github_pat_[A-Za-z0-9_]{30,}",
+        r"AKIA[0-9A-Z]{16}",
+        r"xox[abprs]-[A-Za-z0-9-]{10,}",
+        r"(?i)bearer\s+[A-Za-z0-9._~+/-]{16,}=*",
+        r"-----BEGIN [A-Z ]*PRIVATE KEY-----[\s\S]*?-----END [A-Z ]*PRIVATE KEY-----",
+    ]
+    .iter()
+    .map(|p| p.to_string())
+    .collect()
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct WorkbenchSettings {
@@ -334,6 +791,18 @@ pub struct WorkbenchSettings {
     pub terminal_renderer: String,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub clone_base_dir: Option<String>,
+    /// Saved rules only: overrides of built-in rules (same id) and custom
+    /// rules. See `hook_automation::effective_rules`.
+    #[serde(default)]
+    pub automation_rules: Vec<AutomationRule>,
+    /// Saved prices only: overrides of built-in model prices (same `model`)
+    /// and prices for other models. See `default_model_prices`.
+    #[serde(default)]
+    pub model_prices: Vec<ModelPrice>,
+    /// Regexes whose matches are replaced with `[REDACTED]` in session exports,
+    /// in addition to `default_export_redaction_patterns`. Saved patterns only.
+    #[serde(default)]
+    pub export_redaction_patterns: Vec<String>,
 }
 
 fn default_worktree_strategy() -> String {
@@ -373,6 +842,9 @@ impl Default for WorkbenchSettings {
             use_happy_coder: false,
             terminal_renderer: default_terminal_renderer(),
             clone_base_dir: None,
+            automation_rules: Vec::new(),
+            model_prices: Vec::new(),
+            export_redaction_patterns: Vec::new(),
         }
     }
 }
@@ -386,6 +858,13 @@ pub struct IntegrationStatus {
     pub description: String,
 }
 
+/// What removing an integration changed, one human-readable line per edit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrationRemovalReport {
+    pub changes: Vec<String>,
+}
+
 // Claude Code settings types
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -394,7 +873,74 @@ pub struct PluginInfo {
     pub name: String,
     pub description: String,
     pub version: String,
+    /// `name@marketplace` for marketplace installs, the cache directory otherwise
     pub dir_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub marketplace: Option<String>,
+    #[serde(default)]
+    pub components: PluginComponents,
+}
+
+/// What uninstalling a plugin did to the settings that enable it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginUninstallReport {
+    /// Whether the plugin was installed
+    pub removed: bool,
+    /// Settings files it was cleared from
+    pub cleared: Vec<String>,
+    /// Settings files that still mention it because they could not be updated
+    pub remaining: Vec<String>,
+}
+
+/// What a plugin bundles, by name.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginComponents {
+    pub skills: Vec<String>,
+    pub commands: Vec<String>,
+    pub agents: Vec<String>,
+    /// Hook event names
+    pub hooks: Vec<String>,
+    pub mcp_servers: Vec<String>,
+}
+
+/// A plugin marketplace registered in `~/.claude/plugins/known_marketplaces.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketplaceInfo {
+    pub name: String,
+    pub path: String,
+    pub is_git: bool,
+    pub plugins: Vec<MarketplacePluginInfo>,
+    /// Why the marketplace manifest could not be read
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketplacePluginInfo {
+    /// `name@marketplace`, the key used in `enabledPlugins`
+    pub id: String,
+    pub name: String,
+    pub marketplace: String,
+    pub description: String,
+    pub version: String,
+    /// False for GitHub or URL sources, which need the Claude CLI
+    pub installable: bool,
+    pub installed: bool,
+    pub components: PluginComponents,
+}
+
+/// Where a Claude skill lives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SkillScope {
+    /// `~/.claude/skills`
+    User,
+    /// `<project>/.claude/skills`
+    Project,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -403,6 +949,86 @@ pub struct SkillInfo {
     pub name: String,
     pub dir_name: String,
     pub description: String,
+    pub scope: SkillScope,
+    pub allowed_tools: Vec<String>,
+    /// Path to SKILL.md
+    pub path: String,
+    /// Frontmatter problems; empty when the skill is valid
+    pub issues: Vec<String>,
+}
+
+/// Markdown definitions Claude loads from `.claude/<kind>/`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ClaudeDefinitionKind {
+    /// Subagents in `agents/`
+    Agent,
+    /// Custom slash commands in `commands/`
+    Command,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeDefinitionInfo {
+    pub kind: ClaudeDefinitionKind,
+    pub scope: SkillScope,
+    /// Subagent name from frontmatter, or the command name (its file stem)
+    pub name: String,
+    /// Subdirectory a command lives in (`frontend`), shown by Claude as
+    /// `(project:frontend)`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    /// Path below the definitions directory without `.md`
+    pub file_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    pub tools: Vec<String>,
+    pub path: String,
+    /// Frontmatter problems; empty when the definition is valid
+    pub issues: Vec<String>,
+    /// The project definition that hides this user definition
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shadowed_by: Option<String>,
+    /// The user definition this project definition hides
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overrides: Option<String>,
+}
+
+/// The editable parts of a subagent or slash command file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeDefinition {
+    pub name: String,
+    /// Commands only: the subdirectory the file is stored in
+    #[serde(default)]
+    pub namespace: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+    /// `tools` for subagents, `allowed-tools` for commands
+    #[serde(default)]
+    pub tools: Vec<String>,
+    /// Commands only
+    #[serde(default)]
+    pub argument_hint: Option<String>,
+    #[serde(default)]
+    pub body: String,
+}
+
+/// The editable parts of a SKILL.md.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillDocument {
+    pub name: String,
+    pub description: String,
+    #[serde(default)]
+    pub allowed_tools: Vec<String>,
+    /// Markdown after the frontmatter
+    #[serde(default)]
+    pub body: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -411,6 +1037,307 @@ pub struct HookScriptInfo {
     pub path: String,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SettingsIssueSeverity {
+    /// Claude Code would reject or misread the setting
+    Error,
+    /// Not in the bundled schema; may be a typo or a newer setting
+    Warning,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsValidationIssue {
+    /// JSON pointer to the offending value (`""` for the document root)
+    pub pointer: String,
+    pub severity: SettingsIssueSeverity,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsSaveResult {
+    /// False when validation errors blocked the write
+    pub saved: bool,
+    pub issues: Vec<SettingsValidationIssue>,
+}
+
+// MCP servers
+
+/// Where an MCP server definition lives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum McpScope {
+    /// `<project>/.mcp.json`, shared through the repository
+    Project,
+    /// `mcpServers` in `~/.claude.json`, available in every project
+    User,
+    /// `projects.<path>.mcpServers` in `~/.claude.json`, private to one project
+    Local,
+    /// `[mcp_servers]` in `~/.codex/config.toml`
+    Codex,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpServerConfig {
+    /// `stdio`, `http` or `sse`; stdio is implied by `command`
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub transport: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpServerEntry {
+    pub name: String,
+    pub scope: McpScope,
+    pub config: McpServerConfig,
+    pub source_path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpToolInfo {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// Outcome of launching an MCP server and running the `initialize` handshake.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpHealthReport {
+    pub ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub protocol_version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server_version: Option<String>,
+    pub tools: Vec<McpToolInfo>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Whatever the server wrote to stderr, truncated
+    pub stderr: String,
+    pub duration_ms: u64,
+}
+
+/// A config file with snapshots under `~/.workbench/history/`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigHistoryFile {
+    pub path: String,
+    /// False once the file has been deleted; it can still be restored
+    pub exists: bool,
+    pub versions: usize,
+    /// RFC 3339 time of the newest snapshot
+    pub latest: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigVersion {
+    pub id: String,
+    /// RFC 3339
+    pub created_at: String,
+    pub size: u64,
+    /// Matches what is on disk now
+    pub current: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConfigDiffLineKind {
+    Context,
+    Added,
+    Removed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigDiffLine {
+    pub kind: ConfigDiffLineKind,
+    pub text: String,
+}
+
+/// How Claude would treat a hook run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HookTestOutcome {
+    /// Exit code 0 without a blocking decision
+    Success,
+    /// Exit code 2, or JSON output that blocks, denies or stops
+    Blocked,
+    /// Any other exit code; Claude shows stderr and carries on
+    Error,
+    TimedOut,
+}
+
+/// Result of running a hook command against a synthetic event payload.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HookTestResult {
+    pub outcome: HookTestOutcome,
+    /// None when the command was killed
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+    /// Stdout parsed as JSON, when the command exited 0 and printed an object
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decision: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// What was piped to the command's stdin
+    pub payload: serde_json::Value,
+    pub duration_ms: u64,
+}
+
+/// One settings file that feeds the effective Claude settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsSourceInfo {
+    pub scope: String,
+    pub path: String,
+    pub exists: bool,
+    /// Why the file was skipped, if it could not be read or parsed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Which scope supplied the value at a JSON pointer in the merged settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingProvenance {
+    pub pointer: String,
+    pub scope: String,
+    /// Lower-precedence scopes whose value at this pointer was replaced
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub overridden_scopes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EffectiveClaudeSettings {
+    pub settings: serde_json::Value,
+    /// Scopes in precedence order, lowest first
+    pub sources: Vec<SettingsSourceInfo>,
+    pub provenance: Vec<SettingProvenance>,
+}
+
+// Claude permission rules
+
+/// Outcome of a permission check; also names the list a rule sits in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PermissionDecision {
+    Allow,
+    Ask,
+    Deny,
+}
+
+impl PermissionDecision {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PermissionDecision::Allow => "allow",
+            PermissionDecision::Ask => "ask",
+            PermissionDecision::Deny => "deny",
+        }
+    }
+}
+
+/// A rule string from `permissions.<list>` and the scope that defined it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionRuleRef {
+    pub rule: String,
+    pub list: PermissionDecision,
+    pub scope: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionEvaluation {
+    pub decision: PermissionDecision,
+    /// The rule that decided, or none when the default mode did
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matched_rule: Option<PermissionRuleRef>,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionLintIssue {
+    pub rule: PermissionRuleRef,
+    pub severity: SettingsIssueSeverity,
+    pub message: String,
+    /// The rule that shadows this one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub related: Option<PermissionRuleRef>,
+}
+
+// Claude agent activity tracked from hook events
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SubagentStatus {
+    Running,
+    Completed,
+    /// The parent turn or session ended before the subagent reported completion.
+    Interrupted,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubagentNode {
+    /// Task tool-use ID, or a synthetic ID when the hook payload lacks one.
+    pub id: String,
+    /// The Task this subagent was started from, when it was spawned by
+    /// another subagent rather than the main session.
+    pub parent_id: Option<String>,
+    pub description: Option<String>,
+    pub subagent_type: Option<String>,
+    pub status: SubagentStatus,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    /// Session the subagent's own hooks report, once one differs from the
+    /// pane's main session. Only used to correlate later hooks.
+    #[serde(skip)]
+    pub session_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompactionRecord {
+    pub timestamp: String,
+    /// "manual" (/compact) or "auto" (context window full).
+    pub trigger: Option<String>,
+    pub custom_instructions: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaneAgentActivity {
+    pub pane_id: String,
+    pub session_id: Option<String>,
+    pub subagents: Vec<SubagentNode>,
+    pub compactions: Vec<CompactionRecord>,
+    pub ended_at: Option<String>,
+    pub end_reason: Option<String>,
+}
+
 // Git filesystem watcher event
 
 #[derive(Debug, Clone, Serialize)]
@@ -419,6 +1346,16 @@ pub struct GitChangedEvent {
     pub project_path: String,
 }
 
+// Session index watcher event
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionsChangedEvent {
+    /// "claude" or "codex"
+    pub agent: String,
+    pub paths: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ProjectRefreshRequestedEvent {
@@ -427,6 +1364,13 @@ pub struct ProjectRefreshRequestedEvent {
     pub trigger: String,
 }
 
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutomationRunTaskEvent {
+    pub project_path: String,
+    pub task: ProjectTask,
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GitHubProjectStatusEvent {
@@ -555,7 +1499,6 @@ pub struct GitHubBranchRuns {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use serde_json;
 
     // ProjectConfig round-trip
 
@@ -693,13 +1636,21 @@ mod tests {
             label: "Fix the bug".to_string(),
             timestamp: "2025-01-15T10:30:00Z".to_string(),
             last_message_role: Some("assistant".to_string()),
+            last_assistant_snippet: None,
+            awaiting_input: None,
+            pending_tool_since: None,
+            worktree_path: None,
+            branch: None,
         };
         let json = serde_json::to_string(&session).unwrap();
         assert!(json.contains("\"sessionId\""));
         assert!(json.contains("\"lastMessageRole\""));
         let deserialized: DiscoveredClaudeSession = serde_json::from_str(&json).unwrap();
         assert_eq!(deserialized.session_id, "abc123");
-        assert_eq!(deserialized.last_message_role, Some("assistant".to_string()));
+        assert_eq!(
+            deserialized.last_message_role,
+            Some("assistant".to_string())
+        );
     }
 
     // WorktreeCopyOptions default
@@ -718,6 +1669,37 @@ mod tests {
         assert!(!settings.terminal_telemetry_enabled);
     }
 
+    #[test]
+    fn workbench_settings_saves_no_builtin_defaults() {
+        let json = r#"{"worktreeStrategy":"sibling","trelloEnabled":false,"agentActions":[]}"#;
+        let settings: WorkbenchSettings = serde_json::from_str(json).unwrap();
+        assert!(settings.automation_rules.is_empty());
+        assert!(settings.export_redaction_patterns.is_empty());
+        assert!(settings.model_prices.is_empty());
+        assert!(WorkbenchSettings::default().automation_rules.is_empty());
+        assert!(WorkbenchSettings::default().model_prices.is_empty());
+        assert!(WorkbenchSettings::default()
+            .export_redaction_patterns
+            .is_empty());
+        assert!(default_automation_rules().iter().all(|rule| rule.enabled));
+    }
+
+    #[test]
+    fn automation_action_serializes_with_type_tag() {
+        let action = AutomationAction::RunTask {
+            task_name: "test".to_string(),
+        };
+        let json = serde_json::to_value(&action).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"type": "runTask", "taskName": "test"})
+        );
+
+        let parsed: AutomationAction =
+            serde_json::from_str(r#"{"type":"refreshProject"}"#).unwrap();
+        assert_eq!(parsed, AutomationAction::RefreshProject);
+    }
+
     #[test]
     fn workbench_settings_deserialize_defaults_terminal_fields_when_missing() {
         let json = r#"{"worktreeStrategy":"sibling","trelloEnabled":false,"agentActions":[]}"#;
@@ -0,0 +1,568 @@
+/// Token usage and cost accounting over Claude and Codex session files.
+///
+/// Claude records a `usage` block on each assistant entry (repeated once per
+/// content block of the same API message, so entries are deduplicated by
+/// message ID). Codex emits `token_count` events whose `last_token_usage`
+/// covers one model response.
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::io::BufRead;
+use std::path::Path;
+use std::sync::Mutex;
+
+use serde_json::Value;
+
+use crate::claude_sessions;
+use crate::claude_transcript;
+use crate::codex_sessions;
+use crate::session_index::{FileCache, SessionFiles, SessionKind};
+use crate::session_utils;
+use crate::types::{
+    default_model_prices, ModelPrice, TokenUsage, UsageBucket, UsageGroupBy, UsageRange,
+    UsageSummary,
+};
+
+/// Claude Code writes this model name for locally generated messages
+/// (e.g. API error notices) that carry no real usage.
+const SYNTHETIC_MODEL: &str = "<synthetic>";
+const UNKNOWN_KEY: &str = "unknown";
+
+struct UsageRecord {
+    timestamp: String,
+    model: Option<String>,
+    usage: TokenUsage,
+}
+
+struct UsageDoc {
+    agent: SessionKind,
+    session_id: String,
+    label: String,
+    project_path: Option<String>,
+    records: Vec<UsageRecord>,
+}
+
+fn str_at<'a>(value: &'a Value, pointer: &str) -> Option<&'a str> {
+    value.pointer(pointer).and_then(|v| v.as_str())
+}
+
+fn add_usage(total: &mut TokenUsage, usage: &TokenUsage) {
+    total.input_tokens += usage.input_tokens;
+    total.output_tokens += usage.output_tokens;
+    total.cache_creation_input_tokens += usage.cache_creation_input_tokens;
+    total.cache_read_input_tokens += usage.cache_read_input_tokens;
+}
+
+fn read_lines(path: &Path) -> Option<impl Iterator<Item = Value>> {
+    let file = fs::File::open(path).ok()?;
+    Some(
+        std::io::BufReader::new(file)
+            .lines()
+            .map_while(Result::ok)
+            .filter_map(|line| serde_json::from_str::<Value>(&line).ok()),
+    )
+}
+
+fn claude_usage_doc(path: &Path) -> Option<UsageDoc> {
+    let session_id = path.file_stem()?.to_str()?.to_string();
+    let label = claude_sessions::parse_session_jsonl(path, session_id.clone())
+        .map(|s| s.label)
+        .unwrap_or_else(|| session_utils::fallback_label(&session_id));
+
+    let mut project_path = None;
+    let mut records: Vec<UsageRecord> = Vec::new();
+    let mut by_message_id: HashMap<String, usize> = HashMap::new();
+    for entry in read_lines(path)? {
+        if project_path.is_none() {
+            project_path = str_at(&entry, "/cwd").map(str::to_string);
+        }
+        if str_at(&entry, "/type") != Some("assistant") {
+            continue;
+        }
+        let Some(usage) = entry
+            .pointer("/message/usage")
+            .and_then(claude_transcript::parse_usage)
+        else {
+            continue;
+        };
+        let model = str_at(&entry, "/message/model").map(str::to_string);
+        if model.as_deref() == Some(SYNTHETIC_MODEL) {
+            continue;
+        }
+        let record = UsageRecord {
+            timestamp: str_at(&entry, "/timestamp").unwrap_or_default().to_string(),
+            model,
+            usage,
+        };
+        // Later entries for the same message carry the final output count.
+        match str_at(&entry, "/message/id").or_else(|| str_at(&entry, "/uuid")) {
+            Some(id) => match by_message_id.get(id) {
+                Some(&idx) => records[idx] = record,
+                None => {
+                    by_message_id.insert(id.to_string(), records.len());
+                    records.push(record);
+                }
+            },
+            None => records.push(record),
+        }
+    }
+
+    Some(UsageDoc {
+        agent: SessionKind::Claude,
+        session_id,
+        label,
+        project_path,
+        records,
+    })
+}
+
+fn codex_usage_doc(path: &Path) -> Option<UsageDoc> {
+    let summary = codex_sessions::parse_codex_session_summary(path)?;
+    let mut model: Option<String> = None;
+    let mut last_total: Option<Value> = None;
+    let mut records = Vec::new();
+    for obj in read_lines(path)? {
+        match str_at(&obj, "/type") {
+            Some("turn_context") => {
+                if let Some(m) = str_at(&obj, "/payload/model") {
+                    model = Some(m.to_string());
+                }
+            }
+            Some("event_msg") if str_at(&obj, "/payload/type") == Some("token_count") => {
+                let Some(info) = obj.pointer("/payload/info").filter(|v| v.is_object()) else {
+                    continue;
+                };
+                // Codex re-emits token_count with unchanged totals (e.g. for
+                // rate-limit updates); only count events that moved the total.
+                let total = info.get("total_token_usage").cloned();
+                if total.is_some() && total == last_total {
+                    continue;
+                }
+                last_total = total;
+                let Some(last) = info.get("last_token_usage") else {
+                    continue;
+                };
+                let field = |key: &str| last.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+                let cached = field("cached_input_tokens");
+                records.push(UsageRecord {
+                    timestamp: str_at(&obj, "/timestamp").unwrap_or_default().to_string(),
+                    model: model.clone(),
+                    usage: TokenUsage {
+                        // Codex input counts include cached tokens.
+                        input_tokens: field("input_tokens").saturating_sub(cached),
+                        output_tokens: field("output_tokens"),
+                        cache_creation_input_tokens: 0,
+                        cache_read_input_tokens: cached,
+                    },
+                });
+            }
+            _ => {}
+        }
+    }
+
+    Some(UsageDoc {
+        agent: SessionKind::Codex,
+        session_id: summary.session.session_id,
+        label: summary.session.label,
+        project_path: Some(summary.cwd),
+        records,
+    })
+}
+
+/// The built-in prices with the user's saved prices applied: a saved price for
+/// a built-in model prefix replaces it, any other saved price is appended.
+fn effective_prices(saved: &[ModelPrice]) -> Vec<ModelPrice> {
+    let mut prices = default_model_prices();
+    for price in saved {
+        match prices
+            .iter_mut()
+            .find(|builtin| builtin.model == price.model)
+        {
+            Some(builtin) => *builtin = price.clone(),
+            None => prices.push(price.clone()),
+        }
+    }
+    prices
+}
+
+fn price_for<'a>(prices: &'a [ModelPrice], model: &str) -> Option<&'a ModelPrice> {
+    prices
+        .iter()
+        .filter(|p| !p.model.is_empty() && model.starts_with(&p.model))
+        .max_by_key(|p| p.model.len())
+}
+
+fn cost_usd(price: &ModelPrice, usage: &TokenUsage) -> f64 {
+    (usage.input_tokens as f64 * price.input_per_mtok
+        + usage.output_tokens as f64 * price.output_per_mtok
+        + usage.cache_creation_input_tokens as f64 * price.cache_write_per_mtok
+        + usage.cache_read_input_tokens as f64 * price.cache_read_per_mtok)
+        / 1_000_000.0
+}
+
+fn in_range(day: &str, range: &UsageRange) -> bool {
+    range.from.as_deref().is_none_or(|from| day >= from)
+        && range.to.as_deref().is_none_or(|to| day <= to)
+}
+
+fn empty_bucket(key: String) -> UsageBucket {
+    UsageBucket {
+        key,
+        label: None,
+        agent: None,
+        project_path: None,
+        usage: TokenUsage::default(),
+        cost_usd: 0.0,
+        message_count: 0,
+    }
+}
+
+fn summarize<'a>(
+    docs: impl Iterator<Item = &'a UsageDoc>,
+    range: &UsageRange,
+    group_by: UsageGroupBy,
+    prices: &[ModelPrice],
+) -> UsageSummary {
+    let mut buckets: HashMap<String, UsageBucket> = HashMap::new();
+    let mut total = TokenUsage::default();
+    let mut total_cost_usd = 0.0;
+    let mut unpriced = BTreeSet::new();
+
+    for doc in docs {
+        for record in &doc.records {
+            let day = record.timestamp.get(..10).unwrap_or(UNKNOWN_KEY);
+            if !in_range(day, range) {
+                continue;
+            }
+            let model = record.model.as_deref().unwrap_or(UNKNOWN_KEY);
+            let cost = match price_for(prices, model) {
+                Some(price) => cost_usd(price, &record.usage),
+                None => {
+                    unpriced.insert(model.to_string());
+                    0.0
+                }
+            };
+
+            let key = match group_by {
+                UsageGroupBy::Session => format!("{}:{}", doc.agent.as_str(), doc.session_id),
+                UsageGroupBy::Project => doc
+                    .project_path
+                    .clone()
+                    .unwrap_or_else(|| UNKNOWN_KEY.to_string()),
+                UsageGroupBy::Day => day.to_string(),
+                UsageGroupBy::Model => model.to_string(),
+                UsageGroupBy::Agent => doc.agent.as_str().to_string(),
+            };
+            let bucket = buckets.entry(key.clone()).or_insert_with(|| {
+                let mut bucket = empty_bucket(key);
+                match group_by {
+                    UsageGroupBy::Session => {
+                        bucket.label = Some(doc.label.clone());
+                        bucket.agent = Some(doc.agent.as_str().to_string());
+                        bucket.project_path = doc.project_path.clone();
+                    }
+                    UsageGroupBy::Project => bucket.project_path = doc.project_path.clone(),
+                    UsageGroupBy::Agent => bucket.agent = Some(doc.agent.as_str().to_string()),
+                    UsageGroupBy::Day | UsageGroupBy::Model => {}
+                }
+                bucket
+            });
+            add_usage(&mut bucket.usage, &record.usage);
+            bucket.cost_usd += cost;
+            bucket.message_count += 1;
+            add_usage(&mut total, &record.usage);
+            total_cost_usd += cost;
+        }
+    }
+
+    let mut buckets: Vec<UsageBucket> = buckets.into_values().collect();
+    if group_by == UsageGroupBy::Day {
+        buckets.sort_by(|a, b| a.key.cmp(&b.key));
+    } else {
+        buckets.sort_by(|a, b| {
+            b.cost_usd
+                .total_cmp(&a.cost_usd)
+                .then(b.usage.output_tokens.cmp(&a.usage.output_tokens))
+                .then(a.key.cmp(&b.key))
+        });
+    }
+
+    UsageSummary {
+        group_by,
+        buckets,
+        total,
+        total_cost_usd,
+        unpriced_models: unpriced.into_iter().collect(),
+    }
+}
+
+#[derive(Default)]
+struct UsageState {
+    docs: FileCache<UsageDoc>,
+}
+
+impl UsageState {
+    fn summary(
+        &mut self,
+        files: &SessionFiles,
+        range: &UsageRange,
+        group_by: UsageGroupBy,
+        prices: &[ModelPrice],
+    ) -> UsageSummary {
+        let mut in_scope = Vec::new();
+        for (kind, path) in files.iter() {
+            let build = match kind {
+                SessionKind::Claude => claude_usage_doc,
+                SessionKind::Codex => codex_usage_doc,
+            };
+            if self.docs.get_or_build(path, build).is_some() {
+                in_scope.push(path.to_path_buf());
+            }
+        }
+        self.docs.prune_missing();
+        let docs = in_scope.iter().filter_map(|path| self.docs.get(path));
+        summarize(docs, range, group_by, prices)
+    }
+}
+
+pub struct UsageTracker {
+    state: Mutex<UsageState>,
+}
+
+impl UsageTracker {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(UsageState::default()),
+        }
+    }
+
+    /// Aggregate usage for one project (or every project) within `range`,
+    /// pricing with the built-ins overridden by `saved_prices`.
+    pub fn summary(
+        &self,
+        range: &UsageRange,
+        group_by: UsageGroupBy,
+        project_path: Option<&str>,
+        saved_prices: &[ModelPrice],
+    ) -> UsageSummary {
+        let files = SessionFiles::for_project(project_path);
+        let prices = effective_prices(saved_prices);
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.summary(&files, range, group_by, &prices)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paths;
+    use std::io::Write;
+    use tempfile::tempdir;
+
+    fn write_lines(path: &Path, lines: &[Value]) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let mut file = fs::File::create(path).unwrap();
+        for line in lines {
+            writeln!(file, "{line}").unwrap();
+        }
+    }
+
+    fn claude_assistant(id: &str, ts: &str, model: &str, input: u64, output: u64) -> Value {
+        serde_json::json!({
+            "type": "assistant", "timestamp": ts, "cwd": "/work/app",
+            "message": {
+                "id": id, "role": "assistant", "model": model,
+                "content": [{ "type": "text", "text": "ok" }],
+                "usage": {
+                    "input_tokens": input, "output_tokens": output,
+                    "cache_creation_input_tokens": 1000, "cache_read_input_tokens": 2000
+                }
+            }
+        })
+    }
+
+    fn claude_file(root: &Path, id: &str, lines: &[Value]) {
+        let path = root
+            .join("projects")
+            .join(paths::encode_project_path("/work/app"))
+            .join(format!("{id}.jsonl"));
+        let mut all = vec![serde_json::json!({
+            "type": "user", "timestamp": "2025-01-01T00:00:00Z", "cwd": "/work/app",
+            "message": { "role": "user", "content": "Fix the flaky test" }
+        })];
+        all.extend_from_slice(lines);
+        write_lines(&path, &all);
+    }
+
+    fn summary(root: &Path, range: UsageRange, group_by: UsageGroupBy) -> UsageSummary {
+        let files = SessionFiles::in_dirs(&root.join("projects"), &root.join("sessions"), None);
+        UsageState::default().summary(&files, &range, group_by, &default_model_prices())
+    }
+
+    #[test]
+    fn claude_entries_are_deduplicated_by_message_id() {
+        let dir = tempdir().unwrap();
+        claude_file(
+            dir.path(),
+            "sess-1",
+            &[
+                claude_assistant(
+                    "msg_1",
+                    "2025-01-01T00:00:01Z",
+                    "claude-sonnet-4-5-20250929",
+                    10,
+                    1,
+                ),
+                claude_assistant(
+                    "msg_1",
+                    "2025-01-01T00:00:02Z",
+                    "claude-sonnet-4-5-20250929",
+                    10,
+                    500,
+                ),
+                claude_assistant("msg_2", "2025-01-01T00:00:03Z", "<synthetic>", 0, 0),
+            ],
+        );
+
+        let s = summary(dir.path(), UsageRange::default(), UsageGroupBy::Session);
+        assert_eq!(s.buckets.len(), 1);
+        let bucket = &s.buckets[0];
+        assert_eq!(bucket.key, "claude:sess-1");
+        assert_eq!(bucket.label.as_deref(), Some("Fix the flaky test"));
+        assert_eq!(bucket.project_path.as_deref(), Some("/work/app"));
+        assert_eq!(bucket.message_count, 1);
+        assert_eq!(bucket.usage.output_tokens, 500);
+
+        // 10 * 3 + 500 * 15 + 1000 * 3.75 + 2000 * 0.3, per million
+        let expected = (30.0 + 7500.0 + 3750.0 + 600.0) / 1_000_000.0;
+        assert!((bucket.cost_usd - expected).abs() < 1e-12);
+        assert!((s.total_cost_usd - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn groups_by_day_within_range() {
+        let dir = tempdir().unwrap();
+        claude_file(
+            dir.path(),
+            "sess-1",
+            &[
+                claude_assistant("m1", "2025-01-01T10:00:00Z", "claude-opus-4-1", 100, 10),
+                claude_assistant("m2", "2025-01-02T10:00:00Z", "claude-opus-4-1", 100, 10),
+                claude_assistant("m3", "2025-01-03T10:00:00Z", "claude-opus-4-1", 100, 10),
+            ],
+        );
+
+        let range = UsageRange {
+            from: Some("2025-01-02".into()),
+            to: Some("2025-01-03".into()),
+        };
+        let s = summary(dir.path(), range, UsageGroupBy::Day);
+        let keys: Vec<&str> = s.buckets.iter().map(|b| b.key.as_str()).collect();
+        assert_eq!(keys, ["2025-01-02", "2025-01-03"]);
+        assert_eq!(s.total.input_tokens, 200);
+    }
+
+    #[test]
+    fn codex_token_counts_split_cached_input() {
+        let dir = tempdir().unwrap();
+        let project = dir.path().join("repo");
+        fs::create_dir_all(&project).unwrap();
+        let token_count = |ts: &str, total: u64| {
+            serde_json::json!({ "timestamp": ts, "type": "event_msg", "payload": {
+                "type": "token_count",
+                "info": {
+                    "total_token_usage": { "input_tokens": total },
+                    "last_token_usage": { "input_tokens": 1000, "cached_input_tokens": 800, "output_tokens": 50 }
+                }
+            }})
+        };
+        write_lines(
+            &dir.path().join("sessions/2025/01/01/rollout-1.jsonl"),
+            &[
+                serde_json::json!({ "type": "session_meta", "payload": {
+                    "id": "codex-1", "timestamp": "2025-01-01T00:00:00Z", "cwd": project } }),
+                serde_json::json!({ "type": "turn_context", "payload": { "model": "gpt-5-codex" } }),
+                token_count("2025-01-01T00:00:05Z", 1000),
+                // Repeated event with an unchanged total is not double counted.
+                token_count("2025-01-01T00:00:06Z", 1000),
+                token_count("2025-01-01T00:01:00Z", 2000),
+            ],
+        );
+
+        let s = summary(dir.path(), UsageRange::default(), UsageGroupBy::Model);
+        assert_eq!(s.buckets.len(), 1);
+        assert_eq!(s.buckets[0].key, "gpt-5-codex");
+        assert_eq!(s.buckets[0].message_count, 2);
+        assert_eq!(s.total.input_tokens, 400);
+        assert_eq!(s.total.cache_read_input_tokens, 1600);
+        assert_eq!(s.total.output_tokens, 100);
+        assert!(s.unpriced_models.is_empty());
+    }
+
+    #[test]
+    fn unpriced_models_are_reported() {
+        let dir = tempdir().unwrap();
+        claude_file(
+            dir.path(),
+            "sess-1",
+            &[claude_assistant(
+                "m1",
+                "2025-01-01T10:00:00Z",
+                "mystery-model",
+                100,
+                10,
+            )],
+        );
+        let s = summary(dir.path(), UsageRange::default(), UsageGroupBy::Agent);
+        assert_eq!(s.unpriced_models, ["mystery-model"]);
+        assert_eq!(s.total_cost_usd, 0.0);
+        assert_eq!(s.buckets[0].agent.as_deref(), Some("claude"));
+    }
+
+    #[test]
+    fn longest_price_prefix_wins() {
+        let prices = default_model_prices();
+        assert_eq!(
+            price_for(&prices, "claude-opus-4-5-20251101")
+                .unwrap()
+                .model,
+            "claude-opus-4-5"
+        );
+        assert_eq!(
+            price_for(&prices, "claude-opus-4-1-20250805")
+                .unwrap()
+                .model,
+            "claude-opus-4"
+        );
+        assert!(price_for(&prices, "gemini-2.5-pro").is_none());
+    }
+
+    #[test]
+    fn saved_prices_override_builtins_and_add_models() {
+        let saved = vec![
+            ModelPrice {
+                model: "claude-sonnet-4".to_string(),
+                input_per_mtok: 2.0,
+                output_per_mtok: 10.0,
+                cache_write_per_mtok: 0.0,
+                cache_read_per_mtok: 0.0,
+            },
+            ModelPrice {
+                model: "local-llm".to_string(),
+                input_per_mtok: 0.0,
+                output_per_mtok: 0.0,
+                cache_write_per_mtok: 0.0,
+                cache_read_per_mtok: 0.0,
+            },
+        ];
+        let prices = effective_prices(&saved);
+        assert_eq!(prices.len(), default_model_prices().len() + 1);
+        assert_eq!(
+            price_for(&prices, "claude-sonnet-4-5")
+                .unwrap()
+                .input_per_mtok,
+            2.0
+        );
+        assert!(price_for(&prices, "claude-opus-4-5").is_some());
+        assert!(price_for(&prices, "local-llm-7b").is_some());
+    }
+}
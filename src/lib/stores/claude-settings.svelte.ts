@@ -1,11 +1,33 @@
 import { invoke } from '@tauri-apps/api/core';
 import type {
+	ClaudeDefinition,
+	ClaudeDefinitionInfo,
+	ClaudeDefinitionKind,
 	ClaudeSettings,
+	EffectiveClaudeSettings,
+	HookCommand,
+	HookDefinition,
+	HookEntry,
+	HookListItem,
+	HookLocation,
 	HookScriptInfo,
+	HookTestResult,
+	MarketplaceInfo,
+	McpHealthReport,
+	McpScope,
+	McpServerConfig,
+	McpServerEntry,
+	PermissionEvaluation,
+	PermissionLintIssue,
 	PluginInfo,
+	PluginUninstallReport,
 	SettingsScope,
 	ScopeGroup,
-	SkillInfo
+	SettingsSaveResult,
+	SettingsValidationIssue,
+	SkillDocument,
+	SkillInfo,
+	SkillScope
 } from '$types/claude-settings';
 
 const SCOPES: SettingsScope[] = ['user', 'user-local', 'project', 'project-local'];
@@ -22,8 +44,19 @@ export class ClaudeSettingsStore {
 	localOnly = $state(false);
 
 	plugins: PluginInfo[] = $state([]);
+	marketplaces: MarketplaceInfo[] = $state([]);
 	skills: SkillInfo[] = $state([]);
+	agents: ClaudeDefinitionInfo[] = $state([]);
+	commands: ClaudeDefinitionInfo[] = $state([]);
 	hookScripts: HookScriptInfo[] = $state([]);
+	effective: EffectiveClaudeSettings | null = $state(null);
+	mcpServers: McpServerEntry[] = $state([]);
+	/** Latest health check per server, keyed by `scope:name` */
+	mcpHealth: Record<string, McpHealthReport> = $state({});
+	/** Shadowed, redundant or dead permission rules across every scope */
+	permissionIssues: PermissionLintIssue[] = $state([]);
+	/** Schema issues from the last save attempt */
+	validationIssues: SettingsValidationIssue[] = $state([]);
 
 	loaded = $state(false);
 	dirty = $state(false);
@@ -42,14 +75,21 @@ export class ClaudeSettingsStore {
 		return this.settings[this.activeScope];
 	}
 
+	/** True when the last save was refused because of schema errors */
+	get saveBlocked(): boolean {
+		return this.validationIssues.some((issue) => issue.severity === 'error');
+	}
+
 	setScopeGroup(group: ScopeGroup) {
 		this.activeScopeGroup = group;
 		this.dirty = false;
+		this.validationIssues = [];
 	}
 
 	setLocalOnly(local: boolean) {
 		this.localOnly = local;
 		this.dirty = false;
+		this.validationIssues = [];
 	}
 
 	async load(projectPath: string | null) {
@@ -63,11 +103,36 @@ export class ClaudeSettingsStore {
 			this.activeScopeGroup = 'user';
 		}
 
+		await this.loadScopes();
+
+		const [, skills, agents, commands, hookScripts, mcpServers] = await Promise.all([
+			this.loadPlugins(),
+			this.fetchSkills(),
+			this.fetchDefinitions('agent'),
+			this.fetchDefinitions('command'),
+			invoke<HookScriptInfo[]>('list_claude_hooks_scripts').catch((e) => {
+				console.warn('[ClaudeSettings] Failed to list hook scripts:', e);
+				return [] as HookScriptInfo[];
+			}),
+			this.fetchMcpServers()
+		]);
+
+		this.skills = skills;
+		this.agents = agents;
+		this.commands = commands;
+		this.hookScripts = hookScripts;
+		this.mcpServers = mcpServers;
+		this.loaded = true;
+		this.dirty = false;
+	}
+
+	/** Re-read every scope from disk without changing the active scope */
+	private async loadScopes() {
 		const results = await Promise.all(
 			SCOPES.map((scope) =>
 				invoke<ClaudeSettings>('load_claude_settings', {
 					scope,
-					projectPath: scope.startsWith('project') ? projectPath : null
+					projectPath: scope.startsWith('project') ? this.projectPath : null
 				}).catch((e) => {
 					console.warn(`[ClaudeSettings] Failed to load scope "${scope}":`, e);
 					return {} as ClaudeSettings;
@@ -81,38 +146,300 @@ export class ClaudeSettingsStore {
 			project: results[2],
 			'project-local': results[3]
 		};
+	}
 
-		const [plugins, skills, hookScripts] = await Promise.all([
-			invoke<PluginInfo[]>('list_claude_plugins').catch((e) => {
-				console.warn('[ClaudeSettings] Failed to list plugins:', e);
-				return [] as PluginInfo[];
-			}),
-			invoke<SkillInfo[]>('list_claude_skills').catch((e) => {
-				console.warn('[ClaudeSettings] Failed to list skills:', e);
-				return [] as SkillInfo[];
-			}),
-			invoke<HookScriptInfo[]>('list_claude_hooks_scripts').catch((e) => {
-				console.warn('[ClaudeSettings] Failed to list hook scripts:', e);
-				return [] as HookScriptInfo[];
-			})
-		]);
+	/** Merge every scope in Claude's precedence order, noting which scope supplied each key */
+	async loadEffective(projectPath: string | null = this.projectPath) {
+		try {
+			this.effective = await invoke<EffectiveClaudeSettings>('load_effective_claude_settings', {
+				projectPath
+			});
+		} catch (e) {
+			console.warn('[ClaudeSettings] Failed to load effective settings:', e);
+			this.effective = null;
+		}
+		return this.effective;
+	}
+
+	private fetchPlugins(): Promise<PluginInfo[]> {
+		return invoke<PluginInfo[]>('list_claude_plugins').catch((e) => {
+			console.warn('[ClaudeSettings] Failed to list plugins:', e);
+			return [] as PluginInfo[];
+		});
+	}
+
+	private fetchMarketplaces(): Promise<MarketplaceInfo[]> {
+		return invoke<MarketplaceInfo[]>('list_plugin_marketplaces').catch((e) => {
+			console.warn('[ClaudeSettings] Failed to list plugin marketplaces:', e);
+			return [] as MarketplaceInfo[];
+		});
+	}
 
+	async loadPlugins() {
+		const [plugins, marketplaces] = await Promise.all([
+			this.fetchPlugins(),
+			this.fetchMarketplaces()
+		]);
 		this.plugins = plugins;
-		this.skills = skills;
-		this.hookScripts = hookScripts;
-		this.loaded = true;
+		this.marketplaces = marketplaces;
+	}
+
+	/** Register a local marketplace directory; rejects with the backend error */
+	async addMarketplace(path: string) {
+		await invoke('add_plugin_marketplace', { path });
+		await this.loadPlugins();
+	}
+
+	async removeMarketplace(name: string) {
+		await invoke('remove_plugin_marketplace', { name });
+		await this.loadPlugins();
+	}
+
+	/**
+	 * Install a marketplace plugin and enable it in the active scope. This writes
+	 * the settings file, so the scopes are re-read; callers should not install
+	 * while there are unsaved edits.
+	 */
+	async installPlugin(marketplace: string, plugin: string) {
+		await invoke('install_claude_plugin', {
+			marketplace,
+			plugin,
+			scope: this.activeScope,
+			projectPath: this.projectPath
+		});
+		await Promise.all([this.loadPlugins(), this.loadScopes()]);
+		this.dirty = false;
+	}
+
+	/**
+	 * Uninstall a plugin and drop it from `enabledPlugins` in the user scopes and every known
+	 * project
+	 */
+	async uninstallPlugin(pluginId: string): Promise<PluginUninstallReport> {
+		const report = await invoke<PluginUninstallReport>('uninstall_claude_plugin', {
+			pluginId,
+			projectPath: this.projectPath
+		});
+		await Promise.all([this.loadPlugins(), this.loadScopes()]);
 		this.dirty = false;
+		return report;
+	}
+
+	/** Whether the active scope enables a plugin; unlisted plugins are enabled */
+	isPluginEnabled(pluginId: string): boolean {
+		const { enabledPlugins, disabledPlugins } = this.currentSettings;
+		if (disabledPlugins?.includes(pluginId)) return false;
+		if (enabledPlugins && !Array.isArray(enabledPlugins)) {
+			return enabledPlugins[pluginId] ?? true;
+		}
+		return true;
 	}
 
-	async save() {
+	/** Toggle a plugin in the active scope, converting legacy lists to the object form */
+	setPluginEnabled(pluginId: string, enabled: boolean) {
+		const { enabledPlugins, disabledPlugins, ...rest } = this.currentSettings;
+		const next: Record<string, boolean> = Array.isArray(enabledPlugins)
+			? Object.fromEntries(enabledPlugins.map((id) => [id, true]))
+			: { ...(enabledPlugins ?? {}) };
+		for (const id of disabledPlugins ?? []) next[id] = false;
+		next[pluginId] = enabled;
+		this.settings[this.activeScope] = { ...rest, enabledPlugins: next };
+		this.dirty = true;
+	}
+
+	private fetchSkills(): Promise<SkillInfo[]> {
+		return invoke<SkillInfo[]>('list_claude_skills', { projectPath: this.projectPath }).catch(
+			(e) => {
+				console.warn('[ClaudeSettings] Failed to list skills:', e);
+				return [] as SkillInfo[];
+			}
+		);
+	}
+
+	async loadSkills() {
+		this.skills = await this.fetchSkills();
+	}
+
+	async getSkill(scope: SkillScope, dirName: string): Promise<SkillDocument> {
+		return invoke<SkillDocument>('get_claude_skill', {
+			scope,
+			projectPath: this.projectPath,
+			dirName
+		});
+	}
+
+	/** Frontmatter problems that would block `saveSkill` */
+	async validateSkill(skill: SkillDocument): Promise<string[]> {
+		return invoke<string[]>('validate_claude_skill', { skill });
+	}
+
+	/** Create a skill (no `dirName`) or rewrite an existing one; rejects with the backend error */
+	async saveSkill(scope: SkillScope, skill: SkillDocument, dirName: string | null = null) {
+		await invoke('save_claude_skill', { scope, projectPath: this.projectPath, dirName, skill });
+		await this.loadSkills();
+	}
+
+	async deleteSkill(scope: SkillScope, dirName: string) {
+		await invoke('delete_claude_skill', { scope, projectPath: this.projectPath, dirName });
+		await this.loadSkills();
+	}
+
+	async copySkill(dirName: string, from: SkillScope, to: SkillScope, overwrite = false) {
+		await invoke('copy_claude_skill', {
+			projectPath: this.projectPath,
+			dirName,
+			from,
+			to,
+			overwrite
+		});
+		await this.loadSkills();
+	}
+
+	private fetchDefinitions(kind: ClaudeDefinitionKind): Promise<ClaudeDefinitionInfo[]> {
+		return invoke<ClaudeDefinitionInfo[]>('list_claude_definitions', {
+			kind,
+			projectPath: this.projectPath
+		}).catch((e) => {
+			console.warn(`[ClaudeSettings] Failed to list ${kind} definitions:`, e);
+			return [] as ClaudeDefinitionInfo[];
+		});
+	}
+
+	/** Reload subagents or slash commands */
+	async loadDefinitions(kind: ClaudeDefinitionKind) {
+		const definitions = await this.fetchDefinitions(kind);
+		if (kind === 'agent') {
+			this.agents = definitions;
+		} else {
+			this.commands = definitions;
+		}
+	}
+
+	async getDefinition(
+		kind: ClaudeDefinitionKind,
+		scope: SkillScope,
+		fileName: string
+	): Promise<ClaudeDefinition> {
+		return invoke<ClaudeDefinition>('get_claude_definition', {
+			kind,
+			scope,
+			projectPath: this.projectPath,
+			fileName
+		});
+	}
+
+	/**
+	 * Create a definition (no `originalName`) or rewrite one, renaming its file
+	 * when the name changed; rejects with the backend error
+	 */
+	async saveDefinition(
+		kind: ClaudeDefinitionKind,
+		scope: SkillScope,
+		definition: ClaudeDefinition,
+		originalName: string | null = null
+	) {
+		await invoke('save_claude_definition', {
+			kind,
+			scope,
+			projectPath: this.projectPath,
+			originalName,
+			definition
+		});
+		await this.loadDefinitions(kind);
+	}
+
+	async deleteDefinition(kind: ClaudeDefinitionKind, scope: SkillScope, fileName: string) {
+		await invoke('delete_claude_definition', {
+			kind,
+			scope,
+			projectPath: this.projectPath,
+			fileName
+		});
+		await this.loadDefinitions(kind);
+	}
+
+	private fetchMcpServers(): Promise<McpServerEntry[]> {
+		return invoke<McpServerEntry[]>('list_mcp_servers', { projectPath: this.projectPath }).catch(
+			(e) => {
+				console.warn('[ClaudeSettings] Failed to list MCP servers:', e);
+				return [] as McpServerEntry[];
+			}
+		);
+	}
+
+	async loadMcpServers() {
+		this.mcpServers = await this.fetchMcpServers();
+	}
+
+	/** Add or replace an MCP server definition; rejects with the backend error */
+	async saveMcpServer(scope: McpScope, name: string, config: McpServerConfig) {
+		await invoke('save_mcp_server', { scope, projectPath: this.projectPath, name, config });
+		await this.loadMcpServers();
+	}
+
+	async removeMcpServer(scope: McpScope, name: string) {
+		await invoke('remove_mcp_server', { scope, projectPath: this.projectPath, name });
+		await this.loadMcpServers();
+	}
+
+	/** Mirror a server definition into another scope, e.g. Claude user to Codex */
+	async syncMcpServer(name: string, from: McpScope, to: McpScope) {
+		await invoke('sync_mcp_server', { projectPath: this.projectPath, name, from, to });
+		await this.loadMcpServers();
+	}
+
+	/** Launch a stdio server, run the MCP handshake and list its tools */
+	async checkMcpServer(entry: McpServerEntry): Promise<McpHealthReport> {
+		const report = await invoke<McpHealthReport>('check_mcp_server', {
+			config: entry.config,
+			projectPath: this.projectPath
+		});
+		this.mcpHealth = { ...this.mcpHealth, [`${entry.scope}:${entry.name}`]: report };
+		return report;
+	}
+
+	/** Would Claude allow this tool call under the saved rules of every scope? */
+	async evaluatePermission(
+		toolName: string,
+		toolInput: Record<string, unknown>
+	): Promise<PermissionEvaluation> {
+		return invoke<PermissionEvaluation>('evaluate_claude_permission', {
+			projectPath: this.projectPath,
+			toolName,
+			toolInput
+		});
+	}
+
+	async lintPermissions() {
+		try {
+			this.permissionIssues = await invoke<PermissionLintIssue[]>('lint_claude_permissions', {
+				projectPath: this.projectPath
+			});
+		} catch (e) {
+			console.warn('[ClaudeSettings] Failed to lint permission rules:', e);
+			this.permissionIssues = [];
+		}
+		return this.permissionIssues;
+	}
+
+	/**
+	 * Save the active scope. Schema errors block the write and are left in
+	 * `validationIssues`; pass `force` to save anyway.
+	 */
+	async save(force = false) {
 		this.saving = true;
 		try {
-			await invoke('save_claude_settings', {
+			const result = await invoke<SettingsSaveResult>('save_claude_settings', {
 				scope: this.activeScope,
 				projectPath: this.activeScope.startsWith('project') ? this.projectPath : null,
-				value: this.settings[this.activeScope]
+				value: this.settings[this.activeScope],
+				force
 			});
-			this.dirty = false;
+			this.validationIssues = result.issues;
+			if (result.saved) {
+				this.dirty = false;
+			}
+			return result.saved;
 		} finally {
 			this.saving = false;
 		}
@@ -159,6 +486,120 @@ export class ClaudeSettingsStore {
 		this.updateNested('permissions', perms);
 	}
 
+	/** Every hook command in the active scope, in settings order */
+	get currentHooks(): HookListItem[] {
+		const items: HookListItem[] = [];
+		for (const [event, entries] of Object.entries(this.currentSettings.hooks ?? {})) {
+			entries.forEach((entry, entryIndex) => {
+				const matcher = entry.matcher || undefined;
+				if (typeof entry.command === 'string') {
+					items.push({ event, entryIndex, hookIndex: null, matcher, command: entry.command });
+				}
+				(entry.hooks ?? []).forEach((hook, hookIndex) => {
+					if (hook.type && hook.type !== 'command') return;
+					items.push({
+						event,
+						entryIndex,
+						hookIndex,
+						matcher,
+						command: hook.command ?? '',
+						timeout: hook.timeout ?? entry.timeout
+					});
+				});
+			});
+		}
+		return items;
+	}
+
+	private hookCommand(hook: HookDefinition, base: HookCommand = {}): HookCommand {
+		const command: HookCommand = { ...base, type: 'command', command: hook.command.trim() };
+		if (hook.timeout) {
+			command.timeout = hook.timeout;
+		} else {
+			delete command.timeout;
+		}
+		return command;
+	}
+
+	/** Append a hook, grouping it with an existing entry for the same matcher */
+	addHook(hook: HookDefinition) {
+		const hooks = { ...(this.currentSettings.hooks ?? {}) };
+		const entries = [...(hooks[hook.event] ?? [])];
+		const matcher = hook.matcher?.trim() || undefined;
+		const command = this.hookCommand(hook);
+		const index = entries.findIndex(
+			(entry) => (entry.matcher || undefined) === matcher && Array.isArray(entry.hooks)
+		);
+		if (index >= 0) {
+			entries[index] = { ...entries[index], hooks: [...(entries[index].hooks ?? []), command] };
+		} else {
+			entries.push(matcher ? { matcher, hooks: [command] } : { hooks: [command] });
+		}
+		hooks[hook.event] = entries;
+		this.updateNested('hooks', hooks);
+	}
+
+	/** Drop one hook command, then any entry or event it leaves empty */
+	removeHook(location: HookLocation) {
+		const hooks = { ...(this.currentSettings.hooks ?? {}) };
+		const entries = [...(hooks[location.event] ?? [])];
+		const entry = entries[location.entryIndex];
+		if (!entry) return;
+		let next: HookEntry | null = null;
+		if (location.hookIndex !== null) {
+			const remaining = (entry.hooks ?? []).filter((_, i) => i !== location.hookIndex);
+			if (remaining.length > 0 || typeof entry.command === 'string') {
+				next = { ...entry, hooks: remaining };
+			}
+		} else if (entry.hooks?.length) {
+			next = { ...entry };
+			delete next.command;
+		}
+		if (next) {
+			entries[location.entryIndex] = next;
+		} else {
+			entries.splice(location.entryIndex, 1);
+		}
+		if (entries.length > 0) {
+			hooks[location.event] = entries;
+		} else {
+			delete hooks[location.event];
+		}
+		this.updateNested('hooks', hooks);
+	}
+
+	/** Rewrite a hook in place, or move it when its event or matcher changes */
+	updateHook(location: HookLocation, hook: HookDefinition) {
+		const entries = this.currentSettings.hooks?.[location.event] ?? [];
+		const entry = entries[location.entryIndex];
+		const sameGroup =
+			entry &&
+			hook.event === location.event &&
+			(hook.matcher?.trim() || undefined) === (entry.matcher || undefined);
+		if (!sameGroup || location.hookIndex === null) {
+			this.removeHook(location);
+			this.addHook(hook);
+			return;
+		}
+		const hookIndex = location.hookIndex;
+		const commands = [...(entry.hooks ?? [])];
+		commands[hookIndex] = this.hookCommand(hook, commands[hookIndex]);
+		const updated = [...entries];
+		updated[location.entryIndex] = { ...entry, hooks: commands };
+		this.updateNested('hooks', { ...this.currentSettings.hooks, [location.event]: updated });
+	}
+
+	/** Run a hook command against a synthetic payload for its event */
+	async testHook(hook: HookDefinition): Promise<HookTestResult> {
+		return invoke<HookTestResult>('test_claude_hook', {
+			event: hook.event,
+			matcher: hook.matcher?.trim() || null,
+			command: hook.command,
+			timeout: hook.timeout ?? null,
+			projectPath: this.projectPath
+		});
+	}
+
 	updateSandbox(partial: Record<string, unknown>) {
 		const sandbox = { ...(this.currentSettings.sandbox ?? {}), ...partial };
 		this.updateNested('sandbox', sandbox);
@@ -14,9 +14,12 @@ describe('ClaudeSettingsStore', () => {
 		store = new ClaudeSettingsStore();
 		mockInvoke('load_claude_settings', () => defaultSettings());
 		mockInvoke('list_claude_plugins', () => []);
+		mockInvoke('list_plugin_marketplaces', () => []);
 		mockInvoke('list_claude_skills', () => []);
+		mockInvoke('list_claude_definitions', () => []);
 		mockInvoke('list_claude_hooks_scripts', () => []);
-		mockInvoke('save_claude_settings', () => undefined);
+		mockInvoke('list_mcp_servers', () => []);
+		mockInvoke('save_claude_settings', () => ({ saved: true, issues: [] }));
 	});
 
 	afterEach(() => {
@@ -86,7 +89,17 @@ describe('ClaudeSettingsStore', () => {
 			const userSettings: ClaudeSettings = { language: 'en' };
 			const projectSettings: ClaudeSettings = { language: 'fr' };
 			const plugins = [{ name: 'p1', description: 'd', version: '1.0', dirName: 'p1' }];
-			const skills = [{ name: 's1', dirName: 's1', description: 'skill' }];
+			const skills = [
+				{
+					name: 's1',
+					dirName: 's1',
+					description: 'skill',
+					scope: 'user',
+					allowedTools: [],
+					path: '/home/me/.claude/skills/s1/SKILL.md',
+					issues: []
+				}
+			];
 			const hooks = [{ name: 'h1', path: '/hooks/h1' }];
 
 			clearInvokeMocks();
@@ -106,6 +119,9 @@ describe('ClaudeSettingsStore', () => {
 			expect(store.settings.project).toEqual(projectSettings);
 			expect(store.plugins).toEqual(plugins);
 			expect(store.skills).toEqual(skills);
+			expect(invokeSpy).toHaveBeenCalledWith('list_claude_skills', {
+				projectPath: '/projects/foo'
+			});
 			expect(store.hookScripts).toEqual(hooks);
 			expect(store.loaded).toBe(true);
 			expect(store.dirty).toBe(false);
@@ -165,6 +181,336 @@ describe('ClaudeSettingsStore', () => {
 		});
 	});
 
+	describe('plugins', () => {
+		it('installs into the active scope and re-reads settings', async () => {
+			mockInvoke('install_claude_plugin', () => 'formatter@team');
+			const report = { removed: true, cleared: ['/home/me/.claude/settings.json'], remaining: [] };
+			mockInvoke('uninstall_claude_plugin', () => report);
+			await store.load('/projects/foo');
+			store.setLocalOnly(true);
+			invokeSpy.mockClear();
+
+			await store.installPlugin('team', 'formatter');
+			expect(await store.uninstallPlugin('formatter@team')).toEqual(report);
+
+			expect(invokeSpy).toHaveBeenCalledWith('install_claude_plugin', {
+				marketplace: 'team',
+				plugin: 'formatter',
+				scope: 'project-local',
+				projectPath: '/projects/foo'
+			});
+			expect(invokeSpy).toHaveBeenCalledWith('uninstall_claude_plugin', {
+				pluginId: 'formatter@team',
+				projectPath: '/projects/foo'
+			});
+			const reloads = invokeSpy.mock.calls.filter(([cmd]) => cmd === 'load_claude_settings');
+			expect(reloads).toHaveLength(8);
+			expect(store.activeScope).toBe('project-local');
+		});
+
+		it('registers and removes marketplaces', async () => {
+			mockInvoke('add_plugin_marketplace', () => ({}));
+			mockInvoke('remove_plugin_marketplace', () => true);
+			await store.load(null);
+			invokeSpy.mockClear();
+
+			await store.addMarketplace('/src/team-tools');
+			await store.removeMarketplace('team');
+
+			expect(invokeSpy).toHaveBeenCalledWith('add_plugin_marketplace', {
+				path: '/src/team-tools'
+			});
+			expect(invokeSpy).toHaveBeenCalledWith('remove_plugin_marketplace', { name: 'team' });
+			const reloads = invokeSpy.mock.calls.filter(([cmd]) => cmd === 'list_plugin_marketplaces');
+			expect(reloads).toHaveLength(2);
+		});
+
+		it('converts legacy plugin lists when toggling', () => {
+			store.activeScopeGroup = 'user';
+			store.localOnly = false;
+			store.settings.user = { enabledPlugins: ['a'], disabledPlugins: ['b'] };
+
+			expect(store.isPluginEnabled('a')).toBe(true);
+			expect(store.isPluginEnabled('b')).toBe(false);
+			expect(store.isPluginEnabled('c')).toBe(true);
+
+			store.setPluginEnabled('c', false);
+
+			expect(store.settings.user).toEqual({ enabledPlugins: { a: true, b: false, c: false } });
+			expect(store.isPluginEnabled('c')).toBe(false);
+			expect(store.dirty).toBe(true);
+		});
+	});
+
+	describe('skills', () => {
+		const skill = {
+			name: 'pdf-tools',
+			description: 'Work with PDFs',
+			allowedTools: ['Read'],
+			body: '# PDF\n'
+		};
+
+		it('creates, copies and deletes skills, then reloads the list', async () => {
+			mockInvoke('save_claude_skill', () => true);
+			mockInvoke('copy_claude_skill', () => true);
+			mockInvoke('delete_claude_skill', () => true);
+			await store.load('/projects/foo');
+			invokeSpy.mockClear();
+
+			await store.saveSkill('user', skill);
+			await store.copySkill('pdf-tools', 'user', 'project');
+			await store.deleteSkill('user', 'pdf-tools');
+
+			expect(invokeSpy).toHaveBeenCalledWith('save_claude_skill', {
+				scope: 'user',
+				projectPath: '/projects/foo',
+				dirName: null,
+				skill
+			});
+			expect(invokeSpy).toHaveBeenCalledWith('copy_claude_skill', {
+				projectPath: '/projects/foo',
+				dirName: 'pdf-tools',
+				from: 'user',
+				to: 'project',
+				overwrite: false
+			});
+			expect(invokeSpy).toHaveBeenCalledWith('delete_claude_skill', {
+				scope: 'user',
+				projectPath: '/projects/foo',
+				dirName: 'pdf-tools'
+			});
+			const reloads = invokeSpy.mock.calls.filter(([cmd]) => cmd === 'list_claude_skills');
+			expect(reloads).toHaveLength(3);
+		});
+
+		it('loads a skill document for editing', async () => {
+			mockInvoke('get_claude_skill', () => skill);
+			await store.load(null);
+
+			const doc = await store.getSkill('user', 'pdf-tools');
+
+			expect(invokeSpy).toHaveBeenCalledWith('get_claude_skill', {
+				scope: 'user',
+				projectPath: null,
+				dirName: 'pdf-tools'
+			});
+			expect(doc).toEqual(skill);
+		});
+	});
+
+	describe('agents and commands', () => {
+		const reviewer = {
+			kind: 'agent' as const,
+			scope: 'project' as const,
+			name: 'reviewer',
+			fileName: 'reviewer',
+			tools: [],
+			path: '/projects/foo/.claude/agents/reviewer.md',
+			issues: [],
+			overrides: '/home/me/.claude/agents/reviewer.md'
+		};
+
+		it('lists each kind on load', async () => {
+			mockInvoke('list_claude_definitions', (args: unknown) => {
+				const { kind } = args as { kind: string };
+				return kind === 'agent' ? [reviewer] : [];
+			});
+
+			await store.load('/projects/foo');
+
+			expect(invokeSpy).toHaveBeenCalledWith('list_claude_definitions', {
+				kind: 'command',
+				projectPath: '/projects/foo'
+			});
+			expect(store.agents).toEqual([reviewer]);
+			expect(store.commands).toEqual([]);
+		});
+
+		it('saves and deletes definitions, then reloads that kind', async () => {
+			mockInvoke('save_claude_definition', () => true);
+			mockInvoke('delete_claude_definition', () => true);
+			await store.load('/projects/foo');
+			invokeSpy.mockClear();
+
+			const definition = {
+				name: 'deploy',
+				namespace: 'ops',
+				tools: ['Bash(make deploy)'],
+				body: 'Deploy\n'
+			};
+			await store.saveDefinition('command', 'user', definition, 'ship');
+			await store.deleteDefinition('command', 'user', 'ops/deploy');
+
+			expect(invokeSpy).toHaveBeenCalledWith('save_claude_definition', {
+				kind: 'command',
+				scope: 'user',
+				projectPath: '/projects/foo',
+				originalName: 'ship',
+				definition
+			});
+			expect(invokeSpy).toHaveBeenCalledWith('delete_claude_definition', {
+				kind: 'command',
+				scope: 'user',
+				projectPath: '/projects/foo',
+				fileName: 'ops/deploy'
+			});
+			const reloads = invokeSpy.mock.calls.filter(([cmd]) => cmd === 'list_claude_definitions');
+			expect(reloads).toEqual([
+				['list_claude_definitions', { kind: 'command', projectPath: '/projects/foo' }],
+				['list_claude_definitions', { kind: 'command', projectPath: '/projects/foo' }]
+			]);
+		});
+	});
+
+	describe('MCP servers', () => {
+		const entry = {
+			name: 'docs',
+			scope: 'user' as const,
+			config: { command: 'npx', args: ['-y', 'docs-server'] },
+			sourcePath: '/home/me/.claude.json'
+		};
+
+		it('lists servers for the project on load', async () => {
+			mockInvoke('list_mcp_servers', () => [entry]);
+
+			await store.load('/projects/foo');
+
+			expect(invokeSpy).toHaveBeenCalledWith('list_mcp_servers', { projectPath: '/projects/foo' });
+			expect(store.mcpServers).toEqual([entry]);
+		});
+
+		it('saves, syncs and removes servers, then reloads the list', async () => {
+			mockInvoke('save_mcp_server', () => true);
+			mockInvoke('sync_mcp_server', () => entry.config);
+			mockInvoke('remove_mcp_server', () => true);
+			await store.load('/projects/foo');
+
+			await store.saveMcpServer('project', 'docs', entry.config);
+			await store.syncMcpServer('docs', 'project', 'codex');
+			await store.removeMcpServer('project', 'docs');
+
+			expect(invokeSpy).toHaveBeenCalledWith('save_mcp_server', {
+				scope: 'project',
+				projectPath: '/projects/foo',
+				name: 'docs',
+				config: entry.config
+			});
+			expect(invokeSpy).toHaveBeenCalledWith('sync_mcp_server', {
+				projectPath: '/projects/foo',
+				name: 'docs',
+				from: 'project',
+				to: 'codex'
+			});
+			expect(invokeSpy).toHaveBeenCalledWith('remove_mcp_server', {
+				scope: 'project',
+				projectPath: '/projects/foo',
+				name: 'docs'
+			});
+			const listCalls = invokeSpy.mock.calls.filter((c) => c[0] === 'list_mcp_servers');
+			expect(listCalls).toHaveLength(4);
+		});
+
+		it('records health check results per server', async () => {
+			const report = { ok: true, tools: [{ name: 'search' }], stderr: '', durationMs: 12 };
+			mockInvoke('check_mcp_server', () => report);
+
+			const result = await store.checkMcpServer(entry);
+
+			expect(invokeSpy).toHaveBeenCalledWith('check_mcp_server', {
+				config: entry.config,
+				projectPath: null
+			});
+			expect(result).toEqual(report);
+			expect(store.mcpHealth['user:docs']).toEqual(report);
+		});
+
+		it('falls back to an empty list when listing fails', async () => {
+			const warnSpy = vi.spyOn(console, 'warn').mockImplementation(() => {});
+			mockInvoke('list_mcp_servers', () => {
+				throw new Error('bad config');
+			});
+
+			await store.loadMcpServers();
+
+			expect(store.mcpServers).toEqual([]);
+			warnSpy.mockRestore();
+		});
+	});
+
+	describe('loadEffective', () => {
+		it('loads the merged settings for the current project', async () => {
+			const effective = {
+				settings: { model: 'opus' },
+				sources: [{ scope: 'project', path: '/projects/foo/.claude/settings.json', exists: true }],
+				provenance: [{ pointer: '/model', scope: 'project', overriddenScopes: ['user'] }]
+			};
+			mockInvoke('load_effective_claude_settings', () => effective);
+			await store.load('/projects/foo');
+
+			const result = await store.loadEffective();
+
+			expect(invokeSpy).toHaveBeenCalledWith('load_effective_claude_settings', {
+				projectPath: '/projects/foo'
+			});
+			expect(result).toEqual(effective);
+			expect(store.effective).toEqual(effective);
+		});
+
+		it('clears the effective settings on failure', async () => {
+			const warnSpy = vi.spyOn(console, 'warn').mockImplementation(() => {});
+			mockInvoke('load_effective_claude_settings', () => {
+				throw new Error('boom');
+			});
+
+			const result = await store.loadEffective(null);
+
+			expect(result).toBeNull();
+			expect(store.effective).toBeNull();
+			warnSpy.mockRestore();
+		});
+	});
+
+	describe('permissions', () => {
+		const denyRule = { rule: 'Bash(git push:*)', list: 'deny' as const, scope: 'project' as const };
+
+		it('evaluates a tool call against the project rules', async () => {
+			const evaluation = { decision: 'deny', matchedRule: denyRule, reason: 'Matched deny rule' };
+			mockInvoke('evaluate_claude_permission', () => evaluation);
+			await store.load('/projects/foo');
+
+			const result = await store.evaluatePermission('Bash', { command: 'git push' });
+
+			expect(invokeSpy).toHaveBeenCalledWith('evaluate_claude_permission', {
+				projectPath: '/projects/foo',
+				toolName: 'Bash',
+				toolInput: { command: 'git push' }
+			});
+			expect(result).toEqual(evaluation);
+		});
+
+		it('stores lint issues and clears them on failure', async () => {
+			const issue = {
+				rule: { rule: 'Bash(git push origin:*)', list: 'allow', scope: 'user' },
+				severity: 'warning',
+				message: 'Never applies',
+				related: denyRule
+			};
+			mockInvoke('lint_claude_permissions', () => [issue]);
+			await store.load('/projects/foo');
+
+			await store.lintPermissions();
+			expect(store.permissionIssues).toEqual([issue]);
+
+			const warnSpy = vi.spyOn(console, 'warn').mockImplementation(() => {});
+			mockInvoke('lint_claude_permissions', () => {
+				throw new Error('boom');
+			});
+			await store.lintPermissions();
+			expect(store.permissionIssues).toEqual([]);
+			warnSpy.mockRestore();
+		});
+	});
+
 	describe('save', () => {
 		it('invokes save with the correct scope and settings', async () => {
 			store.activeScopeGroup = 'user';
@@ -176,7 +522,8 @@ describe('ClaudeSettingsStore', () => {
 			expect(invokeSpy).toHaveBeenCalledWith('save_claude_settings', {
 				scope: 'user',
 				projectPath: null,
-				value: { language: 'en' }
+				value: { language: 'en' },
+				force: false
 			});
 		});
 
@@ -191,7 +538,8 @@ describe('ClaudeSettingsStore', () => {
 			expect(invokeSpy).toHaveBeenCalledWith('save_claude_settings', {
 				scope: 'project-local',
 				projectPath: '/projects/foo',
-				value: { language: 'de' }
+				value: { language: 'de' },
+				force: false
 			});
 		});
 
@@ -205,6 +553,40 @@ describe('ClaudeSettingsStore', () => {
 			expect(store.dirty).toBe(false);
 		});
 
+		it('keeps changes dirty when validation errors block the save', async () => {
+			const issues = [
+				{
+					pointer: '/permissions/allow',
+					severity: 'error',
+					message: 'Expected array, found string'
+				}
+			];
+			mockInvoke('save_claude_settings', () => ({ saved: false, issues }));
+			store.dirty = true;
+
+			const saved = await store.save();
+
+			expect(saved).toBe(false);
+			expect(store.dirty).toBe(true);
+			expect(store.validationIssues).toEqual(issues);
+			expect(store.saveBlocked).toBe(true);
+		});
+
+		it('passes force when saving anyway', async () => {
+			const issues = [{ pointer: '/model', severity: 'error', message: 'Expected string' }];
+			mockInvoke('save_claude_settings', () => ({ saved: true, issues }));
+			store.dirty = true;
+
+			const saved = await store.save(true);
+
+			expect(invokeSpy).toHaveBeenCalledWith(
+				'save_claude_settings',
+				expect.objectContaining({ force: true })
+			);
+			expect(saved).toBe(true);
+			expect(store.dirty).toBe(false);
+		});
+
 		it('resets saving flag even on error', async () => {
 			clearInvokeMocks();
 			mockInvoke('save_claude_settings', () => {
@@ -330,6 +712,93 @@ describe('ClaudeSettingsStore', () => {
 		});
 	});
 
+	describe('hooks', () => {
+		beforeEach(() => {
+			store.activeScopeGroup = 'user';
+			store.localOnly = false;
+		});
+
+		it('groups new hooks under an entry with the same matcher', () => {
+			store.addHook({ event: 'PreToolUse', matcher: 'Bash', command: 'lint.sh' });
+			store.addHook({ event: 'PreToolUse', matcher: 'Bash', command: 'audit.sh', timeout: 5 });
+			store.addHook({ event: 'Stop', command: 'notify.sh' });
+
+			expect(store.settings.user.hooks).toEqual({
+				PreToolUse: [
+					{
+						matcher: 'Bash',
+						hooks: [
+							{ type: 'command', command: 'lint.sh' },
+							{ type: 'command', command: 'audit.sh', timeout: 5 }
+						]
+					}
+				],
+				Stop: [{ hooks: [{ type: 'command', command: 'notify.sh' }] }]
+			});
+			expect(store.currentHooks.map((hook) => hook.command)).toEqual([
+				'lint.sh',
+				'audit.sh',
+				'notify.sh'
+			]);
+			expect(store.dirty).toBe(true);
+		});
+
+		it('edits in place and moves hooks whose matcher changes', () => {
+			store.settings.user = {
+				hooks: {
+					PreToolUse: [
+						{ matcher: 'Bash', hooks: [{ type: 'command', command: 'a.sh', extra: 1 }] }
+					]
+				}
+			};
+			const location = { event: 'PreToolUse', entryIndex: 0, hookIndex: 0 };
+
+			store.updateHook(location, {
+				event: 'PreToolUse',
+				matcher: 'Bash',
+				command: 'b.sh',
+				timeout: 10
+			});
+			expect(store.settings.user.hooks?.PreToolUse[0].hooks).toEqual([
+				{ type: 'command', command: 'b.sh', extra: 1, timeout: 10 }
+			]);
+
+			store.updateHook(location, { event: 'PreToolUse', matcher: 'Edit', command: 'b.sh' });
+			expect(store.settings.user.hooks).toEqual({
+				PreToolUse: [{ matcher: 'Edit', hooks: [{ type: 'command', command: 'b.sh' }] }]
+			});
+		});
+
+		it('removes hooks and prunes empty events, including legacy entries', () => {
+			store.settings.user = {
+				hooks: { Stop: [{ command: 'old.sh' }], Notification: [{ hooks: [{ command: 'n.sh' }] }] }
+			};
+			expect(store.currentHooks).toHaveLength(2);
+
+			store.removeHook({ event: 'Stop', entryIndex: 0, hookIndex: null });
+			store.removeHook({ event: 'Notification', entryIndex: 0, hookIndex: 0 });
+
+			expect(store.settings.user.hooks).toEqual({});
+		});
+
+		it('tests a hook against the current project', async () => {
+			const result = { outcome: 'success', exitCode: 0, stdout: '', stderr: '' };
+			mockInvoke('test_claude_hook', () => result);
+			await store.load('/projects/foo');
+
+			const actual = await store.testHook({ event: 'Stop', matcher: ' ', command: 'true' });
+
+			expect(invokeSpy).toHaveBeenCalledWith('test_claude_hook', {
+				event: 'Stop',
+				matcher: null,
+				command: 'true',
+				timeout: null,
+				projectPath: '/projects/foo'
+			});
+			expect(actual).toEqual(result);
+		});
+	});
+
 	describe('updateSandbox', () => {
 		it('merges sandbox settings', () => {
 			store.activeScopeGroup = 'user';
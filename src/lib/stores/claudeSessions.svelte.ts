@@ -3,17 +3,33 @@ import { listen } from '@tauri-apps/api/event';
 import { SvelteMap, SvelteSet } from 'svelte/reactivity';
 import { stripAnsi } from '$lib/utils/format';
 import { newSessionCommandWithPrompt } from '$lib/utils/claude';
+import { effectivePath } from '$lib/utils/path';
 import { getWorkbenchSettingsStore } from './context';
 import {
 	isAISessionType,
 	type ActiveClaudeSession,
 	type AgentAction,
+	type AgentSession,
+	type ArchivedSession,
+	type AgentHookEvent,
+	type PaneAgentActivity,
 	type ClaudeHookEvent,
 	type CodexNotifyEvent,
 	type DiscoveredClaudeSession,
+	type SessionExportFormat,
+	type SessionHousekeepingAction,
+	type SessionHousekeepingReport,
+	type SessionPrunePolicy,
+	type SessionSearchHit,
+	type SessionTouchedFiles,
+	type SessionsChangedEvent,
 	type SessionType,
 	type TerminalActivityEvent,
-	type TerminalDataEvent
+	type TerminalDataEvent,
+	type TranscriptPage,
+	type UsageGroupBy,
+	type UsageRange,
+	type UsageSummary
 } from '$types/workbench';
 import type { IntegrationApprovalStore } from './integration-approval.svelte';
 import type { WorkspaceStore } from './workspaces.svelte';
@@ -32,12 +48,19 @@ export class ClaudeSessionStore {
 	/** Set of terminal pane IDs where Claude is blocked waiting for user action (permission/question) */
 	panesAwaitingInput: SvelteSet<string> = $state(new SvelteSet());
 
+	/** Per-pane subagent tree and compaction history from Claude hooks */
+	paneActivity = new SvelteMap<string, PaneAgentActivity>();
+
 	/** Cached discovered Claude sessions for the current project */
 	discoveredSessions: DiscoveredClaudeSession[] = $state([]);
 
 	/** Cached discovered Codex sessions for the current project */
 	discoveredCodexSessions: DiscoveredClaudeSession[] = $state([]);
 
+	/** Project last passed to discoverSessions / discoverCodexSessions, re-queried on `sessions:changed` */
+	private discoveredProject: string | null = null;
+	private discoveredCodexProject: string | null = null;
+
 	/** Per-pane fallback timeout if no output arrives after Enter submit */
 	private submitFallbackTimeouts = new SvelteMap<string, ReturnType<typeof setTimeout>>();
 	/** Last timestamp when local user input was sent to a pane */
@@ -48,6 +71,8 @@ export class ClaudeSessionStore {
 	private lastViewportChangeAt = new SvelteMap<string, number>();
 	/** Latest Claude session ID observed for each pane from hook events */
 	private latestClaudeSessionByPane = new SvelteMap<string, string>();
+	/** Session named by the last SessionStart hook of each pane */
+	private startedClaudeSessionByPane = new SvelteMap<string, string>();
 	/** Latest Codex session ID observed for each pane from notify events */
 	private latestCodexSessionByPane = new SvelteMap<string, string>();
 	/** Cache of sessionId → resolved label. `null` means discovery ran but no label found yet. */
@@ -103,8 +128,18 @@ export class ClaudeSessionStore {
 		return result;
 	});
 
+	/** Session IDs of every open pane, including ones not yet written to workspaces.json */
+	private get openSessionIds(): string[] {
+		return this.workspaces.workspaces.flatMap((ws) =>
+			ws.terminalTabs.flatMap((tab) =>
+				tab.panes.flatMap((pane) => (pane.claudeSessionId ? [pane.claudeSessionId] : []))
+			)
+		);
+	}
+
 	/** Read Claude CLI session files from ~/.claude/projects/ */
 	async discoverSessions(projectPath: string): Promise<DiscoveredClaudeSession[]> {
+		this.discoveredProject = projectPath;
 		try {
 			const sessions = await invoke<DiscoveredClaudeSession[]>('discover_claude_sessions', {
 				projectPath
@@ -119,6 +154,7 @@ export class ClaudeSessionStore {
 
 	/** Read Codex session files from ~/.codex/sessions/ filtered by cwd */
 	async discoverCodexSessions(projectPath: string): Promise<DiscoveredClaudeSession[]> {
+		this.discoveredCodexProject = projectPath;
 		try {
 			const sessions = await invoke<DiscoveredClaudeSession[]>('discover_codex_sessions', {
 				projectPath
@@ -131,6 +167,211 @@ export class ClaudeSessionStore {
 		}
 	}
 
+	/** Sessions from every agent provider (or only `providers`), newest first */
+	async discoverAgentSessions(projectPath: string, providers?: string[]): Promise<AgentSession[]> {
+		try {
+			return await invoke<AgentSession[]>('discover_agent_sessions', {
+				projectPath,
+				providers: providers ?? null
+			});
+		} catch (e) {
+			console.error('[ClaudeSessionStore] Failed to discover agent sessions:', e);
+			return [];
+		}
+	}
+
+	/** Delete a session's transcript from disk. Returns false if it could not be removed. */
+	async deleteSession(provider: string, projectPath: string, sessionId: string): Promise<boolean> {
+		try {
+			await invoke('delete_agent_session', {
+				provider,
+				projectPath,
+				sessionId,
+				openSessionIds: this.openSessionIds
+			});
+		} catch (e) {
+			console.error('[ClaudeSessionStore] Failed to delete session:', e);
+			return false;
+		}
+		if (provider === 'codex') {
+			this.discoveredCodexSessions = this.discoveredCodexSessions.filter(
+				(s) => s.sessionId !== sessionId
+			);
+		} else {
+			this.discoveredSessions = this.discoveredSessions.filter((s) => s.sessionId !== sessionId);
+		}
+		return true;
+	}
+
+	/**
+	 * Archive or delete specific sessions. Sessions open in a pane are skipped.
+	 * With `dryRun` nothing changes and the report previews the result.
+	 */
+	async housekeepSessions(
+		action: Exclude<SessionHousekeepingAction, 'restore'>,
+		provider: string,
+		projectPath: string,
+		sessionIds: string[],
+		dryRun = false
+	): Promise<SessionHousekeepingReport | null> {
+		const command = action === 'archive' ? 'archive_sessions' : 'delete_sessions';
+		try {
+			return await invoke<SessionHousekeepingReport>(command, {
+				provider,
+				projectPath,
+				sessionIds,
+				openSessionIds: this.openSessionIds,
+				dryRun
+			});
+		} catch (e) {
+			console.error(`[ClaudeSessionStore] Failed to ${action} sessions:`, e);
+			return null;
+		}
+	}
+
+	/** Archive or delete a project's sessions outside the age/size policy */
+	async pruneSessions(
+		projectPath: string,
+		policy: SessionPrunePolicy,
+		action: Exclude<SessionHousekeepingAction, 'restore'>,
+		dryRun = false
+	): Promise<SessionHousekeepingReport | null> {
+		try {
+			return await invoke<SessionHousekeepingReport>('prune_sessions', {
+				projectPath,
+				policy,
+				openSessionIds: this.openSessionIds,
+				action,
+				dryRun
+			});
+		} catch (e) {
+			console.error('[ClaudeSessionStore] Failed to prune sessions:', e);
+			return null;
+		}
+	}
+
+	/** Sessions in the archive, optionally only those archived from `projectPath` */
+	async archivedSessions(projectPath?: string): Promise<ArchivedSession[]> {
+		try {
+			return await invoke<ArchivedSession[]>('list_archived_sessions', {
+				projectPath: projectPath ?? null
+			});
+		} catch (e) {
+			console.error('[ClaudeSessionStore] Failed to list archived sessions:', e);
+			return [];
+		}
+	}
+
+	/** Move archived sessions back into the agent's store */
+	async restoreSessions(
+		provider: string,
+		sessionIds: string[],
+		dryRun = false
+	): Promise<SessionHousekeepingReport | null> {
+		try {
+			return await invoke<SessionHousekeepingReport>('restore_sessions', {
+				provider,
+				sessionIds,
+				dryRun
+			});
+		} catch (e) {
+			console.error('[ClaudeSessionStore] Failed to restore sessions:', e);
+			return null;
+		}
+	}
+
+	/** Load a page of a Claude session transcript, oldest message first */
+	async loadTranscript(
+		projectPath: string,
+		sessionId: string,
+		offset = 0,
+		limit?: number
+	): Promise<TranscriptPage | null> {
+		try {
+			return await invoke<TranscriptPage>('load_claude_transcript', {
+				projectPath,
+				sessionId,
+				offset,
+				limit
+			});
+		} catch (e) {
+			console.error('[ClaudeSessionStore] Failed to load transcript:', e);
+			return null;
+		}
+	}
+
+	/** Full-text search over Claude and Codex sessions; omit projectPath to search every project */
+	async searchSessions(
+		query: string,
+		projectPath?: string,
+		limit?: number
+	): Promise<SessionSearchHit[]> {
+		if (!query.trim()) return [];
+		try {
+			return await invoke<SessionSearchHit[]>('search_sessions', { query, projectPath, limit });
+		} catch (e) {
+			console.error('[ClaudeSessionStore] Failed to search sessions:', e);
+			return [];
+		}
+	}
+
+	/** Files a session wrote, with their current git status */
+	async touchedFiles(
+		sessionId: string,
+		projectPath: string,
+		type: SessionType = 'claude'
+	): Promise<SessionTouchedFiles | null> {
+		try {
+			return await invoke<SessionTouchedFiles>('session_touched_files', {
+				agent: type,
+				projectPath,
+				sessionId
+			});
+		} catch (e) {
+			console.error('[ClaudeSessionStore] Failed to list touched files:', e);
+			return null;
+		}
+	}
+
+	/**
+	 * Render a session as Markdown or HTML with configured secrets redacted.
+	 * Also writes the document to `outputPath` when given.
+	 */
+	async exportSession(
+		sessionId: string,
+		projectPath: string,
+		format: SessionExportFormat,
+		type: SessionType = 'claude',
+		outputPath?: string
+	): Promise<string | null> {
+		try {
+			return await invoke<string>('export_session', {
+				agent: type,
+				projectPath,
+				sessionId,
+				format,
+				outputPath: outputPath ?? null
+			});
+		} catch (e) {
+			console.error('[ClaudeSessionStore] Failed to export session:', e);
+			return null;
+		}
+	}
+
+	/** Token usage and cost across sessions; omit projectPath to include every project */
+	async usageSummary(
+		range: UsageRange,
+		groupBy: UsageGroupBy,
+		projectPath?: string
+	): Promise<UsageSummary | null> {
+		try {
+			return await invoke<UsageSummary>('usage_summary', { range, groupBy, projectPath });
+		} catch (e) {
+			console.error('[ClaudeSessionStore] Failed to load usage summary:', e);
+			return null;
+		}
+	}
+
 	/** Remove a session from the discovered list (does not delete the JSONL file) */
 	removeDiscoveredSession(sessionId: string, type: SessionType = 'claude'): void {
 		if (type === 'codex') {
@@ -173,15 +414,42 @@ export class ClaudeSessionStore {
 		await this.startSession(ws.id, type);
 	}
 
-	/** Resume an existing AI session, gated through integration approval */
+	/**
+	 * Resume an existing AI session, gated through integration approval.
+	 * When `origin` names another worktree of the project, the session resumes in
+	 * that worktree's workspace, which is opened if needed.
+	 */
 	async resumeSession(
 		workspaceId: string,
 		sessionId: string,
 		label: string,
-		type: SessionType = 'claude'
+		type: SessionType = 'claude',
+		origin?: Pick<DiscoveredClaudeSession, 'worktreePath' | 'branch'>
 	) {
 		if (!(await this.integrationApproval.ensureIntegration(type))) return;
-		this.workspaces.resumeAISession(workspaceId, sessionId, label, type);
+		const targetId = this.workspaceForSession(workspaceId, origin) ?? workspaceId;
+		this.workspaces.resumeAISession(targetId, sessionId, label, type);
+	}
+
+	/** Workspace for the checkout a session ran in, opening it if needed */
+	private workspaceForSession(
+		workspaceId: string,
+		origin?: Pick<DiscoveredClaudeSession, 'worktreePath' | 'branch'>
+	): string | undefined {
+		const ws = this.workspaces.workspaces.find((w) => w.id === workspaceId);
+		const worktreePath = origin?.worktreePath;
+		if (!ws || !worktreePath || effectivePath(ws) === worktreePath) return undefined;
+
+		if (worktreePath === ws.projectPath) {
+			this.projects.openProject(ws.projectPath);
+			return this.workspaces.getByProjectPath(ws.projectPath)?.id;
+		}
+		if (!this.workspaces.getByWorktreePath(worktreePath)) {
+			const project = this.projects.getByPath(ws.projectPath);
+			if (!project) return undefined;
+			this.workspaces.openWorktree(project, worktreePath, origin?.branch ?? '');
+		}
+		return this.workspaces.getByWorktreePath(worktreePath)?.id;
 	}
 
 	/** Restart an AI session, gated through integration approval */
@@ -224,6 +492,11 @@ export class ClaudeSessionStore {
 		return this.paneTypeById[paneId] ?? null;
 	}
 
+	/** Drop the activity tree of a closed pane; the backend forgets it when the terminal exits. */
+	forgetPaneActivity(paneId: string): void {
+		this.paneActivity.delete(paneId);
+	}
+
 	/** Mark that local keyboard input was sent for a pane (used to suppress echoed characters). */
 	noteLocalInput(paneId: string, data: string): void {
 		if (this.paneType(paneId) !== 'codex') return;
@@ -355,11 +628,26 @@ export class ClaudeSessionStore {
 		return message.includes('waiting for your input') || message.includes('waiting for input');
 	}
 
+	/**
+	 * `/clear`, `--resume` and compaction fire SessionStart with a new ID in the
+	 * same pane. Hooks run as separate processes, so events of the replaced
+	 * session can arrive after it and must not bring the old ID back.
+	 */
+	private isReplacedClaudeSession(paneId: string, event: ClaudeHookEvent): boolean {
+		if (!event.sessionId) return false;
+		if (event.hookEventName === 'SessionStart') {
+			this.startedClaudeSessionByPane.set(paneId, event.sessionId);
+			return false;
+		}
+		const started = this.startedClaudeSessionByPane.get(paneId);
+		return started !== undefined && started !== event.sessionId;
+	}
+
 	private onClaudeHookEvent(event: ClaudeHookEvent): void {
 		const paneId = event.paneId;
 		if (this.paneType(paneId) !== 'claude') return;
 
-		if (event.sessionId) {
+		if (event.sessionId && !this.isReplacedClaudeSession(paneId, event)) {
 			this.workspaces.updateAISessionByPaneId(paneId, event.sessionId, 'claude');
 			this.latestClaudeSessionByPane.set(paneId, event.sessionId);
 			void this.syncLabelFromSession(paneId, event.sessionId, 'claude');
@@ -378,6 +666,7 @@ export class ClaudeSessionStore {
 				break;
 			}
 			case 'SessionStart':
+			case 'SessionEnd':
 				this.panesInProgress.delete(paneId);
 				this.panesAwaitingInput.delete(paneId);
 				break;
@@ -456,6 +745,41 @@ export class ClaudeSessionStore {
 		}
 	}
 
+	/** Re-query the last discovered project when the backend index sees session file changes. */
+	private onSessionsChanged(event: SessionsChangedEvent): void {
+		if (event.agent === 'claude' && this.discoveredProject) {
+			void this.discoverSessions(this.discoveredProject);
+		} else if (event.agent === 'codex' && this.discoveredCodexProject) {
+			void this.discoverCodexSessions(this.discoveredCodexProject);
+		}
+	}
+
+	private onAgentEvent(event: AgentHookEvent): void {
+		const paneId = event.paneId;
+		switch (event.event) {
+			case 'turn-started':
+			case 'tool-used':
+				this.panesInProgress.add(paneId);
+				this.panesAwaitingInput.delete(paneId);
+				this.clearSubmitFallback(paneId);
+				break;
+			case 'needs-input': {
+				const wasAwaiting = this.panesAwaitingInput.has(paneId);
+				this.panesInProgress.delete(paneId);
+				this.panesAwaitingInput.add(paneId);
+				if (!wasAwaiting) this.emitAwaitingInput(paneId);
+				break;
+			}
+			case 'turn-finished': {
+				const wasInProgress = this.panesInProgress.has(paneId);
+				this.panesInProgress.delete(paneId);
+				this.clearSubmitFallback(paneId);
+				if (wasInProgress) this.emitAwaitingInput(paneId);
+				break;
+			}
+		}
+	}
+
 	constructor(
 		workspaces: WorkspaceStore,
 		projects: ProjectStore,
@@ -471,6 +795,15 @@ export class ClaudeSessionStore {
 		listen<CodexNotifyEvent>('codex:notify', (event) => {
 			this.onCodexNotifyEvent(event.payload);
 		});
+		listen<PaneAgentActivity>('claude:activity', (event) => {
+			this.paneActivity.set(event.payload.paneId, event.payload);
+		});
+		listen<AgentHookEvent>('agent:event', (event) => {
+			this.onAgentEvent(event.payload);
+		});
+		listen<SessionsChangedEvent>('sessions:changed', (event) => {
+			this.onSessionsChanged(event.payload);
+		});
 
 		listen<TerminalDataEvent>('terminal:data', (event) => {
 			const paneId = event.payload.sessionId;
@@ -23,13 +23,18 @@ function createMockWorkspaceStore(workspaces: unknown[] = []) {
 		addAIByProject: vi.fn(),
 		updateAISessionByPaneId: vi.fn(),
 		updateAITabLabelByPaneId: vi.fn(),
-		findAIPaneContext: vi.fn()
+		findAIPaneContext: vi.fn(),
+		resumeAISession: vi.fn(),
+		getByProjectPath: vi.fn(),
+		getByWorktreePath: vi.fn(),
+		openWorktree: vi.fn()
 	} as unknown as WorkspaceStore;
 }
 
 function createMockProjectStore() {
 	return {
-		openProject: vi.fn()
+		openProject: vi.fn(),
+		getByPath: vi.fn()
 	} as unknown as ProjectStore;
 }
 
@@ -62,8 +67,8 @@ describe('ClaudeSessionStore', () => {
 	});
 
 	describe('constructor', () => {
-		it('registers 4 event listeners', () => {
-			expect(listenSpy).toHaveBeenCalledTimes(4);
+		it('registers 7 event listeners', () => {
+			expect(listenSpy).toHaveBeenCalledTimes(7);
 		});
 
 		it('registers a claude:hook listener', () => {
@@ -74,6 +79,18 @@ describe('ClaudeSessionStore', () => {
 			expect(listenSpy).toHaveBeenCalledWith('codex:notify', expect.any(Function));
 		});
 
+		it('registers a claude:activity listener', () => {
+			expect(listenSpy).toHaveBeenCalledWith('claude:activity', expect.any(Function));
+		});
+
+		it('registers an agent:event listener', () => {
+			expect(listenSpy).toHaveBeenCalledWith('agent:event', expect.any(Function));
+		});
+
+		it('registers a sessions:changed listener', () => {
+			expect(listenSpy).toHaveBeenCalledWith('sessions:changed', expect.any(Function));
+		});
+
 		it('registers a terminal:data listener', () => {
 			expect(listenSpy).toHaveBeenCalledWith('terminal:data', expect.any(Function));
 		});
@@ -117,6 +134,273 @@ describe('ClaudeSessionStore', () => {
 		});
 	});
 
+	describe('loadTranscript', () => {
+		it('invokes load_claude_transcript with pagination args', async () => {
+			const page = { sessionId: 'sess-1', messages: [], offset: 50, total: 60, hasMore: false };
+			mockInvoke('load_claude_transcript', () => page);
+
+			const result = await store.loadTranscript('/projects/test', 'sess-1', 50, 25);
+
+			expect(invokeSpy).toHaveBeenCalledWith('load_claude_transcript', {
+				projectPath: '/projects/test',
+				sessionId: 'sess-1',
+				offset: 50,
+				limit: 25
+			});
+			expect(result).toEqual(page);
+		});
+
+		it('returns null on failure', async () => {
+			const errorSpy = vi.spyOn(console, 'error').mockImplementation(() => {});
+			mockInvoke('load_claude_transcript', () => {
+				throw new Error('missing');
+			});
+
+			expect(await store.loadTranscript('/projects/test', 'sess-1')).toBeNull();
+			errorSpy.mockRestore();
+		});
+	});
+
+	describe('searchSessions', () => {
+		it('invokes search_sessions with the query and scope', async () => {
+			const hits = [
+				{
+					agent: 'claude',
+					sessionId: 'sess-1',
+					projectPath: '/projects/test',
+					label: 'Fix auth',
+					timestamp: '2025-01-01T00:00:00Z',
+					kind: 'user',
+					matchedAt: '2025-01-01T00:00:00Z',
+					snippet: 'fix the auth redirect',
+					matchCount: 1
+				}
+			];
+			mockInvoke('search_sessions', () => hits);
+
+			const result = await store.searchSessions('auth redirect', '/projects/test');
+
+			expect(invokeSpy).toHaveBeenCalledWith('search_sessions', {
+				query: 'auth redirect',
+				projectPath: '/projects/test',
+				limit: undefined
+			});
+			expect(result).toEqual(hits);
+		});
+
+		it('skips blank queries', async () => {
+			expect(await store.searchSessions('   ')).toEqual([]);
+			expect(invokeSpy).not.toHaveBeenCalled();
+		});
+	});
+
+	describe('discoverAgentSessions', () => {
+		it('invokes discover_agent_sessions with the provider filter', async () => {
+			const sessions = [
+				{ provider: 'codex', sessionId: 'c1', label: 'Codex work', timestamp: '2025-01-02' },
+				{ provider: 'claude', sessionId: 's1', label: 'Claude work', timestamp: '2025-01-01' }
+			];
+			mockInvoke('discover_agent_sessions', () => sessions);
+
+			expect(await store.discoverAgentSessions('/repo')).toEqual(sessions);
+			expect(invokeSpy).toHaveBeenCalledWith('discover_agent_sessions', {
+				projectPath: '/repo',
+				providers: null
+			});
+
+			await store.discoverAgentSessions('/repo', ['codex']);
+			expect(invokeSpy).toHaveBeenLastCalledWith('discover_agent_sessions', {
+				projectPath: '/repo',
+				providers: ['codex']
+			});
+		});
+	});
+
+	describe('deleteSession', () => {
+		it('removes the session from the discovered list', async () => {
+			mockInvoke('discover_claude_sessions', () => [
+				{ sessionId: 's1', label: 'One', timestamp: '2025-01-02' },
+				{ sessionId: 's2', label: 'Two', timestamp: '2025-01-01' }
+			]);
+			mockInvoke('delete_agent_session', () => true);
+			await store.discoverSessions('/repo');
+
+			expect(await store.deleteSession('claude', '/repo', 's1')).toBe(true);
+			expect(invokeSpy).toHaveBeenCalledWith('delete_agent_session', {
+				provider: 'claude',
+				projectPath: '/repo',
+				sessionId: 's1',
+				openSessionIds: []
+			});
+			expect(store.discoveredSessions.map((s) => s.sessionId)).toEqual(['s2']);
+		});
+
+		it('keeps the list when deletion fails', async () => {
+			mockInvoke('discover_claude_sessions', () => [
+				{ sessionId: 's1', label: 'One', timestamp: '2025-01-02' }
+			]);
+			mockInvoke('delete_agent_session', () => {
+				throw new Error('Session file not found');
+			});
+			await store.discoverSessions('/repo');
+
+			expect(await store.deleteSession('claude', '/repo', 's1')).toBe(false);
+			expect(store.discoveredSessions).toHaveLength(1);
+		});
+	});
+
+	describe('housekeepSessions', () => {
+		it('routes archive and delete to their commands with the open pane sessions', async () => {
+			(mockWorkspaceStore.workspaces as unknown[]).push({
+				id: 'ws-1',
+				projectPath: '/repo',
+				terminalTabs: [
+					{ id: 'tab-1', panes: [{ id: 'p1', claudeSessionId: 's9' }, { id: 'p2' }] }
+				]
+			});
+			const report = {
+				action: 'archive',
+				dryRun: true,
+				sessions: [],
+				skipped: [{ provider: 'claude', sessionId: 's1', reason: 'Open in a terminal pane' }],
+				totalBytes: 0,
+				archiveDir: '/home/u/.workbench/archive/sessions'
+			};
+			mockInvoke('archive_sessions', () => report);
+			mockInvoke('delete_sessions', () => ({ ...report, action: 'delete', dryRun: false }));
+
+			expect(await store.housekeepSessions('archive', 'claude', '/repo', ['s1'], true)).toEqual(
+				report
+			);
+			expect(invokeSpy).toHaveBeenCalledWith('archive_sessions', {
+				provider: 'claude',
+				projectPath: '/repo',
+				sessionIds: ['s1'],
+				openSessionIds: ['s9'],
+				dryRun: true
+			});
+
+			await store.housekeepSessions('delete', 'codex', '/repo', ['c1']);
+			expect(invokeSpy).toHaveBeenLastCalledWith('delete_sessions', {
+				provider: 'codex',
+				projectPath: '/repo',
+				sessionIds: ['c1'],
+				openSessionIds: ['s9'],
+				dryRun: false
+			});
+		});
+	});
+
+	describe('pruneSessions', () => {
+		it('invokes prune_sessions with the policy', async () => {
+			mockInvoke('prune_sessions', () => {
+				throw new Error('workspaces.json is corrupt');
+			});
+
+			expect(await store.pruneSessions('/repo', { olderThanDays: 30 }, 'delete', true)).toBeNull();
+			expect(invokeSpy).toHaveBeenCalledWith('prune_sessions', {
+				projectPath: '/repo',
+				policy: { olderThanDays: 30 },
+				openSessionIds: [],
+				action: 'delete',
+				dryRun: true
+			});
+		});
+	});
+
+	describe('restoreSessions', () => {
+		it('lists the archive and restores by provider and ID', async () => {
+			const archived = {
+				provider: 'claude',
+				sessionId: 's1',
+				label: 'Fix the loader',
+				projectPath: '/repo',
+				sizeBytes: 120,
+				modifiedAt: null,
+				archivedAt: '2026-01-02T00:00:00Z',
+				files: [{ original: '/p/s1.jsonl', archive: '/a/claude/s1.jsonl.gz' }]
+			};
+			mockInvoke('list_archived_sessions', () => [archived]);
+			mockInvoke('restore_sessions', () => {
+				throw new Error('manifest.json is corrupt');
+			});
+
+			expect(await store.archivedSessions('/repo')).toEqual([archived]);
+			expect(invokeSpy).toHaveBeenCalledWith('list_archived_sessions', { projectPath: '/repo' });
+
+			expect(await store.restoreSessions('claude', ['s1'])).toBeNull();
+			expect(invokeSpy).toHaveBeenCalledWith('restore_sessions', {
+				provider: 'claude',
+				sessionIds: ['s1'],
+				dryRun: false
+			});
+		});
+	});
+
+	describe('touchedFiles', () => {
+		it('invokes session_touched_files with the agent type', async () => {
+			const result = { sessionId: 'codex-1', repoRoot: '/repo', files: [], changedPaths: [] };
+			mockInvoke('session_touched_files', () => result);
+
+			expect(await store.touchedFiles('codex-1', '/repo', 'codex')).toEqual(result);
+			expect(invokeSpy).toHaveBeenCalledWith('session_touched_files', {
+				agent: 'codex',
+				projectPath: '/repo',
+				sessionId: 'codex-1'
+			});
+		});
+	});
+
+	describe('exportSession', () => {
+		it('invokes export_session and returns the document', async () => {
+			mockInvoke('export_session', () => '# Claude session s1\n');
+
+			expect(await store.exportSession('s1', '/repo', 'markdown')).toBe('# Claude session s1\n');
+			expect(invokeSpy).toHaveBeenCalledWith('export_session', {
+				agent: 'claude',
+				projectPath: '/repo',
+				sessionId: 's1',
+				format: 'markdown',
+				outputPath: null
+			});
+		});
+
+		it('returns null when the export fails', async () => {
+			mockInvoke('export_session', () => {
+				throw new Error('Session not found');
+			});
+
+			expect(await store.exportSession('s1', '/repo', 'html', 'codex', '/tmp/s1.html')).toBeNull();
+		});
+	});
+
+	describe('usageSummary', () => {
+		it('invokes usage_summary with range and grouping', async () => {
+			const summary = {
+				groupBy: 'day',
+				buckets: [],
+				total: {
+					inputTokens: 0,
+					outputTokens: 0,
+					cacheCreationInputTokens: 0,
+					cacheReadInputTokens: 0
+				},
+				totalCostUsd: 0,
+				unpricedModels: []
+			};
+			mockInvoke('usage_summary', () => summary);
+
+			const result = await store.usageSummary({ from: '2025-01-01' }, 'day', '/projects/test');
+
+			expect(invokeSpy).toHaveBeenCalledWith('usage_summary', {
+				range: { from: '2025-01-01' },
+				groupBy: 'day',
+				projectPath: '/projects/test'
+			});
+			expect(result).toEqual(summary);
+		});
+	});
+
 	describe('removeDiscoveredSession', () => {
 		it('filters claude sessions by default', () => {
 			store.discoveredSessions = [
@@ -161,6 +445,125 @@ describe('ClaudeSessionStore', () => {
 		});
 	});
 
+	describe('agent:event events', () => {
+		it('turn-started marks the pane in progress', () => {
+			emitMockEvent('agent:event', {
+				paneId: 'pane-9',
+				agent: 'gemini',
+				event: 'turn-started',
+				payload: null
+			});
+
+			expect(store.panesInProgress.has('pane-9')).toBe(true);
+		});
+
+		it('needs-input moves the pane to awaiting input', () => {
+			const awaiting = vi.fn();
+			store.onAwaitingInput(awaiting);
+			emitMockEvent('agent:event', {
+				paneId: 'pane-9',
+				agent: 'aider',
+				event: 'turn-started',
+				payload: null
+			});
+			emitMockEvent('agent:event', {
+				paneId: 'pane-9',
+				agent: 'aider',
+				event: 'needs-input',
+				payload: null
+			});
+
+			expect(store.panesInProgress.has('pane-9')).toBe(false);
+			expect(store.panesAwaitingInput.has('pane-9')).toBe(true);
+			expect(awaiting).toHaveBeenCalledWith('pane-9');
+		});
+
+		it('turn-finished clears in-progress state', () => {
+			emitMockEvent('agent:event', {
+				paneId: 'pane-9',
+				agent: 'opencode',
+				event: 'tool-used',
+				toolName: 'edit',
+				payload: {}
+			});
+			emitMockEvent('agent:event', {
+				paneId: 'pane-9',
+				agent: 'opencode',
+				event: 'turn-finished',
+				payload: {}
+			});
+
+			expect(store.panesInProgress.has('pane-9')).toBe(false);
+		});
+	});
+
+	describe('claude:activity events', () => {
+		it('stores the latest activity snapshot per pane', () => {
+			const snapshot = {
+				paneId: 'pane-1',
+				sessionId: 'sess-1',
+				subagents: [
+					{
+						id: 'toolu_1',
+						parentId: null,
+						description: 'Find callers',
+						subagentType: 'Explore',
+						status: 'running',
+						startedAt: '2025-01-01T00:00:00Z',
+						finishedAt: null
+					}
+				],
+				compactions: [],
+				endedAt: null,
+				endReason: null
+			};
+
+			emitMockEvent('claude:activity', snapshot);
+
+			expect(store.paneActivity.get('pane-1')).toEqual(snapshot);
+		});
+
+		it('forgets the snapshot when the pane closes', () => {
+			emitMockEvent('claude:activity', {
+				paneId: 'pane-1',
+				sessionId: 'sess-1',
+				subagents: [],
+				compactions: [],
+				endedAt: null,
+				endReason: null
+			});
+
+			store.forgetPaneActivity('pane-1');
+
+			expect(store.paneActivity.has('pane-1')).toBe(false);
+		});
+	});
+
+	describe('sessions:changed events', () => {
+		it('re-discovers sessions for the last discovered project', async () => {
+			mockInvoke('discover_claude_sessions', () => []);
+			await store.discoverSessions('/projects/test');
+			invokeSpy.mockClear();
+
+			const sessions: DiscoveredClaudeSession[] = [
+				{ sessionId: 'sess-1', label: 'New session', timestamp: '2025-01-01T00:00:00Z' }
+			];
+			mockInvoke('discover_claude_sessions', () => sessions);
+			emitMockEvent('sessions:changed', { agent: 'claude', paths: ['/x/sess-1.jsonl'] });
+			await vi.waitFor(() => expect(store.discoveredSessions).toEqual(sessions));
+
+			expect(invokeSpy).toHaveBeenCalledWith('discover_claude_sessions', {
+				projectPath: '/projects/test'
+			});
+			expect(invokeSpy).not.toHaveBeenCalledWith('discover_codex_sessions', expect.anything());
+		});
+
+		it('ignores changes before any discovery', () => {
+			emitMockEvent('sessions:changed', { agent: 'codex', paths: ['/x/rollout.jsonl'] });
+			expect(invokeSpy).not.toHaveBeenCalled();
+		});
+	});
+
 	describe('claude:hook events', () => {
 		function setupClaudePane() {
 			(mockWorkspaceStore as { workspaces: unknown[] }).workspaces = [
@@ -236,6 +639,23 @@ describe('ClaudeSessionStore', () => {
 			expect(store.panesInProgress.has('pane-1')).toBe(false);
 		});
 
+		it('SessionEnd removes pane from panesInProgress', () => {
+			setupClaudePane();
+
+			emitMockEvent('claude:hook', {
+				paneId: 'pane-1',
+				hookEventName: 'UserPromptSubmit',
+				hookPayload: {}
+			});
+			emitMockEvent('claude:hook', {
+				paneId: 'pane-1',
+				hookEventName: 'SessionEnd',
+				hookPayload: { reason: 'clear' }
+			});
+
+			expect(store.panesInProgress.has('pane-1')).toBe(false);
+		});
+
 		it('with sessionId updates workspace store', () => {
 			setupClaudePane();
 
@@ -253,6 +673,34 @@ describe('ClaudeSessionStore', () => {
 			);
 		});
 
+		it('SessionStart after /clear keeps the new session over late events of the old one', () => {
+			setupClaudePane();
+			const update = mockWorkspaceStore.updateAISessionByPaneId as ReturnType<typeof vi.fn>;
+
+			emitMockEvent('claude:hook', {
+				paneId: 'pane-1',
+				sessionId: 'session-old',
+				hookEventName: 'UserPromptSubmit',
+				hookPayload: {}
+			});
+			emitMockEvent('claude:hook', {
+				paneId: 'pane-1',
+				sessionId: 'session-new',
+				hookEventName: 'SessionStart',
+				source: 'clear',
+				hookPayload: { source: 'clear' }
+			});
+			emitMockEvent('claude:hook', {
+				paneId: 'pane-1',
+				sessionId: 'session-old',
+				hookEventName: 'SessionEnd',
+				hookPayload: { reason: 'clear' }
+			});
+
+			expect(update).toHaveBeenCalledTimes(2);
+			expect(update).toHaveBeenLastCalledWith('pane-1', 'session-new', 'claude');
+		});
+
 		it('with sessionId updates tab label', () => {
 			setupClaudePane();
 
@@ -688,4 +1136,70 @@ describe('ClaudeSessionStore', () => {
 			errorSpy.mockRestore();
 		});
 	});
+
+	describe('resumeSession', () => {
+		const workspace = { id: 'ws-1', projectPath: '/projects/test' };
+
+		beforeEach(() => {
+			(mockWorkspaceStore as unknown as { workspaces: unknown[] }).workspaces = [workspace];
+		});
+
+		it('resumes in the given workspace when the session ran there', async () => {
+			await store.resumeSession('ws-1', 'sess-1', 'Session', 'claude', {
+				worktreePath: '/projects/test'
+			});
+
+			expect(mockWorkspaceStore.resumeAISession).toHaveBeenCalledWith(
+				'ws-1',
+				'sess-1',
+				'Session',
+				'claude'
+			);
+			expect(mockWorkspaceStore.openWorktree).not.toHaveBeenCalled();
+		});
+
+		it('opens the worktree workspace a session ran in', async () => {
+			const project = { name: 'test', path: '/projects/test' };
+			vi.mocked(mockProjectStore.getByPath).mockReturnValue(project as never);
+			vi.mocked(mockWorkspaceStore.getByWorktreePath)
+				.mockReturnValueOnce(undefined)
+				.mockReturnValueOnce({ id: 'ws-feature' } as never);
+
+			await store.resumeSession('ws-1', 'sess-1', 'Session', 'codex', {
+				worktreePath: '/projects/test-feature',
+				branch: 'feature'
+			});
+
+			expect(mockWorkspaceStore.openWorktree).toHaveBeenCalledWith(
+				project,
+				'/projects/test-feature',
+				'feature'
+			);
+			expect(mockWorkspaceStore.resumeAISession).toHaveBeenCalledWith(
+				'ws-feature',
+				'sess-1',
+				'Session',
+				'codex'
+			);
+		});
+
+		it('resumes a main-checkout session from a worktree workspace', async () => {
+			(mockWorkspaceStore as unknown as { workspaces: unknown[] }).workspaces = [
+				{ id: 'ws-wt', projectPath: '/projects/test', worktreePath: '/projects/test-feature' }
+			];
+			vi.mocked(mockWorkspaceStore.getByProjectPath).mockReturnValue({ id: 'ws-main' } as never);
+
+			await store.resumeSession('ws-wt', 'sess-1', 'Session', 'claude', {
+				worktreePath: '/projects/test'
+			});
+
+			expect(mockProjectStore.openProject).toHaveBeenCalledWith('/projects/test');
+			expect(mockWorkspaceStore.resumeAISession).toHaveBeenCalledWith(
+				'ws-main',
+				'sess-1',
+				'Session',
+				'claude'
+			);
+		});
+	});
 });
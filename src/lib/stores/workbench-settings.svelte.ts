@@ -2,6 +2,8 @@ import { uid } from '$lib/utils/uid';
 import type {
 	AgentAction,
 	AgentActionTarget,
+	AutomationRule,
+	ModelPrice,
 	SessionType,
 	TerminalPerformanceMode,
 	TerminalRenderer,
@@ -14,7 +16,12 @@ import { invoke } from '@tauri-apps/api/core';
 /** Fields on WorkbenchSettingsStore that can be updated via the generic `set()` method. */
 type SettableField = keyof Omit<
 	WorkbenchSettings,
-	'agentActions' | 'claudeHooksApproved' | 'codexConfigApproved'
+	| 'agentActions'
+	| 'claudeHooksApproved'
+	| 'codexConfigApproved'
+	| 'automationRules'
+	| 'modelPrices'
+	| 'exportRedactionPatterns'
 >;
 
 export class WorkbenchSettingsStore {
@@ -32,6 +39,9 @@ export class WorkbenchSettingsStore {
 	codexConfigApproved: boolean | null = $state(null);
 	useHappyCoder = $state(false);
 	cloneBaseDir: string | null = $state(null);
+	automationRules: AutomationRule[] = $state([]);
+	modelPrices: ModelPrice[] = $state([]);
+	exportRedactionPatterns: string[] = $state([]);
 	loaded = $state(false);
 	saving = $state(false);
 	dirty = $state(false);
@@ -58,6 +68,9 @@ export class WorkbenchSettingsStore {
 		this.codexConfigApproved = settings.codexConfigApproved ?? null;
 		this.useHappyCoder = settings.useHappyCoder ?? false;
 		this.cloneBaseDir = settings.cloneBaseDir ?? null;
+		this.automationRules = settings.automationRules ?? [];
+		this.modelPrices = settings.modelPrices ?? [];
+		this.exportRedactionPatterns = settings.exportRedactionPatterns ?? [];
 		this.loaded = true;
 		this.dirty = false;
 	}
@@ -110,6 +123,30 @@ export class WorkbenchSettingsStore {
 		this.dirty = true;
 	}
 
+	/** Save a rule: an override when its id is a built-in rule's, otherwise a custom rule */
+	saveAutomationRule(rule: AutomationRule) {
+		const saved = this.automationRules.some((r) => r.id === rule.id);
+		this.automationRules = saved
+			? this.automationRules.map((r) => (r.id === rule.id ? rule : r))
+			: [...this.automationRules, rule];
+		this.dirty = true;
+	}
+
+	addAutomationRule() {
+		this.saveAutomationRule({
+			id: uid(),
+			name: '',
+			enabled: true,
+			actions: [{ type: 'refreshProject' }]
+		});
+	}
+
+	/** Delete a custom rule, or reset a built-in rule to its default */
+	removeAutomationRule(id: string) {
+		this.automationRules = this.automationRules.filter((rule) => rule.id !== id);
+		this.dirty = true;
+	}
+
 	getApproval(type: SessionType): boolean | null {
 		if (type === 'claude') return this.claudeHooksApproved;
 		if (type === 'codex') return this.codexConfigApproved;
@@ -137,7 +174,10 @@ export class WorkbenchSettingsStore {
 			claudeHooksApproved: this.claudeHooksApproved,
 			codexConfigApproved: this.codexConfigApproved,
 			useHappyCoder: this.useHappyCoder,
-			cloneBaseDir: this.cloneBaseDir
+			cloneBaseDir: this.cloneBaseDir,
+			automationRules: this.automationRules,
+			modelPrices: this.modelPrices,
+			exportRedactionPatterns: this.exportRedactionPatterns
 		};
 	}
 
@@ -224,7 +224,10 @@ describe('WorkbenchSettingsStore', () => {
 					claudeHooksApproved: null,
 					codexConfigApproved: null,
 					useHappyCoder: false,
-					cloneBaseDir: null
+					cloneBaseDir: null,
+					automationRules: [],
+					modelPrices: [],
+					exportRedactionPatterns: []
 				}
 			});
 		});
@@ -370,6 +373,46 @@ describe('WorkbenchSettingsStore', () => {
 		});
 	});
 
+	// ─── automation rules ───────────────────────────────────
+
+	describe('automation rules', () => {
+		it('adds an enabled custom rule that refreshes the project', () => {
+			store.addAutomationRule();
+
+			expect(store.automationRules).toEqual([
+				{ id: 'uid-1', name: '', enabled: true, actions: [{ type: 'refreshProject' }] }
+			]);
+			expect(store.dirty).toBe(true);
+		});
+
+		it('saves a built-in override once and replaces it on later edits', () => {
+			const builtin = {
+				id: 'builtin-refresh-git-commands',
+				name: 'Refresh after git/gh commands',
+				enabled: true,
+				actions: [{ type: 'refreshProject' as const }]
+			};
+
+			store.saveAutomationRule({ ...builtin, enabled: false });
+			store.saveAutomationRule({ ...builtin, enabled: false, name: 'Git refresh' });
+
+			expect(store.automationRules).toHaveLength(1);
+			expect(store.automationRules[0]).toMatchObject({ enabled: false, name: 'Git refresh' });
+		});
+
+		it('removing an override resets the built-in rule', () => {
+			store.automationRules = [
+				{ id: 'builtin-refresh-file-edits', name: 'x', enabled: false, actions: [] },
+				{ id: 'custom', name: 'y', enabled: true, actions: [] }
+			];
+
+			store.removeAutomationRule('builtin-refresh-file-edits');
+
+			expect(store.automationRules.map((rule) => rule.id)).toEqual(['custom']);
+			expect(store.dirty).toBe(true);
+		});
+	});
+
 	// ─── runnableActions derived ────────────────────────────
 
 	describe('runnableActions', () => {
@@ -476,7 +519,10 @@ describe('WorkbenchSettingsStore', () => {
 					claudeHooksApproved: null,
 					codexConfigApproved: null,
 					useHappyCoder: false,
-					cloneBaseDir: null
+					cloneBaseDir: null,
+					automationRules: [],
+					modelPrices: [],
+					exportRedactionPatterns: []
 				}
 			});
 			expect(store.agentActions[0].name).toBe('Review');
@@ -0,0 +1,73 @@
+import { afterEach, describe, expect, it } from 'vitest';
+import { clearInvokeMocks, invokeSpy, mockInvoke } from '../../test/tauri-mocks';
+import type { ConfigDiffLine } from '$types/workbench';
+import { collapseDiff, diffConfigVersions, restoreConfigVersion } from './config-history';
+
+function context(count: number, prefix = 'line'): ConfigDiffLine[] {
+	return Array.from({ length: count }, (_, i) => ({
+		kind: 'context' as const,
+		text: `${prefix} ${i}`
+	}));
+}
+
+describe('collapseDiff', () => {
+	it('keeps context around changes and folds the rest', () => {
+		const lines: ConfigDiffLine[] = [
+			...context(6, 'before'),
+			{ kind: 'removed', text: 'old' },
+			{ kind: 'added', text: 'new' },
+			...context(5, 'after')
+		];
+
+		const collapsed = collapseDiff(lines, 2);
+
+		expect(collapsed).toEqual([
+			{ kind: 'collapsed', count: 4 },
+			{ kind: 'context', text: 'before 4' },
+			{ kind: 'context', text: 'before 5' },
+			{ kind: 'removed', text: 'old' },
+			{ kind: 'added', text: 'new' },
+			{ kind: 'context', text: 'after 0' },
+			{ kind: 'context', text: 'after 1' },
+			{ kind: 'collapsed', count: 3 }
+		]);
+	});
+
+	it('folds an unchanged file into a single marker', () => {
+		expect(collapseDiff(context(4))).toEqual([{ kind: 'collapsed', count: 4 }]);
+	});
+});
+
+describe('config history commands', () => {
+	afterEach(() => {
+		clearInvokeMocks();
+	});
+
+	it('diffs against the file on disk unless a target version is given', async () => {
+		mockInvoke('diff_config_versions', () => []);
+
+		await diffConfigVersions('/home/me/.claude/settings.json', 'v1');
+		await diffConfigVersions('/home/me/.claude/settings.json', 'v1', 'v2');
+
+		expect(invokeSpy).toHaveBeenCalledWith('diff_config_versions', {
+			path: '/home/me/.claude/settings.json',
+			from: 'v1',
+			to: null
+		});
+		expect(invokeSpy).toHaveBeenCalledWith('diff_config_versions', {
+			path: '/home/me/.claude/settings.json',
+			from: 'v1',
+			to: 'v2'
+		});
+	});
+
+	it('restores a version', async () => {
+		mockInvoke('restore_config_version', () => true);
+
+		expect(await restoreConfigVersion('/tmp/a.json', 'v1')).toBe(true);
+		expect(invokeSpy).toHaveBeenCalledWith('restore_config_version', {
+			path: '/tmp/a.json',
+			version: 'v1'
+		});
+	});
+});
@@ -0,0 +1,61 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { ConfigDiffLine, ConfigHistoryFile, ConfigVersion } from '$types/workbench';
+
+export async function listConfigHistory(): Promise<ConfigHistoryFile[]> {
+	return invoke<ConfigHistoryFile[]>('list_config_history');
+}
+
+/** Snapshots of one file, newest first */
+export async function listConfigVersions(path: string): Promise<ConfigVersion[]> {
+	return invoke<ConfigVersion[]>('list_config_versions', { path });
+}
+
+/** Diff two snapshots; without `to` the file on disk is the newer side */
+export async function diffConfigVersions(
+	path: string,
+	from: string,
+	to: string | null = null
+): Promise<ConfigDiffLine[]> {
+	return invoke<ConfigDiffLine[]>('diff_config_versions', { path, from, to });
+}
+
+export async function restoreConfigVersion(path: string, version: string): Promise<boolean> {
+	return invoke<boolean>('restore_config_version', { path, version });
+}
+
+/** A run of unchanged lines hidden from a diff */
+export interface CollapsedLines {
+	kind: 'collapsed';
+	count: number;
+}
+
+/**
+ * Keep `context` unchanged lines around each change and fold the rest into
+ * `collapsed` markers, the way unified diffs show hunks.
+ */
+export function collapseDiff(
+	lines: ConfigDiffLine[],
+	context = 3
+): Array<ConfigDiffLine | CollapsedLines> {
+	const keep = lines.map(() => false);
+	lines.forEach((line, i) => {
+		if (line.kind === 'context') return;
+		for (let j = Math.max(0, i - context); j <= Math.min(lines.length - 1, i + context); j++) {
+			keep[j] = true;
+		}
+	});
+
+	const result: Array<ConfigDiffLine | CollapsedLines> = [];
+	let hidden = 0;
+	lines.forEach((line, i) => {
+		if (keep[i]) {
+			if (hidden > 0) result.push({ kind: 'collapsed', count: hidden });
+			hidden = 0;
+			result.push(line);
+		} else {
+			hidden++;
+		}
+	});
+	if (hidden > 0) result.push({ kind: 'collapsed', count: hidden });
+	return result;
+}
@@ -3,6 +3,7 @@ import { listen, type UnlistenFn } from '@tauri-apps/api/event';
 import type {
 	CreateTerminalRequest,
 	CreateTerminalResponse,
+	IntegrationRemovalReport,
 	IntegrationStatus,
 	TerminalDataEvent,
 	TerminalExitEvent
@@ -160,6 +161,14 @@ export async function applyCodexIntegration(): Promise<boolean> {
 	return invoke<boolean>('apply_codex_integration');
 }
 
+export async function removeClaudeIntegration(): Promise<IntegrationRemovalReport> {
+	return invoke<IntegrationRemovalReport>('remove_claude_integration');
+}
+
+export async function removeCodexIntegration(): Promise<IntegrationRemovalReport> {
+	return invoke<IntegrationRemovalReport>('remove_codex_integration');
+}
+
 // ── Native terminal (SwiftTerm) IPC wrappers ───────────────────────
 
 export async function createNativeTerminal(request: {
@@ -1,5 +1,8 @@
 export type SettingsScope = 'user' | 'user-local' | 'project' | 'project-local';
 
+/** Every scope that feeds the effective settings, including read-only managed settings */
+export type EffectiveSettingsScope = SettingsScope | 'managed';
+
 export type ScopeGroup = 'user' | 'project';
 
 export interface SandboxNetworkConfig {
@@ -82,13 +85,47 @@ export interface ClaudeSettings {
 }
 
 export interface McpServerConfig {
+	/** 'stdio', 'http' or 'sse'; stdio is implied by `command` */
+	type?: string;
 	command?: string;
 	args?: string[];
 	env?: Record<string, string>;
+	url?: string;
+	headers?: Record<string, string>;
 	disabled?: boolean;
 	[key: string]: unknown;
 }
 
+/**
+ * Where an MCP server definition lives: `.mcp.json` (project), `~/.claude.json`
+ * (user, or local for one project) or `~/.codex/config.toml` (codex).
+ */
+export type McpScope = 'project' | 'user' | 'local' | 'codex';
+
+export interface McpServerEntry {
+	name: string;
+	scope: McpScope;
+	config: McpServerConfig;
+	sourcePath: string;
+}
+
+export interface McpToolInfo {
+	name: string;
+	description?: string;
+}
+
+export interface McpHealthReport {
+	ok: boolean;
+	protocolVersion?: string;
+	serverName?: string;
+	serverVersion?: string;
+	tools: McpToolInfo[];
+	error?: string;
+	/** Whatever the server wrote to stderr, truncated */
+	stderr: string;
+	durationMs: number;
+}
+
 export interface HookEntry {
 	command?: string;
 	hooks?: HookCommand[];
@@ -100,23 +137,220 @@ export interface HookEntry {
 export interface HookCommand {
 	type?: string;
 	command?: string;
+	/** Seconds before Claude kills the command */
+	timeout?: number;
 	[key: string]: unknown;
 }
 
+/** One hook command flattened out of the `hooks` settings tree */
+export interface HookDefinition {
+	event: string;
+	matcher?: string;
+	command: string;
+	timeout?: number;
+}
+
+/** Where a hook command sits in `hooks[event][entryIndex].hooks[hookIndex]` */
+export interface HookLocation {
+	event: string;
+	entryIndex: number;
+	/** Null for legacy entries that carry `command` directly */
+	hookIndex: number | null;
+}
+
+export type HookListItem = HookDefinition & HookLocation;
+
+export type HookTestOutcome = 'success' | 'blocked' | 'error' | 'timedOut';
+
+export interface HookTestResult {
+	outcome: HookTestOutcome;
+	/** Null when the command was killed */
+	exitCode: number | null;
+	stdout: string;
+	stderr: string;
+	/** Stdout parsed as JSON, when the command exited 0 and printed an object */
+	output?: Record<string, unknown>;
+	decision?: string;
+	reason?: string;
+	/** What was piped to the command's stdin */
+	payload: Record<string, unknown>;
+	durationMs: number;
+}
+
+export interface PluginComponents {
+	skills: string[];
+	commands: string[];
+	agents: string[];
+	/** Hook event names */
+	hooks: string[];
+	mcpServers: string[];
+}
+
 export interface PluginInfo {
 	name: string;
 	description: string;
 	version: string;
+	/** `name@marketplace` for marketplace installs, the cache directory otherwise */
 	dirName: string;
+	marketplace?: string;
+	components: PluginComponents;
+}
+
+/** What uninstalling a plugin did to the settings that enable it */
+export interface PluginUninstallReport {
+	/** Whether the plugin was installed */
+	removed: boolean;
+	/** Settings files it was cleared from */
+	cleared: string[];
+	/** Settings files that still mention it because they could not be updated */
+	remaining: string[];
+}
+
+export interface MarketplacePluginInfo {
+	/** `name@marketplace`, the key used in `enabledPlugins` */
+	id: string;
+	name: string;
+	marketplace: string;
+	description: string;
+	version: string;
+	/** False for GitHub or URL sources, which need the Claude CLI */
+	installable: boolean;
+	installed: boolean;
+	components: PluginComponents;
+}
+
+export interface MarketplaceInfo {
+	name: string;
+	path: string;
+	isGit: boolean;
+	plugins: MarketplacePluginInfo[];
+	/** Why the marketplace manifest could not be read */
+	error?: string;
 }
 
+export type SkillScope = 'user' | 'project';
+
 export interface SkillInfo {
 	name: string;
 	dirName: string;
 	description: string;
+	scope: SkillScope;
+	allowedTools: string[];
+	/** Path to SKILL.md */
+	path: string;
+	/** Frontmatter problems; empty when the skill is valid */
+	issues: string[];
+}
+
+/** The editable parts of a SKILL.md */
+export interface SkillDocument {
+	name: string;
+	description: string;
+	allowedTools: string[];
+	/** Markdown after the frontmatter */
+	body: string;
+}
+
+/** Markdown definitions Claude loads from `.claude/agents` or `.claude/commands` */
+export type ClaudeDefinitionKind = 'agent' | 'command';
+
+export interface ClaudeDefinitionInfo {
+	kind: ClaudeDefinitionKind;
+	scope: SkillScope;
+	/** Subagent name from frontmatter, or the command name (its file stem) */
+	name: string;
+	/** Subdirectory a command lives in (`frontend`), shown by Claude as `(project:frontend)` */
+	namespace?: string;
+	/** Path below the definitions directory without `.md` */
+	fileName: string;
+	description?: string;
+	model?: string;
+	tools: string[];
+	path: string;
+	/** Frontmatter problems; empty when the definition is valid */
+	issues: string[];
+	/** The project definition that hides this user definition */
+	shadowedBy?: string;
+	/** The user definition this project definition hides */
+	overrides?: string;
+}
+
+/** The editable parts of a subagent or slash command file */
+export interface ClaudeDefinition {
+	name: string;
+	/** Commands only: the subdirectory the file is stored in */
+	namespace?: string | null;
+	description?: string | null;
+	model?: string | null;
+	/** `tools` for subagents, `allowed-tools` for commands */
+	tools: string[];
+	/** Commands only */
+	argumentHint?: string | null;
+	body: string;
 }
 
 export interface HookScriptInfo {
 	name: string;
 	path: string;
 }
+
+export type SettingsIssueSeverity = 'error' | 'warning';
+
+export interface SettingsValidationIssue {
+	/** JSON pointer to the offending value ('' for the document root) */
+	pointer: string;
+	severity: SettingsIssueSeverity;
+	message: string;
+}
+
+export interface SettingsSaveResult {
+	/** False when validation errors blocked the write */
+	saved: boolean;
+	issues: SettingsValidationIssue[];
+}
+
+export interface SettingsSourceInfo {
+	scope: EffectiveSettingsScope;
+	path: string;
+	exists: boolean;
+	/** Why the file was skipped, if it could not be read or parsed */
+	error?: string;
+}
+
+export interface SettingProvenance {
+	/** JSON pointer into the merged settings */
+	pointer: string;
+	scope: EffectiveSettingsScope;
+	/** Lower-precedence scopes whose value at this pointer was replaced */
+	overriddenScopes?: EffectiveSettingsScope[];
+}
+
+export interface EffectiveClaudeSettings {
+	settings: ClaudeSettings;
+	/** Scopes in precedence order, lowest first */
+	sources: SettingsSourceInfo[];
+	provenance: SettingProvenance[];
+}
+
+export type PermissionDecision = 'allow' | 'ask' | 'deny';
+
+export interface PermissionRuleRef {
+	rule: string;
+	list: PermissionDecision;
+	scope: EffectiveSettingsScope;
+}
+
+export interface PermissionEvaluation {
+	decision: PermissionDecision;
+	/** The rule that decided, or absent when the default mode did */
+	matchedRule?: PermissionRuleRef;
+	reason: string;
+}
+
+export interface PermissionLintIssue {
+	rule: PermissionRuleRef;
+	severity: SettingsIssueSeverity;
+	message: string;
+	/** The rule that shadows this one */
+	related?: PermissionRuleRef;
+}
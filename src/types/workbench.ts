@@ -62,6 +62,48 @@ export interface CodexNotifyEvent {
 	codexPayload: Record<string, unknown>;
 }
 
+export type SubagentStatus = 'running' | 'completed' | 'interrupted';
+
+export interface SubagentNode {
+	id: string;
+	/** Task that spawned this subagent; null for subagents of the main session */
+	parentId: string | null;
+	description: string | null;
+	subagentType: string | null;
+	status: SubagentStatus;
+	startedAt: string;
+	finishedAt: string | null;
+}
+
+export interface CompactionRecord {
+	timestamp: string;
+	trigger: string | null;
+	customInstructions: string | null;
+}
+
+/** Task subagents and compactions seen in a Claude pane (`claude:activity` event) */
+export interface PaneAgentActivity {
+	paneId: string;
+	sessionId: string | null;
+	subagents: SubagentNode[];
+	compactions: CompactionRecord[];
+	endedAt: string | null;
+	endReason: string | null;
+}
+
+export type AgentEventType = 'turn-started' | 'tool-used' | 'needs-input' | 'turn-finished';
+
+/** Normalized lifecycle event from a non-Claude/Codex agent bridge script */
+export interface AgentHookEvent {
+	paneId: string;
+	agent: string;
+	event: AgentEventType;
+	sessionId?: string;
+	toolName?: string;
+	cwd?: string;
+	payload: unknown;
+}
+
 export type SessionType = 'shell' | 'claude' | 'codex';
 export type AISessionType = 'claude' | 'codex';
 
@@ -95,11 +137,153 @@ export interface ActiveClaudeSession {
 	worktreePath?: string;
 }
 
+export type AwaitingInputReason = 'question' | 'permission';
+
+/** A session listed from an agent's store; Claude and Codex discovery share this shape */
 export interface DiscoveredClaudeSession {
 	sessionId: string;
 	label: string;
 	timestamp: string;
 	lastMessageRole?: 'user' | 'assistant';
+	lastAssistantSnippet?: string;
+	/** Set when the last turn ended waiting on the user */
+	awaitingInput?: AwaitingInputReason;
+	/** Timestamp of a trailing tool call that has no result yet */
+	pendingToolSince?: string;
+	/** Checkout the session ran in: the project itself or one of its worktrees */
+	worktreePath?: string;
+	/** Branch checked out in `worktreePath` at discovery time */
+	branch?: string;
+}
+
+/** A session from any agent's store, tagged with the provider that found it */
+export interface AgentSession extends DiscoveredClaudeSession {
+	/** Provider name, e.g. 'claude' or 'codex' */
+	provider: string;
+}
+
+export interface TokenUsage {
+	inputTokens: number;
+	outputTokens: number;
+	cacheCreationInputTokens: number;
+	cacheReadInputTokens: number;
+}
+
+export type TranscriptBlock =
+	| { type: 'text'; text: string }
+	| { type: 'thinking'; text: string }
+	| { type: 'toolUse'; id: string; name: string; input: unknown }
+	| { type: 'toolResult'; toolUseId: string; content: string; isError: boolean }
+	| { type: 'image'; mediaType: string | null };
+
+export interface TranscriptMessage {
+	uuid: string | null;
+	parentUuid: string | null;
+	timestamp: string | null;
+	role: 'user' | 'assistant' | 'system';
+	isMeta: boolean;
+	isSidechain: boolean;
+	subtype?: string;
+	model?: string;
+	usage?: TokenUsage;
+	cwd?: string;
+	blocks: TranscriptBlock[];
+}
+
+export interface TranscriptPage {
+	sessionId: string;
+	messages: TranscriptMessage[];
+	offset: number;
+	total: number;
+	hasMore: boolean;
+}
+
+export interface TouchedFile {
+	/** Relative to the repository root when inside it, otherwise absolute */
+	path: string;
+	tools: string[];
+	editCount: number;
+	firstTouchedAt: string | null;
+	lastTouchedAt: string | null;
+	/** Present when the file still has uncommitted changes */
+	gitStatus: GitFileStatus | null;
+}
+
+export interface SessionTouchedFiles {
+	sessionId: string;
+	repoRoot: string | null;
+	files: TouchedFile[];
+	/** Touched paths still in `git status`, ready to stage */
+	changedPaths: string[];
+}
+
+export type SessionExportFormat = 'markdown' | 'html';
+
+export type SessionHousekeepingAction = 'archive' | 'delete' | 'restore';
+
+/** A session matching either limit is pruned; with neither set nothing is */
+export interface SessionPrunePolicy {
+	olderThanDays?: number | null;
+	/** Keep the newest sessions up to this many bytes */
+	maxTotalBytes?: number | null;
+}
+
+export interface HousekeptSession {
+	provider: string;
+	sessionId: string;
+	label: string;
+	sizeBytes: number;
+	modifiedAt: string | null;
+	paths: string[];
+}
+
+export interface SkippedSession {
+	provider: string;
+	sessionId: string;
+	reason: string;
+}
+
+export interface ArchivedFile {
+	original: string;
+	archive: string;
+}
+
+/** A session moved into the archive, with what restoring it needs */
+export interface ArchivedSession {
+	provider: string;
+	sessionId: string;
+	label: string;
+	/** Project the session was archived from */
+	projectPath: string;
+	sizeBytes: number;
+	modifiedAt: string | null;
+	archivedAt: string;
+	files: ArchivedFile[];
+}
+
+export interface SessionHousekeepingReport {
+	action: SessionHousekeepingAction;
+	/** Nothing was changed; `sessions` previews what would be affected */
+	dryRun: boolean;
+	sessions: HousekeptSession[];
+	skipped: SkippedSession[];
+	totalBytes: number;
+	archiveDir?: string;
+}
+
+export type SessionSearchMatchKind = 'user' | 'assistant' | 'toolInput';
+
+export interface SessionSearchHit {
+	agent: 'claude' | 'codex';
+	sessionId: string;
+	projectPath: string | null;
+	label: string;
+	timestamp: string;
+	kind: SessionSearchMatchKind;
+	toolName?: string;
+	matchedAt: string | null;
+	snippet: string;
+	matchCount: number;
 }
 
 export interface ProjectWorkspace {
@@ -183,6 +367,11 @@ export interface GitChangedEvent {
 	projectPath: string;
 }
 
+export interface SessionsChangedEvent {
+	agent: 'claude' | 'codex';
+	paths: string[];
+}
+
 export interface ProjectRefreshRequestedEvent {
 	projectPath: string;
 	source: string;
@@ -336,6 +525,70 @@ export interface WorkbenchSettings {
 	codexConfigApproved?: boolean | null;
 	useHappyCoder: boolean;
 	cloneBaseDir?: string | null;
+	/** Overrides of built-in rules (same id) and custom rules; the backend merges in the rest */
+	automationRules: AutomationRule[];
+	/** Overrides of built-in prices (same model) and prices for other models */
+	modelPrices: ModelPrice[];
+	/** Extra regexes redacted in session exports; the built-in secret patterns always apply */
+	exportRedactionPatterns: string[];
+}
+
+/** USD per million tokens for models whose name starts with `model` (longest prefix wins) */
+export interface ModelPrice {
+	model: string;
+	inputPerMtok: number;
+	outputPerMtok: number;
+	cacheWritePerMtok: number;
+	cacheReadPerMtok: number;
+}
+
+export interface UsageRange {
+	/** Inclusive YYYY-MM-DD (UTC) */
+	from?: string;
+	to?: string;
+}
+
+export type UsageGroupBy = 'session' | 'project' | 'day' | 'model' | 'agent';
+
+export interface UsageBucket {
+	key: string;
+	label?: string;
+	agent?: 'claude' | 'codex';
+	projectPath?: string;
+	usage: TokenUsage;
+	costUsd: number;
+	messageCount: number;
+}
+
+export interface UsageSummary {
+	groupBy: UsageGroupBy;
+	buckets: UsageBucket[];
+	total: TokenUsage;
+	totalCostUsd: number;
+	unpricedModels: string[];
+}
+
+export type AutomationAction =
+	| { type: 'refreshProject' }
+	| { type: 'notify'; title: string; body: string }
+	| { type: 'runTask'; taskName: string }
+	| { type: 'writeToPane'; paneId: string; text: string }
+	| { type: 'moveTrelloCard'; columnId: string };
+
+export interface AutomationRule {
+	id: string;
+	name: string;
+	enabled: boolean;
+	source?: 'claude' | 'codex';
+	eventName?: string;
+	toolNames?: string[];
+	inputPattern?: string;
+	actions: AutomationAction[];
+}
+
+export interface AutomationRunTaskEvent {
+	projectPath: string;
+	task: ProjectTask;
 }
 
 export interface IntegrationStatus {
@@ -343,6 +596,10 @@ export interface IntegrationStatus {
 	description: string;
 }
 
+export interface IntegrationRemovalReport {
+	changes: string[];
+}
+
 export interface HookLogEntry {
 	timestamp: string;
 	level: 'event' | 'error';
@@ -361,3 +618,27 @@ export interface ProjectFormState {
 	startupCommand: string;
 	tasks: ProjectTask[];
 }
+
+/** A config file with snapshots under ~/.workbench/history/ */
+export interface ConfigHistoryFile {
+	path: string;
+	/** False once the file has been deleted; it can still be restored */
+	exists: boolean;
+	versions: number;
+	/** RFC 3339 time of the newest snapshot */
+	latest: string;
+}
+
+export interface ConfigVersion {
+	id: string;
+	/** RFC 3339 */
+	createdAt: string;
+	size: number;
+	/** Matches what is on disk now */
+	current: boolean;
+}
+
+export interface ConfigDiffLine {
+	kind: 'context' | 'added' | 'removed';
+	text: string;
+}